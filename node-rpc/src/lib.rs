@@ -118,6 +118,8 @@ where
     C::Api: BlockBuilder<Block>,
     C::Api: node_rpc::nft::NFTRuntimeApi<Block>,
     C::Api: node_rpc::settlement::SettlementRuntimeApi<Block>,
+    C::Api: node_rpc::order_book::OrderBookRuntimeApi<Block>,
+    C::Api: node_rpc::compliance::ComplianceRuntimeApi<Block>,
     P: TransactionPool + 'static,
     SC: SelectChain<Block> + 'static,
     B: sc_client_api::Backend<Block> + Send + Sync + 'static,
@@ -125,8 +127,10 @@ where
 {
     use node_rpc::{
         asset::{Asset, AssetApiServer},
+        compliance::{Compliance, ComplianceApiServer},
         identity::{Identity, IdentityApiServer},
         nft::{NFTApiServer, NFT},
+        order_book::{OrderBook, OrderBookApiServer},
         pips::{Pips, PipsApiServer},
         settlement::{Settlement, SettlementApiServer},
         transaction_payment::{TransactionPayment, TransactionPaymentApiServer},
@@ -219,7 +223,9 @@ where
     io.merge(Asset::new(client.clone()).into_rpc())?;
     io.merge(Group::from(client.clone()).into_rpc())?;
     io.merge(NFT::new(client.clone()).into_rpc())?;
-    io.merge(Settlement::new(client).into_rpc())?;
+    io.merge(Settlement::new(client.clone()).into_rpc())?;
+    io.merge(OrderBook::new(client.clone()).into_rpc())?;
+    io.merge(Compliance::new(client).into_rpc())?;
 
     Ok(io)
 }
@@ -29,7 +29,9 @@ use polymesh_common_utilities::constants::currency::POLY;
 use polymesh_common_utilities::traits::compliance_manager::ComplianceFnConfig;
 use polymesh_common_utilities::traits::nft::NFTTrait;
 use polymesh_common_utilities::TestUtilsFn;
-use polymesh_primitives::asset::{AssetName, NonFungibleType};
+use polymesh_primitives::asset::{
+    AssetHolder, AssetName, IssuanceTranche, NonFungibleType, VestingRelease,
+};
 use polymesh_primitives::asset_metadata::{
     AssetMetadataDescription, AssetMetadataKey, AssetMetadataName, AssetMetadataSpec,
     AssetMetadataValue, AssetMetadataValueDetail,
@@ -174,6 +176,7 @@ fn setup_create_asset<T: Config + TestUtilsFn<<T as frame_system::Config>::Accou
         total_supply: total_supply.into(),
         divisible: true,
         asset_type: AssetType::default(),
+        max_supply: None,
     };
     (owner.origin, name, ticker, token, identifiers, fundr)
 }
@@ -277,6 +280,48 @@ fn move_from_default_portfolio<T: Config>(
     .unwrap();
 }
 
+/// Moves tokens of a new asset to a holder and creates `s` linear vesting schedules over them.
+fn setup_vesting<T>(s: u32) -> (User<T>, Ticker, PortfolioId)
+where
+    T: Config + TestUtilsFn<AccountIdOf<T>>,
+{
+    let (owner, ticker) = owned_ticker::<T>();
+    let holder = UserBuilder::<T>::default().generate_did().build("holder");
+    let portfolio = PortfolioId::default_portfolio(holder.did());
+    Module::<T>::unsafe_transfer(
+        PortfolioId::default_portfolio(owner.did()),
+        portfolio,
+        &ticker,
+        ONE_UNIT * MAX_VESTING_SCHEDULES as Balance,
+        None,
+        None,
+        owner.did(),
+        &mut WeightMeter::max_limit_no_minimum(),
+    )
+    .unwrap();
+    for _ in 0..s {
+        Module::<T>::create_vesting_schedule(
+            owner.origin().into(),
+            ticker,
+            portfolio,
+            vesting_schedule(),
+        )
+        .unwrap();
+    }
+    (owner, ticker, portfolio)
+}
+
+fn vesting_schedule() -> VestingSchedule {
+    VestingSchedule {
+        amount: ONE_UNIT,
+        start: 0,
+        cliff: 0,
+        end: 2000,
+        release: VestingRelease::Linear,
+        released: 0,
+    }
+}
+
 benchmarks! {
     where_clause { where T: TestUtilsFn<AccountIdOf<T>> }
 
@@ -721,4 +766,96 @@ benchmarks! {
         ).unwrap();
     }: _(alice.origin, ticker, mediators.try_into().unwrap())
 
+
+    create_vesting_schedule {
+        let (owner, ticker, portfolio) = setup_vesting::<T>(MAX_VESTING_SCHEDULES - 1);
+    }: _(owner.origin, ticker, portfolio, vesting_schedule())
+    verify {
+        assert_eq!(Module::<T>::vesting_schedules(ticker, portfolio).len() as u32, MAX_VESTING_SCHEDULES);
+    }
+
+    release_vested_tokens {
+        let s in 1 .. MAX_VESTING_SCHEDULES;
+
+        let (owner, ticker, portfolio) = setup_vesting::<T>(s);
+        <pallet_timestamp::Now<T>>::set(1000u32.into());
+    }: _(owner.origin, ticker, portfolio)
+    verify {
+        assert_eq!(Module::<T>::vesting_schedules(ticker, portfolio)[0].released, ONE_UNIT / 2);
+    }
+
+    revoke_vesting_schedule {
+        let (owner, ticker, portfolio) = setup_vesting::<T>(1);
+        <pallet_timestamp::Now<T>>::set(1000u32.into());
+    }: _(owner.origin, ticker, portfolio, 0)
+    verify {
+        assert!(Module::<T>::vesting_schedules(ticker, portfolio).is_empty());
+    }
+
+    freeze_holder {
+        let (owner, ticker) = owned_ticker::<T>();
+        let holder = AssetHolder::Identity(IdentityId::from(1u128));
+    }: _(owner.origin, ticker, holder)
+    verify {
+        assert!(Module::<T>::frozen_holders(ticker, holder), "holder not frozen");
+    }
+
+    unfreeze_holder {
+        let (owner, ticker) = owned_ticker::<T>();
+        let holder = AssetHolder::Identity(IdentityId::from(1u128));
+        Module::<T>::freeze_holder(owner.origin().into(), ticker, holder).unwrap();
+    }: _(owner.origin, ticker, holder)
+    verify {
+        assert!(!Module::<T>::frozen_holders(ticker, holder), "holder still frozen");
+    }
+
+    force_transfer {
+        let (owner, ticker) = owned_ticker::<T>();
+        let from = UserBuilder::<T>::default().generate_did().build("from");
+        let to = UserBuilder::<T>::default().generate_did().build("to");
+        let from_portfolio = PortfolioId::default_portfolio(from.did());
+        let to_portfolio = PortfolioId::default_portfolio(to.did());
+        Module::<T>::unsafe_transfer(
+            PortfolioId::default_portfolio(owner.did()),
+            from_portfolio,
+            &ticker,
+            ONE_UNIT,
+            None,
+            None,
+            owner.did(),
+            &mut WeightMeter::max_limit_no_minimum(),
+        )
+        .unwrap();
+    }: _(owner.origin, ticker, from_portfolio, to_portfolio, ONE_UNIT, DocumentHash::None)
+    verify {
+        assert_eq!(Module::<T>::balance_of(ticker, to.did()), ONE_UNIT);
+    }
+
+    lower_max_supply {
+        let (owner, ticker) = owned_ticker::<T>();
+    }: _(owner.origin, ticker, MAX_SUPPLY)
+    verify {
+        assert_eq!(token_details::<T>(ticker).max_supply, Some(MAX_SUPPLY));
+    }
+
+    raise_max_supply {
+        let (owner, ticker) = owned_ticker::<T>();
+        let supply = token_details::<T>(ticker).total_supply;
+        Module::<T>::lower_max_supply(owner.origin().into(), ticker, supply).unwrap();
+    }: _(owner.origin, ticker, Some(MAX_SUPPLY))
+    verify {
+        assert_eq!(token_details::<T>(ticker).max_supply, Some(MAX_SUPPLY));
+    }
+
+    set_issuance_tranches {
+        let t in 0 .. MAX_ISSUANCE_TRANCHES;
+
+        let (owner, ticker) = owned_ticker::<T>();
+        let tranches: Vec<_> = (0..t)
+            .map(|i| IssuanceTranche { start: i as u64 * 1000, supply: MAX_SUPPLY })
+            .collect();
+    }: _(owner.origin, ticker, tranches.clone())
+    verify {
+        assert_eq!(Module::<T>::issuance_tranches(ticker), tranches);
+    }
 }
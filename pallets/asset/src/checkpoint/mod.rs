@@ -498,6 +498,16 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// The `caller_did` creates a checkpoint for `ticker` at the current time,
+    /// without checking that it is a permissioned agent.
+    /// The ID of the new checkpoint is returned.
+    pub fn unsafe_create_checkpoint(
+        caller_did: IdentityId,
+        ticker: Ticker,
+    ) -> Result<CheckpointId, DispatchError> {
+        Self::create_at_by(caller_did, ticker, Self::now_unix())
+    }
+
     /// The `caller_did` creates a checkpoint at `at` for `ticker`.
     /// The ID of the new checkpoint is returned.
     fn create_at_by(
@@ -92,7 +92,9 @@ use currency::*;
 use frame_support::dispatch::{DispatchError, DispatchResult, Weight};
 use frame_support::traits::{Get, PalletInfoAccess};
 use frame_support::BoundedBTreeSet;
-use frame_support::{decl_error, decl_module, decl_storage, ensure, fail};
+use frame_support::{
+    decl_error, decl_module, decl_storage, ensure, fail, IterableStorageDoubleMap,
+};
 use frame_system::ensure_root;
 use scale_info::TypeInfo;
 use sp_runtime::traits::Zero;
@@ -106,6 +108,7 @@ use pallet_identity::PermissionedCallOriginData;
 use polymesh_common_utilities::asset::AssetFnTrait;
 use polymesh_common_utilities::compliance_manager::ComplianceFnConfig;
 use polymesh_common_utilities::constants::*;
+use polymesh_common_utilities::portfolio::PortfolioSubTrait;
 use polymesh_common_utilities::protocol_fee::{ChargeProtocolFee, ProtocolOp};
 pub use polymesh_common_utilities::traits::asset::{Config, Event, RawEvent, WeightInfo};
 use polymesh_common_utilities::traits::nft::NFTTrait;
@@ -113,8 +116,8 @@ use polymesh_common_utilities::with_transaction;
 
 use polymesh_primitives::agent::AgentGroup;
 use polymesh_primitives::asset::{
-    AssetName, AssetType, CheckpointId, CustomAssetTypeId, FundingRoundName,
-    GranularCanTransferResult,
+    AssetHolder, AssetName, AssetType, CheckpointId, CustomAssetTypeId, FundingRoundName,
+    GranularCanTransferResult, IssuanceTranche, VestingSchedule,
 };
 use polymesh_primitives::asset_metadata::{
     AssetMetadataGlobalKey, AssetMetadataKey, AssetMetadataLocalKey, AssetMetadataName,
@@ -123,9 +126,9 @@ use polymesh_primitives::asset_metadata::{
 use polymesh_primitives::settlement::InstructionId;
 use polymesh_primitives::transfer_compliance::TransferConditionResult;
 use polymesh_primitives::{
-    extract_auth, storage_migration_ver, AssetIdentifier, Balance, Document, DocumentId,
-    IdentityId, Memo, PortfolioId, PortfolioKind, PortfolioUpdateReason, SecondaryKey, Ticker,
-    WeightMeter,
+    extract_auth, storage_migration_ver, AssetIdentifier, Balance, Document, DocumentHash,
+    DocumentId, IdentityId, Memo, PortfolioId, PortfolioKind, PortfolioUpdateReason, SecondaryKey,
+    Ticker, WeightMeter,
 };
 
 type Checkpoint<T> = checkpoint::Module<T>;
@@ -154,6 +157,8 @@ pub struct SecurityToken {
     pub owner_did: IdentityId,
     pub divisible: bool,
     pub asset_type: AssetType,
+    /// The authorized supply of the token, if it is capped below `MAX_SUPPLY`.
+    pub max_supply: Option<Balance>,
 }
 
 /// struct to store the ticker registration details.
@@ -193,7 +198,13 @@ impl Default for RestrictionResult {
     }
 }
 
-storage_migration_ver!(3);
+/// The maximum number of vesting schedules over a ticker in a single portfolio.
+pub const MAX_VESTING_SCHEDULES: u32 = 16;
+
+/// The maximum number of tranches in the issuance schedule of a ticker.
+pub const MAX_ISSUANCE_TRANCHES: u32 = 16;
+
+storage_migration_ver!(4);
 
 decl_storage! {
     trait Store for Module<T: Config> as Asset {
@@ -297,8 +308,30 @@ decl_storage! {
         pub MandatoryMediators get(fn mandatory_mediators):
             map hasher(blake2_128_concat) Ticker => BoundedBTreeSet<IdentityId, T::MaxAssetMediators>;
 
+        /// The vesting schedules over tokens in a portfolio.
+        /// Tokens of a schedule which are not yet released are locked in the portfolio.
+        /// (ticker, portfolio) -> [vesting schedule]
+        pub VestingSchedules get(fn vesting_schedules):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) PortfolioId => Vec<VestingSchedule>;
+
+        /// Holders for which transfers of a ticker are frozen.
+        /// (ticker, holder) -> bool
+        pub FrozenHolders get(fn frozen_holders):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) AssetHolder => bool;
+
+        /// The issuance schedule of a ticker, ordered by start date.
+        /// (ticker) -> [issuance tranche]
+        pub IssuanceTranches get(fn issuance_tranches):
+            map hasher(blake2_128_concat) Ticker => Vec<IssuanceTranche>;
+
+        /// The part of a ticker's supply, from before an ongoing rescale, that is yet to be rescaled.
+        /// While non-zero, the asset cannot be unfrozen, issued, redeemed or controller transferred.
+        /// (ticker) -> balance yet to be rescaled
+        pub BalanceToRescale get(fn balance_to_rescale):
+            map hasher(blake2_128_concat) Ticker => Balance;
+
         /// Storage version.
-        StorageVersion get(fn storage_version) build(|_| Version::new(3)): Version;
+        StorageVersion get(fn storage_version) build(|_| Version::new(4)): Version;
     }
     add_extra_genesis {
         config(reserved_country_currency_codes): Vec<Ticker>;
@@ -346,6 +379,9 @@ decl_module! {
                         res.maybe_cursor, res.backend, res.unique, res.loops);
                 }
             });
+            storage_migrate_on!(StorageVersion, 4, {
+                migration::migrate_to_v4::<T>();
+            });
             Weight::zero()
         }
 
@@ -935,6 +971,200 @@ decl_module! {
         ) {
             Self::base_remove_mandatory_mediators(origin, ticker, mediators)?;
         }
+
+        /// Creates a vesting schedule over `schedule.amount` tokens of `ticker` already held in `portfolio`.
+        ///
+        /// The tokens are locked in the portfolio, so they cannot be transferred, until they vest
+        /// and are released with `release_vested_tokens`.
+        ///
+        /// # Arguments
+        /// * `origin`: The secondary key of the sender.
+        /// * `ticker`: The [`Ticker`] of the vesting tokens.
+        /// * `portfolio`: The [`PortfolioId`] holding the vesting tokens.
+        /// * `schedule`: The [`VestingSchedule`] of the tokens.
+        ///
+        /// # Errors
+        /// * `InvalidVestingSchedule` if the amount is zero, some tokens are already released,
+        ///    the dates are not ordered or a step is zero.
+        /// * `TooManyVestingSchedules` if the portfolio already has `MAX_VESTING_SCHEDULES` schedules over `ticker`.
+        /// * `RescaleInProgress` if some holders of the asset are yet to be rescaled by a split.
+        /// * `InsufficientPortfolioBalance` if the portfolio doesn't hold enough free tokens.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::create_vesting_schedule()]
+        pub fn create_vesting_schedule(
+            origin,
+            ticker: Ticker,
+            portfolio: PortfolioId,
+            schedule: VestingSchedule
+        ) {
+            Self::base_create_vesting_schedule(origin, ticker, portfolio, schedule)?;
+        }
+
+        /// Releases the tokens of `ticker` vested so far in `portfolio` from their lock.
+        ///
+        /// Schedules whose tokens are all released are removed.
+        ///
+        /// # Arguments
+        /// * `origin`: The secondary key of the sender.
+        /// * `ticker`: The [`Ticker`] of the vesting tokens.
+        /// * `portfolio`: The [`PortfolioId`] holding the vesting tokens.
+        ///
+        /// # Errors
+        /// * `NoVestedTokens` if no tokens have vested since the last release.
+        #[weight = <T as Config>::WeightInfo::release_vested_tokens(MAX_VESTING_SCHEDULES)]
+        pub fn release_vested_tokens(origin, ticker: Ticker, portfolio: PortfolioId) {
+            Self::base_release_vested_tokens(origin, ticker, portfolio)?;
+        }
+
+        /// Revokes the vesting schedule at `index` for `ticker` in `portfolio`.
+        ///
+        /// Tokens vested so far are released to the holder,
+        /// while the unvested tokens are clawed back into the caller's default portfolio.
+        ///
+        /// # Arguments
+        /// * `origin`: The secondary key of the sender.
+        /// * `ticker`: The [`Ticker`] of the vesting tokens.
+        /// * `portfolio`: The [`PortfolioId`] holding the vesting tokens.
+        /// * `index`: The index of the schedule in `vesting_schedules(ticker, portfolio)`.
+        ///
+        /// # Errors
+        /// * `NoSuchVestingSchedule` if there's no schedule at `index`.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::revoke_vesting_schedule()]
+        pub fn revoke_vesting_schedule(origin, ticker: Ticker, portfolio: PortfolioId, index: u32) {
+            Self::base_revoke_vesting_schedule(origin, ticker, portfolio, index)?;
+        }
+
+        /// Freezes transfers of a given token to and from a single holder.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `holder` - the identity, or the single portfolio, to freeze.
+        ///
+        /// ## Errors
+        /// - `HolderAlreadyFrozen` if `holder` is already frozen for `ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::freeze_holder()]
+        pub fn freeze_holder(origin, ticker: Ticker, holder: AssetHolder) -> DispatchResult {
+            Self::set_holder_freeze(origin, ticker, holder, true)
+        }
+
+        /// Unfreezes transfers of a given token to and from a single holder.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `holder` - the frozen identity or portfolio.
+        ///
+        /// ## Errors
+        /// - `HolderNotFrozen` if `holder` is not frozen for `ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::unfreeze_holder()]
+        pub fn unfreeze_holder(origin, ticker: Ticker, holder: AssetHolder) -> DispatchResult {
+            Self::set_holder_freeze(origin, ticker, holder, false)
+        }
+
+        /// Forces a transfer of `value` tokens between two arbitrary portfolios, as ordered by a legal authority.
+        ///
+        /// Freezes of the asset or of its holders don't prevent the transfer, but locked tokens can't be moved.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `from_portfolio` - the portfolio to take the tokens from.
+        /// * `to_portfolio` - the portfolio to move the tokens to.
+        /// * `value` - the amount of tokens to move.
+        /// * `legal_order` - the hash of the document ordering the transfer, e.g. a court order.
+        ///
+        /// ## Errors
+        /// - `SenderSameAsReceiver` if both portfolios belong to the same identity.
+        /// - `InsufficientPortfolioBalance` if `from_portfolio` doesn't hold enough free tokens.
+        /// - `RescaleInProgress` if some holders of the asset are yet to be rescaled by a split.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::force_transfer()]
+        pub fn force_transfer(
+            origin,
+            ticker: Ticker,
+            from_portfolio: PortfolioId,
+            to_portfolio: PortfolioId,
+            value: Balance,
+            legal_order: DocumentHash
+        ) -> DispatchResult {
+            Self::base_force_transfer(origin, ticker, from_portfolio, to_portfolio, value, legal_order)
+        }
+
+        /// Caps, or lowers the cap on, the supply of a given token.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `max_supply` - the new maximum supply.
+        ///
+        /// ## Errors
+        /// - `MaxSupplyNotLowered` if `max_supply` is not below the current cap.
+        /// - `MaxSupplyBelowTotalSupply` if more than `max_supply` tokens are already issued.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::lower_max_supply()]
+        pub fn lower_max_supply(origin, ticker: Ticker, max_supply: Balance) -> DispatchResult {
+            Self::base_lower_max_supply(origin, ticker, max_supply)
+        }
+
+        /// Raises, or removes with `None`, the cap on the supply of a given token.
+        ///
+        /// This is a separate permission from `lower_max_supply`,
+        /// so that it can be reserved to an agent group, e.g. one holding a multisig.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `max_supply` - the new maximum supply, if any.
+        ///
+        /// ## Errors
+        /// - `MaxSupplyNotRaised` if the supply isn't capped or `max_supply` is not above the current cap.
+        /// - `TotalSupplyAboveLimit` if `max_supply` is above `MAX_SUPPLY`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::raise_max_supply()]
+        pub fn raise_max_supply(origin, ticker: Ticker, max_supply: Option<Balance>) -> DispatchResult {
+            Self::base_raise_max_supply(origin, ticker, max_supply)
+        }
+
+        /// Sets the issuance schedule of a given token, replacing the previous one.
+        ///
+        /// While the schedule is not empty, tokens can only be issued up to the supply
+        /// of the latest tranche started, and not at all before the first tranche.
+        /// The maximum supply of the token applies regardless.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `tranches` - the tranches of the schedule, ordered by start date.
+        ///
+        /// ## Errors
+        /// - `TooManyIssuanceTranches` if there are more than `MAX_ISSUANCE_TRANCHES` tranches.
+        /// - `IssuanceTranchesNotOrdered` if the tranches are not strictly ordered by start date.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::set_issuance_tranches(tranches.len() as u32)]
+        pub fn set_issuance_tranches(origin, ticker: Ticker, tranches: Vec<IssuanceTranche>) -> DispatchResult {
+            Self::base_set_issuance_tranches(origin, ticker, tranches)
+        }
     }
 }
 
@@ -1017,7 +1247,33 @@ decl_error! {
         /// Number of asset mediators would exceed the maximum allowed.
         NumberOfAssetMediatorsExceeded,
         /// Invalid ticker character - valid set: A`..`Z` `0`..`9` `_` `-` `.` `/`.
-        InvalidTickerCharacter
+        InvalidTickerCharacter,
+        /// The vesting schedule has a zero amount, released tokens, unordered dates or a zero step.
+        InvalidVestingSchedule,
+        /// The portfolio already has the maximum number of vesting schedules over the ticker.
+        TooManyVestingSchedules,
+        /// There's no vesting schedule at the given index.
+        NoSuchVestingSchedule,
+        /// No tokens have vested since the last release.
+        NoVestedTokens,
+        /// The holder is already frozen for the asset.
+        HolderAlreadyFrozen,
+        /// The holder is not frozen for the asset.
+        HolderNotFrozen,
+        /// Issuance would raise the supply above the maximum supply or the current issuance tranche.
+        MaxSupplyExceeded,
+        /// The new maximum supply is below the tokens already issued.
+        MaxSupplyBelowTotalSupply,
+        /// The new maximum supply is not below the current one.
+        MaxSupplyNotLowered,
+        /// The supply is not capped, or the new maximum supply is not above the current one.
+        MaxSupplyNotRaised,
+        /// The issuance schedule has more than `MAX_ISSUANCE_TRANCHES` tranches.
+        TooManyIssuanceTranches,
+        /// The issuance tranches are not strictly ordered by start date.
+        IssuanceTranchesNotOrdered,
+        /// Some holders of the asset are yet to be rescaled by a reorganization.
+        RescaleInProgress
     }
 }
 
@@ -1282,7 +1538,10 @@ impl<T: Config> Module<T> {
         value: Balance,
         weight_meter: &mut WeightMeter,
     ) -> StdResult<u8, DispatchError> {
-        if Self::frozen(ticker) {
+        if Self::frozen(ticker)
+            || Self::holder_frozen(ticker, &from_portfolio)
+            || Self::holder_frozen(ticker, &to_portfolio)
+        {
             return Ok(ERC1400_TRANSFERS_HALTED);
         }
 
@@ -1304,6 +1563,7 @@ impl<T: Config> Module<T> {
             ticker,
             from_portfolio.did,
             to_portfolio.did,
+            value,
             weight_meter,
         )? {
             return Ok(COMPLIANCE_MANAGER_FAILURE);
@@ -1401,6 +1661,7 @@ impl<T: Config> Module<T> {
         weight_meter: &mut WeightMeter,
     ) -> DispatchResult {
         Self::ensure_granular(ticker, value)?;
+        Self::ensure_not_rescaling(ticker)?;
         // Read the token details
         let mut token = Self::token_details(ticker)?;
         // Ensures the token is fungible
@@ -1415,6 +1676,7 @@ impl<T: Config> Module<T> {
             .checked_add(value)
             .ok_or(Error::<T>::TotalSupplyOverflow)?;
         Self::ensure_within_max_supply(updated_total_supply)?;
+        Self::ensure_within_issuance_limit(ticker, updated_total_supply)?;
         // Increase receiver balance.
         let current_to_balance = Self::balance_of(ticker, issuer_portfolio.did);
         // No check since the total balance is always <= the total supply. The
@@ -1475,6 +1737,145 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Issues `value` new tokens of `ticker` directly into `portfolio`, without charging a protocol fee.
+    ///
+    /// Used to pay scrip dividends in newly issued shares of the distributed asset.
+    pub fn unsafe_issue(
+        ticker: &Ticker,
+        portfolio: PortfolioId,
+        value: Balance,
+        weight_meter: &mut WeightMeter,
+    ) -> DispatchResult {
+        Self::_mint(ticker, portfolio, value, None, weight_meter)
+    }
+
+    /// Starts rescaling every holder of `ticker`, by marking its whole supply as yet to be rescaled.
+    ///
+    /// Until every holder has been rescaled through `unsafe_set_portfolio_balances`,
+    /// the asset can neither be unfrozen, issued, redeemed, controller transferred nor force transferred.
+    pub fn start_rescale(ticker: &Ticker) -> DispatchResult {
+        Self::ensure_not_rescaling(ticker)?;
+        let token = Self::token_details(ticker)?;
+        BalanceToRescale::insert(ticker, token.total_supply);
+        Ok(())
+    }
+
+    /// Returns `true` if some tokens of `ticker` are vesting in any portfolio.
+    pub fn has_vesting_schedules(ticker: &Ticker) -> bool {
+        VestingSchedules::iter_prefix(ticker).next().is_some()
+    }
+
+    /// Ensures that no holder of `ticker` is yet to be rescaled.
+    pub fn ensure_not_rescaling(ticker: &Ticker) -> DispatchResult {
+        ensure!(
+            Self::balance_to_rescale(ticker).is_zero(),
+            Error::<T>::RescaleInProgress
+        );
+        Ok(())
+    }
+
+    /// Sets the balance of each of the given portfolios of `did` in `ticker` to a new amount,
+    /// updating the identity's balance, the total supply, the checkpoints and the statistics.
+    ///
+    /// Used to rescale holders during reorganizations such as stock splits, and to redeem matured bonds.
+    /// The caller must ensure that the new balances are granular and that no assets are locked in `portfolios`.
+    /// The previous balance of `did` is deducted from the balance yet to be rescaled, see `start_rescale`.
+    pub fn unsafe_set_portfolio_balances(
+        caller_did: IdentityId,
+        ticker: &Ticker,
+        did: IdentityId,
+        portfolios: &[(PortfolioId, Balance)],
+        weight_meter: &mut WeightMeter,
+    ) -> DispatchResult {
+        let mut token = Self::token_details(ticker)?;
+        // Ensures the token is fungible
+        ensure!(
+            token.asset_type.is_fungible(),
+            Error::<T>::UnexpectedNonFungibleToken
+        );
+
+        let current_balance = Self::balance_of(ticker, did);
+        let mut updated_balance = current_balance;
+        let mut updated_total_supply = token.total_supply;
+        let mut updates = Vec::with_capacity(portfolios.len());
+        for (pid, new) in portfolios {
+            let old = Portfolio::<T>::portfolio_asset_balances(pid, ticker);
+            // The portfolio balances of `did` always sum up to its total balance,
+            // which in turn is always <= the total supply.
+            updated_balance = (updated_balance - old)
+                .checked_add(*new)
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            updated_total_supply = (updated_total_supply - old)
+                .checked_add(*new)
+                .ok_or(Error::<T>::TotalSupplyOverflow)?;
+            updates.push((*pid, old, *new));
+        }
+        Self::ensure_within_max_supply(updated_total_supply)?;
+
+        <Checkpoint<T>>::advance_update_balances(ticker, &[(did, current_balance)])?;
+
+        token.total_supply = updated_total_supply;
+        BalanceOf::insert(ticker, did, updated_balance);
+        Tokens::insert(ticker, token);
+        BalanceToRescale::mutate(ticker, |balance| {
+            *balance = balance.saturating_sub(current_balance)
+        });
+        for (pid, _, new) in &updates {
+            Portfolio::<T>::set_portfolio_balance(*pid, ticker, *new);
+        }
+
+        // Update statistic info, as if the difference was issued or redeemed.
+        if updated_balance > current_balance {
+            Statistics::<T>::update_asset_stats(
+                ticker,
+                None,
+                Some(&did),
+                None,
+                Some(updated_balance),
+                updated_balance - current_balance,
+                weight_meter,
+            )?;
+        } else if updated_balance < current_balance {
+            Statistics::<T>::update_asset_stats(
+                ticker,
+                Some(&did),
+                None,
+                Some(updated_balance),
+                None,
+                current_balance - updated_balance,
+                weight_meter,
+            )?;
+        }
+
+        for (pid, old, new) in updates {
+            let event = if new > old {
+                RawEvent::AssetBalanceUpdated(
+                    caller_did,
+                    *ticker,
+                    new - old,
+                    None,
+                    Some(pid),
+                    PortfolioUpdateReason::Issued {
+                        funding_round_name: None,
+                    },
+                )
+            } else if new < old {
+                RawEvent::AssetBalanceUpdated(
+                    caller_did,
+                    *ticker,
+                    old - new,
+                    Some(pid),
+                    None,
+                    PortfolioUpdateReason::Redeemed,
+                )
+            } else {
+                continue;
+            };
+            Self::deposit_event(event);
+        }
+        Ok(())
+    }
+
     fn ensure_granular(ticker: &Ticker, value: Balance) -> DispatchResult {
         ensure!(
             Self::check_granularity(&ticker, value),
@@ -1773,6 +2174,7 @@ impl<T: Config> Module<T> {
             owner_did: did,
             divisible,
             asset_type,
+            max_supply: None,
         };
         Tokens::insert(&ticker, token);
         AssetNames::insert(&ticker, &name);
@@ -1818,12 +2220,52 @@ impl<T: Config> Module<T> {
         };
 
         ensure!(Self::frozen(&ticker) != freeze, error);
+        if !freeze {
+            Self::ensure_not_rescaling(&ticker)?;
+        }
         Frozen::insert(&ticker, freeze);
 
         Self::deposit_event(event);
         Ok(())
     }
 
+    fn set_holder_freeze(
+        origin: T::RuntimeOrigin,
+        ticker: Ticker,
+        holder: AssetHolder,
+        freeze: bool,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        Self::ensure_asset_exists(&ticker)?;
+
+        let (event, error) = match freeze {
+            true => (
+                RawEvent::HolderFrozen(did, ticker, holder),
+                Error::<T>::HolderAlreadyFrozen,
+            ),
+            false => (
+                RawEvent::HolderUnfrozen(did, ticker, holder),
+                Error::<T>::HolderNotFrozen,
+            ),
+        };
+
+        ensure!(FrozenHolders::get(&ticker, &holder) != freeze, error);
+        if freeze {
+            FrozenHolders::insert(&ticker, &holder, true);
+        } else {
+            FrozenHolders::remove(&ticker, &holder);
+        }
+
+        Self::deposit_event(event);
+        Ok(())
+    }
+
+    /// Returns `true` if transfers of `ticker` are frozen for `portfolio` or for its owner.
+    pub fn holder_frozen(ticker: &Ticker, portfolio: &PortfolioId) -> bool {
+        FrozenHolders::get(ticker, AssetHolder::Identity(portfolio.did))
+            || FrozenHolders::get(ticker, AssetHolder::Portfolio(*portfolio))
+    }
+
     fn base_rename_asset(
         origin: T::RuntimeOrigin,
         ticker: Ticker,
@@ -1862,6 +2304,7 @@ impl<T: Config> Module<T> {
         )?;
 
         Self::ensure_granular(&ticker, value)?;
+        Self::ensure_not_rescaling(&ticker)?;
 
         let mut token = Self::token_details(&ticker)?;
         // Ensures the token is fungible
@@ -2241,6 +2684,7 @@ impl<T: Config> Module<T> {
             PortfolioKind::Default,
             false,
         )?;
+        Self::ensure_not_rescaling(&ticker)?;
 
         // Transfer `value` of ticker tokens from `investor_did` to controller
         Self::unsafe_transfer(
@@ -2262,6 +2706,157 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    fn base_lower_max_supply(
+        origin: T::RuntimeOrigin,
+        ticker: Ticker,
+        max_supply: Balance,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        let mut token = Self::token_details(&ticker)?;
+        ensure!(
+            token.max_supply.map_or(true, |cap| max_supply < cap),
+            Error::<T>::MaxSupplyNotLowered
+        );
+        ensure!(
+            Self::current_supply(&ticker, &token) <= max_supply,
+            Error::<T>::MaxSupplyBelowTotalSupply
+        );
+
+        let old = mem::replace(&mut token.max_supply, Some(max_supply));
+        Tokens::insert(&ticker, token);
+        Self::deposit_event(RawEvent::MaxSupplyChanged(
+            did,
+            ticker,
+            old,
+            Some(max_supply),
+        ));
+        Ok(())
+    }
+
+    fn base_raise_max_supply(
+        origin: T::RuntimeOrigin,
+        ticker: Ticker,
+        max_supply: Option<Balance>,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        let mut token = Self::token_details(&ticker)?;
+        let raised = match (token.max_supply, max_supply) {
+            (Some(cap), Some(new)) => new > cap,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        ensure!(raised, Error::<T>::MaxSupplyNotRaised);
+        if let Some(max_supply) = max_supply {
+            Self::ensure_within_max_supply(max_supply)?;
+        }
+
+        let old = mem::replace(&mut token.max_supply, max_supply);
+        Tokens::insert(&ticker, token);
+        Self::deposit_event(RawEvent::MaxSupplyChanged(did, ticker, old, max_supply));
+        Ok(())
+    }
+
+    fn base_set_issuance_tranches(
+        origin: T::RuntimeOrigin,
+        ticker: Ticker,
+        tranches: Vec<IssuanceTranche>,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        Self::ensure_asset_exists(&ticker)?;
+        ensure!(
+            tranches.len() as u32 <= MAX_ISSUANCE_TRANCHES,
+            Error::<T>::TooManyIssuanceTranches
+        );
+        ensure!(
+            tranches.windows(2).all(|w| w[0].start < w[1].start),
+            Error::<T>::IssuanceTranchesNotOrdered
+        );
+
+        if tranches.is_empty() {
+            IssuanceTranches::remove(&ticker);
+        } else {
+            IssuanceTranches::insert(&ticker, tranches.clone());
+        }
+        Self::deposit_event(RawEvent::IssuanceTranchesSet(did, ticker, tranches));
+        Ok(())
+    }
+
+    /// Returns the supply up to which `ticker` can currently be issued,
+    /// given its maximum supply and issuance schedule.
+    pub fn issuance_limit(ticker: &Ticker) -> Balance {
+        let max_supply = Tokens::get(ticker)
+            .and_then(|token| token.max_supply)
+            .unwrap_or(MAX_SUPPLY);
+        let tranches = IssuanceTranches::get(ticker);
+        if tranches.is_empty() {
+            return max_supply;
+        }
+        let now = <Checkpoint<T>>::now_unix();
+        tranches
+            .iter()
+            .take_while(|tranche| tranche.start <= now)
+            .last()
+            .map_or(0, |tranche| tranche.supply.min(max_supply))
+    }
+
+    /// Returns the amount of `ticker` that can currently be issued.
+    pub fn issuance_headroom(ticker: &Ticker) -> Balance {
+        let supply = Tokens::get(ticker).map_or(0, |token| Self::current_supply(ticker, &token));
+        Self::issuance_limit(ticker).saturating_sub(supply)
+    }
+
+    /// Ensures that issuing `ticker` can raise its supply to `supply`.
+    pub fn ensure_within_issuance_limit(ticker: &Ticker, supply: Balance) -> DispatchResult {
+        ensure!(
+            supply <= Self::issuance_limit(ticker),
+            Error::<T>::MaxSupplyExceeded
+        );
+        Ok(())
+    }
+
+    /// Returns the number of tokens, or of NFTs, issued for `ticker`.
+    fn current_supply(ticker: &Ticker, token: &SecurityToken) -> Balance {
+        match token.asset_type.is_fungible() {
+            true => token.total_supply,
+            false => T::NFTFn::nfts_in_collection(ticker),
+        }
+    }
+
+    fn base_force_transfer(
+        origin: T::RuntimeOrigin,
+        ticker: Ticker,
+        from_portfolio: PortfolioId,
+        to_portfolio: PortfolioId,
+        value: Balance,
+        legal_order: DocumentHash,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        Portfolio::<T>::ensure_portfolio_validity(&from_portfolio)?;
+        Portfolio::<T>::ensure_portfolio_validity(&to_portfolio)?;
+        Self::ensure_not_rescaling(&ticker)?;
+
+        Self::unsafe_transfer(
+            from_portfolio,
+            to_portfolio,
+            &ticker,
+            value,
+            None,
+            None,
+            did,
+            &mut WeightMeter::max_limit_no_minimum(),
+        )?;
+
+        Self::deposit_event(RawEvent::ForcedTransfer(
+            did,
+            ticker,
+            from_portfolio,
+            to_portfolio,
+            value,
+            legal_order,
+        ));
+        Ok(())
+    }
+
     pub fn unsafe_can_transfer_granular(
         from_custodian: Option<IdentityId>,
         from_portfolio: PortfolioId,
@@ -2287,7 +2882,9 @@ impl<T: Config> Module<T> {
             ticker,
             value,
         );
-        let asset_frozen = Self::frozen(ticker);
+        let asset_frozen = Self::frozen(ticker)
+            || Self::holder_frozen(ticker, &from_portfolio)
+            || Self::holder_frozen(ticker, &to_portfolio);
         let transfer_condition_result = Self::transfer_condition_failures_granular(
             &from_portfolio.did,
             &to_portfolio.did,
@@ -2299,6 +2896,7 @@ impl<T: Config> Module<T> {
             ticker,
             Some(from_portfolio.did),
             Some(to_portfolio.did),
+            value,
             weight_meter,
         )?;
 
@@ -2618,6 +3216,116 @@ impl<T: Config> Module<T> {
         ));
         Ok(())
     }
+
+    /// Locks the tokens of `schedule` in `portfolio` and records the schedule.
+    fn base_create_vesting_schedule(
+        origin: T::RuntimeOrigin,
+        ticker: Ticker,
+        portfolio: PortfolioId,
+        schedule: VestingSchedule,
+    ) -> DispatchResult {
+        let caller_did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        // The locked tokens would prevent their holder from being rescaled.
+        Self::ensure_not_rescaling(&ticker)?;
+        ensure!(schedule.is_valid(), Error::<T>::InvalidVestingSchedule);
+        Self::ensure_granular(&ticker, schedule.amount)?;
+        let mut schedules = VestingSchedules::get(ticker, portfolio);
+        ensure!(
+            (schedules.len() as u32) < MAX_VESTING_SCHEDULES,
+            Error::<T>::TooManyVestingSchedules
+        );
+
+        Portfolio::<T>::lock_tokens(&portfolio, &ticker, schedule.amount)?;
+        schedules.push(schedule.clone());
+        VestingSchedules::insert(ticker, portfolio, schedules);
+        Self::deposit_event(RawEvent::VestingScheduleCreated(
+            caller_did, ticker, portfolio, schedule,
+        ));
+        Ok(())
+    }
+
+    /// Unlocks the tokens of every schedule in `portfolio` vested since their last release.
+    fn base_release_vested_tokens(
+        origin: T::RuntimeOrigin,
+        ticker: Ticker,
+        portfolio: PortfolioId,
+    ) -> DispatchResult {
+        let caller_did = Identity::<T>::ensure_perms(origin)?;
+        let now = <Checkpoint<T>>::now_unix();
+
+        let mut schedules = VestingSchedules::get(ticker, portfolio);
+        let mut released: Balance = 0;
+        for schedule in schedules.iter_mut() {
+            let releasable = schedule.releasable_at(now);
+            schedule.released += releasable;
+            released += releasable;
+        }
+        ensure!(released > 0, Error::<T>::NoVestedTokens);
+
+        Portfolio::<T>::unlock_tokens(&portfolio, &ticker, released)?;
+        schedules.retain(|schedule| schedule.released < schedule.amount);
+        if schedules.is_empty() {
+            VestingSchedules::remove(ticker, portfolio);
+        } else {
+            VestingSchedules::insert(ticker, portfolio, schedules);
+        }
+        Self::deposit_event(RawEvent::VestedTokensReleased(
+            caller_did, ticker, portfolio, released,
+        ));
+        Ok(())
+    }
+
+    /// Removes the schedule at `index`, releasing its vested tokens and clawing back the rest.
+    fn base_revoke_vesting_schedule(
+        origin: T::RuntimeOrigin,
+        ticker: Ticker,
+        portfolio: PortfolioId,
+        index: u32,
+    ) -> DispatchResult {
+        let agent_portfolio = Self::ensure_origin_ticker_and_portfolio_permissions(
+            origin,
+            ticker,
+            PortfolioKind::Default,
+            false,
+        )?;
+        let mut schedules = VestingSchedules::get(ticker, portfolio);
+        ensure!(
+            (index as usize) < schedules.len(),
+            Error::<T>::NoSuchVestingSchedule
+        );
+
+        let schedule = schedules.remove(index as usize);
+        let vested = schedule.vested_at(<Checkpoint<T>>::now_unix());
+        let unvested = schedule.amount - vested;
+        Portfolio::<T>::unlock_tokens(&portfolio, &ticker, schedule.amount - schedule.released)?;
+        // The tokens stay put when the agent revokes a schedule over its own tokens.
+        if unvested > 0 && agent_portfolio.did != portfolio.did {
+            Self::unsafe_transfer(
+                portfolio,
+                agent_portfolio,
+                &ticker,
+                unvested,
+                None,
+                None,
+                agent_portfolio.did,
+                &mut WeightMeter::max_limit_no_minimum(),
+            )?;
+        }
+
+        if schedules.is_empty() {
+            VestingSchedules::remove(ticker, portfolio);
+        } else {
+            VestingSchedules::insert(ticker, portfolio, schedules);
+        }
+        Self::deposit_event(RawEvent::VestingScheduleRevoked(
+            agent_portfolio.did,
+            ticker,
+            portfolio,
+            schedule,
+            unvested,
+        ));
+        Ok(())
+    }
 }
 
 impl<T: Config> AssetFnTrait<T::AccountId, T::RuntimeOrigin> for Module<T> {
@@ -2674,6 +3382,10 @@ impl<T: Config> AssetFnTrait<T::AccountId, T::RuntimeOrigin> for Module<T> {
         TickersExemptFromAffirmation::get(ticker)
     }
 
+    fn holder_frozen(ticker: &Ticker, portfolio: &PortfolioId) -> bool {
+        Self::holder_frozen(ticker, portfolio)
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     fn register_asset_metadata_type(
         origin: T::RuntimeOrigin,
@@ -2696,3 +3408,36 @@ impl<T: Config> AssetFnTrait<T::AccountId, T::RuntimeOrigin> for Module<T> {
         Self::add_mandatory_mediators(origin, ticker, mediators.try_into().unwrap_or_default())
     }
 }
+
+pub mod migration {
+    use super::*;
+    use frame_support::IterableStorageMap;
+    use sp_runtime::runtime_logger::RuntimeLogger;
+
+    mod v3 {
+        use super::*;
+
+        #[derive(Decode)]
+        pub struct SecurityToken {
+            pub total_supply: Balance,
+            pub owner_did: IdentityId,
+            pub divisible: bool,
+            pub asset_type: AssetType,
+        }
+    }
+
+    pub fn migrate_to_v4<T: Config>() {
+        RuntimeLogger::init();
+        log::info!(" >>> Adding the max supply to Tokens");
+        Tokens::translate::<v3::SecurityToken, _>(|_, old| {
+            Some(SecurityToken {
+                total_supply: old.total_supply,
+                owner_did: old.owner_did,
+                divisible: old.divisible,
+                asset_type: old.asset_type,
+                max_supply: None,
+            })
+        });
+        log::info!(" >>> Tokens have been migrated");
+    }
+}
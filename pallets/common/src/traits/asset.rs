@@ -20,14 +20,17 @@ use frame_support::weights::Weight;
 use sp_std::collections::btree_set::BTreeSet;
 use sp_std::prelude::Vec;
 
-use polymesh_primitives::asset::{AssetName, AssetType, CustomAssetTypeId, FundingRoundName};
+use polymesh_primitives::asset::{
+    AssetHolder, AssetName, AssetType, CustomAssetTypeId, FundingRoundName, IssuanceTranche,
+    VestingSchedule,
+};
 use polymesh_primitives::asset_metadata::{
     AssetMetadataGlobalKey, AssetMetadataKey, AssetMetadataLocalKey, AssetMetadataName,
     AssetMetadataSpec, AssetMetadataValue, AssetMetadataValueDetail,
 };
 use polymesh_primitives::{
-    AssetIdentifier, Balance, Document, DocumentId, IdentityId, PortfolioId, PortfolioKind,
-    PortfolioUpdateReason, Ticker,
+    AssetIdentifier, Balance, Document, DocumentHash, DocumentId, IdentityId, PortfolioId,
+    PortfolioKind, PortfolioUpdateReason, Ticker,
 };
 
 use crate::traits::nft::NFTTrait;
@@ -187,7 +190,33 @@ decl_event! {
         AssetMediatorsAdded(IdentityId, Ticker, BTreeSet<IdentityId>),
         /// An identity has removed mediators from an asset.
         /// Parameters: [`IdentityId`] of caller, [`Ticker`] of the asset, the identity of all mediators removed.
-        AssetMediatorsRemoved(IdentityId, Ticker, BTreeSet<IdentityId>)
+        AssetMediatorsRemoved(IdentityId, Ticker, BTreeSet<IdentityId>),
+        /// An agent has created a vesting schedule over tokens in a portfolio.
+        /// Parameters: [`IdentityId`] of caller, [`Ticker`] of the asset, the vesting portfolio, the schedule.
+        VestingScheduleCreated(IdentityId, Ticker, PortfolioId, VestingSchedule),
+        /// Vested tokens have been released from the lock of a portfolio.
+        /// Parameters: [`IdentityId`] of caller, [`Ticker`] of the asset, the vesting portfolio, the amount released.
+        VestedTokensReleased(IdentityId, Ticker, PortfolioId, Balance),
+        /// An agent has revoked a vesting schedule, clawing back its unvested tokens.
+        /// Parameters: [`IdentityId`] of caller, [`Ticker`] of the asset, the vesting portfolio, the revoked schedule,
+        /// the amount clawed back.
+        VestingScheduleRevoked(IdentityId, Ticker, PortfolioId, VestingSchedule, Balance),
+        /// An agent has frozen transfers of an asset for a single holder.
+        /// Parameters: [`IdentityId`] of caller, [`Ticker`] of the asset, the frozen [`AssetHolder`].
+        HolderFrozen(IdentityId, Ticker, AssetHolder),
+        /// An agent has unfrozen transfers of an asset for a single holder.
+        /// Parameters: [`IdentityId`] of caller, [`Ticker`] of the asset, the unfrozen [`AssetHolder`].
+        HolderUnfrozen(IdentityId, Ticker, AssetHolder),
+        /// An agent has forced a transfer between two portfolios under a legal order.
+        /// Parameters: [`IdentityId`] of caller, [`Ticker`] of the asset, sender portfolio, receiver portfolio,
+        /// amount transferred, hash of the legal order document.
+        ForcedTransfer(IdentityId, Ticker, PortfolioId, PortfolioId, Balance, DocumentHash),
+        /// An agent has changed the maximum supply of an asset.
+        /// Parameters: [`IdentityId`] of caller, [`Ticker`] of the asset, old maximum supply, new maximum supply.
+        MaxSupplyChanged(IdentityId, Ticker, Option<Balance>, Option<Balance>),
+        /// An agent has set the issuance schedule of an asset.
+        /// Parameters: [`IdentityId`] of caller, [`Ticker`] of the asset, the tranches of the schedule.
+        IssuanceTranchesSet(IdentityId, Ticker, Vec<IssuanceTranche>)
     }
 }
 
@@ -224,6 +253,15 @@ pub trait WeightInfo {
     fn remove_ticker_pre_approval() -> Weight;
     fn add_mandatory_mediators(n: u32) -> Weight;
     fn remove_mandatory_mediators(n: u32) -> Weight;
+    fn create_vesting_schedule() -> Weight;
+    fn release_vested_tokens(s: u32) -> Weight;
+    fn revoke_vesting_schedule() -> Weight;
+    fn freeze_holder() -> Weight;
+    fn unfreeze_holder() -> Weight;
+    fn force_transfer() -> Weight;
+    fn lower_max_supply() -> Weight;
+    fn raise_max_supply() -> Weight;
+    fn set_issuance_tranches(t: u32) -> Weight;
 }
 
 pub trait AssetFnTrait<Account, Origin> {
@@ -250,6 +288,9 @@ pub trait AssetFnTrait<Account, Origin> {
     /// Returns `true` if the receivement of `ticker` is exempt from being affirmed, otherwise returns `false`.
     fn ticker_affirmation_exemption(ticker: &Ticker) -> bool;
 
+    /// Returns `true` if transfers of `ticker` are frozen for `portfolio` or for its owner.
+    fn holder_frozen(ticker: &Ticker, portfolio: &PortfolioId) -> bool;
+
     /// Issues `amount` tokens for `ticker` into the caller's portfolio.
     fn issue(
         origin: Origin,
@@ -20,9 +20,12 @@ use frame_support::traits::Get;
 use frame_support::weights::Weight;
 use sp_std::prelude::*;
 
-use polymesh_primitives::compliance_manager::{AssetComplianceResult, ComplianceRequirement};
+use polymesh_primitives::compliance_manager::{
+    AssetComplianceResult, ComplianceExpression, ComplianceRequirement, ComplianceTemplateId,
+    ComplianceTemplateName,
+};
 use polymesh_primitives::condition::{conditions_total_counts, Condition};
-use polymesh_primitives::{IdentityId, Ticker, TrustedIssuer, WeightMeter};
+use polymesh_primitives::{Balance, IdentityId, Ticker, TrustedIssuer, WeightMeter};
 
 use crate::asset::AssetFnTrait;
 use crate::balances::Config as BalancesConfig;
@@ -75,6 +78,24 @@ decl_event!(
         /// Emitted when default claim issuer list for a given ticker get removed.
         /// (caller DID, Ticker, Removed TrustedIssuer).
         TrustedDefaultClaimIssuerRemoved(IdentityId, Ticker, IdentityId),
+        /// Emitted when the compliance expression of a ticker is set or removed.
+        /// (caller DID, Ticker, new ComplianceExpression).
+        ComplianceExpressionSet(IdentityId, Ticker, Option<ComplianceExpression>),
+        /// Emitted when a compliance template is created.
+        /// (caller DID, template ID, template name).
+        ComplianceTemplateCreated(IdentityId, ComplianceTemplateId, ComplianceTemplateName),
+        /// Emitted when a new version of a compliance template is published.
+        /// (caller DID, template ID, version, requires acceptance).
+        ComplianceTemplateUpdated(IdentityId, ComplianceTemplateId, u32, bool),
+        /// Emitted when a ticker subscribes to a compliance template.
+        /// (caller DID, Ticker, template ID, accepted version).
+        ComplianceTemplateSubscribed(IdentityId, Ticker, ComplianceTemplateId, u32),
+        /// Emitted when a ticker unsubscribes from a compliance template.
+        /// (caller DID, Ticker, template ID).
+        ComplianceTemplateUnsubscribed(IdentityId, Ticker, ComplianceTemplateId),
+        /// Emitted when a new version of a compliance template is accepted for a ticker.
+        /// (caller DID, Ticker, template ID, accepted version).
+        ComplianceTemplateVersionAccepted(IdentityId, Ticker, ComplianceTemplateId, u32),
     }
 );
 
@@ -85,6 +106,7 @@ pub trait ComplianceFnConfig {
         ticker: &Ticker,
         sender_did: IdentityId,
         receiver_did: IdentityId,
+        value: Balance,
         weight_meter: &mut WeightMeter,
     ) -> Result<bool, DispatchError>;
 
@@ -92,6 +114,7 @@ pub trait ComplianceFnConfig {
         ticker: &Ticker,
         from_did_opt: Option<IdentityId>,
         to_did_opt: Option<IdentityId>,
+        value: Balance,
         weight_meter: &mut WeightMeter,
     ) -> Result<AssetComplianceResult, DispatchError>;
 
@@ -117,6 +140,12 @@ pub trait WeightInfo {
     fn is_condition_satisfied(c: u32, t: u32) -> Weight;
     fn is_identity_condition(e: u32) -> Weight;
     fn is_any_requirement_compliant(i: u32) -> Weight;
+    fn set_compliance_expression(n: u32) -> Weight;
+    fn create_compliance_template(c: u32) -> Weight;
+    fn update_compliance_template(c: u32, s: u32) -> Weight;
+    fn subscribe_to_compliance_template() -> Weight;
+    fn unsubscribe_from_compliance_template() -> Weight;
+    fn accept_compliance_template_version() -> Weight;
 
     fn condition_costs(conditions: u32, claims: u32, issuers: u32, claim_types: u32) -> Weight;
 
@@ -152,6 +181,36 @@ pub trait WeightInfo {
         ))
     }
 
+    fn set_compliance_expression_full(expr: Option<&ComplianceExpression>) -> Weight {
+        let (nodes, conditions, claims, issuers, claim_types) =
+            expr.map(|expr| expr.counts()).unwrap_or_default();
+        Self::set_compliance_expression(nodes).saturating_add(Self::condition_costs(
+            conditions,
+            claims,
+            issuers,
+            claim_types,
+        ))
+    }
+
+    fn create_compliance_template_full(reqs: &[ComplianceRequirement]) -> Weight {
+        let (conditions, claims, issuers, claim_types) =
+            conditions_total_counts(reqs.iter().flat_map(|req| req.conditions()));
+        Self::create_compliance_template(reqs.len() as u32).saturating_add(Self::condition_costs(
+            conditions,
+            claims,
+            issuers,
+            claim_types,
+        ))
+    }
+
+    fn update_compliance_template_full(reqs: &[ComplianceRequirement], subscribers: u32) -> Weight {
+        let (conditions, claims, issuers, claim_types) =
+            conditions_total_counts(reqs.iter().flat_map(|req| req.conditions()));
+        Self::update_compliance_template(reqs.len() as u32, subscribers).saturating_add(
+            Self::condition_costs(conditions, claims, issuers, claim_types),
+        )
+    }
+
     fn is_any_requirement_compliant_loop(i: u32) -> Weight {
         Self::is_any_requirement_compliant(i)
             .saturating_sub(Self::is_identity_condition(0).saturating_mul(i.into()))
@@ -26,8 +26,8 @@ use polymesh_primitives::identity::limits::{
     MAX_ASSETS, MAX_EXTRINSICS, MAX_PALLETS, MAX_PORTFOLIOS,
 };
 use polymesh_primitives::{
-    secondary_key::SecondaryKey, AuthorizationData, Balance, CustomClaimTypeId, IdentityClaim,
-    IdentityId, Permissions, Ticker,
+    secondary_key::SecondaryKey, AuthorizationData, Balance, Claim, CustomClaimTypeId,
+    IdentityClaim, IdentityId, Permissions, Ticker,
 };
 
 use crate::traits::group::GroupTrait;
@@ -57,6 +57,45 @@ pub struct TargetIdAuthorization<Moment> {
     pub expires_at: Moment,
 }
 
+/// The prefix of the message signed for a [`ClaimAttestation`].
+pub const CLAIM_ATTESTATION_PREFIX: &[u8] = b"polymesh_claim_attestation";
+
+/// It represents a claim that an issuer attests off-chain, by signing it with its primary key,
+/// or a secondary key permitted to add claims, so that `target` can register it.
+///
+/// # Safety
+///
+/// Please note, that `nonce` has been added to avoid **replay attack** and each value can only be
+/// used once per issuer. See `Identity::used_claim_attestation_nonce`.
+/// The attestation is bound to a chain by its genesis hash, so it can't be replayed on another chain,
+/// and can't be registered after `valid_until`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct ClaimAttestation<Moment, Hash> {
+    /// The genesis hash of the chain the attestation is valid on.
+    pub genesis_hash: Hash,
+    /// Target identity of the claim.
+    pub target: IdentityId,
+    /// The attested claim.
+    pub claim: Claim,
+    /// Expiry of the claim, if any.
+    pub expiry: Option<Moment>,
+    /// When the claim was attested, used as its issuance date.
+    pub issued_at: Moment,
+    /// The time after which the attestation can no longer be registered.
+    pub valid_until: Moment,
+    /// It HAS TO be unused by the issuer: See `Identity::used_claim_attestation_nonce`
+    pub nonce: AuthorizationNonce,
+}
+
+impl<Moment: Encode, Hash: Encode> ClaimAttestation<Moment, Hash> {
+    /// Returns the message signed by the issuer, i.e. `CLAIM_ATTESTATION_PREFIX` followed by the encoded attestation.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let mut payload = CLAIM_ATTESTATION_PREFIX.to_vec();
+        self.encode_to(&mut payload);
+        payload
+    }
+}
+
 /// Secondary key with authorization of that secondary key (off-chain operation) to be added
 /// to an identity.
 ///
@@ -94,6 +133,7 @@ pub trait WeightInfo {
     fn join_identity_as_key() -> Weight;
     fn leave_identity_as_key() -> Weight;
     fn add_claim() -> Weight;
+    fn add_claim_with_attestation() -> Weight;
     fn revoke_claim() -> Weight;
     fn set_secondary_key_permissions() -> Weight;
     /// Complexity Parameters:
@@ -11,7 +11,7 @@ use frame_support::weights::Weight;
 use polymesh_primitives::asset_metadata::AssetMetadataKey;
 use polymesh_primitives::nft::{NFTCollectionId, NFTs};
 use polymesh_primitives::ticker::Ticker;
-use polymesh_primitives::{IdentityId, NFTId, PortfolioId, PortfolioUpdateReason};
+use polymesh_primitives::{Balance, IdentityId, NFTId, PortfolioId, PortfolioUpdateReason};
 
 use crate::compliance_manager::ComplianceFnConfig;
 use crate::{asset, base, identity, portfolio};
@@ -60,6 +60,8 @@ pub trait NFTTrait<Origin> {
     fn is_collection_key(ticker: &Ticker, metadata_key: &AssetMetadataKey) -> bool;
     /// Updates the NFTOwner storage after moving funds.
     fn move_portfolio_owner(ticker: Ticker, nft_id: NFTId, new_owner_portfolio: PortfolioId);
+    /// Returns the number of NFTs issued in the ticker's collection.
+    fn nfts_in_collection(ticker: &Ticker) -> Balance;
 
     #[cfg(feature = "runtime-benchmarks")]
     fn create_nft_collection(
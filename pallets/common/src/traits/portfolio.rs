@@ -85,6 +85,9 @@ pub trait PortfolioSubTrait<AccountId> {
 
     /// Returns `true` if the portfolio has pre-approved the receivement of `ticker`, otherwise returns `false`.
     fn skip_portfolio_affirmation(portfolio_id: &PortfolioId, ticker: &Ticker) -> bool;
+
+    /// Returns the identity that has custody of the portfolio.
+    fn current_custodian(portfolio_id: &PortfolioId) -> IdentityId;
 }
 
 pub trait WeightInfo {
@@ -1,13 +1,16 @@
 use frame_support::decl_event;
 use frame_support::dispatch::DispatchError;
 use frame_support::weights::Weight;
+use sp_runtime::Perbill;
 use sp_std::vec::Vec;
 
+use polymesh_primitives::calendar::CalendarWindow;
+use polymesh_primitives::ethereum::EthereumAddress;
 use polymesh_primitives::settlement::{
     AffirmationCount, AssetCount, InstructionId, Leg, LegId, ReceiptMetadata, SettlementType,
-    VenueDetails, VenueId, VenueType,
+    VenueDetails, VenueId, VenueInstructionLimits, VenueType,
 };
-use polymesh_primitives::{IdentityId, Memo, PortfolioId, Ticker};
+use polymesh_primitives::{Balance, IdentityId, Memo, PortfolioId, Ticker};
 
 decl_event!(
     pub enum Event<T>
@@ -83,6 +86,64 @@ decl_event!(
         /// An instruction affirmation has been withdrawn by a mediator.
         /// Parameters: [`IdentityId`] of the mediator and [`InstructionId`] of the instruction.
         MediatorAffirmationWithdrawn(IdentityId, InstructionId),
+        /// A fraction of the remaining amount of each leg in an instruction has been transferred.
+        /// Parameters: [`IdentityId`] of the caller, [`InstructionId`] of the instruction, the filled fraction
+        /// and the amount transferred for each [`LegId`].
+        InstructionPartiallyFilled(IdentityId, InstructionId, Perbill, Vec<(LegId, Balance)>),
+        /// An instruction has expired and all its affirmations and locks have been released.
+        /// Parameters: [`InstructionId`] of the instruction.
+        InstructionExpired(InstructionId),
+        /// A batch of instructions has been executed by transferring the net position of each portfolio.
+        /// Parameters: [`IdentityId`] of the caller, [`VenueId`] of the venue, [`InstructionId`] of all
+        /// instructions in the batch and the net fungible transfers.
+        NettedInstructionsExecuted(IdentityId, VenueId, Vec<InstructionId>, Vec<Leg>),
+        /// An instruction has been rejected because one of its prerequisites has been rejected.
+        /// Parameters: [`InstructionId`] of the rejected instruction and [`InstructionId`] of the prerequisite.
+        DependentInstructionRejected(InstructionId, InstructionId),
+        /// The trading window of a venue has been updated (did, venue_id, trading_window)
+        VenueTradingWindowUpdated(IdentityId, VenueId, Option<CalendarWindow>),
+        /// A venue has been halted or resumed (did, venue_id, halted)
+        VenueHaltUpdated(IdentityId, VenueId, bool),
+        /// The instruction limits of a venue have been updated (did, venue_id, limits)
+        VenueInstructionLimitsUpdated(IdentityId, VenueId, VenueInstructionLimits),
+        /// The opening legs of a repo agreement have been executed and its unwind has been scheduled.
+        /// Parameters: [`InstructionId`] of the opening instruction and the maturity date.
+        RepoOpened(InstructionId, Moment),
+        /// A repo agreement has been unwound at its maturity date.
+        /// Parameters: [`InstructionId`] of the opening instruction.
+        RepoUnwound(InstructionId),
+        /// A repo agreement has been unwound before its maturity date.
+        /// Parameters: [`IdentityId`] of the caller and [`InstructionId`] of the opening instruction.
+        RepoClosedEarly(IdentityId, InstructionId),
+        /// The closing legs of a repo agreement could not be settled and all its locked assets have been released.
+        /// Parameters: [`InstructionId`] of the opening instruction.
+        RepoDefaulted(InstructionId),
+        /// An existing venue's ECDSA signers has been updated (did, venue_id, signers, update_type)
+        VenueEcdsaSignersUpdated(IdentityId, VenueId, Vec<EthereumAddress>, bool),
+        /// An ECDSA receipt has been claimed (did, instruction_id, leg_id, receipt_uid, signer, receipt metadata)
+        EcdsaReceiptClaimed(
+            IdentityId,
+            InstructionId,
+            LegId,
+            u64,
+            EthereumAddress,
+            Option<ReceiptMetadata>,
+        ),
+        /// A new version of an instruction has been proposed and all its affirmations have been reset.
+        /// Parameters: [`IdentityId`] of the caller, [`InstructionId`] of the instruction, the new version,
+        /// the new trade and value dates and the (leg_id, old leg, new leg) of every leg that changed.
+        InstructionAmended(
+            IdentityId,
+            InstructionId,
+            u32,
+            Option<Moment>,
+            Option<Moment>,
+            Vec<(LegId, Option<Leg>, Option<Leg>)>,
+        ),
+        /// A net transfer of a batch of netted instructions has been executed.
+        /// Parameters: [`IdentityId`] of the caller, [`VenueId`] of the venue, the net fungible transfer
+        /// and the [`InstructionId`] of all instructions whose legs it settles.
+        NetTransferExecuted(IdentityId, VenueId, Leg, Vec<InstructionId>),
     }
 );
 
@@ -112,6 +173,12 @@ pub trait WeightInfo {
     fn affirm_instruction_as_mediator() -> Weight;
     fn withdraw_affirmation_as_mediator() -> Weight;
     fn reject_instruction_as_mediator(f: u32, n: u32, o: u32) -> Weight;
+    fn expire_instruction(f: u32, n: u32, o: u32) -> Weight;
+    fn reject_dependents(d: u32) -> Weight;
+    fn set_venue_trading_window() -> Weight;
+    fn set_venue_halt() -> Weight;
+    fn set_venue_instruction_limits() -> Weight;
+    fn close_repo_early(f: u32, n: u32) -> Weight;
 
     fn add_and_affirm_with_mediators_legs(legs: &[Leg], n_mediators: u32) -> Weight {
         let (f, n, o) = Self::get_transfer_by_asset(legs);
@@ -140,6 +207,19 @@ pub trait WeightInfo {
         }
         Self::execute_manual_instruction(*f, *n, *o)
     }
+    fn execute_netted_instructions_weight_limit(
+        weight_limit: &Option<Weight>,
+        i: u32,
+        f: &u32,
+        n: &u32,
+        o: &u32,
+    ) -> Weight {
+        if let Some(weight_limit) = weight_limit {
+            return *weight_limit;
+        }
+        Self::execute_manual_instruction(*f, *n, *o)
+            .saturating_add(Self::execute_instruction_paused(0, 0, 0).saturating_mul(i.into()))
+    }
     fn get_transfer_by_asset(legs: &[Leg]) -> (u32, u32, u32) {
         let asset_count =
             AssetCount::try_from_legs(legs).unwrap_or(AssetCount::new(1024, 1024, 1024));
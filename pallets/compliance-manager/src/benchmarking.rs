@@ -22,6 +22,7 @@ use polymesh_common_utilities::asset::AssetFnTrait;
 use polymesh_common_utilities::benchs::{AccountIdOf, User, UserBuilder};
 use polymesh_common_utilities::{identity::Config as IdentityConfig, TestUtilsFn};
 use polymesh_primitives::agent::AgentGroup;
+use polymesh_primitives::compliance_manager::TransferParty;
 use polymesh_primitives::{
     asset::AssetType, AuthorizationData, ClaimType, CountryCode, PortfolioKind, Scope,
     TargetIdentity, TrustedFor, TrustedIssuer, WeightMeter,
@@ -225,6 +226,39 @@ impl<T: Config> ComplianceRequirementBuilder<T> {
     }
 }
 
+/// Creates the rules of a compliance template with `c` requirements.
+fn template_rules<T: Config + TestUtilsFn<AccountIdOf<T>>>(
+    c: u32,
+) -> (Vec<ComplianceRequirement>, Vec<TrustedIssuer>) {
+    let issuers = make_issuers::<T>(MAX_TRUSTED_ISSUER_PER_CONDITION, None);
+    let requirements = (0..c)
+        .map(|id| {
+            let (sender_count, receiver_count) = split_conditions(MAX_CONDITIONS_PER_COMPLIANCE);
+            ComplianceRequirement {
+                sender_conditions: make_conditions(sender_count, None, &issuers),
+                receiver_conditions: make_conditions(receiver_count, None, &issuers),
+                id: id + 1,
+            }
+        })
+        .collect();
+    (requirements, issuers)
+}
+
+/// Creates a compliance template without requirements.
+fn make_template<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> (User<T>, ComplianceTemplateId) {
+    let owner = UserBuilder::<T>::default()
+        .generate_did()
+        .build("TEMPLATE_OWNER");
+    Module::<T>::create_compliance_template(
+        owner.origin().into(),
+        ComplianceTemplateName(b"TEMPLATE".to_vec()),
+        Vec::new(),
+        Vec::new(),
+    )
+    .unwrap();
+    (owner, Module::<T>::compliance_template_id_sequence())
+}
+
 fn setup_conditions_bench<T: Config + TestUtilsFn<AccountIdOf<T>>>(
     conditions: u32,
     claims: u32,
@@ -553,6 +587,7 @@ benchmarks! {
             Module::<T>::is_condition_satisfied(
                 &ticker,
                 alice.did(),
+                0,
                 &condition,
                 &mut None,
                 &mut weight_meter
@@ -588,6 +623,7 @@ benchmarks! {
             Module::<T>::is_condition_satisfied(
                 &ticker,
                 alice.did(),
+                0,
                 &condition,
                 &mut None,
                 &mut weight_meter
@@ -622,9 +658,94 @@ benchmarks! {
                 &requirements,
                 alice.did(),
                 bob.did(),
+                0,
                 &mut weight_meter
             )
             .unwrap()
         );
     }
+
+    set_compliance_expression {
+        // Number of nodes in the expression.
+        let n in 1..50;
+
+        let owner = UserBuilder::<T>::default().generate_did().build("OWNER");
+        let ticker = make_token::<T>(&owner, b"1".to_vec());
+        let leaf = ComplianceExpression::Condition(
+            TransferParty::Receiver,
+            ConditionType::IsIdentity(TargetIdentity::Specific(owner.did())).into(),
+        );
+        let expression = ComplianceExpression::And(vec![leaf; n as usize - 1]);
+    }: _(owner.origin, ticker, Some(expression.clone()))
+    verify {
+        assert_eq!(Module::<T>::compliance_expression(ticker), Some(expression));
+    }
+
+    create_compliance_template {
+        let c in 0..MAX_COMPLIANCE_REQUIREMENTS;
+
+        let owner = UserBuilder::<T>::default().generate_did().build("OWNER");
+        let (requirements, trusted_issuers) = template_rules::<T>(c);
+        let name = ComplianceTemplateName(b"TEMPLATE".to_vec());
+    }: _(owner.origin, name, requirements, trusted_issuers)
+    verify {
+        assert!(Module::<T>::compliance_template(ComplianceTemplateId(1)).is_some());
+    }
+
+    update_compliance_template {
+        let c in 0..MAX_COMPLIANCE_REQUIREMENTS;
+        let s in 0..MAX_TEMPLATE_SUBSCRIBERS;
+
+        let (owner, id) = make_template::<T>();
+        let (requirements, trusted_issuers) = template_rules::<T>(c);
+        // Every subscriber has a compliance expression, checked along with the new version.
+        let agent = UserBuilder::<T>::default().generate_did().build("AGENT");
+        let expression = ComplianceExpression::Condition(
+            TransferParty::Receiver,
+            ConditionType::IsIdentity(TargetIdentity::Specific(agent.did())).into(),
+        );
+        for i in 0..s {
+            let ticker = make_token::<T>(&agent, format!("SUB{}", i).into_bytes());
+            Module::<T>::set_compliance_expression(agent.origin.clone().into(), ticker, Some(expression.clone())).unwrap();
+            Module::<T>::subscribe_to_compliance_template(agent.origin.clone().into(), ticker, id).unwrap();
+        }
+    }: _(owner.origin, id, requirements, trusted_issuers, false)
+    verify {
+        assert!(Module::<T>::compliance_template_version(id, 2).is_some());
+    }
+
+    subscribe_to_compliance_template {
+        let (_, id) = make_template::<T>();
+        let d = ComplianceRequirementBuilder::<T>::new(1, 1).build();
+    }: _(d.owner.origin, d.ticker, id)
+    verify {
+        assert!(Module::<T>::template_subscription(d.ticker).is_some());
+    }
+
+    unsubscribe_from_compliance_template {
+        let (_, id) = make_template::<T>();
+        let d = ComplianceRequirementBuilder::<T>::new(1, 1).build();
+        Module::<T>::subscribe_to_compliance_template(d.owner.origin.clone().into(), d.ticker, id).unwrap();
+    }: _(d.owner.origin, d.ticker)
+    verify {
+        assert!(Module::<T>::template_subscription(d.ticker).is_none());
+    }
+
+    accept_compliance_template_version {
+        let (owner, id) = make_template::<T>();
+        let d = ComplianceRequirementBuilder::<T>::new(1, 1).build();
+        Module::<T>::subscribe_to_compliance_template(d.owner.origin.clone().into(), d.ticker, id).unwrap();
+        let (requirements, trusted_issuers) = template_rules::<T>(MAX_COMPLIANCE_REQUIREMENTS);
+        Module::<T>::update_compliance_template(
+            owner.origin().into(),
+            id,
+            requirements,
+            trusted_issuers,
+            true,
+        )
+        .unwrap();
+    }: _(d.owner.origin, d.ticker)
+    verify {
+        assert_eq!(Module::<T>::template_subscription(d.ticker).unwrap().accepted_version, 2);
+    }
 }
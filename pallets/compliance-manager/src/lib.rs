@@ -64,6 +64,18 @@
 //!  the default claim issuer.
 //! - [change_compliance_requirement](Module::change_compliance_requirement) - Updates a compliance requirement, based on its id.
 //! based on its id for a given asset.
+//! - [set_compliance_expression](Module::set_compliance_expression) - Sets or removes the compliance
+//!  expression of an asset, which a transfer may satisfy instead of any of its requirements.
+//! - [create_compliance_template](Module::create_compliance_template) - Creates a compliance template
+//!  which assets can subscribe to.
+//! - [update_compliance_template](Module::update_compliance_template) - Publishes a new version of a
+//!  compliance template.
+//! - [subscribe_to_compliance_template](Module::subscribe_to_compliance_template) - Subscribes an asset
+//!  to a compliance template.
+//! - [unsubscribe_from_compliance_template](Module::unsubscribe_from_compliance_template) - Unsubscribes
+//!  an asset from its compliance template.
+//! - [accept_compliance_template_version](Module::accept_compliance_template_version) - Accepts the
+//!  latest version of the compliance template of an asset.
 //!
 //! ### Public Functions
 //!
@@ -81,19 +93,24 @@ use frame_support::dispatch::{DispatchError, DispatchResult};
 use frame_support::traits::Get;
 use frame_support::weights::Weight;
 use frame_support::{decl_error, decl_module, decl_storage, ensure};
+use sp_runtime::traits::SaturatedConversion;
 use sp_std::{convert::From, prelude::*};
 
-use pallet_base::ensure_length_ok;
+use pallet_base::{ensure_length_ok, ensure_string_limited, try_next_pre};
 use polymesh_common_utilities::protocol_fee::{ChargeProtocolFee, ProtocolOp};
 pub use polymesh_common_utilities::traits::compliance_manager::{
     ComplianceFnConfig, Config, Event, WeightInfo,
 };
 use polymesh_primitives::compliance_manager::{
-    AssetCompliance, AssetComplianceResult, ComplianceRequirement, ConditionResult,
+    AssetCompliance, AssetComplianceResult, AssetComplianceSimulation, ComplianceExpression,
+    ComplianceExpressionResult, ComplianceRequirement, ComplianceRequirementSimulation,
+    ComplianceTemplate, ComplianceTemplateId, ComplianceTemplateName, ComplianceTemplateRules,
+    ConditionResult, ConditionSimulation, MissingClaim, TemplateSubscription,
 };
 use polymesh_primitives::{
-    proposition, storage_migration_ver, Claim, Condition, ConditionType, Context, IdentityId,
-    TargetIdentity, Ticker, TrustedFor, TrustedIssuer, WeightMeter,
+    proposition, storage_migration_ver, Balance, Claim, ClaimAge, ClaimType, Condition,
+    ConditionType, Context, CountryCode, IdentityClaim, IdentityId, Scope, TargetIdentity, Ticker,
+    TrustedFor, TrustedIssuer, WeightMeter,
 };
 
 type ExternalAgents<T> = pallet_external_agents::Module<T>;
@@ -111,6 +128,12 @@ pub mod weight_for {
     }
 }
 
+/// The maximum depth of a compliance expression.
+pub const MAX_COMPLIANCE_EXPRESSION_DEPTH: u32 = 8;
+
+/// The maximum number of tickers subscribed to a compliance template.
+pub const MAX_TEMPLATE_SUBSCRIBERS: u32 = 100;
+
 storage_migration_ver!(0);
 
 decl_storage! {
@@ -119,6 +142,27 @@ decl_storage! {
         pub AssetCompliances get(fn asset_compliance): map hasher(blake2_128_concat) Ticker => AssetCompliance;
         /// List of trusted claim issuer Ticker -> Issuer Identity
         pub TrustedClaimIssuer get(fn trusted_claim_issuer): map hasher(blake2_128_concat) Ticker => Vec<TrustedIssuer>;
+        /// Compliance expression for a ticker, evaluated as an alternative to its requirements.
+        /// (Ticker -> ComplianceExpression)
+        pub ComplianceExpressions get(fn compliance_expression): map hasher(blake2_128_concat) Ticker => Option<ComplianceExpression>;
+        /// Compliance templates. (ComplianceTemplateId -> ComplianceTemplate)
+        pub ComplianceTemplates get(fn compliance_template): map hasher(twox_64_concat) ComplianceTemplateId => Option<ComplianceTemplate>;
+        /// Rules of each version of a compliance template.
+        /// (ComplianceTemplateId, version) -> ComplianceTemplateRules
+        pub ComplianceTemplateVersions get(fn compliance_template_version):
+            double_map hasher(twox_64_concat) ComplianceTemplateId, hasher(twox_64_concat) u32 => Option<ComplianceTemplateRules>;
+        /// The compliance template a ticker is subscribed to, replacing its own requirements
+        /// and default trusted claim issuers. (Ticker -> TemplateSubscription)
+        pub TemplateSubscriptions get(fn template_subscription): map hasher(blake2_128_concat) Ticker => Option<TemplateSubscription>;
+        /// The last compliance template ID.
+        pub ComplianceTemplateIdSequence get(fn compliance_template_id_sequence): ComplianceTemplateId;
+        /// The tickers subscribed to each compliance template.
+        /// (ComplianceTemplateId, Ticker) -> subscribed?
+        pub TemplateSubscribers get(fn template_subscribers):
+            double_map hasher(twox_64_concat) ComplianceTemplateId, hasher(blake2_128_concat) Ticker => bool;
+        /// The number of tickers subscribed to each compliance template.
+        /// (ComplianceTemplateId -> count)
+        pub TemplateSubscriberCount get(fn template_subscriber_count): map hasher(twox_64_concat) ComplianceTemplateId => u32;
         /// Storage version.
         StorageVersion get(fn storage_version) build(|_| Version::new(0)): Version;
     }
@@ -139,7 +183,17 @@ decl_error! {
         /// The worst case scenario of the compliance requirement is too complex.
         ComplianceRequirementTooComplex,
         /// The maximum weight limit for executing the function was exceeded.
-        WeightLimitExceeded
+        WeightLimitExceeded,
+        /// The compliance expression is nested deeper than allowed.
+        ComplianceExpressionTooDeep,
+        /// The compliance template doesn't exist.
+        NoSuchComplianceTemplate,
+        /// The ticker is not subscribed to a compliance template.
+        NotSubscribedToComplianceTemplate,
+        /// The latest version of the compliance template has already been accepted.
+        ComplianceTemplateVersionAlreadyAccepted,
+        /// The compliance template already has `MAX_TEMPLATE_SUBSCRIBERS` subscribers.
+        TooManyTemplateSubscribers
     }
 }
 
@@ -351,6 +405,239 @@ decl_module! {
             AssetCompliances::insert(&ticker, asset_compliance);
             Self::deposit_event(Event::ComplianceRequirementChanged(did, ticker, new_req));
         }
+
+        /// Sets or removes the compliance expression of an asset.
+        /// If there are duplicate ClaimTypes for a particular trusted issuer, duplicates are removed.
+        ///
+        /// The expression is an alternative to the compliance requirements, not an additional gate:
+        /// a transfer is compliant if any compliance requirement holds, or if the expression holds.
+        /// Conditions that must always hold, e.g. `Not` a blocked claim, must therefore also be part
+        /// of every requirement, or the asset should only use the expression, without requirements.
+        ///
+        /// # Arguments
+        /// * origin - Signer of the dispatchable. It should be the owner of the ticker.
+        /// * ticker - Symbol of the asset.
+        /// * expression - The new compliance expression, or `None` to remove it.
+        ///
+        /// # Errors
+        /// * `ComplianceExpressionTooDeep` if `expression` is nested deeper than `MAX_COMPLIANCE_EXPRESSION_DEPTH`.
+        /// * `ComplianceRequirementTooComplex` if the requirements and `expression` are too complex.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::set_compliance_expression_full(expression.as_ref())]
+        pub fn set_compliance_expression(origin, ticker: Ticker, expression: Option<ComplianceExpression>) {
+            let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+
+            let mut expression = expression;
+            if let Some(expr) = &mut expression {
+                ensure!(
+                    expr.depth() <= MAX_COMPLIANCE_EXPRESSION_DEPTH,
+                    Error::<T>::ComplianceExpressionTooDeep
+                );
+
+                // Ensure `Scope::Custom(..)`s are limited.
+                Self::ensure_custom_scopes_limited(expr.conditions().into_iter())?;
+
+                // Dedup `ClaimType`s and ensure issuers are limited in length.
+                expr.dedup();
+                Self::ensure_issuers_in_expression_limited(expr)?;
+
+                // Ensure the complexity is limited.
+                Self::base_verify_compliance_complexity(
+                    &Self::effective_asset_compliance(&ticker).requirements,
+                    Some(expr),
+                    Self::effective_trusted_issuers(&ticker).len(),
+                )?;
+            }
+
+            ComplianceExpressions::set(ticker, expression.clone());
+            Self::deposit_event(Event::ComplianceExpressionSet(did, ticker, expression));
+        }
+
+        /// Creates a compliance template owned by the caller's identity, which assets can subscribe to.
+        /// If there are duplicate ClaimTypes for a particular trusted issuer, duplicates are removed.
+        ///
+        /// # Arguments
+        /// * origin - Signer of the dispatchable.
+        /// * name - Name of the template.
+        /// * requirements - Compliance requirements of the template.
+        /// * trusted_issuers - Default trusted claim issuers of the template.
+        ///
+        /// # Errors
+        /// * `DuplicateComplianceRequirements` if `requirements` contains multiple entries with the same id.
+        /// * `ComplianceRequirementTooComplex` if `requirements` are too complex.
+        #[weight = <T as Config>::WeightInfo::create_compliance_template_full(&requirements)]
+        pub fn create_compliance_template(
+            origin,
+            name: ComplianceTemplateName,
+            requirements: Vec<ComplianceRequirement>,
+            trusted_issuers: Vec<TrustedIssuer>
+        ) {
+            let did = <Identity<T>>::ensure_perms(origin)?;
+            ensure_string_limited::<T>(&name)?;
+            let rules = Self::ensure_valid_template_rules(requirements, trusted_issuers)?;
+
+            let id = ComplianceTemplateIdSequence::try_mutate(try_next_pre::<T, _>)?;
+            ComplianceTemplates::insert(id, ComplianceTemplate {
+                owner: did,
+                name: name.clone(),
+                version: 1,
+                auto_version: 1,
+            });
+            ComplianceTemplateVersions::insert(id, 1, rules);
+            Self::deposit_event(Event::ComplianceTemplateCreated(did, id, name));
+        }
+
+        /// Publishes a new version of the compliance template `id`.
+        ///
+        /// # Arguments
+        /// * origin - Signer of the dispatchable. It should be the owner of the template.
+        /// * id - The template to update.
+        /// * requirements - Compliance requirements of the new version.
+        /// * trusted_issuers - Default trusted claim issuers of the new version.
+        /// * require_acceptance - If `true`, the new version only applies to an asset
+        ///   once accepted by one of its agents. Otherwise it applies to all subscribers.
+        ///
+        /// # Errors
+        /// * `NoSuchComplianceTemplate` if the template doesn't exist.
+        /// * `Unauthorized` if the caller is not the owner of the template.
+        /// * `ComplianceRequirementTooComplex` if the new version applies to all subscribers,
+        ///   and it is too complex along with the compliance expression of a subscriber.
+        #[weight = <T as Config>::WeightInfo::update_compliance_template_full(
+            &requirements,
+            if *require_acceptance { 0 } else { MAX_TEMPLATE_SUBSCRIBERS },
+        )]
+        pub fn update_compliance_template(
+            origin,
+            id: ComplianceTemplateId,
+            requirements: Vec<ComplianceRequirement>,
+            trusted_issuers: Vec<TrustedIssuer>,
+            require_acceptance: bool
+        ) {
+            let did = <Identity<T>>::ensure_perms(origin)?;
+            let mut template = Self::compliance_template(id)
+                .ok_or(Error::<T>::NoSuchComplianceTemplate)?;
+            ensure!(template.owner == did, Error::<T>::Unauthorized);
+            let rules = Self::ensure_valid_template_rules(requirements, trusted_issuers)?;
+
+            template.version = template.version.saturating_add(1);
+            if !require_acceptance {
+                // The new version applies to subscribers with compliance expressions as well.
+                Self::ensure_subscribers_complexity_limited(id, &rules)?;
+                template.auto_version = template.version;
+            }
+            let version = template.version;
+            ComplianceTemplates::insert(id, template);
+            ComplianceTemplateVersions::insert(id, version, rules);
+            Self::deposit_event(Event::ComplianceTemplateUpdated(did, id, version, require_acceptance));
+        }
+
+        /// Subscribes `ticker` to the compliance template `id`, accepting its latest version.
+        /// While subscribed, the requirements and default trusted claim issuers of the template
+        /// replace those of the asset.
+        ///
+        /// # Arguments
+        /// * origin - Signer of the dispatchable. It should be the owner of the ticker.
+        /// * ticker - Symbol of the asset.
+        /// * id - The template to subscribe to.
+        ///
+        /// # Errors
+        /// * `NoSuchComplianceTemplate` if the template doesn't exist.
+        /// * `ComplianceRequirementTooComplex` if the template and the compliance expression of `ticker` are too complex.
+        /// * `TooManyTemplateSubscribers` if the template already has `MAX_TEMPLATE_SUBSCRIBERS` subscribers.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::subscribe_to_compliance_template()]
+        pub fn subscribe_to_compliance_template(origin, ticker: Ticker, id: ComplianceTemplateId) {
+            let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+            let template = Self::compliance_template(id)
+                .ok_or(Error::<T>::NoSuchComplianceTemplate)?;
+            let rules = Self::compliance_template_version(id, template.version).unwrap_or_default();
+
+            // Ensure the complexity is limited.
+            Self::base_verify_compliance_complexity(
+                &rules.requirements,
+                ComplianceExpressions::get(ticker).as_ref(),
+                rules.trusted_issuers.len(),
+            )?;
+
+            if let Some(subscription) = Self::template_subscription(ticker) {
+                Self::remove_template_subscriber(subscription.template_id, ticker);
+            }
+            ensure!(
+                Self::template_subscriber_count(id) < MAX_TEMPLATE_SUBSCRIBERS,
+                Error::<T>::TooManyTemplateSubscribers
+            );
+            TemplateSubscribers::insert(id, ticker, true);
+            TemplateSubscriberCount::mutate(id, |count| *count += 1);
+            TemplateSubscriptions::insert(ticker, TemplateSubscription {
+                template_id: id,
+                accepted_version: template.version,
+            });
+            Self::deposit_event(Event::ComplianceTemplateSubscribed(did, ticker, id, template.version));
+        }
+
+        /// Unsubscribes `ticker` from its compliance template,
+        /// restoring its own requirements and default trusted claim issuers.
+        ///
+        /// # Arguments
+        /// * origin - Signer of the dispatchable. It should be the owner of the ticker.
+        /// * ticker - Symbol of the asset.
+        ///
+        /// # Errors
+        /// * `NotSubscribedToComplianceTemplate` if `ticker` is not subscribed to a template.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::unsubscribe_from_compliance_template()]
+        pub fn unsubscribe_from_compliance_template(origin, ticker: Ticker) {
+            let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+            let subscription = TemplateSubscriptions::take(ticker)
+                .ok_or(Error::<T>::NotSubscribedToComplianceTemplate)?;
+            Self::remove_template_subscriber(subscription.template_id, ticker);
+            Self::deposit_event(Event::ComplianceTemplateUnsubscribed(did, ticker, subscription.template_id));
+        }
+
+        /// Accepts the latest version of the compliance template `ticker` is subscribed to.
+        ///
+        /// # Arguments
+        /// * origin - Signer of the dispatchable. It should be the owner of the ticker.
+        /// * ticker - Symbol of the asset.
+        ///
+        /// # Errors
+        /// * `NotSubscribedToComplianceTemplate` if `ticker` is not subscribed to a template.
+        /// * `ComplianceTemplateVersionAlreadyAccepted` if the latest version already applies to `ticker`.
+        /// * `ComplianceRequirementTooComplex` if the new version and the compliance expression of `ticker` are too complex.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::accept_compliance_template_version()]
+        pub fn accept_compliance_template_version(origin, ticker: Ticker) {
+            let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+            let mut subscription = Self::template_subscription(ticker)
+                .ok_or(Error::<T>::NotSubscribedToComplianceTemplate)?;
+            let id = subscription.template_id;
+            let template = Self::compliance_template(id)
+                .ok_or(Error::<T>::NoSuchComplianceTemplate)?;
+            ensure!(
+                subscription.version(&template) < template.version,
+                Error::<T>::ComplianceTemplateVersionAlreadyAccepted
+            );
+            let rules = Self::compliance_template_version(id, template.version).unwrap_or_default();
+
+            // Ensure the complexity is limited.
+            Self::base_verify_compliance_complexity(
+                &rules.requirements,
+                ComplianceExpressions::get(ticker).as_ref(),
+                rules.trusted_issuers.len(),
+            )?;
+
+            subscription.accepted_version = template.version;
+            TemplateSubscriptions::insert(ticker, subscription);
+            Self::deposit_event(Event::ComplianceTemplateVersionAccepted(did, ticker, id, template.version));
+        }
     }
 }
 
@@ -421,6 +708,7 @@ impl<T: Config> Module<T> {
             // Ensure the complexity is limited for the ticker.
             Self::base_verify_compliance_complexity(
                 &AssetCompliances::get(ticker).requirements,
+                ComplianceExpressions::get(ticker).as_ref(),
                 new_count,
             )?;
 
@@ -442,6 +730,16 @@ impl<T: Config> Module<T> {
         claim: &'a Claim,
         issuers: &'a [TrustedIssuer],
     ) -> impl 'a + Iterator<Item = Claim> {
+        Self::fetch_identity_claims(target, claim, issuers).map(|id_claim| id_claim.claim)
+    }
+
+    /// Fetches the claims of `target` matching `claim` issued by any of `issuers`,
+    /// along with their issuance and last update dates.
+    fn fetch_identity_claims<'a>(
+        target: IdentityId,
+        claim: &'a Claim,
+        issuers: &'a [TrustedIssuer],
+    ) -> impl 'a + Iterator<Item = IdentityClaim> {
         let claim_type = claim.claim_type();
         let scope = claim.as_scope();
 
@@ -450,43 +748,253 @@ impl<T: Config> Module<T> {
             .filter(move |issuer| issuer.is_trusted_for(claim_type))
             .filter_map(move |issuer| {
                 Identity::<T>::fetch_claim(target, claim_type, issuer.issuer, scope.cloned())
-                    .map(|id_claim| id_claim.claim)
             })
     }
 
-    /// Returns trusted issuers specified in `condition` if any,
+    /// Fetches the claims of `target` matching `claim` issued by any of `issuers`,
+    /// which are no older than `age`.
+    fn fetch_recent_claims<'a>(
+        target: IdentityId,
+        claim: &'a Claim,
+        age: ClaimAge,
+        issuers: &'a [TrustedIssuer],
+    ) -> impl 'a + Iterator<Item = Claim> {
+        let now = <pallet_timestamp::Pallet<T>>::get().saturated_into::<u64>();
+        Self::fetch_identity_claims(target, claim, issuers)
+            .filter(move |id_claim| age.allows(id_claim, now))
+            .map(|id_claim| id_claim.claim)
+    }
+
+    /// Returns `issuers`, as specified in a condition, if any,
     /// or otherwise returns the default trusted issuers for `ticker`.
     /// Defaults are cached in `slot`.
     fn issuers_for<'a>(
         ticker: &Ticker,
-        condition: &'a Condition,
+        issuers: &'a [TrustedIssuer],
         slot: &'a mut Option<Vec<TrustedIssuer>>,
     ) -> &'a [TrustedIssuer] {
-        if condition.issuers.is_empty() {
-            slot.get_or_insert_with(|| Self::trusted_claim_issuer(ticker))
+        if issuers.is_empty() {
+            slot.get_or_insert_with(|| Self::effective_trusted_issuers(ticker))
         } else {
-            &condition.issuers
+            issuers
+        }
+    }
+
+    /// Fetches the countries of the jurisdiction claims of `target` with `scope` issued by any of `issuers`.
+    fn fetch_jurisdictions<'a>(
+        target: IdentityId,
+        scope: &'a Scope,
+        issuers: &'a [TrustedIssuer],
+    ) -> impl 'a + Iterator<Item = CountryCode> {
+        issuers
+            .iter()
+            .filter(|issuer| issuer.is_trusted_for(ClaimType::Jurisdiction))
+            .filter_map(move |issuer| {
+                Identity::<T>::fetch_claim(
+                    target,
+                    ClaimType::Jurisdiction,
+                    issuer.issuer,
+                    Some(scope.clone()),
+                )
+            })
+            .filter_map(|id_claim| match id_claim.claim {
+                Claim::Jurisdiction(country, _) => Some(country),
+                _ => None,
+            })
+    }
+
+    /// Checks whether `sender` and `receiver` have jurisdiction claims for the same country.
+    fn is_same_jurisdiction(
+        ticker: &Ticker,
+        sender: IdentityId,
+        receiver: IdentityId,
+        scope: &Scope,
+        issuers: &[TrustedIssuer],
+        slot: &mut Option<Vec<TrustedIssuer>>,
+        weight_meter: &mut WeightMeter,
+    ) -> Result<bool, DispatchError> {
+        let uses_defaults = issuers.is_empty();
+        let trusted_issuers = Self::issuers_for(ticker, issuers, slot);
+        // Consumes the weight for this condition
+        Self::consume_weight_meter(
+            weight_meter,
+            <T as Config>::WeightInfo::is_condition_satisfied(
+                (trusted_issuers.len() * 2) as u32,
+                uses_defaults as u32,
+            ),
+        )?;
+        let sender_countries: Vec<_> =
+            Self::fetch_jurisdictions(sender, scope, trusted_issuers).collect();
+        Ok(Self::fetch_jurisdictions(receiver, scope, trusted_issuers)
+            .any(|country| sender_countries.contains(&country)))
+    }
+
+    /// Checks whether `expr` holds for a transfer of `amount` from `sender` to `receiver`,
+    /// evaluating sub-expressions lazily.
+    fn is_expression_satisfied(
+        ticker: &Ticker,
+        expr: &ComplianceExpression,
+        sender: IdentityId,
+        receiver: IdentityId,
+        amount: Balance,
+        slot: &mut Option<Vec<TrustedIssuer>>,
+        weight_meter: &mut WeightMeter,
+    ) -> Result<bool, DispatchError> {
+        let eval = |expr: &ComplianceExpression,
+                    slot: &mut Option<Vec<TrustedIssuer>>,
+                    weight_meter: &mut WeightMeter| {
+            Self::is_expression_satisfied(
+                ticker,
+                expr,
+                sender,
+                receiver,
+                amount,
+                slot,
+                weight_meter,
+            )
+        };
+        match expr {
+            ComplianceExpression::Condition(party, condition) => Self::is_condition_satisfied(
+                ticker,
+                party.select(sender, receiver),
+                amount,
+                condition,
+                slot,
+                weight_meter,
+            ),
+            ComplianceExpression::SameJurisdiction(scope, issuers) => Self::is_same_jurisdiction(
+                ticker,
+                sender,
+                receiver,
+                scope,
+                issuers,
+                slot,
+                weight_meter,
+            ),
+            ComplianceExpression::And(exprs) => {
+                for expr in exprs {
+                    if !eval(expr, slot, weight_meter)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            ComplianceExpression::Or(exprs) => {
+                for expr in exprs {
+                    if eval(expr, slot, weight_meter)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            ComplianceExpression::Not(expr) => Ok(!eval(expr, slot, weight_meter)?),
         }
     }
 
-    /// Fetches the proposition context for target `id` and specific `condition`.
+    /// Evaluates `expr` for a transfer of `amount` from `sender` to `receiver`,
+    /// returning the result of each of its nodes.
+    /// Every sub-expression is evaluated, implying strict (non-lazy) evaluation.
+    fn evaluate_expression(
+        ticker: &Ticker,
+        expr: ComplianceExpression,
+        sender: IdentityId,
+        receiver: IdentityId,
+        amount: Balance,
+        weight_meter: &mut WeightMeter,
+    ) -> Result<ComplianceExpressionResult, DispatchError> {
+        let eval_all = |exprs: Vec<ComplianceExpression>, weight_meter: &mut WeightMeter| {
+            exprs
+                .into_iter()
+                .map(|expr| {
+                    Self::evaluate_expression(ticker, expr, sender, receiver, amount, weight_meter)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(match expr {
+            ComplianceExpression::Condition(party, condition) => {
+                let result = Self::is_condition_satisfied(
+                    ticker,
+                    party.select(sender, receiver),
+                    amount,
+                    &condition,
+                    &mut None,
+                    weight_meter,
+                )?;
+                ComplianceExpressionResult::Condition(party, ConditionResult { condition, result })
+            }
+            ComplianceExpression::SameJurisdiction(scope, issuers) => {
+                let result = Self::is_same_jurisdiction(
+                    ticker,
+                    sender,
+                    receiver,
+                    &scope,
+                    &issuers,
+                    &mut None,
+                    weight_meter,
+                )?;
+                ComplianceExpressionResult::SameJurisdiction(scope, issuers, result)
+            }
+            ComplianceExpression::And(exprs) => {
+                let results = eval_all(exprs, weight_meter)?;
+                let result = results.iter().all(|r| r.result());
+                ComplianceExpressionResult::And(results, result)
+            }
+            ComplianceExpression::Or(exprs) => {
+                let results = eval_all(exprs, weight_meter)?;
+                let result = results.iter().any(|r| r.result());
+                ComplianceExpressionResult::Or(results, result)
+            }
+            ComplianceExpression::Not(expr) => {
+                let result = Self::evaluate_expression(
+                    ticker,
+                    *expr,
+                    sender,
+                    receiver,
+                    amount,
+                    weight_meter,
+                )?;
+                let holds = !result.result();
+                ComplianceExpressionResult::Not(Box::new(result), holds)
+            }
+        })
+    }
+
+    /// Evaluates the compliance expression of `ticker`, if any, for a transfer of `amount`
+    /// from `sender` to `receiver`, returning the result of each of its nodes.
+    pub fn compliance_expression_result(
+        ticker: &Ticker,
+        sender: IdentityId,
+        receiver: IdentityId,
+        amount: Balance,
+        weight_meter: &mut WeightMeter,
+    ) -> Result<Option<ComplianceExpressionResult>, DispatchError> {
+        Self::compliance_expression(ticker)
+            .map(|expr| {
+                Self::evaluate_expression(ticker, expr, sender, receiver, amount, weight_meter)
+            })
+            .transpose()
+    }
+
+    /// Fetches the proposition context for target `id`, transferring `amount`, and specific `condition`.
     /// Default trusted issuers, if fetched, are cached in `slot`.
     fn fetch_context<'a>(
         id: IdentityId,
+        amount: Balance,
         ticker: &Ticker,
         slot: &'a mut Option<Vec<TrustedIssuer>>,
         condition: &'a Condition,
         weight_meter: &mut WeightMeter,
     ) -> Result<proposition::Context<impl 'a + Iterator<Item = Claim>>, DispatchError> {
         // Because of `-> impl Iterator`, we need to return a **single type** in each of the branches below.
-        // To do this, we use `Either<Either<MatchArm1, MatchArm2>, MatchArm3>`,
-        // equivalent to a 3-variant enum with iterators in each variant corresponding to the branches below.
-        // `Left(Left(arm1))`, `Left(Right(arm2))` and `Right(arm3)` correspond to arms 1, 2 and 3 respectively.
+        // To do this, we use `Either<Either<MatchArm1, MatchArm2>, Either<MatchArm3, MatchArm4>>`,
+        // equivalent to a 4-variant enum with iterators in each variant corresponding to the branches below.
+        // `Left(Left(arm1))`, `Left(Right(arm2))`, `Right(Left(arm3))` and `Right(Right(arm4))`
+        // correspond to arms 1, 2, 3 and 4 respectively.
         use either::Either::{Left, Right};
 
         let claims = match &condition.condition_type {
             ConditionType::IsPresent(claim) | ConditionType::IsAbsent(claim) => {
-                let trusted_issuers = Self::issuers_for(ticker, condition, slot);
+                let trusted_issuers = Self::issuers_for(ticker, &condition.issuers, slot);
                 // Consumes the weight for this condition
                 Self::consume_weight_meter(
                     weight_meter,
@@ -498,7 +1006,7 @@ impl<T: Config> Module<T> {
                 Left(Left(Self::fetch_claims(id, claim, trusted_issuers)))
             }
             ConditionType::IsAnyOf(claims) | ConditionType::IsNoneOf(claims) => {
-                let trusted_issuers = Self::issuers_for(ticker, condition, slot);
+                let trusted_issuers = Self::issuers_for(ticker, &condition.issuers, slot);
                 // Consumes the weight for this condition
                 Self::consume_weight_meter(
                     weight_meter,
@@ -517,31 +1025,50 @@ impl<T: Config> Module<T> {
                     weight_meter,
                     <T as Config>::WeightInfo::is_identity_condition(1),
                 )?;
-                Right(core::iter::empty())
+                Right(Right(core::iter::empty()))
             }
-            ConditionType::IsIdentity(TargetIdentity::Specific(_)) => {
+            ConditionType::IsIdentity(TargetIdentity::Specific(_))
+            | ConditionType::IsAmountWithin(_) => {
                 // Consumes the weight for this condition
                 Self::consume_weight_meter(
                     weight_meter,
                     <T as Config>::WeightInfo::is_identity_condition(0),
                 )?;
-                Right(core::iter::empty())
+                Right(Right(core::iter::empty()))
+            }
+            ConditionType::IsRecent(claim, age) => {
+                let trusted_issuers = Self::issuers_for(ticker, &condition.issuers, slot);
+                // Consumes the weight for this condition
+                Self::consume_weight_meter(
+                    weight_meter,
+                    <T as Config>::WeightInfo::is_condition_satisfied(
+                        trusted_issuers.len() as u32,
+                        condition.issuers.is_empty() as u32,
+                    ),
+                )?;
+                Right(Left(Self::fetch_recent_claims(
+                    id,
+                    claim,
+                    *age,
+                    trusted_issuers,
+                )))
             }
         };
 
-        Ok(proposition::Context { claims, id })
+        Ok(proposition::Context { claims, id, amount })
     }
 
     /// Loads the context for each condition in `conditions` and verifies that all of them evaluate to `true`.
     fn are_all_conditions_satisfied(
         ticker: &Ticker,
         did: IdentityId,
+        amount: Balance,
         conditions: &[Condition],
         weight_meter: &mut WeightMeter,
     ) -> Result<bool, DispatchError> {
         let slot = &mut None;
         for condition in conditions {
-            if !Self::is_condition_satisfied(ticker, did, condition, slot, weight_meter)? {
+            if !Self::is_condition_satisfied(ticker, did, amount, condition, slot, weight_meter)? {
                 return Ok(false);
             }
         }
@@ -552,11 +1079,12 @@ impl<T: Config> Module<T> {
     fn is_condition_satisfied(
         ticker: &Ticker,
         did: IdentityId,
+        amount: Balance,
         condition: &Condition,
         slot: &mut Option<Vec<TrustedIssuer>>,
         weight_meter: &mut WeightMeter,
     ) -> Result<bool, DispatchError> {
-        let context = Self::fetch_context(did, ticker, slot, &condition, weight_meter)?;
+        let context = Self::fetch_context(did, amount, ticker, slot, &condition, weight_meter)?;
         let any_ea = |ctx: Context<_>| ExternalAgents::<T>::agents(ticker, ctx.id).is_some();
         Ok(proposition::run(&condition, context, any_ea))
     }
@@ -567,6 +1095,7 @@ impl<T: Config> Module<T> {
     fn evaluate_conditions(
         ticker: &Ticker,
         did: IdentityId,
+        amount: Balance,
         conditions: &mut [ConditionResult],
         weight_meter: &mut WeightMeter,
     ) -> Result<bool, DispatchError> {
@@ -575,6 +1104,7 @@ impl<T: Config> Module<T> {
             let condition_holds = Self::is_condition_satisfied(
                 ticker,
                 did,
+                amount,
                 &condition.condition,
                 &mut None,
                 weight_meter,
@@ -596,6 +1126,188 @@ impl<T: Config> Module<T> {
         Ok(did)
     }
 
+    /// Simulates the compliance of `ticker` for a transfer of `amount` from `sender` to `receiver`,
+    /// returning, for each condition which doesn't hold, the claims which would satisfy it.
+    /// This does not care if the compliance is paused or not.
+    pub fn simulate_compliance(
+        ticker: &Ticker,
+        sender: IdentityId,
+        receiver: IdentityId,
+        amount: Balance,
+        weight_meter: &mut WeightMeter,
+    ) -> Result<AssetComplianceSimulation, DispatchError> {
+        let compliance = Self::verify_restriction_granular(
+            ticker,
+            Some(sender),
+            Some(receiver),
+            amount,
+            weight_meter,
+        )?;
+        let expression =
+            Self::compliance_expression_result(ticker, sender, receiver, amount, weight_meter)?;
+
+        let default_issuers = Self::effective_trusted_issuers(ticker);
+        let simulate = |conditions: Vec<ConditionResult>| {
+            conditions
+                .into_iter()
+                .map(|condition| Self::simulate_condition(condition, &default_issuers))
+                .collect()
+        };
+        let requirements = compliance
+            .requirements
+            .into_iter()
+            .map(|req| ComplianceRequirementSimulation {
+                sender_conditions: simulate(req.sender_conditions),
+                receiver_conditions: simulate(req.receiver_conditions),
+                id: req.id,
+                result: req.result,
+            })
+            .collect();
+
+        Ok(AssetComplianceSimulation {
+            paused: compliance.paused,
+            requirements,
+            expression,
+            result: compliance.result,
+        })
+    }
+
+    /// Returns the claims which would satisfy the evaluated `condition`, if it doesn't hold,
+    /// along with the trusted issuers of each, using `default_issuers` if it specifies none.
+    fn simulate_condition(
+        condition: ConditionResult,
+        default_issuers: &[TrustedIssuer],
+    ) -> ConditionSimulation {
+        let ConditionResult { condition, result } = condition;
+        let missing_claims = if result {
+            Vec::new()
+        } else {
+            let issuers = match condition.issuers.is_empty() {
+                true => default_issuers,
+                false => &condition.issuers,
+            };
+            let claims: &[Claim] = match &condition.condition_type {
+                ConditionType::IsPresent(claim) | ConditionType::IsRecent(claim, _) => {
+                    sp_std::slice::from_ref(claim)
+                }
+                ConditionType::IsAnyOf(claims) => claims,
+                // Those can't be satisfied by adding a claim.
+                ConditionType::IsAbsent(_)
+                | ConditionType::IsNoneOf(_)
+                | ConditionType::IsIdentity(_)
+                | ConditionType::IsAmountWithin(_) => &[],
+            };
+            claims
+                .iter()
+                .map(|claim| MissingClaim {
+                    claim: claim.clone(),
+                    issuers: issuers
+                        .iter()
+                        .filter(|issuer| issuer.is_trusted_for(claim.claim_type()))
+                        .map(|issuer| issuer.issuer)
+                        .collect(),
+                })
+                .collect()
+        };
+        ConditionSimulation {
+            condition,
+            result,
+            missing_claims,
+        }
+    }
+
+    /// Returns the rules of the compliance template version which applies to `ticker`, if subscribed.
+    pub fn subscribed_template_rules(ticker: &Ticker) -> Option<ComplianceTemplateRules> {
+        let subscription = Self::template_subscription(ticker)?;
+        let template = Self::compliance_template(subscription.template_id)?;
+        Self::compliance_template_version(subscription.template_id, subscription.version(&template))
+    }
+
+    /// Returns the asset compliance of `ticker`,
+    /// with the requirements of its compliance template, if subscribed.
+    pub fn effective_asset_compliance(ticker: &Ticker) -> AssetCompliance {
+        let mut asset_compliance = Self::asset_compliance(ticker);
+        if let Some(rules) = Self::subscribed_template_rules(ticker) {
+            asset_compliance.requirements = rules.requirements;
+        }
+        asset_compliance
+    }
+
+    /// Returns the default trusted claim issuers of `ticker`,
+    /// inherited from its compliance template, if subscribed.
+    pub fn effective_trusted_issuers(ticker: &Ticker) -> Vec<TrustedIssuer> {
+        match Self::subscribed_template_rules(ticker) {
+            Some(rules) => rules.trusted_issuers,
+            None => Self::trusted_claim_issuer(ticker),
+        }
+    }
+
+    /// Dedups and verifies the limits of the rules of a compliance template version.
+    fn ensure_valid_template_rules(
+        requirements: Vec<ComplianceRequirement>,
+        trusted_issuers: Vec<TrustedIssuer>,
+    ) -> Result<ComplianceTemplateRules, DispatchError> {
+        // Ensure `Scope::Custom(..)`s are limited.
+        Self::ensure_custom_scopes_limited(requirements.iter().flat_map(|c| c.conditions()))?;
+
+        // Ensure there are no duplicate requirement ids.
+        let mut requirements = requirements;
+        let start_len = requirements.len();
+        requirements.sort_by_key(|r| r.id);
+        requirements.dedup_by_key(|r| r.id);
+        ensure!(
+            start_len == requirements.len(),
+            Error::<T>::DuplicateComplianceRequirements
+        );
+
+        // Dedup `ClaimType`s and ensure issuers are limited in length.
+        requirements
+            .iter_mut()
+            .try_for_each(Self::dedup_and_ensure_requirement_limited)?;
+        let mut trusted_issuers = trusted_issuers;
+        ensure_length_ok::<T>(trusted_issuers.len())?;
+        for issuer in &mut trusted_issuers {
+            ensure!(
+                <Identity<T>>::is_identity_exists(&issuer.issuer),
+                Error::<T>::DidNotExist
+            );
+            issuer.dedup();
+            Self::ensure_issuer_limited(issuer)?;
+        }
+
+        // Ensure the complexity is limited.
+        Self::base_verify_compliance_complexity(&requirements, None, trusted_issuers.len())?;
+
+        Ok(ComplianceTemplateRules {
+            requirements,
+            trusted_issuers,
+        })
+    }
+
+    /// Removes `ticker` from the subscribers of the compliance template `id`.
+    fn remove_template_subscriber(id: ComplianceTemplateId, ticker: Ticker) {
+        if TemplateSubscribers::take(id, ticker) {
+            TemplateSubscriberCount::mutate(id, |count| *count = count.saturating_sub(1));
+        }
+    }
+
+    /// Verifies that `rules`, along with the compliance expression of each subscriber
+    /// of the compliance template `id`, are within the maximum condition complexity allowed.
+    fn ensure_subscribers_complexity_limited(
+        id: ComplianceTemplateId,
+        rules: &ComplianceTemplateRules,
+    ) -> DispatchResult {
+        TemplateSubscribers::iter_key_prefix(id)
+            .filter_map(|ticker| ComplianceExpressions::get(ticker))
+            .try_for_each(|expr| {
+                Self::base_verify_compliance_complexity(
+                    &rules.requirements,
+                    Some(&expr),
+                    rules.trusted_issuers.len(),
+                )
+            })
+    }
+
     /// Compute the id of the last requirement in a `ticker`'s compliance rules.
     fn get_latest_requirement_id(ticker: Ticker) -> u32 {
         Self::asset_compliance(ticker)
@@ -615,13 +1327,18 @@ impl<T: Config> Module<T> {
         let count = TrustedClaimIssuer::decode_len(ticker)
             .unwrap_or_default()
             .saturating_add(add);
-        Self::base_verify_compliance_complexity(asset_compliance, count)
+        Self::base_verify_compliance_complexity(
+            asset_compliance,
+            ComplianceExpressions::get(ticker).as_ref(),
+            count,
+        )
     }
 
-    /// Verify that `asset_compliance`, with `default_issuer_count`,
-    /// is within the maximum condition complexity allowed.
+    /// Verify that `asset_compliance` and `expression`, with `default_issuer_count`,
+    /// are within the maximum condition complexity allowed.
     pub fn base_verify_compliance_complexity(
         asset_compliance: &[ComplianceRequirement],
+        expression: Option<&ComplianceExpression>,
         default_issuer_count: usize,
     ) -> DispatchResult {
         let complexity = asset_compliance
@@ -633,7 +1350,8 @@ impl<T: Config> Module<T> {
             })
             // NB: If the compliance requirements are empty (0 complexity),
             // then use the count of requirements.
-            .max(asset_compliance.len() as u32);
+            .max(asset_compliance.len() as u32)
+            .saturating_add(expression.map_or(0, |expr| expr.complexity(default_issuer_count)));
         if complexity <= T::MaxConditionComplexity::get() {
             return Ok(());
         }
@@ -665,6 +1383,13 @@ impl<T: Config> Module<T> {
         })
     }
 
+    fn ensure_issuers_in_expression_limited(expr: &ComplianceExpression) -> DispatchResult {
+        ensure_length_ok::<T>(expr.issuers().len())?;
+        expr.issuers()
+            .into_iter()
+            .try_for_each(Self::ensure_issuer_limited)
+    }
+
     fn ensure_issuer_limited(issuer: &TrustedIssuer) -> DispatchResult {
         match &issuer.trusted_for {
             TrustedFor::Any => Ok(()),
@@ -686,6 +1411,7 @@ impl<T: Config> Module<T> {
         requirements: &[ComplianceRequirement],
         sender_did: IdentityId,
         receiver_did: IdentityId,
+        amount: Balance,
         weight_meter: &mut WeightMeter,
     ) -> Result<bool, DispatchError> {
         for requirement in requirements {
@@ -693,11 +1419,13 @@ impl<T: Config> Module<T> {
             if Self::are_all_conditions_satisfied(
                 ticker,
                 sender_did,
+                amount,
                 &requirement.sender_conditions,
                 weight_meter,
             )? && Self::are_all_conditions_satisfied(
                 ticker,
                 receiver_did,
+                amount,
                 &requirement.receiver_conditions,
                 weight_meter,
             )? {
@@ -714,22 +1442,39 @@ impl<T: Config> ComplianceFnConfig for Module<T> {
         ticker: &Ticker,
         sender_did: IdentityId,
         receiver_did: IdentityId,
+        value: Balance,
         weight_meter: &mut WeightMeter,
     ) -> Result<bool, DispatchError> {
-        let asset_compliance = Self::asset_compliance(ticker);
+        let asset_compliance = Self::effective_asset_compliance(ticker);
 
         // If compliance is paused, the rules are not checked
         if asset_compliance.paused {
             return Ok(true);
         }
 
-        Self::is_any_requirement_compliant(
+        if Self::is_any_requirement_compliant(
             ticker,
             &asset_compliance.requirements,
             sender_did,
             receiver_did,
+            value,
             weight_meter,
-        )
+        )? {
+            return Ok(true);
+        }
+
+        match Self::compliance_expression(ticker) {
+            Some(expr) => Self::is_expression_satisfied(
+                ticker,
+                &expr,
+                sender_did,
+                receiver_did,
+                value,
+                &mut None,
+                weight_meter,
+            ),
+            None => Ok(false),
+        }
     }
 
     /// verifies all requirements and returns the result in an array of booleans.
@@ -739,15 +1484,16 @@ impl<T: Config> ComplianceFnConfig for Module<T> {
         ticker: &Ticker,
         from_did_opt: Option<IdentityId>,
         to_did_opt: Option<IdentityId>,
+        value: Balance,
         weight_meter: &mut WeightMeter,
     ) -> Result<AssetComplianceResult, DispatchError> {
         let mut compliance_with_results =
-            AssetComplianceResult::from(Self::asset_compliance(ticker));
+            AssetComplianceResult::from(Self::effective_asset_compliance(ticker));
 
         // Evaluates all conditions.
         // False result in any of the conditions => False requirement result.
         let all_conditions_hold = |did, conditions, weight_meter: &mut WeightMeter| match did {
-            Some(did) => Self::evaluate_conditions(ticker, did, conditions, weight_meter),
+            Some(did) => Self::evaluate_conditions(ticker, did, value, conditions, weight_meter),
             None => Ok(false),
         };
 
@@ -760,6 +1506,23 @@ impl<T: Config> ComplianceFnConfig for Module<T> {
             }
             compliance_with_results.result |= req.result;
         }
+
+        // The compliance expression is an alternative to the requirements.
+        if let (Some(expr), Some(from_did), Some(to_did)) = (
+            Self::compliance_expression(ticker),
+            from_did_opt,
+            to_did_opt,
+        ) {
+            compliance_with_results.result |= Self::is_expression_satisfied(
+                ticker,
+                &expr,
+                from_did,
+                to_did,
+                value,
+                &mut None,
+                weight_meter,
+            )?;
+        }
         Ok(compliance_with_results)
     }
 
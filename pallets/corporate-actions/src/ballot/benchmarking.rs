@@ -14,16 +14,17 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use super::*;
-use crate::benchmarking::{set_ca_targets, setup_ca};
+use crate::benchmarking::{set_ca_targets, setup_ca, SEED};
 use core::iter;
 use frame_benchmarking::benchmarks;
 use polymesh_common_utilities::{
-    benchs::{AccountIdOf, User},
+    benchs::{user, AccountIdOf, User},
     TestUtilsFn,
 };
 
 const MAX_CHOICES: u32 = 1000;
 const MAX_TARGETS: u32 = 1000;
+const MAX_VOTERS: u32 = 1000;
 
 const RANGE: BallotTimeRange = BallotTimeRange {
     start: 3000,
@@ -124,4 +125,73 @@ benchmarks! {
     verify {
         assert_eq!(TimeRanges::get(ca_id), None, "ballot not removed");
     }
+
+    finalize_ballot {
+        let v in 0..MAX_VOTERS;
+        let c in 0..MAX_CHOICES;
+
+        let (owner, ca_id) = attach::<T>(1, c);
+
+        // Each choice has more votes than the last, falling back to the previous choice,
+        // so that a choice is eliminated in every round.
+        let votes = (0..c)
+            .map(|i| BallotVote {
+                power: (i + 1).into(),
+                fallback: (i > 0).then(|| (i - 1) as u16),
+            })
+            .collect::<Vec<_>>();
+        for did in 0..v {
+            Votes::insert(ca_id, IdentityId::from(did as u128), votes.clone());
+        }
+        <pallet_timestamp::Now<T>>::set(5000u32.into());
+    }: _(owner.origin(), ca_id, v, c)
+    verify {
+        assert!(FinalResults::get(ca_id).is_some(), "ballot not finalized");
+    }
+
+    delegate {
+        let (owner, ca_id) = attach::<T>(0, 0);
+        let proxy = user::<T>("proxy", SEED);
+    }: _(owner.origin(), DelegationScope::Ballot(ca_id), proxy.did())
+    verify {
+        assert_eq!(BallotDelegations::get(ca_id, owner.did()), Some(proxy.did()), "not delegated");
+    }
+
+    revoke_delegation {
+        let (owner, ca_id) = attach::<T>(0, 0);
+        let proxy = user::<T>("proxy", SEED);
+        let scope = DelegationScope::Ballot(ca_id);
+        <Module<T>>::delegate(owner.origin().into(), scope, proxy.did()).unwrap();
+    }: _(owner.origin(), scope)
+    verify {
+        assert_eq!(BallotDelegations::get(ca_id, owner.did()), None, "delegation not revoked");
+    }
+
+    proxy_vote {
+        let c in 0..MAX_CHOICES;
+        let t in 0..MAX_TARGETS;
+
+        // Attach, delegate, and prepare to vote.
+        let (owner, ca_id) = attach::<T>(1, c);
+        let proxy = user::<T>("proxy", SEED);
+        <Module<T>>::delegate(owner.origin().into(), DelegationScope::Ballot(ca_id), proxy.did()).unwrap();
+        <pallet_timestamp::Now<T>>::set(3000u32.into());
+
+        // Change targets, as they are read in voting.
+        set_ca_targets::<T>(ca_id, t);
+
+        // Construct the voting list.
+        let votes = (0..c)
+            .map(|c| BallotVote {
+                power: 0u32.into(),
+                fallback: (c as u16).checked_sub(1),
+            })
+            .collect::<Vec<_>>();
+
+        // Vote already to force a longer code path.
+        <Module<T>>::proxy_vote(proxy.origin().into(), ca_id, owner.did(), votes.clone()).unwrap();
+    }: _(proxy.origin(), ca_id, owner.did(), votes)
+    verify {
+        assert_eq!(ProxyVotes::get(ca_id, owner.did()), Some(proxy.did()), "proxy vote not cast");
+    }
 }
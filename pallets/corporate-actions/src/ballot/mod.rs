@@ -32,9 +32,13 @@
 //! and the holder's balance at that point is used as the available power.
 //!
 //! Eventually, the voting duration will be over.
-//! The interpretation of the vote results can then be interpreted off-chain,
+//! Anyone may then finalize the ballot through `finalize_ballot`,
+//! which tallies the votes of each motion and stores the outcome on-chain.
+//! When Ranked-Choice Voting (RCV) is enabled, the tally runs instant-runoff elimination,
+//! transferring the votes of eliminated choices to their fallbacks.
+//! Otherwise, the choice with the most votes wins.
+//! The vote results may still be interpreted off-chain instead,
 //! depending on the exact by-laws of the corporation.
-//! For example, Ranked-Choice Voting (RCV), may be used, when fallbacks are provided in votes.
 //!
 //! ## Overview
 //!
@@ -44,6 +48,8 @@
 //! - Adjusting details of a ballot that hasn't yet started.
 //! - Voting on motions.
 //! - Removing/Cancelling ballots.
+//! - Finalizing ballots, tallying their outcome.
+//! - Delegating voting power to proxies, and voting as a proxy.
 //!
 //! ### Terminology
 //!
@@ -58,6 +64,11 @@
 //!     Commonly, a motion will only have two choices, "Yay" or "Nay".
 //!     Any voting power that is not used is considered as abstain.
 //!
+//! - **Proxy:** An identity to which a token holder delegated their voting power,
+//!     either on the ballot of a specific CA, or on all ballots of a ticker.
+//!     A proxy votes on behalf of each holder separately, using the holder's balance at the record date.
+//!     Holders may always override the votes of their proxy by voting themselves.
+//!
 //! - **RCV:** Ranked-Choice Voting allows voters to select a fallback choice should their first
 //!     preference fail to reach a certain threshold or e.g., be eliminated in the top-2 run-off.
 //!     The chain supports this by admitting fallback choices, if the ballot is configured to support this.
@@ -73,6 +84,10 @@
 //! - `change_meta(origin, ca_id, meta)` changes the motions of the ballot for CA with `ca_id`.
 //! - `change_rcv(origin, ca_id, rcv)` changes the support for RCV to `rcv` in the ballot for CA with `ca_id`.
 //! - `remove_ballot(origin, ca_id)` removes the ballot for CA with `ca_id`.
+//! - `finalize_ballot(origin, ca_id, voters, choices)` tallies the outcome of the ended ballot for CA with `ca_id`.
+//! - `delegate(origin, scope, proxy)` delegates `origin`'s voting power within `scope` to `proxy`.
+//! - `revoke_delegation(origin, scope)` revokes `origin`'s delegation within `scope`.
+//! - `proxy_vote(origin, ca_id, delegator, votes)` casts `votes` on behalf of `delegator` in the ballot for CA with `ca_id`.
 
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
@@ -88,12 +103,13 @@ use frame_support::{
     ensure,
     traits::Get,
     weights::Weight,
+    IterableStorageDoubleMap,
 };
 use pallet_asset::checkpoint;
 use pallet_base::ensure_string_limited;
 use pallet_identity as identity;
 use polymesh_common_utilities::protocol_fee::{ChargeProtocolFee, ProtocolOp};
-use polymesh_primitives::{Balance, EventDid, IdentityId, Moment};
+use polymesh_primitives::{Balance, EventDid, IdentityId, Moment, Ticker};
 use polymesh_primitives_derive::VecU8StrongTyped;
 use scale_info::TypeInfo;
 use sp_runtime::traits::Zero;
@@ -235,6 +251,43 @@ pub struct BallotVote {
     pub fallback: Option<u16>,
 }
 
+/// A round of tallying the votes on a motion in a finalized ballot.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Default, Debug, Encode, Decode, TypeInfo)]
+pub struct TallyRound {
+    /// The voting power counted for each choice in the motion,
+    /// after transferring the votes of eliminated choices to their fallbacks.
+    /// Choices eliminated in earlier rounds count as zero.
+    pub tallies: Vec<Balance>,
+    /// The choices eliminated at the end of this round, if any.
+    pub eliminated: Vec<u16>,
+}
+
+/// The outcome of a motion in a finalized ballot.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Default, Debug, Encode, Decode, TypeInfo)]
+pub struct MotionOutcome {
+    /// The winning choice within the motion, if any.
+    ///
+    /// With RCV, this is the first choice to reach a majority of the continuing votes.
+    /// Otherwise, it's the choice with the most votes.
+    /// Ties, and motions without votes, have no winner.
+    pub winner: Option<u16>,
+    /// The rounds of the tally, in order.
+    /// Without RCV, there's exactly one round.
+    pub rounds: Vec<TallyRound>,
+}
+
+/// The ballots on which a token holder delegates their voting power to a proxy.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub enum DelegationScope {
+    /// All ballots of the ticker, including future ones.
+    Ticker(Ticker),
+    /// Only the ballot of the CA.
+    /// Takes precedence over a delegation on the CA's ticker.
+    Ballot(CAId),
+}
+
 /// Weight abstraction for the corporate actions module.
 pub trait WeightInfo {
     fn attach_ballot(num_choices: u32) -> Weight;
@@ -243,6 +296,10 @@ pub trait WeightInfo {
     fn change_meta(num_choices: u32) -> Weight;
     fn change_rcv() -> Weight;
     fn remove_ballot() -> Weight;
+    fn finalize_ballot(voters: u32, choices: u32) -> Weight;
+    fn delegate() -> Weight;
+    fn revoke_delegation() -> Weight;
+    fn proxy_vote(votes: u32, target_ids: u32) -> Weight;
 }
 
 decl_storage! {
@@ -280,6 +337,7 @@ decl_storage! {
         /// RCV is not accounted for,
         /// as there are too many wants to interpret the graph,
         /// and because it would not be efficient.
+        /// Instead, RCV is accounted for once the ballot is finalized, see `FinalResults`.
         ///
         /// (CAId) => [current vote weights]
         pub Results get(fn results): map hasher(blake2_128_concat) CAId => Vec<Balance>;
@@ -293,6 +351,33 @@ decl_storage! {
         pub Votes get(fn votes):
             double_map hasher(blake2_128_concat) CAId, hasher(identity) IdentityId =>
                 Vec<BallotVote>;
+
+        /// Stores the outcome of each motion in a finalized ballot.
+        ///
+        /// (CAId) => [motion outcome]
+        pub FinalResults get(fn final_results): map hasher(blake2_128_concat) CAId => Option<Vec<MotionOutcome>>;
+
+        /// Stores the proxy to which a DID delegated their voting power on all ballots of a ticker.
+        ///
+        /// (Ticker) => (delegator DID) => proxy DID
+        pub TickerDelegations get(fn ticker_delegations):
+            double_map hasher(blake2_128_concat) Ticker, hasher(identity) IdentityId =>
+                Option<IdentityId>;
+
+        /// Stores the proxy to which a DID delegated their voting power on a specific ballot.
+        /// These take precedence over `TickerDelegations`.
+        ///
+        /// (CAId) => (delegator DID) => proxy DID
+        pub BallotDelegations get(fn ballot_delegations):
+            double_map hasher(blake2_128_concat) CAId, hasher(identity) IdentityId =>
+                Option<IdentityId>;
+
+        /// Stores the proxy which cast a DID's current votes in a given ballot, if any.
+        ///
+        /// (CAId) => (delegator DID) => proxy DID
+        pub ProxyVotes get(fn proxy_votes):
+            double_map hasher(blake2_128_concat) CAId, hasher(identity) IdentityId =>
+                Option<IdentityId>;
     }
 }
 
@@ -377,87 +462,10 @@ decl_module! {
         pub fn vote(origin, ca_id: CAId, votes: Vec<BallotVote>) {
             let did = <Identity<T>>::ensure_perms(origin)?;
 
-            // Ensure ballot has started but not ended, i.e. `start <= now <= end`.
-            let range = Self::ensure_ballot_exists(ca_id)?;
-            let now = <Checkpoint<T>>::now_unix();
-            ensure!(range.start <= now, Error::<T>::VotingNotStarted);
-            ensure!(now <= range.end, Error::<T>::VotingAlreadyEnded);
-
-            // Ensure that `did` is targeted by this ballot.
-            let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
-            <CA<T>>::ensure_ca_targets(&ca, &did)?;
-
-            // Ensure we have balances provided for each choice.
-            let choices_count = MotionNumChoices::get(ca_id);
-            let total_choices = choices_count.iter().copied().map(|c| c as usize).sum::<usize>();
-            ensure!(votes.len() == total_choices, Error::<T>::WrongVoteCount);
-
-            // Divide `votes` into motions.
-            let motions = choices_count
-                .iter()
-                .map(|c| *c as usize)
-                .scan(0, |start, count| {
-                    let end = *start + count;
-                    Some(&votes[mem::replace(start, end)..end])
-                });
-
-            if RCV::get(ca_id) {
-                // RCV is enabled.
-                // Ensure that all fallback choices point to some choice in the same motion.
-                // For in-depth discussion on `fallback`, consult `BallotVote`'s definition.
-                motions
-                    .clone()
-                    .try_for_each(|votes| -> DispatchResult {
-                        let count = votes.len();
-                        votes
-                            .iter()
-                            .enumerate()
-                            // Only check when a fallback is actually provided.
-                            .filter_map(|(idx, vote)| Some((idx, vote.fallback? as usize)))
-                            .try_for_each(|(idx, fallback)| {
-                                // Exclude self-cycles.
-                                ensure!(idx != fallback, Error::<T>::RCVSelfCycle);
-                                // Ensure the index does not point outside, i.e. beyond, the motion.
-                                ensure!(fallback < count, Error::<T>::NoSuchRCVFallback);
-                                Ok(())
-                            })
-                    })?;
-            } else {
-                // It's not. Make sure its also not used.
-                votes
-                    .iter()
-                    .all(|vote| vote.fallback.is_none())
-                    .then_some(())
-                    .ok_or(Error::<T>::RCVNotAllowed)?;
-            }
-
-            // Extract `did`'s balance at the record date.
-            // Record date has passed by definition.
-            let cp_id = <CA<T>>::record_date_cp(&ca, ca_id);
-            let available_power = <CA<T>>::balance_at_cp(did, ca_id, cp_id);
+            Self::base_vote(did, ca_id, &votes)?;
 
-            // Ensure the total balance used in each motion doesn't exceed caller's voting power.
-            motions
-                .map(|vs| vs.iter().try_fold(Balance::zero(), |acc, vote| acc.checked_add(vote.power)))
-                .all(|power| power.filter(|&p| p <= available_power).is_some())
-                .then_some(())
-                .ok_or(Error::<T>::InsufficientVotes)?;
-
-            // Update vote and total results.
-            Votes::mutate(ca_id, did, |vslot| {
-                Results::mutate_exists(ca_id, |rslot| match rslot {
-                    Some(rslot) => {
-                        for (result, old) in rslot.iter_mut().zip(vslot.iter()) {
-                            *result -= old.power;
-                        }
-                        for (result, new) in rslot.iter_mut().zip(votes.iter()) {
-                            *result += new.power;
-                        }
-                    }
-                    None => *rslot = Some(votes.iter().map(|v| v.power).collect()),
-                });
-                *vslot = votes.clone();
-            });
+            // A vote of one's own overrides that of any proxy.
+            ProxyVotes::remove(ca_id, did);
 
             // Emit event.
             Self::deposit_event(Event::VoteCast(did, ca_id, votes));
@@ -558,6 +566,143 @@ decl_module! {
             let range = Self::ensure_ballot_exists(ca_id)?;
             Self::remove_ballot_base(agent, ca_id, range)?;
         }
+
+        /// Finalize the ballot of the CA identified by `ca_id`, once voting has ended,
+        /// storing the outcome of each motion in `FinalResults`.
+        ///
+        /// When RCV is enabled, each motion is tallied by instant-runoff elimination.
+        /// In each round, the choices with the fewest votes are eliminated,
+        /// and votes on them are transferred to their fallbacks, if any,
+        /// until some choice has a majority of the continuing votes, or the remaining choices tie.
+        /// Otherwise, there's a single round, and the choice with the most votes wins.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer with an identity.
+        /// - `ca_id` identifies the attached ballot's CA.
+        /// - `voters` is an upper bound on the number of DIDs that voted in the ballot.
+        /// - `choices` is an upper bound on the total number of choices in the ballot.
+        ///
+        /// # Errors
+        /// - `NoSuchBallot` if `ca_id` does not identify a ballot.
+        /// - `VotingNotEnded` if `now <= end`, where `now` is the current time.
+        /// - `AlreadyFinalized` if the ballot was finalized before.
+        /// - `ChoiceCountExceeded` if the ballot has more than `choices` choices.
+        /// - `VoterCountExceeded` if more than `voters` DIDs voted in the ballot.
+        #[weight = <T as Config>::BallotWeightInfo::finalize_ballot(*voters, *choices)]
+        pub fn finalize_ballot(origin, ca_id: CAId, voters: u32, choices: u32) {
+            let did = <Identity<T>>::ensure_perms(origin)?;
+
+            // Ensure the ballot ended, and hasn't been finalized.
+            let range = Self::ensure_ballot_exists(ca_id)?;
+            ensure!(range.end < <Checkpoint<T>>::now_unix(), Error::<T>::VotingNotEnded);
+            ensure!(!FinalResults::contains_key(ca_id), Error::<T>::AlreadyFinalized);
+
+            // Ensure the tally is within the bounds paid for.
+            let choices_count = MotionNumChoices::get(ca_id);
+            let total_choices = choices_count.iter().map(|&c| c as u32).sum::<u32>();
+            ensure!(total_choices <= choices, Error::<T>::ChoiceCountExceeded);
+            let ballots = Votes::iter_prefix_values(ca_id)
+                .take(voters as usize + 1)
+                .collect::<Vec<_>>();
+            ensure!(ballots.len() <= voters as usize, Error::<T>::VoterCountExceeded);
+
+            // Tally each motion, and commit the outcomes to storage + emit event.
+            let outcomes = Self::tally(&choices_count, &ballots, RCV::get(ca_id));
+            let winners = outcomes.iter().map(|o| o.winner).collect();
+            FinalResults::insert(ca_id, outcomes);
+            Self::deposit_event(Event::Finalized(did, ca_id, winners));
+        }
+
+        /// Delegate the voting power of `origin`'s DID within `scope` to `proxy`,
+        /// replacing any existing delegation within the same `scope`.
+        ///
+        /// The voting power used by the proxy is `origin`'s DID's balance at the record date of each ballot.
+        /// Votes cast by `origin`'s DID itself override those of the proxy.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer with an identity, delegating their voting power.
+        /// - `scope` specifies whether to delegate on a single ballot or on all ballots of a ticker.
+        /// - `proxy` is the DID to delegate to.
+        ///
+        /// # Errors
+        /// - `SelfDelegation` if `proxy` is `origin`'s DID.
+        /// - `NoSuchProxy` if `proxy` is not an identity.
+        /// - `NoSuchBallot` if `scope` is a ballot that does not exist.
+        #[weight = <T as Config>::BallotWeightInfo::delegate()]
+        pub fn delegate(origin, scope: DelegationScope, proxy: IdentityId) {
+            let did = <Identity<T>>::ensure_perms(origin)?;
+            ensure!(did != proxy, Error::<T>::SelfDelegation);
+            ensure!(<Identity<T>>::is_identity_exists(&proxy), Error::<T>::NoSuchProxy);
+
+            // Commit to storage + emit event.
+            match scope {
+                DelegationScope::Ticker(ticker) => TickerDelegations::insert(ticker, did, proxy),
+                DelegationScope::Ballot(ca_id) => {
+                    Self::ensure_ballot_exists(ca_id)?;
+                    BallotDelegations::insert(ca_id, did, proxy);
+                }
+            }
+            Self::deposit_event(Event::Delegated(did, scope, proxy));
+        }
+
+        /// Revoke the delegation of the voting power of `origin`'s DID within `scope`.
+        ///
+        /// Votes already cast by the proxy are unaffected.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer with an identity, which delegated their voting power.
+        /// - `scope` specifies the delegation to revoke.
+        ///
+        /// # Errors
+        /// - `NoSuchDelegation` if `origin`'s DID has no delegation within `scope`.
+        #[weight = <T as Config>::BallotWeightInfo::revoke_delegation()]
+        pub fn revoke_delegation(origin, scope: DelegationScope) {
+            let did = <Identity<T>>::ensure_perms(origin)?;
+            let proxy = match scope {
+                DelegationScope::Ticker(ticker) => TickerDelegations::take(ticker, did),
+                DelegationScope::Ballot(ca_id) => BallotDelegations::take(ca_id, did),
+            };
+            let proxy = proxy.ok_or(Error::<T>::NoSuchDelegation)?;
+            Self::deposit_event(Event::DelegationRevoked(did, scope, proxy));
+        }
+
+        /// Cast `votes` on behalf of `delegator` in the ballot attached to the CA identified by `ca_id`.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer whose DID is the proxy of `delegator` for the ballot.
+        /// - `ca_id` identifies the attached ballot's CA.
+        /// - `delegator` is the DID which delegated their voting power to `origin`'s DID.
+        /// - `votes` specifies the balances to assign to each choice in the ballot.
+        ///    The full voting power of `delegator` may be used for each motion in the ballot.
+        ///
+        /// # Errors
+        /// - `NotProxy` if `origin`'s DID is not the proxy of `delegator` for the ballot.
+        /// - `DelegatorAlreadyVoted` if `delegator` voted in the ballot themselves.
+        /// - `NoSuchBallot` if `ca_id` does not identify a ballot.
+        /// - `VotingNotStarted` if the voting period hasn't commenced yet.
+        /// - `VotingAlreadyEnded` if the voting period has ended.
+        /// - `WrongVoteCount` if the number of choices in the ballot does not match `votes.len()`.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `NotTargetedByCA` if the CA does not target `delegator`.
+        /// - `InsufficientVotes` if the voting power used for any motion in `votes`
+        ///    exceeds `delegator`'s voting power.
+        #[weight = <T as Config>::BallotWeightInfo::proxy_vote(votes.len() as u32, T::MaxTargetIds::get())]
+        pub fn proxy_vote(origin, ca_id: CAId, delegator: IdentityId, votes: Vec<BallotVote>) {
+            let proxy = <Identity<T>>::ensure_perms(origin)?;
+            ensure!(Self::proxy_of(ca_id, delegator) == Some(proxy), Error::<T>::NotProxy);
+
+            // Ensure the proxy doesn't override the delegator's own votes.
+            ensure!(
+                !Votes::contains_key(ca_id, delegator) || ProxyVotes::contains_key(ca_id, delegator),
+                Error::<T>::DelegatorAlreadyVoted
+            );
+
+            Self::base_vote(delegator, ca_id, &votes)?;
+            ProxyVotes::insert(ca_id, delegator, proxy);
+
+            // Emit event.
+            Self::deposit_event(Event::ProxyVoteCast(proxy, delegator, ca_id, votes));
+        }
     }
 }
 
@@ -592,6 +737,26 @@ decl_event! {
         ///
         /// (Agent DID, CA's ID)
         Removed(EventDid, CAId),
+
+        /// A corporate ballot was finalized.
+        ///
+        /// (Caller DID, CA's ID, The winning choice of each motion, if any)
+        Finalized(IdentityId, CAId, Vec<Option<u16>>),
+
+        /// A DID delegated their voting power to a proxy.
+        ///
+        /// (delegator DID, Scope of delegation, proxy DID)
+        Delegated(IdentityId, DelegationScope, IdentityId),
+
+        /// A DID revoked the delegation of their voting power to a proxy.
+        ///
+        /// (delegator DID, Scope of delegation, proxy DID)
+        DelegationRevoked(IdentityId, DelegationScope, IdentityId),
+
+        /// A proxy cast votes in a corporate ballot, using the voting power delegated to them.
+        ///
+        /// (proxy DID, delegator DID, CAId, Votes)
+        ProxyVoteCast(IdentityId, IdentityId, CAId, Vec<BallotVote>),
     }
 }
 
@@ -624,7 +789,25 @@ decl_error! {
         /// The RCV fallback points to the origin choice.
         RCVSelfCycle,
         /// RCV is not allowed for this ballot.
-        RCVNotAllowed
+        RCVNotAllowed,
+        /// Voting hasn't ended yet.
+        VotingNotEnded,
+        /// The ballot was already finalized.
+        AlreadyFinalized,
+        /// More DIDs voted in the ballot than were provided for.
+        VoterCountExceeded,
+        /// The ballot has more choices than were provided for.
+        ChoiceCountExceeded,
+        /// Voting power cannot be delegated to oneself.
+        SelfDelegation,
+        /// The proxy to delegate to is not an identity.
+        NoSuchProxy,
+        /// No delegation exists within the given scope.
+        NoSuchDelegation,
+        /// The caller is not the proxy of the delegator for this ballot.
+        NotProxy,
+        /// The delegator voted themselves, which a proxy cannot override.
+        DelegatorAlreadyVoted
     }
 }
 
@@ -642,12 +825,222 @@ impl<T: Config> Module<T> {
         Metas::remove(ca_id);
         MotionNumChoices::remove(ca_id);
         RCV::remove(ca_id);
+        #[allow(deprecated)]
+        BallotDelegations::remove_prefix(ca_id, None);
 
         // Emit event.
         Self::deposit_event(Event::Removed(agent, ca_id));
         Ok(())
     }
 
+    /// Returns the proxy of `delegator` for the ballot of `ca_id`, if any.
+    pub fn proxy_of(ca_id: CAId, delegator: IdentityId) -> Option<IdentityId> {
+        BallotDelegations::get(ca_id, delegator)
+            .or_else(|| TickerDelegations::get(ca_id.ticker, delegator))
+    }
+
+    /// Cast `votes` for `did` in the ballot attached to the CA identified by `ca_id`,
+    /// using the voting power of `did` at the record date.
+    fn base_vote(did: IdentityId, ca_id: CAId, votes: &[BallotVote]) -> DispatchResult {
+        // Ensure ballot has started but not ended, i.e. `start <= now <= end`.
+        let range = Self::ensure_ballot_exists(ca_id)?;
+        let now = <Checkpoint<T>>::now_unix();
+        ensure!(range.start <= now, Error::<T>::VotingNotStarted);
+        ensure!(now <= range.end, Error::<T>::VotingAlreadyEnded);
+
+        // Ensure that `did` is targeted by this ballot.
+        let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+        <CA<T>>::ensure_ca_targets(&ca, &did)?;
+
+        // Ensure we have balances provided for each choice.
+        let choices_count = MotionNumChoices::get(ca_id);
+        let total_choices = choices_count
+            .iter()
+            .copied()
+            .map(|c| c as usize)
+            .sum::<usize>();
+        ensure!(votes.len() == total_choices, Error::<T>::WrongVoteCount);
+
+        // Divide `votes` into motions.
+        let motions = choices_count
+            .iter()
+            .map(|c| *c as usize)
+            .scan(0, |start, count| {
+                let end = *start + count;
+                Some(&votes[mem::replace(start, end)..end])
+            });
+
+        if RCV::get(ca_id) {
+            // RCV is enabled.
+            // Ensure that all fallback choices point to some choice in the same motion.
+            // For in-depth discussion on `fallback`, consult `BallotVote`'s definition.
+            motions.clone().try_for_each(|votes| -> DispatchResult {
+                let count = votes.len();
+                votes
+                    .iter()
+                    .enumerate()
+                    // Only check when a fallback is actually provided.
+                    .filter_map(|(idx, vote)| Some((idx, vote.fallback? as usize)))
+                    .try_for_each(|(idx, fallback)| {
+                        // Exclude self-cycles.
+                        ensure!(idx != fallback, Error::<T>::RCVSelfCycle);
+                        // Ensure the index does not point outside, i.e. beyond, the motion.
+                        ensure!(fallback < count, Error::<T>::NoSuchRCVFallback);
+                        Ok(())
+                    })
+            })?;
+        } else {
+            // It's not. Make sure its also not used.
+            votes
+                .iter()
+                .all(|vote| vote.fallback.is_none())
+                .then_some(())
+                .ok_or(Error::<T>::RCVNotAllowed)?;
+        }
+
+        // Extract `did`'s balance at the record date.
+        // Record date has passed by definition.
+        let cp_id = <CA<T>>::record_date_cp(&ca, ca_id);
+        let available_power = <CA<T>>::balance_at_cp(did, ca_id, cp_id);
+
+        // Ensure the total balance used in each motion doesn't exceed caller's voting power.
+        motions
+            .map(|vs| {
+                vs.iter()
+                    .try_fold(Balance::zero(), |acc, vote| acc.checked_add(vote.power))
+            })
+            .all(|power| power.filter(|&p| p <= available_power).is_some())
+            .then_some(())
+            .ok_or(Error::<T>::InsufficientVotes)?;
+
+        // Update vote and total results.
+        Votes::mutate(ca_id, did, |vslot| {
+            Results::mutate_exists(ca_id, |rslot| match rslot {
+                Some(rslot) => {
+                    for (result, old) in rslot.iter_mut().zip(vslot.iter()) {
+                        *result -= old.power;
+                    }
+                    for (result, new) in rslot.iter_mut().zip(votes.iter()) {
+                        *result += new.power;
+                    }
+                }
+                None => *rslot = Some(votes.iter().map(|v| v.power).collect()),
+            });
+            *vslot = votes.to_vec();
+        });
+        Ok(())
+    }
+
+    /// Tally the outcome of each motion, given the number of choices in each,
+    /// and the votes in `ballots`, each of which covers all motions.
+    fn tally(choices_count: &[u16], ballots: &[Vec<BallotVote>], rcv: bool) -> Vec<MotionOutcome> {
+        choices_count
+            .iter()
+            .map(|c| *c as usize)
+            .scan(0, |start, count| {
+                let end = *start + count;
+                Some(mem::replace(start, end)..end)
+            })
+            .map(|motion| {
+                let votes = ballots.iter().filter_map(|b| b.get(motion.clone()));
+                Self::tally_motion(motion.len(), votes, rcv)
+            })
+            .collect()
+    }
+
+    /// Tally the `votes` of each voter on a motion with `count` choices.
+    fn tally_motion<'a>(
+        count: usize,
+        votes: impl Iterator<Item = &'a [BallotVote]> + Clone,
+        rcv: bool,
+    ) -> MotionOutcome {
+        let mut eliminated = vec![false; count];
+        let mut rounds = Vec::new();
+        loop {
+            // Count each vote towards its first continuing choice.
+            let mut tallies = vec![Balance::zero(); count];
+            for votes in votes.clone() {
+                for (idx, vote) in votes.iter().enumerate() {
+                    if let Some(choice) = Self::continuing_choice(votes, idx, &eliminated) {
+                        tallies[choice] = tallies[choice].saturating_add(vote.power);
+                    }
+                }
+            }
+
+            let (elim, tals) = (&eliminated, &tallies);
+            let continuing = || {
+                (0..count)
+                    .filter(move |&c| !elim[c])
+                    .map(move |c| (c, tals[c]))
+            };
+            let (max, min) = match (
+                continuing().map(|(_, t)| t).max(),
+                continuing().map(|(_, t)| t).min(),
+            ) {
+                (Some(max), Some(min)) => (max, min),
+                // No choices in the motion.
+                _ => return MotionOutcome::default(),
+            };
+            let total = continuing().fold(Balance::zero(), |acc, (_, t)| acc.saturating_add(t));
+            let mut leaders = continuing()
+                .filter(|&(_, t)| t == max)
+                .map(|(c, _)| c as u16);
+            let leader = leaders
+                .next()
+                .filter(|_| leaders.next().is_none() && !max.is_zero());
+
+            let winner = if !rcv {
+                // Plurality, with a single round.
+                Some(leader)
+            } else if max.saturating_mul(2) > total {
+                // Majority of the continuing votes.
+                Some(leader)
+            } else if max == min {
+                // All remaining choices tie.
+                Some(None)
+            } else {
+                None
+            };
+            if let Some(winner) = winner {
+                rounds.push(TallyRound {
+                    tallies,
+                    eliminated: Vec::new(),
+                });
+                return MotionOutcome { winner, rounds };
+            }
+
+            // Eliminate the choices with the fewest votes, and run another round.
+            let losers = continuing()
+                .filter(|&(_, t)| t == min)
+                .map(|(c, _)| c as u16)
+                .collect::<Vec<_>>();
+            for &c in &losers {
+                eliminated[c as usize] = true;
+            }
+            rounds.push(TallyRound {
+                tallies,
+                eliminated: losers,
+            });
+        }
+    }
+
+    /// Returns the first choice, starting from `idx` and following fallbacks in `votes`,
+    /// that hasn't been eliminated, if any.
+    fn continuing_choice(
+        votes: &[BallotVote],
+        mut idx: usize,
+        eliminated: &[bool],
+    ) -> Option<usize> {
+        // Fallbacks may form cycles, so follow at most one per choice.
+        for _ in 0..=votes.len() {
+            if !*eliminated.get(idx)? {
+                return Some(idx);
+            }
+            idx = votes.get(idx)?.fallback? as usize;
+        }
+        None
+    }
+
     /// Ensure that no string embedded within `meta` is too long.
     fn ensure_meta_lengths_limited(meta: &BallotMeta) -> DispatchResult {
         ensure_string_limited::<T>(&meta.title)?;
@@ -37,7 +37,7 @@ const RD_SPEC2: Option<RecordDateSpec> = Some(RecordDateSpec::Scheduled(3000));
 // NOTE(Centril): A non-owner CAA is the less complex code path.
 // Therefore, in general, we'll be using the owner as the CAA.
 
-fn setup<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> (User<T>, Ticker) {
+pub(crate) fn setup<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> (User<T>, Ticker) {
     <pallet_timestamp::Now<T>>::set(1000u32.into());
 
     let owner = user("owner", SEED);
@@ -328,4 +328,24 @@ benchmarks! {
         check_ca_created::<T>(ca_id).unwrap();
         check_rd::<T>(ca_id).unwrap();
     }
+
+    set_issuer_tax_jurisdiction {
+        let (owner, ticker) = setup::<T>();
+        let jurisdiction = Some(TaxJurisdiction {
+            country: CountryCode::US,
+            claim_issuer: owner.did(),
+        });
+    }: _(owner.origin(), ticker, jurisdiction)
+    verify {
+        assert_eq!(IssuerTaxJurisdiction::get(ticker), jurisdiction, "Jurisdiction not set");
+    }
+
+    set_treaty_withholding_tax {}: _(RawOrigin::Root, CountryCode::US, CountryCode::DE, Some(TAX))
+    verify {
+        assert_eq!(
+            TreatyWithholdingTax::get(CountryCode::US, CountryCode::DE),
+            Some(TAX),
+            "Treaty WHT not set"
+        );
+    }
 }
@@ -0,0 +1,108 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use core::iter;
+use frame_benchmarking::benchmarks;
+
+use polymesh_common_utilities::benchs::{user, AccountIdOf, User};
+use polymesh_common_utilities::TestUtilsFn;
+use polymesh_primitives::calendar::CalendarUnit;
+use polymesh_primitives::{PortfolioKind, PortfolioName};
+
+use super::*;
+use crate::benchmarking::{currency, setup, SEED};
+
+const DAY: Moment = 86_400_000;
+const MAX_HOLDERS: u32 = 100;
+const MAX_PORTFOLIOS: u32 = 100;
+
+fn terms<T: Config>(owner: &User<T>) -> CouponTerms {
+    let currency = currency::<T>(owner);
+    // Fund the coupons, as the asset has a large supply.
+    <Asset<T>>::issue(
+        owner.origin().into(),
+        currency,
+        1_000_000_000_000,
+        PortfolioKind::Default,
+    )
+    .unwrap();
+    CouponTerms {
+        currency,
+        face_value: PER_SHARE_PRECISION,
+        rate: Permill::from_percent(10),
+        day_count: DayCount::Actual360,
+        start: 1000,
+        period: CalendarPeriod {
+            unit: CalendarUnit::Day,
+            amount: 1,
+        },
+        maturity: 1000 + 10 * DAY,
+        claim_period: None,
+    }
+}
+
+fn schedule<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> (User<T>, Ticker) {
+    let (owner, ticker) = setup::<T>();
+    let terms = terms::<T>(&owner);
+    <Module<T>>::create_schedule(owner.origin().into(), ticker, None, terms).unwrap();
+    (owner, ticker)
+}
+
+benchmarks! {
+    where_clause { where T: TestUtilsFn<AccountIdOf<T>> }
+
+    create_schedule {
+        let (owner, ticker) = setup::<T>();
+        let terms = terms::<T>(&owner);
+    }: _(owner.origin(), ticker, None, terms)
+    verify {
+        assert!(Schedules::contains_key(ticker), "schedule not created");
+    }
+
+    remove_schedule {
+        let (owner, ticker) = schedule::<T>();
+    }: _(owner.origin(), ticker)
+    verify {
+        assert!(!Schedules::contains_key(ticker), "schedule not removed");
+    }
+
+    pay_coupon {
+        let (owner, ticker) = schedule::<T>();
+        // Make the first coupon outstanding, as if its funding was insufficient.
+        DueCoupons::kill();
+        NextCouponDue::kill();
+        <pallet_timestamp::Now<T>>::set(86_401_000u32.into());
+    }: _(owner.origin(), ticker)
+    verify {
+        assert_eq!(Schedules::get(ticker).unwrap().coupons_paid, 1, "coupon not paid");
+    }
+
+    redeem_matured {
+        let h in 1..MAX_HOLDERS;
+        let p in 0..MAX_PORTFOLIOS;
+
+        let (owner, ticker) = setup::<T>();
+        for i in 0..p {
+            <Portfolio<T>>::create_portfolio(owner.origin().into(), PortfolioName(i.encode())).unwrap();
+        }
+        MaturedAssets::insert(ticker, true);
+        let holders = iter::once(owner.did())
+            .chain((1..h).map(|i| user::<T>("holder", SEED + i).did()))
+            .collect::<Vec<_>>();
+    }: _(owner.origin(), ticker, holders, p)
+    verify {
+        assert!(<Asset<T>>::balance_of(ticker, owner.did()).is_zero(), "not redeemed");
+    }
+}
@@ -0,0 +1,723 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! # Corporate Coupon Module
+//!
+//! The corporate coupon module provides functionality for paying the coupons of bonds
+//! and other fixed-income assets on a schedule, without creating every distribution by hand.
+//!
+//! A permissioned external agent attaches a coupon schedule to an asset through `create_schedule`.
+//! The schedule specifies the currency to pay in, the face value of the asset,
+//! an annual coupon rate, a day count convention, a payment frequency, and a maturity date.
+//! Coupons are funded from one of the agent's portfolios.
+//!
+//! Whenever a coupon falls due, a checkpoint of the asset is created,
+//! and a `PredictableBenefit` corporate action (CA) with that checkpoint as its record date,
+//! to which a capital distribution of the coupon is attached.
+//! Holders then claim their coupons, or have them pushed, as with any other distribution.
+//! The last coupon is due at maturity, and its distribution also repays the principal,
+//! after which the agent burns the matured asset of every holder, in batches, through `redeem_matured`.
+//!
+//! If the portfolio has insufficient funds for a coupon when it falls due,
+//! or the agent funding the schedule is no longer an agent of the asset,
+//! an event is emitted and the coupon stays outstanding.
+//! The checkpoint is still created when the coupon falls due, so that it is paid to the holders at that date.
+//! Once the portfolio is funded, the agent may pay the coupon through `pay_coupon`.
+//!
+//! A schedule can be removed at any time through `remove_schedule`,
+//! leaving the distributions of coupons already due untouched.
+//! At most `MAX_COUPON_SCHEDULES` schedules can exist at once.
+//!
+//! ## Overview
+//!
+//! The module provides functions for:
+//!
+//! - Attaching and removing coupon schedules.
+//! - Paying outstanding coupons.
+//! - Redeeming the asset at maturity.
+//!
+//! ### Terminology
+//!
+//! - **Face value:** The principal repaid at maturity per share of the asset.
+//! - **Day count convention:** How the fraction of a year between two coupons is computed.
+//! - **Maturity:** The date of the last coupon, at which the principal is redeemed.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create_schedule` attaches a coupon schedule to an asset.
+//! - `remove_schedule` removes the coupon schedule of an asset.
+//! - `pay_coupon` pays an outstanding coupon whose funding was insufficient when it fell due.
+//! - `redeem_matured` burns the balances of a batch of holders of a matured asset.
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+use crate as ca;
+use ca::{
+    distribution::{self, PER_SHARE_PRECISION},
+    reorganization, CADetails, CAId, CAKind, Config, RecordDateSpec,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    traits::{ConstU32, Get},
+    weights::Weight,
+    BoundedVec,
+};
+use pallet_asset::{self as asset, checkpoint};
+use pallet_identity::PermissionedCallOriginData;
+use polymesh_common_utilities::with_transaction;
+use polymesh_primitives::calendar::{CalendarPeriod, CheckpointSchedule, DayCount};
+use polymesh_primitives::{
+    asset::CheckpointId, storage_migration_ver, Balance, EventDid, IdentityId, Moment, PortfolioId,
+    PortfolioNumber, Ticker, WeightMeter,
+};
+use scale_info::TypeInfo;
+use sp_arithmetic::Permill;
+use sp_runtime::traits::Zero;
+use sp_std::prelude::*;
+
+type Asset<T> = asset::Module<T>;
+type Checkpoint<T> = checkpoint::Module<T>;
+type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type CA<T> = ca::Module<T>;
+type Distribution<T> = distribution::Module<T>;
+type Portfolio<T> = pallet_portfolio::Module<T>;
+type Reorganization<T> = reorganization::Module<T>;
+
+/// The maximum number of coupons paid automatically in a block.
+/// Coupons beyond this are paid in the following blocks.
+pub const MAX_COUPONS_PER_BLOCK: u32 = 10;
+
+/// The maximum number of coupon schedules, across all assets.
+pub const MAX_COUPON_SCHEDULES: u32 = 1000;
+
+/// The terms of a coupon schedule.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct CouponTerms {
+    /// The currency that coupons and the principal are paid in.
+    pub currency: Ticker,
+    /// The principal repaid at maturity per share, in per-million,
+    /// i.e. `1 / 10^6`th of one `currency` token.
+    pub face_value: Balance,
+    /// The annual coupon rate, relative to `face_value`.
+    pub rate: Permill,
+    /// The day count convention used to accrue interest between coupons.
+    pub day_count: DayCount,
+    /// The date from which interest first accrues.
+    pub start: Moment,
+    /// The payment frequency, with coupons due every `period` after `start`.
+    pub period: CalendarPeriod,
+    /// The date of the last coupon, at which the principal is also redeemed.
+    pub maturity: Moment,
+    /// If provided, how long after a coupon is due holders have to claim it.
+    pub claim_period: Option<Moment>,
+}
+
+/// A coupon schedule attached to an asset.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct CouponSchedule {
+    /// The terms of the schedule.
+    pub terms: CouponTerms,
+    /// The agent paying the coupons.
+    pub agent: IdentityId,
+    /// The portfolio number of `agent` that coupons are funded from.
+    pub portfolio: Option<PortfolioNumber>,
+    /// The date from which the next coupon accrues.
+    pub accrued_from: Moment,
+    /// The date the next coupon is due.
+    pub next_coupon: Moment,
+    /// The number of coupons paid so far.
+    pub coupons_paid: u32,
+    /// The checkpoint recording holders' balances when the next coupon fell due, if it has.
+    pub record_checkpoint: Option<CheckpointId>,
+}
+
+impl CouponSchedule {
+    /// Returns the portfolio that coupons are funded from.
+    pub fn funding_portfolio(&self) -> PortfolioId {
+        PortfolioId {
+            did: self.agent,
+            kind: self.portfolio.into(),
+        }
+    }
+
+    /// Returns whether the next coupon is due at maturity.
+    pub fn at_maturity(&self) -> bool {
+        self.next_coupon >= self.terms.maturity
+    }
+
+    /// Returns the date of the coupon after `at`, which is at most the maturity date.
+    fn coupon_after(terms: &CouponTerms, at: Moment) -> Moment {
+        CheckpointSchedule {
+            start: terms.start,
+            period: terms.period,
+        }
+        .next_checkpoint(at)
+        .map_or(terms.maturity, |next| next.min(terms.maturity))
+    }
+}
+
+/// Weight abstraction for the corporate coupon module.
+pub trait WeightInfo {
+    fn create_schedule() -> Weight;
+    fn remove_schedule() -> Weight;
+    fn pay_coupon() -> Weight;
+    fn redeem_matured(holders: u32, portfolios: u32) -> Weight;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as CorporateCoupon {
+        /// The coupon schedules attached to assets.
+        ///
+        /// (ticker) => CouponSchedule
+        Schedules get(fn schedules): map hasher(blake2_128_concat) Ticker => Option<CouponSchedule>;
+
+        /// The coupons due to be paid automatically, sorted by their due date.
+        /// As every schedule has at most one queued coupon, the queue is bounded by `MAX_COUPON_SCHEDULES`.
+        ///
+        /// [(due date, ticker)]
+        DueCoupons get(fn due_coupons): BoundedVec<(Moment, Ticker), ConstU32<MAX_COUPON_SCHEDULES>>;
+
+        /// The due date of the first coupon in `DueCoupons`, if any.
+        NextCouponDue get(fn next_coupon_due): Option<Moment>;
+
+        /// Assets whose last coupon, repaying the principal, was paid.
+        ///
+        /// (ticker) => matured?
+        MaturedAssets get(fn matured): map hasher(blake2_128_concat) Ticker => bool;
+
+        /// Storage version.
+        StorageVersion get(fn storage_version) build(|_| Version::new(0)): Version;
+    }
+}
+
+storage_migration_ver!(0);
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::RuntimeOrigin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        fn on_initialize(_n: T::BlockNumber) -> Weight {
+            Self::pay_due_coupons()
+        }
+
+        /// Attach a coupon schedule with `terms` to `ticker`,
+        /// funded from `portfolio` belonging to `origin`'s DID.
+        ///
+        /// The first coupon is due one `period` after `start`, or at maturity, whichever is earlier.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ticker`.
+        /// - `ticker` of the asset to pay coupons for.
+        /// - `portfolio` specifies the portfolio number of the agent to fund coupons from.
+        /// - `terms` of the coupon schedule.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `AlreadyExists` if `ticker` already has a coupon schedule.
+        /// - `FaceValueIsZero` if `terms.face_value` is zero.
+        /// - `PeriodNotRecurring` if `terms.period` is not recurring.
+        /// - `MaturityBeforeStart` if `terms.maturity <= terms.start`.
+        /// - `FirstCouponInPast` if the first coupon would be due before now.
+        /// - `UnauthorizedCustodian` if the caller is not the custodian of `portfolio`.
+        /// - `TooManySchedules` if there are already `MAX_COUPON_SCHEDULES` schedules.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::CouponWeightInfo::create_schedule()]
+        pub fn create_schedule(
+            origin,
+            ticker: Ticker,
+            portfolio: Option<PortfolioNumber>,
+            terms: CouponTerms,
+        ) {
+            Self::base_create_schedule(origin, ticker, portfolio, terms)?;
+        }
+
+        /// Remove the coupon schedule of `ticker`.
+        /// Distributions of coupons that were already due are unaffected.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ticker`.
+        /// - `ticker` of the asset with a coupon schedule to remove.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchSchedule` if `ticker` has no coupon schedule.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::CouponWeightInfo::remove_schedule()]
+        pub fn remove_schedule(origin, ticker: Ticker) {
+            let agent = <ExternalAgents<T>>::ensure_perms(origin, ticker)?.for_event();
+            ensure!(Schedules::contains_key(ticker), Error::<T>::NoSuchSchedule);
+            Schedules::remove(ticker);
+            let mut due = DueCoupons::get();
+            due.retain(|(_, t)| *t != ticker);
+            Self::put_due_coupons(due);
+            Self::deposit_event(Event::ScheduleRemoved(agent, ticker));
+        }
+
+        /// Pay the outstanding coupon of `ticker`,
+        /// i.e., one that is due but could not be paid due to insufficient funding.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ticker`.
+        /// - `ticker` of the asset with an outstanding coupon.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchSchedule` if `ticker` has no coupon schedule.
+        /// - `CouponNotOutstanding` if the next coupon is not due, or will be paid automatically.
+        /// - `ScheduleAgentRemoved` if the schedule's agent is no longer an agent of `ticker`.
+        /// - `InsufficientPortfolioBalance` if the funding portfolio cannot pay the coupon.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::CouponWeightInfo::pay_coupon()]
+        pub fn pay_coupon(origin, ticker: Ticker) {
+            <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+            let schedule = Self::schedules(ticker).ok_or(Error::<T>::NoSuchSchedule)?;
+            ensure!(
+                schedule.next_coupon <= <Checkpoint<T>>::now_unix()
+                    && !Self::due_coupons().iter().any(|(_, t)| *t == ticker),
+                Error::<T>::CouponNotOutstanding
+            );
+            with_transaction(|| Self::unverified_pay_coupon(ticker, schedule))?;
+        }
+
+        /// Burn the balances of `holders` in `ticker`, whose last coupon, repaying the principal, was paid.
+        ///
+        /// Every portfolio of a holder is redeemed.
+        /// Holders are paid the principal through the distribution of the last coupon,
+        /// based on their balances when it fell due.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ticker`.
+        /// - `ticker` of the matured asset.
+        /// - `holders` to redeem.
+        /// - `portfolios` is the number of user portfolios, across all `holders`, to redeem.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NotMatured` if the last coupon of `ticker` has not been paid.
+        /// - `RescaleInProgress` if some holders of `ticker` are yet to be rescaled by a split.
+        /// - `TooManyPortfolios` if `holders` have more than `portfolios` user portfolios.
+        /// - `HolderHasLockedAssets` if some assets are locked in a portfolio of a holder.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::CouponWeightInfo::redeem_matured(holders.len() as u32, *portfolios)]
+        pub fn redeem_matured(origin, ticker: Ticker, holders: Vec<IdentityId>, portfolios: u32) {
+            let agent = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+            Self::base_redeem_matured(agent, ticker, holders, portfolios)?;
+        }
+    }
+}
+
+decl_event! {
+    pub enum Event {
+        /// A coupon schedule was attached to an asset.
+        ///
+        /// (Agent DID, Ticker, schedule)
+        ScheduleCreated(EventDid, Ticker, CouponSchedule),
+
+        /// A coupon schedule was removed.
+        ///
+        /// (Agent DID, Ticker)
+        ScheduleRemoved(EventDid, Ticker),
+
+        /// A coupon fell due, and a distribution for it was created.
+        ///
+        /// (Ticker, CA's ID, due date, amount per share, total amount)
+        CouponCreated(Ticker, CAId, Moment, Balance, Balance),
+
+        /// The last coupon, which also redeems the principal, was created, and the schedule ended.
+        ///
+        /// (Ticker, CA's ID)
+        Matured(Ticker, CAId),
+
+        /// A coupon fell due, but the funding portfolio had insufficient funds to pay it.
+        /// The coupon stays outstanding until paid through `pay_coupon`.
+        ///
+        /// (Ticker, due date, funding portfolio, total amount required)
+        CouponFundingInsufficient(Ticker, Moment, PortfolioId, Balance),
+
+        /// A coupon fell due, but could not be paid for another reason.
+        /// The coupon stays outstanding until paid through `pay_coupon`.
+        ///
+        /// (Ticker, due date, error)
+        CouponFailed(Ticker, Moment, DispatchError),
+
+        /// The balance of a holder in a matured asset was redeemed.
+        ///
+        /// (Agent DID, Ticker, holder DID, amount redeemed)
+        MaturedRedeemed(EventDid, Ticker, IdentityId, Balance),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// A coupon schedule already exists for this asset.
+        AlreadyExists,
+        /// A coupon schedule doesn't exist for this asset.
+        NoSuchSchedule,
+        /// The `face_value` of a coupon schedule cannot be zero.
+        FaceValueIsZero,
+        /// The payment frequency of a coupon schedule must be recurring.
+        PeriodNotRecurring,
+        /// The maturity of a coupon schedule must be after its start.
+        MaturityBeforeStart,
+        /// The first coupon of a schedule would be due in the past.
+        FirstCouponInPast,
+        /// The next coupon is not due, or is queued to be paid automatically.
+        CouponNotOutstanding,
+        /// The interest accrued between two coupons could not be computed.
+        InvalidCouponDates,
+        /// Computing the amount of a coupon overflowed.
+        CouponAmountOverflowed,
+        /// There are already `MAX_COUPON_SCHEDULES` coupon schedules.
+        TooManySchedules,
+        /// The agent funding the schedule is no longer an agent of the asset.
+        ScheduleAgentRemoved,
+        /// The last coupon of the asset, repaying the principal, has not been paid.
+        NotMatured,
+        /// The holders have more user portfolios than were given.
+        TooManyPortfolios,
+        /// Some assets are locked in a portfolio of the holder.
+        HolderHasLockedAssets,
+    }
+}
+
+impl<T: Config> Module<T> {
+    fn base_create_schedule(
+        origin: T::RuntimeOrigin,
+        ticker: Ticker,
+        portfolio: Option<PortfolioNumber>,
+        terms: CouponTerms,
+    ) -> DispatchResult {
+        let PermissionedCallOriginData {
+            primary_did: agent,
+            secondary_key,
+            ..
+        } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, ticker)?;
+        ensure!(!Schedules::contains_key(ticker), Error::<T>::AlreadyExists);
+
+        // Ensure the terms are valid.
+        ensure!(!terms.face_value.is_zero(), Error::<T>::FaceValueIsZero);
+        ensure!(
+            terms.period.to_recurring().is_some(),
+            Error::<T>::PeriodNotRecurring
+        );
+        ensure!(
+            terms.start < terms.maturity,
+            Error::<T>::MaturityBeforeStart
+        );
+        let next_coupon = CouponSchedule::coupon_after(&terms, terms.start);
+        ensure!(
+            <Checkpoint<T>>::now_unix() < next_coupon,
+            Error::<T>::FirstCouponInPast
+        );
+
+        // Ensure secondary key has perms for the portfolio + portfolio is valid.
+        let schedule = CouponSchedule {
+            terms,
+            agent,
+            portfolio,
+            accrued_from: terms.start,
+            next_coupon,
+            coupons_paid: 0,
+            record_checkpoint: None,
+        };
+        let from = schedule.funding_portfolio();
+        <Portfolio<T>>::ensure_portfolio_custody_and_permission(
+            from,
+            agent,
+            secondary_key.as_ref(),
+        )?;
+        <Portfolio<T>>::ensure_portfolio_validity(&from)?;
+
+        // Commit to storage + emit event.
+        Self::queue_coupon(next_coupon, ticker)?;
+        Schedules::insert(ticker, schedule);
+        Self::deposit_event(Event::ScheduleCreated(agent.for_event(), ticker, schedule));
+        Ok(())
+    }
+
+    /// Pay the coupons that are due, up to `MAX_COUPONS_PER_BLOCK`.
+    ///
+    /// The balances of holders are recorded for every coupon that falls due.
+    /// Coupons that cannot be paid are dropped from the queue and stay outstanding.
+    fn pay_due_coupons() -> Weight {
+        let db = <T as frame_system::Config>::DbWeight::get();
+        let now = <Checkpoint<T>>::now_unix();
+        let weight = db.reads(2);
+        if Self::next_coupon_due().map_or(true, |at| at > now) {
+            return weight;
+        }
+
+        let mut due = DueCoupons::get().into_inner();
+        let count = due
+            .iter()
+            .take(MAX_COUPONS_PER_BLOCK as usize)
+            .take_while(|(at, _)| *at <= now)
+            .count();
+        let paid = due.drain(..count).collect::<Vec<_>>();
+        // Shrinking the queue keeps it within its bound.
+        Self::put_due_coupons(BoundedVec::truncate_from(due));
+        let insufficient: DispatchError =
+            pallet_portfolio::Error::<T>::InsufficientPortfolioBalance.into();
+        for (at, ticker) in paid {
+            let schedule = match Self::schedules(ticker) {
+                Some(schedule) => schedule,
+                None => continue,
+            };
+            // Record balances at the due date, even if the coupon cannot be paid yet.
+            let schedule = match Self::record_balances(ticker, schedule) {
+                Ok(schedule) => schedule,
+                Err(e) => {
+                    Self::deposit_event(Event::CouponFailed(ticker, at, e));
+                    continue;
+                }
+            };
+            let from = schedule.funding_portfolio();
+            match with_transaction(|| Self::unverified_pay_coupon(ticker, schedule)) {
+                Ok(()) => {}
+                Err(e) if e == insufficient => {
+                    let amount = Self::coupon_amount(ticker, &schedule)
+                        .map(|(_, amount)| amount)
+                        .unwrap_or_default();
+                    Self::deposit_event(Event::CouponFundingInsufficient(ticker, at, from, amount));
+                }
+                Err(e) => Self::deposit_event(Event::CouponFailed(ticker, at, e)),
+            }
+        }
+
+        weight.saturating_add(db.reads_writes(1, 2)).saturating_add(
+            <T as Config>::CouponWeightInfo::pay_coupon().saturating_mul(count as u64),
+        )
+    }
+
+    /// Create the checkpoint recording holders' balances for the next coupon of `ticker`,
+    /// unless it already exists or the coupon pays nothing, returning the updated `schedule`.
+    fn record_balances(
+        ticker: Ticker,
+        mut schedule: CouponSchedule,
+    ) -> Result<CouponSchedule, DispatchError> {
+        if schedule.record_checkpoint.is_none() && !Self::coupon_per_share(&schedule)?.is_zero() {
+            let cp_id = <Checkpoint<T>>::unsafe_create_checkpoint(schedule.agent, ticker)?;
+            schedule.record_checkpoint = Some(cp_id);
+            Schedules::insert(ticker, schedule);
+        }
+        Ok(schedule)
+    }
+
+    /// Create the CA and distribution of the next coupon of `ticker`,
+    /// with the checkpoint of the coupon as the record date,
+    /// and advance `schedule` to the following coupon.
+    ///
+    /// Unlike `pay_coupon`, this won't check permissions or that the coupon is due.
+    fn unverified_pay_coupon(ticker: Ticker, schedule: CouponSchedule) -> DispatchResult {
+        let mut schedule = Self::record_balances(ticker, schedule)?;
+        let (per_share, amount) = Self::coupon_amount(ticker, &schedule)?;
+        let at_maturity = schedule.at_maturity();
+        let now = <Checkpoint<T>>::now_unix();
+
+        // Nothing to pay, e.g., for zero-coupon bonds before maturity.
+        let ca_id = match schedule.record_checkpoint {
+            Some(cp_id) if !amount.is_zero() => {
+                // Coupons are only funded by agents of the asset.
+                let agent = schedule.agent;
+                ensure!(
+                    <ExternalAgents<T>>::agents(ticker, agent).is_some(),
+                    Error::<T>::ScheduleAgentRemoved
+                );
+
+                // Ensure funding before creating anything, so that we can report it.
+                <Portfolio<T>>::ensure_sufficient_balance(
+                    &schedule.funding_portfolio(),
+                    &schedule.terms.currency,
+                    amount,
+                )?;
+
+                // Holders' balances at the due date are the record date of a new CA.
+                let ca_id = <CA<T>>::unsafe_initiate_corporate_action(
+                    agent,
+                    ticker,
+                    CAKind::PredictableBenefit,
+                    now,
+                    Some(RecordDateSpec::Existing(cp_id)),
+                    CADetails::default(),
+                    None,
+                    None,
+                    None,
+                )?;
+                <Distribution<T>>::unverified_distribute(
+                    agent,
+                    None,
+                    ca_id,
+                    schedule.portfolio,
+                    schedule.terms.currency,
+                    per_share,
+                    amount,
+                    now,
+                    schedule
+                        .terms
+                        .claim_period
+                        .map(|period| now.saturating_add(period)),
+                )?;
+                Self::deposit_event(Event::CouponCreated(
+                    ticker,
+                    ca_id,
+                    schedule.next_coupon,
+                    per_share,
+                    amount,
+                ));
+                Some(ca_id)
+            }
+            _ => None,
+        };
+
+        // Advance to the next coupon, or end the schedule at maturity.
+        if at_maturity {
+            Schedules::remove(ticker);
+            MaturedAssets::insert(ticker, true);
+            if let Some(ca_id) = ca_id {
+                Self::deposit_event(Event::Matured(ticker, ca_id));
+            }
+        } else {
+            schedule.accrued_from = schedule.next_coupon;
+            schedule.next_coupon =
+                CouponSchedule::coupon_after(&schedule.terms, schedule.next_coupon);
+            schedule.coupons_paid += 1;
+            schedule.record_checkpoint = None;
+            // The schedule's previous coupon was dequeued, so this won't exceed the bound.
+            Self::queue_coupon(schedule.next_coupon, ticker)?;
+            Schedules::insert(ticker, schedule);
+        }
+        Ok(())
+    }
+
+    /// Returns the amount per share of the next coupon of `schedule`,
+    /// including the principal at maturity.
+    fn coupon_per_share(schedule: &CouponSchedule) -> Result<Balance, DispatchError> {
+        let terms = &schedule.terms;
+        let (days, days_in_year) = terms
+            .day_count
+            .accrued_days(schedule.accrued_from, schedule.next_coupon)
+            .ok_or(Error::<T>::InvalidCouponDates)?;
+        let interest = (terms.rate * terms.face_value)
+            .checked_mul(days.into())
+            .map(|v| v / Balance::from(days_in_year))
+            .ok_or(Error::<T>::CouponAmountOverflowed)?;
+        if schedule.at_maturity() {
+            interest.checked_add(terms.face_value)
+        } else {
+            Some(interest)
+        }
+        .ok_or_else(|| Error::<T>::CouponAmountOverflowed.into())
+    }
+
+    /// Returns the amount per share, and the total amount, of the next coupon of `ticker`,
+    /// including the principal at maturity.
+    ///
+    /// The total amount is based on the supply at the coupon's checkpoint, or is zero without one.
+    fn coupon_amount(
+        ticker: Ticker,
+        schedule: &CouponSchedule,
+    ) -> Result<(Balance, Balance), DispatchError> {
+        let per_share = Self::coupon_per_share(schedule)?;
+        let supply = schedule
+            .record_checkpoint
+            .map_or(0, |cp_id| <Checkpoint<T>>::total_supply_at(ticker, cp_id));
+        let amount = supply
+            .checked_mul(per_share)
+            // `per_share` is in per-million, i.e. scaled by `PER_SHARE_PRECISION`.
+            .map(|v| v / PER_SHARE_PRECISION)
+            .ok_or(Error::<T>::CouponAmountOverflowed)?;
+        Ok((per_share, amount))
+    }
+
+    /// Queue the coupon of `ticker` due `at`, keeping the queue sorted.
+    fn queue_coupon(at: Moment, ticker: Ticker) -> DispatchResult {
+        let mut due = DueCoupons::get();
+        let idx = due.partition_point(|(other, _)| *other <= at);
+        due.try_insert(idx, (at, ticker))
+            .map_err(|_| Error::<T>::TooManySchedules)?;
+        Self::put_due_coupons(due);
+        Ok(())
+    }
+
+    /// Store the queue of due coupons, along with the due date of its first coupon.
+    fn put_due_coupons(due: BoundedVec<(Moment, Ticker), ConstU32<MAX_COUPON_SCHEDULES>>) {
+        NextCouponDue::set(due.first().map(|(at, _)| *at));
+        DueCoupons::put(due);
+    }
+
+    fn base_redeem_matured(
+        agent: IdentityId,
+        ticker: Ticker,
+        holders: Vec<IdentityId>,
+        portfolios: u32,
+    ) -> DispatchResult {
+        ensure!(Self::matured(ticker), Error::<T>::NotMatured);
+        // Redeeming deducts from the balance yet to be rescaled by a split.
+        <Asset<T>>::ensure_not_rescaling(&ticker)?;
+
+        let mut portfolios_left = portfolios;
+        with_transaction(|| {
+            for holder in holders {
+                let portfolios =
+                    <Reorganization<T>>::holder_portfolios(holder, &mut portfolios_left)
+                        .ok_or(Error::<T>::TooManyPortfolios)?;
+                let mut balances = Vec::new();
+                for pid in portfolios {
+                    if <Portfolio<T>>::portfolio_asset_balances(pid, ticker).is_zero() {
+                        continue;
+                    }
+                    ensure!(
+                        <Portfolio<T>>::locked_assets(pid, ticker).is_zero(),
+                        Error::<T>::HolderHasLockedAssets
+                    );
+                    balances.push((pid, 0));
+                }
+
+                let amount = <Asset<T>>::balance_of(ticker, holder);
+                <Asset<T>>::unsafe_set_portfolio_balances(
+                    agent,
+                    &ticker,
+                    holder,
+                    &balances,
+                    &mut WeightMeter::max_limit_no_minimum(),
+                )?;
+                Self::deposit_event(Event::MaturedRedeemed(
+                    agent.for_event(),
+                    ticker,
+                    holder,
+                    amount,
+                ));
+            }
+            Ok(())
+        })
+    }
+}
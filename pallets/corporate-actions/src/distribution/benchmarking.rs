@@ -18,7 +18,9 @@ use frame_benchmarking::benchmarks;
 use pallet_compliance_manager::Module as ComplianceManager;
 use polymesh_common_utilities::benchs::{user, AccountIdOf, User};
 use polymesh_common_utilities::TestUtilsFn;
-use polymesh_primitives::{Fund, FundDescription, PortfolioId, PortfolioNumber, Ticker};
+use polymesh_primitives::{
+    Fund, FundDescription, PortfolioId, PortfolioKind, PortfolioNumber, Ticker,
+};
 
 use super::*;
 use crate::benchmarking::{currency, did_whts, set_ca_targets, setup_ca, SEED};
@@ -94,6 +96,52 @@ fn prepare_transfer<T: Config + pallet_compliance_manager::Config + TestUtilsFn<
     (owner, holder, ca_id)
 }
 
+/// Creates `count` alternative currencies, moved into the portfolio `pnum` of `owner`.
+fn cash_options<T: Config>(owner: &User<T>, pnum: PortfolioNumber, count: u32) -> Vec<CashOption> {
+    let did = owner.did();
+    let amount: Balance = 1000u32.into();
+    (0..count)
+        .map(|i| {
+            let currency = Ticker::from_slice_truncated(&[b'A', b'L', b'T', b'0' + i as u8]);
+            <Asset<T>>::create_asset(
+                owner.origin().into(),
+                currency.as_slice().into(),
+                currency,
+                true,
+                <_>::default(),
+                vec![],
+                None,
+            )
+            .unwrap();
+            <Asset<T>>::issue(
+                owner.origin().into(),
+                currency,
+                amount,
+                PortfolioKind::Default,
+            )
+            .unwrap();
+            <Portfolio<T>>::move_portfolio_funds(
+                owner.origin().into(),
+                PortfolioId::default_portfolio(did),
+                PortfolioId::user_portfolio(did, pnum),
+                vec![Fund {
+                    description: FundDescription::Fungible {
+                        ticker: currency,
+                        amount,
+                    },
+                    memo: None,
+                }],
+            )
+            .unwrap();
+            CashOption {
+                currency,
+                per_share: 2u32.into(),
+                amount,
+            }
+        })
+        .collect()
+}
+
 benchmarks! {
     where_clause { where
         T: pallet_compliance_manager::Config,
@@ -147,4 +195,43 @@ benchmarks! {
     verify {
         assert!(Distributions::get(ca_id).is_none(), "not removed");
     }
+
+    set_elective_options {
+        let c in 1..MAX_CASH_OPTIONS;
+
+        let (owner, ca_id, _) = dist::<T>(0);
+        let options = ElectiveOptions {
+            cash: cash_options::<T>(&owner, 1u64.into(), c),
+            scrip_per_share: Some(PER_SHARE_PRECISION),
+            default_election: Election::Scrip,
+        };
+    }: _(owner.origin(), ca_id, options)
+    verify {
+        assert!(Electives::get(ca_id).is_some(), "not elective");
+    }
+
+    elect {
+        let (owner, ca_id, _) = dist::<T>(0);
+        let options = ElectiveOptions {
+            cash: vec![],
+            scrip_per_share: Some(PER_SHARE_PRECISION),
+            default_election: Election::Scrip,
+        };
+        <Module<T>>::set_elective_options(owner.origin().into(), ca_id, options).unwrap();
+        let holder = user::<T>("holder", SEED);
+        let election = Election::Cash(Distributions::get(ca_id).unwrap().currency);
+    }: _(holder.origin(), ca_id, election)
+    verify {
+        assert_eq!(HolderElections::get(ca_id, holder.did()), Some(election), "not elected");
+    }
+
+    refund_withheld_tax {
+        let (owner, holder, ca_id) = prepare_transfer::<T>(0, 0);
+        <pallet_timestamp::Now<T>>::set(5000u32.into());
+        <Module<T>>::reclaim(owner.origin().into(), ca_id).unwrap();
+        WithheldTax::insert(ca_id, holder.did(), 100);
+    }: _(owner.origin(), ca_id, holder.did(), 100)
+    verify {
+        assert!(WithheldTax::get(ca_id, holder.did()).is_zero(), "not refunded");
+    }
 }
@@ -37,6 +37,18 @@
 //! Before `payment_at` is due, however,
 //! a planned distribution can be cancelled by calling `remove_distribution`.
 //!
+//! A distribution can also be made elective via `set_elective_options`,
+//! before `payment_at` is due.
+//! Holders may then choose, through `elect`, between cash in `currency`,
+//! cash in one of several alternative currencies, each withdrawn from the same portfolio,
+//! or a scrip dividend paid in newly issued shares of the CA's asset.
+//! Holders who make no election before `payment_at` receive the default election.
+//! When benefits are claimed or pushed, they are paid out in the elected form.
+//!
+//! Taxes withheld from each holder are tracked per CA.
+//! If a holder is later found to have been over-withheld, e.g., as they reclaim tax under a treaty,
+//! a permissioned external agent may refund (part of) the withheld amount via `refund_withheld_tax`.
+//!
 //! ## Overview
 //!
 //! The module provides functions for:
@@ -44,12 +56,15 @@
 //! - Starting a distribution.
 //! - Claiming or pushing benefits of a distribution.
 //! - Reclaiming unclaimed dividends.
+//! - Electing between cash in several currencies or a scrip dividend.
 //!
 //! ### Terminology
 //!
 //! - **Currency:** The ticker being distributed to holders as a benefit, e.g., USDC or some such.
 //! - **Payment-at date:** The date at which benefits may be claimed by or pushed to holders.
 //! - **Expires-at date:** The date at which benefits are forfeit, and may be reclaimed by a permissioned external agent.
+//! - **Scrip dividend:** A benefit paid in newly issued shares of the asset, rather than in cash.
+//! - **Election:** The form, cash in some currency or scrip, a holder chose to receive their benefit in.
 //!
 //! ## Interface
 //!
@@ -60,6 +75,9 @@
 //! - `push_benefit` pushes a benefit of an active capital distribution to a holder.
 //! - `reclaim` reclaims forfeited benefits of a capital distribution that has expired.
 //! - `remove_distribution` removes a capital distribution which hasn't reached its payment date yet.
+//! - `set_elective_options` lets holders elect between several forms of a not-yet-started distribution.
+//! - `elect` records the form a holder wishes to receive their benefit in.
+//! - `refund_withheld_tax` refunds tax withheld from a holder's benefit.
 
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
@@ -90,7 +108,7 @@ use scale_info::TypeInfo;
 use sp_runtime::traits::Zero;
 #[cfg(feature = "std")]
 use sp_runtime::{Deserialize, Serialize};
-use sp_std::prelude::*;
+use sp_std::{collections::btree_set::BTreeSet, prelude::*};
 
 type Asset<T> = asset::Module<T>;
 type Checkpoint<T> = checkpoint::Module<T>;
@@ -102,6 +120,9 @@ type Portfolio<T> = pallet_portfolio::Module<T>;
 /// The value `per_share` must take to get 1 `currency`.
 pub const PER_SHARE_PRECISION: Balance = 1_000_000;
 
+/// The maximum number of alternative currencies an elective distribution may offer.
+pub const MAX_CASH_OPTIONS: u32 = 8;
+
 /// A capital distribution's various details.
 ///
 /// All information contained is used by on-chain logic.
@@ -127,6 +148,59 @@ pub struct Distribution {
     pub expires_at: Option<Moment>,
 }
 
+/// An alternative currency, besides the distribution's own,
+/// that holders may elect to receive their benefit in.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct CashOption {
+    /// The currency that payouts happen in.
+    pub currency: Ticker,
+    /// Amount per share to pay out, in per-million,
+    /// i.e. `1 / 10^6`th of one `currency` token.
+    pub per_share: Balance,
+    /// Total amount to be distributed at most.
+    pub amount: Balance,
+}
+
+/// The form a holder elected to receive their benefit in.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub enum Election {
+    /// Cash in the given currency.
+    Cash(Ticker),
+    /// Newly issued shares of the CA's asset.
+    Scrip,
+}
+
+/// The forms, besides cash in the distribution's `currency`,
+/// that holders of an elective distribution may elect to be paid in.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct ElectiveOptions {
+    /// Alternative currencies, withdrawn from the distribution's portfolio.
+    pub cash: Vec<CashOption>,
+    /// If a scrip dividend is offered, the amount of new shares to issue per share,
+    /// in per-million, i.e. `1 / 10^6`th of one share.
+    pub scrip_per_share: Option<Balance>,
+    /// The election of holders who did not elect before `payment_at`.
+    pub default_election: Election,
+}
+
+impl ElectiveOptions {
+    /// Returns whether `election` is offered, given `currency`, the distribution's own.
+    pub fn offers(&self, currency: Ticker, election: Election) -> bool {
+        match election {
+            Election::Cash(c) => c == currency || self.cash_option(c).is_some(),
+            Election::Scrip => self.scrip_per_share.is_some(),
+        }
+    }
+
+    /// Returns the alternative cash option in `currency`, if any.
+    pub fn cash_option(&self, currency: Ticker) -> Option<&CashOption> {
+        self.cash.iter().find(|o| o.currency == currency)
+    }
+}
+
 /// Has the distribution expired?
 fn expired(expiry: Option<Moment>, now: Moment) -> bool {
     expiry.filter(|&e| e <= now).is_some()
@@ -139,6 +213,9 @@ pub trait WeightInfo {
     fn push_benefit(target_ids: u32, did_whts: u32) -> Weight;
     fn reclaim() -> Weight;
     fn remove_distribution() -> Weight;
+    fn set_elective_options(c: u32) -> Weight;
+    fn elect() -> Weight;
+    fn refund_withheld_tax() -> Weight;
 }
 
 decl_storage! {
@@ -153,6 +230,30 @@ decl_storage! {
         /// (CAId, DID) -> Was DID paid in the CAId?
         HolderPaid get(fn holder_paid): map hasher(blake2_128_concat) (CAId, IdentityId) => bool;
 
+        /// The elective options of capital distributions, if any, tied to their CAs.
+        ///
+        /// (CAId) => ElectiveOptions
+        Electives get(fn electives): map hasher(blake2_128_concat) CAId => Option<ElectiveOptions>;
+
+        /// Amount left to distribute in each alternative currency of an elective distribution.
+        ///
+        /// (CAId, currency) => remaining amount
+        ElectiveRemaining get(fn elective_remaining):
+            double_map hasher(blake2_128_concat) CAId, hasher(blake2_128_concat) Ticker => Balance;
+
+        /// The form each holder elected to receive their benefit in.
+        ///
+        /// (CAId, DID) => DID's election in CAId
+        HolderElections get(fn holder_election):
+            double_map hasher(blake2_128_concat) CAId, hasher(blake2_128_concat) IdentityId => Option<Election>;
+
+        /// The amount of tax withheld from each holder's benefit, less any refunds,
+        /// in the form the holder was paid in.
+        ///
+        /// (CAId, DID) => amount withheld from DID in CAId
+        WithheldTax get(fn withheld_tax):
+            double_map hasher(blake2_128_concat) CAId, hasher(blake2_128_concat) IdentityId => Balance;
+
         /// Storage version.
         StorageVersion get(fn storage_version) build(|_| Version::new(0)): Version;
     }
@@ -320,6 +421,94 @@ decl_module! {
         pub fn remove_distribution(origin, ca_id: CAId) {
             Self::base_remove_distribution(origin, ca_id)?;
         }
+
+        /// Make the not-yet-started distribution attached to `ca_id` elective,
+        /// letting holders choose between the forms in `options` and cash in the distribution's `currency`.
+        ///
+        /// The `amount` of each alternative currency is locked in the distribution's portfolio,
+        /// and is unlocked, as with `currency`, when benefits are paid, reclaimed, or the distribution is removed.
+        /// Scrip dividends are paid by issuing new shares of `ca_id.ticker`, so nothing is locked for them.
+        ///
+        /// As the share of `currency` left unclaimed by holders electing other forms
+        /// is only unlocked through `reclaim`, the distribution must expire.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ca_id.ticker`.
+        /// - `ca_id` identifies the CA with a not-yet-started capital distribution to make elective.
+        /// - `options` are the alternative forms to offer and the default election.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchDistribution` if there's no capital distribution for `ca_id`.
+        /// - `DistributionStarted` if `payment_at <= now`.
+        /// - `AlreadyElective` if the distribution already has elective options.
+        /// - `ElectiveWithoutExpiry` if the distribution has no `expires_at`.
+        /// - `NoElectiveOptions` if `options` offers neither an alternative currency nor scrip.
+        /// - `TooManyCashOptions` if more than `MAX_CASH_OPTIONS` alternative currencies are offered.
+        /// - `DuplicateCurrency` if a currency is offered more than once.
+        /// - `DistributionAmountIsZero` if the `amount` of an alternative currency is zero.
+        /// - `DistributionPerShareIsZero` if some `per_share` is zero.
+        /// - `InvalidElection` if the default election is not offered.
+        /// - `UnauthorizedCustodian` if the caller is not the custodian of the distribution's portfolio.
+        /// - `InsufficientPortfolioBalance` if the portfolio has less than `amount` of some currency.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::DistWeightInfo::set_elective_options(options.cash.len() as u32)]
+        pub fn set_elective_options(origin, ca_id: CAId, options: ElectiveOptions) {
+            Self::base_set_elective_options(origin, ca_id, options)?;
+        }
+
+        /// Elect the form in which the benefit of `origin`'s DID,
+        /// in the elective distribution attached to `ca_id`, is paid.
+        ///
+        /// Elections can be made, and changed, until `payment_at`.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a holder of the asset and eligible for the distribution.
+        /// - `ca_id` identifies the CA with an elective capital distribution.
+        /// - `election` is the form to receive the benefit in.
+        ///
+        /// # Errors
+        /// - `NoSuchDistribution` if there's no capital distribution for `ca_id`.
+        /// - `DistributionStarted` if `payment_at <= now`.
+        /// - `NotElective` if the distribution has no elective options.
+        /// - `InvalidElection` if `election` is not offered by the distribution.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `NotTargetedByCA` if the CA does not target `origin`'s DID.
+        #[weight = <T as Config>::DistWeightInfo::elect()]
+        pub fn elect(origin, ca_id: CAId, election: Election) {
+            Self::base_elect(origin, ca_id, election)?;
+        }
+
+        /// Refund `amount` of the tax withheld from `holder`'s benefit
+        /// in the capital distribution attached to `ca_id`.
+        ///
+        /// The refund is paid in the form `holder` received their benefit in.
+        /// Cash is transferred from the distribution's portfolio, where withheld amounts remain,
+        /// to the default portfolio of `holder`, while scrip is issued to it.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ca_id.ticker`.
+        /// - `ca_id` identifies the CA with a capital distribution to refund tax for.
+        /// - `holder` to refund withheld tax to.
+        /// - `amount` of withheld tax to refund.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchDistribution` if there's no capital distribution for `ca_id`.
+        /// - `RefundExceedsWithheld` if `amount` is more than what remains withheld from `holder`.
+        /// - `UnauthorizedCustodian` if the caller is not the custodian of the distribution's portfolio.
+        /// - Other errors can occur if the compliance manager rejects the transfer.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::DistWeightInfo::refund_withheld_tax()]
+        pub fn refund_withheld_tax(origin, ca_id: CAId, holder: IdentityId, amount: Balance) {
+            Self::base_refund_withheld_tax(origin, ca_id, holder, amount)?;
+        }
     }
 }
 
@@ -345,6 +534,31 @@ decl_event! {
         ///
         /// (Agent DID, CA's ID)
         Removed(EventDid, CAId),
+
+        /// A capital distribution was made elective.
+        ///
+        /// (Agent DID, CA's ID, elective options)
+        ElectiveOptionsSet(EventDid, CAId, ElectiveOptions),
+
+        /// A holder elected the form to receive their benefit in.
+        ///
+        /// (Holder DID, CA's ID, election)
+        Elected(EventDid, CAId, Election),
+
+        /// A token holder's benefit was paid in an elected form other than the distribution's `currency`.
+        ///
+        /// (Caller DID, Holder/Claimant DID, CA's ID, election, DID's benefit, DID's tax %)
+        ElectedBenefitClaimed(EventDid, EventDid, CAId, Election, Balance, Tax),
+
+        /// The remaining amount of an alternative currency was reclaimed.
+        ///
+        /// (Agent DID, CA's ID, currency, amount reclaimed)
+        ElectiveReclaimed(EventDid, CAId, Ticker, Balance),
+
+        /// Tax withheld from a holder's benefit was refunded.
+        ///
+        /// (Agent DID, Holder DID, CA's ID, amount refunded)
+        WithheldTaxRefunded(EventDid, EventDid, CAId, Balance),
     }
 }
 
@@ -381,6 +595,22 @@ decl_error! {
         DistributionAmountIsZero,
         /// Distribution `per_share` cannot be zero.
         DistributionPerShareIsZero,
+        /// The distribution already has elective options.
+        AlreadyElective,
+        /// The distribution has no elective options.
+        NotElective,
+        /// Elective options must offer an alternative currency or a scrip dividend.
+        NoElectiveOptions,
+        /// More than `MAX_CASH_OPTIONS` alternative currencies were offered.
+        TooManyCashOptions,
+        /// A currency was offered more than once.
+        DuplicateCurrency,
+        /// The election is not offered by the distribution.
+        InvalidElection,
+        /// The refund exceeds the tax withheld from the holder.
+        RefundExceedsWithheld,
+        /// Only distributions that expire can be made elective.
+        ElectiveWithoutExpiry,
     }
 }
 
@@ -454,6 +684,17 @@ impl<T: Config> Module<T> {
         // This won't fail, as we've already locked the requisite amount prior.
         Self::unlock(&dist, dist.remaining)?;
 
+        // Unlock what remains of alternative currencies as well.
+        for (currency, remaining) in ElectiveRemaining::drain_prefix(ca_id) {
+            <Portfolio<T>>::unlock_tokens(&dist.from, &currency, remaining)?;
+            Self::deposit_event(Event::ElectiveReclaimed(
+                agent.for_event(),
+                ca_id,
+                currency,
+                remaining,
+            ));
+        }
+
         // Zero `remaining` + note that we've reclaimed.
         Distributions::insert(
             ca_id,
@@ -478,6 +719,150 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    fn base_set_elective_options(
+        origin: T::RuntimeOrigin,
+        ca_id: CAId,
+        options: ElectiveOptions,
+    ) -> DispatchResult {
+        let PermissionedCallOriginData {
+            primary_did: agent,
+            secondary_key,
+            ..
+        } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, ca_id.ticker)?;
+        let dist = Self::ensure_distribution_exists(ca_id)?;
+        Self::ensure_distribution_not_started(&dist)?;
+        ensure!(!Electives::contains_key(ca_id), Error::<T>::AlreadyElective);
+        // The cash in `dist.currency` not owed to holders is only unlocked by `reclaim`.
+        ensure!(dist.expires_at.is_some(), Error::<T>::ElectiveWithoutExpiry);
+
+        // Ensure there's something to elect between, and that the options are valid.
+        ensure!(
+            !options.cash.is_empty() || options.scrip_per_share.is_some(),
+            Error::<T>::NoElectiveOptions
+        );
+        ensure!(
+            options.cash.len() <= MAX_CASH_OPTIONS as usize,
+            Error::<T>::TooManyCashOptions
+        );
+        ensure!(
+            options.scrip_per_share != Some(0),
+            Error::<T>::DistributionPerShareIsZero
+        );
+        let mut currencies = BTreeSet::new();
+        currencies.insert(dist.currency);
+        for option in &options.cash {
+            ensure!(
+                !option.amount.is_zero(),
+                Error::<T>::DistributionAmountIsZero
+            );
+            ensure!(
+                !option.per_share.is_zero(),
+                Error::<T>::DistributionPerShareIsZero
+            );
+            ensure!(
+                currencies.insert(option.currency),
+                Error::<T>::DuplicateCurrency
+            );
+        }
+        ensure!(
+            options.offers(dist.currency, options.default_election),
+            Error::<T>::InvalidElection
+        );
+
+        // Ensure `dist.from` has enough of each alternative currency to later lock (1).
+        <Portfolio<T>>::ensure_portfolio_custody_and_permission(
+            dist.from,
+            agent,
+            secondary_key.as_ref(),
+        )?;
+        for option in &options.cash {
+            <Portfolio<T>>::ensure_sufficient_balance(&dist.from, &option.currency, option.amount)?;
+        }
+
+        // (1) Lock each `amount` in `dist.from` + commit to storage.
+        for option in &options.cash {
+            <Portfolio<T>>::unchecked_lock_tokens(&dist.from, &option.currency, option.amount);
+            ElectiveRemaining::insert(ca_id, option.currency, option.amount);
+        }
+        Electives::insert(ca_id, &options);
+
+        Self::deposit_event(Event::ElectiveOptionsSet(agent.for_event(), ca_id, options));
+        Ok(())
+    }
+
+    fn base_elect(origin: T::RuntimeOrigin, ca_id: CAId, election: Election) -> DispatchResult {
+        let did = <Identity<T>>::ensure_perms(origin)?;
+
+        // Elections must be made before payment starts, and be offered.
+        let dist = Self::ensure_distribution_exists(ca_id)?;
+        Self::ensure_distribution_not_started(&dist)?;
+        let elective = Electives::get(ca_id).ok_or(Error::<T>::NotElective)?;
+        ensure!(
+            elective.offers(dist.currency, election),
+            Error::<T>::InvalidElection
+        );
+
+        // Ensure CA targets DID.
+        let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+        <CA<T>>::ensure_ca_targets(&ca, &did)?;
+
+        HolderElections::insert(ca_id, did, election);
+        Self::deposit_event(Event::Elected(did.for_event(), ca_id, election));
+        Ok(())
+    }
+
+    fn base_refund_withheld_tax(
+        origin: T::RuntimeOrigin,
+        ca_id: CAId,
+        holder: IdentityId,
+        amount: Balance,
+    ) -> DispatchResult {
+        let PermissionedCallOriginData {
+            primary_did: agent,
+            secondary_key,
+            ..
+        } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, ca_id.ticker)?;
+        let dist = Self::ensure_distribution_exists(ca_id)?;
+        let withheld = WithheldTax::get(ca_id, holder)
+            .checked_sub(amount)
+            .ok_or(Error::<T>::RefundExceedsWithheld)?;
+
+        // Refund in the form `holder` was paid in.
+        let to = PortfolioId::default_portfolio(holder);
+        let mut weight_meter = WeightMeter::max_limit_no_minimum();
+        match Self::election_of(ca_id, holder, &dist) {
+            Election::Cash(currency) => {
+                <Portfolio<T>>::ensure_portfolio_custody_and_permission(
+                    dist.from,
+                    agent,
+                    secondary_key.as_ref(),
+                )?;
+                <Asset<T>>::base_transfer(
+                    dist.from,
+                    to,
+                    &currency,
+                    amount,
+                    None,
+                    None,
+                    agent,
+                    &mut weight_meter,
+                )?;
+            }
+            Election::Scrip => {
+                <Asset<T>>::unsafe_issue(&ca_id.ticker, to, amount, &mut weight_meter)?
+            }
+        }
+        WithheldTax::insert(ca_id, holder, withheld);
+
+        Self::deposit_event(Event::WithheldTaxRefunded(
+            agent.for_event(),
+            holder.for_event(),
+            ca_id,
+            amount,
+        ));
+        Ok(())
+    }
+
     /// Kill the distribution identified by `ca_id`.
     ///
     /// Unlike `base_remove_distribution`, this won't check permissions and that the dist exists.
@@ -492,6 +877,15 @@ impl<T: Config> Module<T> {
         // Unlock and remove chain data.
         Self::unlock(&dist, dist.amount)?;
         Distributions::remove(ca_id);
+        if let Some(elective) = Electives::take(ca_id) {
+            for option in &elective.cash {
+                <Portfolio<T>>::unlock_tokens(&dist.from, &option.currency, option.amount)?;
+            }
+            #[allow(deprecated)]
+            ElectiveRemaining::remove_prefix(ca_id, None);
+            #[allow(deprecated)]
+            HolderElections::remove_prefix(ca_id, None);
+        }
 
         // Emit event.
         Self::deposit_event(Event::Removed(agent, ca_id));
@@ -527,6 +921,27 @@ impl<T: Config> Module<T> {
 
         // Compute `balance * amount / supply`, i.e. DID's benefit.
         let balance = <CA<T>>::balance_at_cp(holder, ca_id, cp_id);
+        let tax = <CA<T>>::withholding_tax_of(&ca, ca_id.ticker, holder);
+
+        // Pay out in the elected form, if it isn't `currency`.
+        let election = Self::election_of(ca_id, holder, &dist);
+        if election != Election::Cash(dist.currency) {
+            let benefit = Self::transfer_elected_benefit(
+                &actor, holder, ca_id, &dist, election, balance, tax,
+            )?;
+            HolderPaid::insert((ca_id, holder), true);
+            Self::record_withheld(ca_id, holder, tax * benefit);
+            Self::deposit_event(Event::ElectedBenefitClaimed(
+                actor,
+                holder.for_event(),
+                ca_id,
+                election,
+                benefit,
+                tax,
+            ));
+            return Ok(());
+        }
+
         let benefit = Self::benefit_of(balance, dist.per_share)?;
 
         // Ensure we have enough remaining.
@@ -536,15 +951,8 @@ impl<T: Config> Module<T> {
             .ok_or(Error::<T>::InsufficientRemainingAmount)?;
 
         // Compute withholding tax + gain.
-        let tax = ca.tax_of(&holder);
-        let gain = benefit - tax * benefit;
-
         // Round down to unit multiple if indivisible.
-        let gain = if Asset::<T>::is_divisible(&dist.currency) {
-            gain
-        } else {
-            gain / ONE_UNIT * ONE_UNIT
-        };
+        let gain = Self::round_to_unit(&dist.currency, benefit - tax * benefit);
 
         with_transaction(|| {
             // Unlock `benefit` of `currency` from the calling agent's portfolio.
@@ -565,8 +973,9 @@ impl<T: Config> Module<T> {
             )
         })?;
 
-        // Note that DID was paid.
+        // Note that DID was paid, and how much was withheld.
         HolderPaid::insert((ca_id, holder), true);
+        Self::record_withheld(ca_id, holder, tax * benefit);
         let holder = holder.for_event();
 
         // Commit `dist` change to storage.
@@ -580,6 +989,87 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Pay `holder`'s benefit in `ca_id` in the elected `election`,
+    /// other than cash in `dist.currency`, returning the pre-tax benefit.
+    fn transfer_elected_benefit(
+        actor: &EventDid,
+        holder: IdentityId,
+        ca_id: CAId,
+        dist: &Distribution,
+        election: Election,
+        balance: Balance,
+        tax: Tax,
+    ) -> Result<Balance, DispatchError> {
+        let elective = Electives::get(ca_id).ok_or(Error::<T>::NotElective)?;
+        let to = PortfolioId::default_portfolio(holder);
+        let mut weight_meter = WeightMeter::max_limit_no_minimum();
+        match election {
+            Election::Cash(currency) => {
+                let option = elective
+                    .cash_option(currency)
+                    .ok_or(Error::<T>::InvalidElection)?;
+                let benefit = Self::benefit_of(balance, option.per_share)?;
+                let remaining = ElectiveRemaining::get(ca_id, currency)
+                    .checked_sub(benefit)
+                    .ok_or(Error::<T>::InsufficientRemainingAmount)?;
+                let gain = Self::round_to_unit(&currency, benefit - tax * benefit);
+
+                with_transaction(|| {
+                    <Portfolio<T>>::unlock_tokens(&dist.from, &currency, benefit)?;
+                    <Asset<T>>::base_transfer(
+                        dist.from,
+                        to,
+                        &currency,
+                        gain,
+                        None,
+                        None,
+                        actor.clone().risky_into_inner(),
+                        &mut weight_meter,
+                    )
+                })?;
+                ElectiveRemaining::insert(ca_id, currency, remaining);
+                Ok(benefit)
+            }
+            Election::Scrip => {
+                // Withheld tax is paid by not issuing the corresponding shares.
+                let per_share = elective
+                    .scrip_per_share
+                    .ok_or(Error::<T>::InvalidElection)?;
+                let benefit = Self::benefit_of(balance, per_share)?;
+                let gain = Self::round_to_unit(&ca_id.ticker, benefit - tax * benefit);
+                <Asset<T>>::unsafe_issue(&ca_id.ticker, to, gain, &mut weight_meter)?;
+                Ok(benefit)
+            }
+        }
+    }
+
+    /// Note that `withheld` was withheld from `holder`'s benefit in `ca_id`.
+    fn record_withheld(ca_id: CAId, holder: IdentityId, withheld: Balance) {
+        if !withheld.is_zero() {
+            WithheldTax::insert(ca_id, holder, withheld);
+        }
+    }
+
+    /// Returns `holder`'s election in `ca_id`, falling back to the default election,
+    /// or to cash in `dist.currency` if the distribution is not elective.
+    fn election_of(ca_id: CAId, holder: IdentityId, dist: &Distribution) -> Election {
+        match Electives::get(ca_id) {
+            Some(elective) => {
+                HolderElections::get(ca_id, holder).unwrap_or(elective.default_election)
+            }
+            None => Election::Cash(dist.currency),
+        }
+    }
+
+    /// Round `amount` down to a whole unit if `ticker` is indivisible.
+    fn round_to_unit(ticker: &Ticker, amount: Balance) -> Balance {
+        if Asset::<T>::is_divisible(ticker) {
+            amount
+        } else {
+            amount / ONE_UNIT * ONE_UNIT
+        }
+    }
+
     /// Unlock `amount` of `dist.currency` in the `dist.from` portfolio.
     fn unlock(dist: &Distribution, amount: Balance) -> DispatchResult {
         <Portfolio<T>>::unlock_tokens(&dist.from, &dist.currency, amount)
@@ -0,0 +1,162 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use frame_benchmarking::benchmarks;
+
+use pallet_compliance_manager::Module as ComplianceManager;
+use polymesh_common_utilities::benchs::{user, AccountIdOf, User};
+use polymesh_common_utilities::TestUtilsFn;
+use polymesh_primitives::settlement::{VenueDetails, VenueType};
+use polymesh_primitives::PortfolioName;
+
+use super::*;
+use crate::benchmarking::{currency, set_ca_targets, setup_ca, SEED};
+
+const AMOUNT: Balance = 1000;
+
+fn venue<T: Config>(owner: &User<T>) -> VenueId {
+    let venue_id = <Settlement<T>>::venue_counter();
+    <Settlement<T>>::create_venue(
+        owner.origin().into(),
+        VenueDetails::default(),
+        vec![owner.account()],
+        VenueType::Other,
+    )
+    .unwrap();
+    venue_id
+}
+
+fn offer<T: Config + TestUtilsFn<AccountIdOf<T>>>(kind: OfferKind) -> (User<T>, CAId) {
+    let (owner, ca_id) = setup_ca::<T>(CAKind::Reorganization);
+    let currency = currency::<T>(&owner);
+    let venue_id = venue::<T>(&owner);
+    <Module<T>>::create_offer(
+        owner.origin().into(),
+        ca_id,
+        kind,
+        None,
+        currency,
+        PER_SHARE_PRECISION,
+        AMOUNT,
+        venue_id,
+        3000,
+    )
+    .unwrap();
+    (owner, ca_id)
+}
+
+/// Creates an offer and a holder of `ca_id.ticker` at the record date,
+/// whose balance is spread over its default portfolio and `portfolios` user portfolios.
+fn prepare_exchange<T>(
+    kind: OfferKind,
+    portfolios: u32,
+) -> (User<T>, User<T>, CAId, Vec<PortfolioNumber>)
+where
+    T: Config + pallet_compliance_manager::Config + TestUtilsFn<AccountIdOf<T>>,
+{
+    let (owner, ca_id) = offer::<T>(kind);
+    let new_ticker = Offers::get(ca_id).unwrap().new_ticker;
+    for ticker in [ca_id.ticker, new_ticker] {
+        <ComplianceManager<T>>::add_compliance_requirement(
+            owner.origin().into(),
+            ticker,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+    }
+
+    let holder = user::<T>("holder", SEED);
+    let user_portfolios = (1..=portfolios)
+        .map(|num| PortfolioNumber(num.into()))
+        .collect::<Vec<_>>();
+    let amount = AMOUNT / Balance::from(portfolios + 1);
+    let receivers = iter::once(PortfolioId::default_portfolio(holder.did())).chain(
+        user_portfolios.iter().map(|num| {
+            <Portfolio<T>>::create_portfolio(holder.origin().into(), PortfolioName(num.encode()))
+                .unwrap();
+            PortfolioId::user_portfolio(holder.did(), *num)
+        }),
+    );
+    for receiver in receivers {
+        <Asset<T>>::unsafe_transfer(
+            PortfolioId::default_portfolio(owner.did()),
+            receiver,
+            &ca_id.ticker,
+            amount,
+            None,
+            None,
+            owner.did(),
+            &mut WeightMeter::max_limit_no_minimum(),
+        )
+        .unwrap();
+    }
+    (owner, holder, ca_id, user_portfolios)
+}
+
+benchmarks! {
+    where_clause { where
+        T: pallet_compliance_manager::Config,
+        T: TestUtilsFn<AccountIdOf<T>>,
+    }
+
+    create_offer {
+        let (owner, ca_id) = setup_ca::<T>(CAKind::Reorganization);
+        let currency = currency::<T>(&owner);
+        let venue_id = venue::<T>(&owner);
+    }: _(owner.origin(), ca_id, OfferKind::Voluntary, None, currency, PER_SHARE_PRECISION, AMOUNT, venue_id, 3000)
+    verify {
+        assert!(Offers::get(ca_id).is_some(), "offer not created");
+    }
+
+    elect {
+        let p in 0..MAX_EXCHANGE_PORTFOLIOS;
+
+        let (_, holder, ca_id, portfolios) = prepare_exchange::<T>(OfferKind::Voluntary, p);
+        <pallet_timestamp::Now<T>>::set(2500u32.into());
+    }: _(holder.origin(), ca_id, portfolios)
+    verify {
+        assert!(HolderExchanged::get((ca_id, holder.did())), "not exchanged");
+    }
+
+    push_exchange {
+        let p in 0..MAX_EXCHANGE_PORTFOLIOS;
+
+        let (owner, holder, ca_id, portfolios) = prepare_exchange::<T>(OfferKind::Mandatory, p);
+        <pallet_timestamp::Now<T>>::set(3000u32.into());
+    }: _(owner.origin(), ca_id, holder.did(), portfolios)
+    verify {
+        assert!(HolderExchanged::get((ca_id, holder.did())), "not exchanged");
+    }
+
+    reclaim {
+        let t in 0..T::MaxTargetIds::get();
+
+        // Targets are read to ensure every holder of a mandatory offer was exchanged.
+        let (owner, ca_id) = offer::<T>(OfferKind::Mandatory);
+        set_ca_targets::<T>(ca_id, t);
+        <pallet_timestamp::Now<T>>::set(3000u32.into());
+    }: _(owner.origin(), ca_id)
+    verify {
+        assert!(Offers::get(ca_id).unwrap().reclaimed, "not reclaimed");
+    }
+
+    remove_offer {
+        let (owner, ca_id) = offer::<T>(OfferKind::Voluntary);
+    }: _(owner.origin(), ca_id)
+    verify {
+        assert!(Offers::get(ca_id).is_none(), "not removed");
+    }
+}
@@ -0,0 +1,796 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! # Corporate Exchange Module
+//!
+//! The corporate exchange module provides functionality for exchange offers,
+//! e.g., mergers and tender offers, where holders swap one asset for another.
+//!
+//! The process works by first initiating a corporate action (CA) of kind `Reorganization`,
+//! with a record date, through `initiate_corporate_action`,
+//! and then attaching an exchange offer to it via `create_offer`.
+//!
+//! When creating an offer, the issuer deposits an `amount` of the new asset,
+//! which is locked in one of the agent's portfolios.
+//! Holders at the record date may then exchange their balance at the record date
+//! for `per_share` of the new asset for every share of the old asset.
+//! Every exchange is settled through an instruction on a venue of the agent,
+//! so that the compliance rules of both assets are enforced.
+//! The old asset is transferred from the default portfolio of the holder,
+//! followed by up to `MAX_EXCHANGE_PORTFOLIOS` given user portfolios, to the agent's portfolio,
+//! and the new asset is transferred from the agent's portfolio to the default portfolio of the holder.
+//!
+//! An offer is either voluntary or mandatory.
+//! Before the offer's deadline, holders may elect to exchange through `elect`.
+//! Positions not elected by the deadline default according to the kind of offer.
+//! In a voluntary offer, holders keep their old asset.
+//! In a mandatory offer, the agent exchanges them through `push_exchange`.
+//!
+//! Once the deadline has passed, the agent may reclaim the remaining deposit through `reclaim`.
+//! For a mandatory offer, the deposit can only be reclaimed once every targeted holder,
+//! other than the agent, has been exchanged.
+//! Before any holder has exchanged, an offer can be removed by calling `remove_offer`.
+//!
+//! ## Overview
+//!
+//! The module provides functions for:
+//!
+//! - Creating a voluntary or mandatory exchange offer.
+//! - Electing to exchange, or forcing an exchange.
+//! - Reclaiming the remaining deposit.
+//!
+//! ### Terminology
+//!
+//! - **Deadline:** The date before which holders may elect to exchange,
+//!   and after which unelected positions default.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create_offer` attaches an exchange offer to a reorganization CA.
+//! - `elect` exchanges the position of a holder on their behalf, before the deadline.
+//! - `push_exchange` exchanges the position of a holder in a mandatory offer, after the deadline.
+//! - `reclaim` unlocks the remaining deposit of an offer whose deadline has passed,
+//!   and in which, if mandatory, every holder was exchanged.
+//! - `remove_offer` removes an offer which no holder has exchanged in yet.
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+use crate as ca;
+use ca::{distribution::PER_SHARE_PRECISION, reorganization, CAId, CAKind, Config};
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    traits::Get,
+    weights::Weight,
+};
+use pallet_asset::{self as asset, checkpoint};
+use pallet_identity::{self as identity, PermissionedCallOriginData};
+use polymesh_common_utilities::{
+    constants::currency::ONE_UNIT, portfolio::PortfolioSubTrait, with_transaction,
+};
+use polymesh_primitives::settlement::{InstructionId, Leg, SettlementType, VenueId};
+use polymesh_primitives::{
+    storage_migration_ver, Balance, EventDid, IdentityId, Moment, PortfolioId, PortfolioNumber,
+    SecondaryKey, Ticker, WeightMeter,
+};
+use scale_info::TypeInfo;
+use sp_runtime::traits::Zero;
+#[cfg(feature = "std")]
+use sp_runtime::{Deserialize, Serialize};
+use sp_std::{collections::btree_set::BTreeSet, iter, prelude::*};
+
+type Asset<T> = asset::Module<T>;
+type Checkpoint<T> = checkpoint::Module<T>;
+type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type CA<T> = ca::Module<T>;
+type Identity<T> = identity::Module<T>;
+type Portfolio<T> = pallet_portfolio::Module<T>;
+type Reorganization<T> = reorganization::Module<T>;
+type Settlement<T> = pallet_settlement::Module<T>;
+
+/// Maximum number of user portfolios the old asset of a holder is exchanged from.
+pub const MAX_EXCHANGE_PORTFOLIOS: u32 = 10;
+
+/// Whether holders choose to exchange, or are forced to.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub enum OfferKind {
+    /// Holders elect to exchange. Unelected positions keep the old asset.
+    Voluntary,
+    /// Every holder is exchanged. Unelected positions are exchanged after the deadline.
+    Mandatory,
+}
+
+/// An exchange offer's various details.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct ExchangeOffer {
+    /// The kind of offer.
+    pub kind: OfferKind,
+    /// The portfolio holding the deposit and receiving the exchanged old asset.
+    pub portfolio: PortfolioId,
+    /// The asset that holders receive.
+    pub new_ticker: Ticker,
+    /// Amount of the new asset per share of the old asset, in per-million,
+    /// i.e. `1 / 10^6`th of one `new_ticker` token.
+    pub per_share: Balance,
+    /// Total amount of the new asset deposited.
+    pub amount: Balance,
+    /// Amount of the new asset left to exchange.
+    pub remaining: Balance,
+    /// The venue that exchanges are settled on.
+    pub venue_id: VenueId,
+    /// A timestamp before which holders may elect to exchange.
+    pub deadline: Moment,
+    /// The number of holders exchanged so far.
+    pub holders_exchanged: u32,
+    /// Amount of the old asset exchanged so far.
+    pub old_exchanged: Balance,
+    /// Whether a permissioned external agent has reclaimed the remaining deposit.
+    pub reclaimed: bool,
+}
+
+/// Weight abstraction for the corporate exchange module.
+pub trait WeightInfo {
+    fn create_offer() -> Weight;
+    fn elect(portfolios: u32) -> Weight;
+    fn push_exchange(portfolios: u32) -> Weight;
+    fn reclaim(targets: u32) -> Weight;
+    fn remove_offer() -> Weight;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as CorporateExchange {
+        /// All exchange offers, tied to their respective corporate actions (CAs).
+        ///
+        /// (CAId) => ExchangeOffer
+        Offers get(fn offers): map hasher(blake2_128_concat) CAId => Option<ExchangeOffer>;
+
+        /// Has an asset holder been exchanged yet?
+        ///
+        /// (CAId, DID) -> Was DID exchanged in the CAId?
+        HolderExchanged get(fn holder_exchanged): map hasher(blake2_128_concat) (CAId, IdentityId) => bool;
+
+        /// Storage version.
+        StorageVersion get(fn storage_version) build(|_| Version::new(0)): Version;
+    }
+}
+
+storage_migration_ver!(0);
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::RuntimeOrigin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Attach an exchange offer to the CA identified by `ca_id`,
+        /// depositing `amount` of `new_ticker` from `portfolio` belonging to `origin`'s DID.
+        ///
+        /// Holders at the record date of the CA receive `per_share` of `new_ticker`
+        /// for every share of `ca_id.ticker`, settled on `venue_id`.
+        ///
+        /// The deposit will be locked in `portfolio` until it is exchanged or reclaimed.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ca_id.ticker`.
+        /// - `ca_id` identifies the CA to attach the exchange offer to.
+        /// - `kind` specifies whether holders elect to exchange, or are forced to.
+        /// - `portfolio` specifies the portfolio number of the agent to deposit `amount` from,
+        ///    which also receives the exchanged `ca_id.ticker`.
+        /// - `new_ticker` is the asset that holders receive.
+        /// - `per_share` amount of `new_ticker` per share of `ca_id.ticker`.
+        ///    Specified as a per-million, i.e. `1 / 10^6`th of one `new_ticker` token.
+        /// - `amount` of `new_ticker` to deposit.
+        /// - `venue_id` of a venue created by `origin`'s DID, to settle exchanges on.
+        /// - `deadline` before which holders may elect to exchange.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `OfferAmountIsZero` if `amount` or `per_share` is zero.
+        /// - `SameTicker` if `new_ticker` is `ca_id.ticker`.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `CANotReorganization` if the CA is not of kind Reorganization.
+        /// - `NoRecordDate` if the CA has no record date.
+        /// - `RecordDateAfterStart` if the CA's record date > `deadline`.
+        /// - `AlreadyExists` if an exchange offer or a split is already attached to the CA.
+        /// - `InvalidVenue` if `venue_id` doesn't exist or wasn't created by `origin`'s DID.
+        /// - `UnauthorizedCustodian` if the caller is not the custodian of `portfolio`.
+        /// - `InsufficientPortfolioBalance` if `portfolio` has less than `amount` of `new_ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::ExchangeWeightInfo::create_offer()]
+        pub fn create_offer(
+            origin,
+            ca_id: CAId,
+            kind: OfferKind,
+            portfolio: Option<PortfolioNumber>,
+            new_ticker: Ticker,
+            per_share: Balance,
+            amount: Balance,
+            venue_id: VenueId,
+            deadline: Moment,
+        ) {
+            let PermissionedCallOriginData {
+                primary_did: agent,
+                secondary_key,
+                ..
+            } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, ca_id.ticker)?;
+
+            Self::unverified_create_offer(
+                agent,
+                secondary_key,
+                ca_id,
+                kind,
+                portfolio,
+                new_ticker,
+                per_share,
+                amount,
+                venue_id,
+                deadline,
+            )?;
+        }
+
+        /// Elect to exchange the position of `origin`'s DID in the offer attached to `ca_id`.
+        ///
+        /// The balance at the record date is transferred from the default portfolio of the DID,
+        /// and then from `portfolios`, in order, until it is covered,
+        /// in exchange for the new asset, which is transferred to the default portfolio.
+        /// For indivisible new assets, the amount received is rounded down to a whole unit.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a holder of the asset and targeted by the CA.
+        /// - `ca_id` identifies the CA with an exchange offer.
+        /// - `portfolios` are the user portfolios to transfer the old asset from,
+        ///    after the default portfolio.
+        ///
+        /// # Errors
+        /// - `TooManyPortfolios` if more than `MAX_EXCHANGE_PORTFOLIOS` portfolios are given.
+        /// - `UnauthorizedCustodian` if the caller is not the custodian of one of the portfolios.
+        /// - `HolderAlreadyExchanged` if `origin`'s DID has already exchanged.
+        /// - `NoSuchOffer` if there's no exchange offer for `ca_id`.
+        /// - `CannotExchangeBeforeRecordDate` if `now < record_date`.
+        /// - `CannotElectAfterDeadline` if `now >= deadline`.
+        /// - `NotTargetedByCA` if the CA does not target `origin`'s DID.
+        /// - `NothingToExchange` if `origin`'s DID had no balance at the record date.
+        /// - `InsufficientRemainingAmount` if the offer has an insufficient remaining deposit.
+        /// - `InsufficientHolderBalance` if the portfolios have less than the balance at the record date.
+        /// - Other errors can occur if the settlement of the exchange fails,
+        ///   e.g., when the compliance manager rejects one of the transfers.
+        #[weight = <T as Config>::ExchangeWeightInfo::elect(portfolios.len() as u32)]
+        pub fn elect(origin, ca_id: CAId, portfolios: Vec<PortfolioNumber>) {
+            let PermissionedCallOriginData {
+                primary_did: holder,
+                secondary_key,
+                ..
+            } = <Identity<T>>::ensure_origin_call_permissions(origin)?;
+            Self::ensure_portfolios_limit(&portfolios)?;
+            let default_portfolio = PortfolioId::default_portfolio(holder);
+            let user_portfolios = portfolios.iter().map(|num| PortfolioId::user_portfolio(holder, *num));
+            for portfolio in iter::once(default_portfolio).chain(user_portfolios) {
+                <Portfolio<T>>::ensure_portfolio_custody_and_permission(
+                    portfolio,
+                    holder,
+                    secondary_key.as_ref(),
+                )?;
+            }
+
+            let offer = Self::ensure_offer_exists(ca_id)?;
+            ensure!(
+                <Checkpoint<T>>::now_unix() < offer.deadline,
+                Error::<T>::CannotElectAfterDeadline
+            );
+            Self::exchange(holder.for_event(), holder, ca_id, offer, portfolios)?;
+        }
+
+        /// Push the exchange of an unelected position of `holder`
+        /// in the mandatory offer attached to `ca_id`, after its deadline.
+        ///
+        /// The balance at the record date is transferred from the default portfolio of `holder`,
+        /// and then from `portfolios`, in order, until it is covered,
+        /// in exchange for the new asset, which is transferred to the default portfolio.
+        /// For indivisible new assets, the amount received is rounded down to a whole unit.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ca_id.ticker`.
+        /// - `ca_id` identifies the CA with a mandatory exchange offer.
+        /// - `holder` to exchange.
+        /// - `portfolios` are the user portfolios of `holder` to transfer the old asset from,
+        ///    after the default portfolio.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `TooManyPortfolios` if more than `MAX_EXCHANGE_PORTFOLIOS` portfolios are given.
+        /// - `HolderAlreadyExchanged` if `holder` has already exchanged.
+        /// - `NoSuchOffer` if there's no exchange offer for `ca_id`.
+        /// - `OfferNotMandatory` if the exchange offer is voluntary.
+        /// - `CannotPushBeforeDeadline` if `now < deadline`.
+        /// - `NotTargetedByCA` if the CA does not target `holder`.
+        /// - `NothingToExchange` if `holder` had no balance at the record date.
+        /// - `InsufficientRemainingAmount` if the offer has an insufficient remaining deposit.
+        /// - `InsufficientHolderBalance` if the portfolios have less than the balance at the record date.
+        /// - Other errors can occur if the settlement of the exchange fails,
+        ///   e.g., when the compliance manager rejects one of the transfers.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::ExchangeWeightInfo::push_exchange(portfolios.len() as u32)]
+        pub fn push_exchange(origin, ca_id: CAId, holder: IdentityId, portfolios: Vec<PortfolioNumber>) {
+            let agent = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
+            Self::ensure_portfolios_limit(&portfolios)?;
+
+            let offer = Self::ensure_offer_exists(ca_id)?;
+            ensure!(offer.kind == OfferKind::Mandatory, Error::<T>::OfferNotMandatory);
+            ensure!(
+                <Checkpoint<T>>::now_unix() >= offer.deadline,
+                Error::<T>::CannotPushBeforeDeadline
+            );
+            Self::exchange(agent, holder, ca_id, offer, portfolios)?;
+        }
+
+        /// Assuming the deadline of an exchange offer has passed,
+        /// unlock the remaining deposit in the agent's portfolio.
+        ///
+        /// A mandatory offer can only be reclaimed once the positions of every holder targeted by the CA,
+        /// other than the agent, have been exchanged, i.e., once the old asset exchanged
+        /// covers the targeted balance at the record date.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ca_id.ticker`.
+        /// - `ca_id` identifies the CA with an exchange offer to reclaim for.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchOffer` if there's no exchange offer for `ca_id`.
+        /// - `AlreadyReclaimed` if this function has already been called successfully.
+        /// - `DeadlineNotPassed` if `now < deadline`.
+        /// - `HoldersNotExchanged` if some holders of a mandatory offer are yet to be exchanged.
+        /// - `UnauthorizedCustodian` if the caller is not the custodian of the offer's portfolio.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::ExchangeWeightInfo::reclaim(T::MaxTargetIds::get())]
+        pub fn reclaim(origin, ca_id: CAId) {
+            let PermissionedCallOriginData {
+                primary_did: agent,
+                secondary_key,
+                ..
+            } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, ca_id.ticker)?;
+            let offer = Self::ensure_offer_exists(ca_id)?;
+            ensure!(!offer.reclaimed, Error::<T>::AlreadyReclaimed);
+            ensure!(
+                <Checkpoint<T>>::now_unix() >= offer.deadline,
+                Error::<T>::DeadlineNotPassed
+            );
+            if offer.kind == OfferKind::Mandatory {
+                ensure!(
+                    offer.old_exchanged >= Self::targeted_balance(ca_id, offer.portfolio.did)?,
+                    Error::<T>::HoldersNotExchanged
+                );
+            }
+            <Portfolio<T>>::ensure_portfolio_custody_and_permission(
+                offer.portfolio,
+                agent,
+                secondary_key.as_ref(),
+            )?;
+
+            // Unlock `remaining` of `new_ticker` from the agent's portfolio.
+            // This won't fail, as we've already locked the requisite amount prior.
+            Self::unlock(&offer, offer.remaining)?;
+
+            // Zero `remaining` + note that we've reclaimed.
+            Offers::insert(
+                ca_id,
+                ExchangeOffer {
+                    reclaimed: true,
+                    remaining: 0,
+                    ..offer
+                },
+            );
+            Self::deposit_event(Event::Reclaimed(agent.for_event(), ca_id, offer.remaining));
+        }
+
+        /// Removes an exchange offer in which no holder has exchanged yet,
+        /// unlocking the full deposit in the agent's portfolio.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ca_id.ticker`.
+        /// - `ca_id` identifies the CA with an exchange offer to remove.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchOffer` if there's no exchange offer for `ca_id`.
+        /// - `OfferStarted` if some holders have already exchanged.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::ExchangeWeightInfo::remove_offer()]
+        pub fn remove_offer(origin, ca_id: CAId) {
+            let agent = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
+            let offer = Self::ensure_offer_exists(ca_id)?;
+            Self::unverified_remove_offer(agent, ca_id, &offer)?;
+        }
+    }
+}
+
+decl_event! {
+    pub enum Event {
+        /// An exchange offer, with details included,
+        /// was created by the DID (permissioned agent) for the CA identified by `CAId`.
+        ///
+        /// (Agent DID, CA's ID, offer details)
+        Created(EventDid, CAId, ExchangeOffer),
+
+        /// A holder's position was exchanged in the offer for the given `CAId`.
+        ///
+        /// (Caller DID, holder DID, CA's ID, old asset amount, new asset amount, settling instruction)
+        Exchanged(EventDid, IdentityId, CAId, Balance, Balance, InstructionId),
+
+        /// The remaining deposit of an exchange offer was reclaimed.
+        ///
+        /// (Agent DID, CA's ID, amount reclaimed)
+        Reclaimed(EventDid, CAId, Balance),
+
+        /// An exchange offer was removed.
+        ///
+        /// (Agent DID, CA's ID)
+        Removed(EventDid, CAId),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// An exchange offer was made for a non-reorganization CA.
+        CANotReorganization,
+        /// An exchange offer or a split already exists for this CA.
+        AlreadyExists,
+        /// The offer's `amount` or `per_share` is zero.
+        OfferAmountIsZero,
+        /// Holders cannot exchange an asset for itself.
+        SameTicker,
+        /// The venue doesn't exist or wasn't created by the agent.
+        InvalidVenue,
+        /// An exchange offer doesn't exist for this CA.
+        NoSuchOffer,
+        /// Positions cannot be exchanged as the current time is before the record date.
+        CannotExchangeBeforeRecordDate,
+        /// Holders cannot elect to exchange after the offer's deadline.
+        CannotElectAfterDeadline,
+        /// Unelected positions cannot be exchanged before the offer's deadline.
+        CannotPushBeforeDeadline,
+        /// Only positions of mandatory offers can be exchanged by agents.
+        OfferNotMandatory,
+        /// The holder has already exchanged their position.
+        HolderAlreadyExchanged,
+        /// The holder had no balance at the record date.
+        NothingToExchange,
+        /// Multiplication of the balance with the per share amount overflowed.
+        BalancePerShareProductOverflowed,
+        /// The offer has an insufficient remaining deposit of the new asset.
+        InsufficientRemainingAmount,
+        /// The remaining deposit has already been reclaimed.
+        AlreadyReclaimed,
+        /// The offer's deadline has not passed yet.
+        DeadlineNotPassed,
+        /// Some holders have already exchanged in the offer.
+        OfferStarted,
+        /// More than `MAX_EXCHANGE_PORTFOLIOS` portfolios were given.
+        TooManyPortfolios,
+        /// The holder's portfolios have less of the old asset than the balance at the record date.
+        InsufficientHolderBalance,
+        /// Some holders of a mandatory offer are yet to be exchanged.
+        HoldersNotExchanged,
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Create an exchange offer.
+    ///
+    /// Unlike `create_offer`, this won't check permissions.
+    fn unverified_create_offer(
+        agent: IdentityId,
+        secondary_key: Option<SecondaryKey<T::AccountId>>,
+        ca_id: CAId,
+        kind: OfferKind,
+        portfolio: Option<PortfolioNumber>,
+        new_ticker: Ticker,
+        per_share: Balance,
+        amount: Balance,
+        venue_id: VenueId,
+        deadline: Moment,
+    ) -> DispatchResult {
+        // Ensure valid `amount`, `per_share` and `new_ticker`.
+        ensure!(
+            !amount.is_zero() && !per_share.is_zero(),
+            Error::<T>::OfferAmountIsZero
+        );
+        ensure!(new_ticker != ca_id.ticker, Error::<T>::SameTicker);
+
+        // Ensure that `ca_id` exists, is a reorganization, and has a record `date <= deadline`.
+        let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+        ensure!(
+            ca.kind == CAKind::Reorganization,
+            Error::<T>::CANotReorganization
+        );
+        <CA<T>>::ensure_record_date_before_start(&ca, deadline)?;
+
+        // Ensure CA doesn't have an offer or a split yet.
+        ensure!(
+            !Offers::contains_key(ca_id) && <Reorganization<T>>::splits(ca_id).is_none(),
+            Error::<T>::AlreadyExists
+        );
+
+        // Ensure exchanges are settled on a venue of the agent.
+        ensure!(
+            <Settlement<T>>::venue_info(venue_id)
+                .filter(|v| v.creator == agent)
+                .is_some(),
+            Error::<T>::InvalidVenue
+        );
+
+        // Ensure secondary key has perms for `portfolio` + portfolio is valid.
+        let portfolio = PortfolioId {
+            did: agent,
+            kind: portfolio.into(),
+        };
+        <Portfolio<T>>::ensure_portfolio_custody_and_permission(
+            portfolio,
+            agent,
+            secondary_key.as_ref(),
+        )?;
+        <Portfolio<T>>::ensure_portfolio_validity(&portfolio)?;
+
+        // Ensure `portfolio` has at least `amount` to lock.
+        <Portfolio<T>>::ensure_sufficient_balance(&portfolio, &new_ticker, amount)?;
+        <Portfolio<T>>::unchecked_lock_tokens(&portfolio, &new_ticker, amount);
+
+        // Commit to storage + emit event.
+        let offer = ExchangeOffer {
+            kind,
+            portfolio,
+            new_ticker,
+            per_share,
+            amount,
+            remaining: amount,
+            venue_id,
+            deadline,
+            holders_exchanged: 0,
+            old_exchanged: 0,
+            reclaimed: false,
+        };
+        Offers::insert(ca_id, offer);
+        Self::deposit_event(Event::Created(agent.for_event(), ca_id, offer));
+        Ok(())
+    }
+
+    /// Exchange the position of `holder` at the record date of `ca_id`,
+    /// from the default portfolio and then `portfolios` of `holder`,
+    /// settling all transfers in a single instruction.
+    fn exchange(
+        actor: EventDid,
+        holder: IdentityId,
+        ca_id: CAId,
+        mut offer: ExchangeOffer,
+        portfolios: Vec<PortfolioNumber>,
+    ) -> DispatchResult {
+        // Ensure holder not exchanged yet.
+        ensure!(
+            !HolderExchanged::get((ca_id, holder)),
+            Error::<T>::HolderAlreadyExchanged
+        );
+        ensure!(!offer.reclaimed, Error::<T>::AlreadyReclaimed);
+
+        // Fetch the CA data + ensure the record date has passed and that the CA targets DID.
+        let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+        let record_date = ca.record_date.ok_or(ca::Error::<T>::NoRecordDate)?;
+        ensure!(
+            <Checkpoint<T>>::now_unix() >= record_date.date,
+            Error::<T>::CannotExchangeBeforeRecordDate
+        );
+        <CA<T>>::ensure_ca_targets(&ca, &holder)?;
+
+        // Compute the position at the record date, and what it's exchanged for.
+        let cp_id = <CA<T>>::record_date_cp(&ca, ca_id);
+        let old_amount = <CA<T>>::balance_at_cp(holder, ca_id, cp_id);
+        ensure!(!old_amount.is_zero(), Error::<T>::NothingToExchange);
+        let new_amount = old_amount
+            .checked_mul(offer.per_share)
+            // `per_share` is in per-million, i.e. scaled by `PER_SHARE_PRECISION`.
+            .map(|v| v / PER_SHARE_PRECISION)
+            .ok_or(Error::<T>::BalancePerShareProductOverflowed)?;
+        // Round down to unit multiple if indivisible.
+        let new_amount = if <Asset<T>>::is_divisible(&offer.new_ticker) {
+            new_amount
+        } else {
+            new_amount / ONE_UNIT * ONE_UNIT
+        };
+        offer.remaining = offer
+            .remaining
+            .checked_sub(new_amount)
+            .ok_or(Error::<T>::InsufficientRemainingAmount)?;
+
+        // Take the old asset from the holder's portfolios, in order, until `old_amount` is covered.
+        let holder_portfolio = PortfolioId::default_portfolio(holder);
+        let user_portfolios = portfolios
+            .into_iter()
+            .map(|num| PortfolioId::user_portfolio(holder, num));
+        let mut holder_portfolios: BTreeSet<PortfolioId> = [holder_portfolio].into_iter().collect();
+        let mut legs = Vec::new();
+        let mut left = old_amount;
+        for portfolio in iter::once(holder_portfolio).chain(user_portfolios) {
+            if left.is_zero() {
+                break;
+            }
+            let free = <Portfolio<T>>::portfolio_asset_balances(portfolio, ca_id.ticker)
+                .saturating_sub(<Portfolio<T>>::locked_assets(portfolio, ca_id.ticker));
+            let amount = free.min(left);
+            // Skip empty and repeated portfolios.
+            if amount.is_zero()
+                || (portfolio != holder_portfolio && !holder_portfolios.insert(portfolio))
+            {
+                continue;
+            }
+            left -= amount;
+            legs.push(Leg::Fungible {
+                sender: portfolio,
+                receiver: offer.portfolio,
+                ticker: ca_id.ticker,
+                amount,
+            });
+        }
+        ensure!(left.is_zero(), Error::<T>::InsufficientHolderBalance);
+        legs.push(Leg::Fungible {
+            sender: offer.portfolio,
+            receiver: holder_portfolio,
+            ticker: offer.new_ticker,
+            amount: new_amount,
+        });
+        let agent = offer.portfolio.did;
+        let instruction_id = with_transaction(|| {
+            Self::unlock(&offer, new_amount)?;
+
+            let instruction_id = <Settlement<T>>::base_add_instruction(
+                agent,
+                offer.venue_id,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                legs,
+                None,
+                None,
+                None,
+                None,
+            )?;
+
+            let agent_portfolios = [offer.portfolio].into_iter().collect();
+            for (did, portfolios) in [(agent, agent_portfolios), (holder, holder_portfolios)] {
+                <Settlement<T>>::unsafe_affirm_instruction(
+                    did,
+                    instruction_id,
+                    portfolios,
+                    None,
+                    None,
+                )?;
+            }
+
+            <Settlement<T>>::execute_instruction_now(
+                instruction_id,
+                agent,
+                &mut WeightMeter::max_limit_no_minimum(),
+            )?;
+            Ok::<_, DispatchError>(instruction_id)
+        })?;
+
+        // Note that DID was exchanged + commit `offer` change to storage.
+        HolderExchanged::insert((ca_id, holder), true);
+        offer.holders_exchanged = offer.holders_exchanged.saturating_add(1);
+        offer.old_exchanged = offer.old_exchanged.saturating_add(old_amount);
+        Offers::insert(ca_id, offer);
+
+        Self::deposit_event(Event::Exchanged(
+            actor,
+            holder,
+            ca_id,
+            old_amount,
+            new_amount,
+            instruction_id,
+        ));
+        Ok(())
+    }
+
+    /// Kill the exchange offer identified by `ca_id`.
+    ///
+    /// Unlike `remove_offer`, this won't check permissions and that the offer exists.
+    pub(crate) fn unverified_remove_offer(
+        agent: EventDid,
+        ca_id: CAId,
+        offer: &ExchangeOffer,
+    ) -> DispatchResult {
+        Self::ensure_offer_not_started(offer)?;
+
+        // Unlock and remove chain data.
+        Self::unlock(offer, offer.remaining)?;
+        Offers::remove(ca_id);
+        Self::deposit_event(Event::Removed(agent, ca_id));
+        Ok(())
+    }
+
+    /// Ensure that no holder has exchanged in `offer` yet.
+    pub(crate) fn ensure_offer_not_started(offer: &ExchangeOffer) -> DispatchResult {
+        ensure!(offer.holders_exchanged == 0, Error::<T>::OfferStarted);
+        Ok(())
+    }
+
+    /// Ensure that at most `MAX_EXCHANGE_PORTFOLIOS` portfolios were given.
+    fn ensure_portfolios_limit(portfolios: &[PortfolioNumber]) -> DispatchResult {
+        ensure!(
+            portfolios.len() <= MAX_EXCHANGE_PORTFOLIOS as usize,
+            Error::<T>::TooManyPortfolios
+        );
+        Ok(())
+    }
+
+    /// Returns the balance at the record date of `ca_id` of the holders targeted by the CA,
+    /// other than `agent`, i.e., the old asset that a mandatory offer must exchange.
+    fn targeted_balance(ca_id: CAId, agent: IdentityId) -> Result<Balance, DispatchError> {
+        let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+        let cp_id = <CA<T>>::record_date_cp(&ca, ca_id);
+        let balance = |did| <CA<T>>::balance_at_cp(did, ca_id, cp_id);
+        let targets = &ca.targets;
+        let listed = |include_agent: bool| {
+            targets
+                .identities
+                .iter()
+                .filter(|did| include_agent || **did != agent)
+                .map(|did| balance(*did))
+                .fold(0, Balance::saturating_add)
+        };
+        if targets.treatment.is_include() {
+            return Ok(listed(false));
+        }
+
+        // Every holder is targeted, except for the listed ones.
+        let supply = match cp_id {
+            Some(cp_id) => <Checkpoint<T>>::total_supply_at(ca_id.ticker, cp_id),
+            None => <Asset<T>>::token_details(&ca_id.ticker)?.total_supply,
+        };
+        let agent_balance = if targets.targets(&agent) {
+            balance(agent)
+        } else {
+            0
+        };
+        Ok(supply
+            .saturating_sub(listed(true))
+            .saturating_sub(agent_balance))
+    }
+
+    /// Unlock `amount` of `offer.new_ticker` in the `offer.portfolio` portfolio.
+    fn unlock(offer: &ExchangeOffer, amount: Balance) -> DispatchResult {
+        <Portfolio<T>>::unlock_tokens(&offer.portfolio, &offer.new_ticker, amount)
+    }
+
+    /// Ensure `ca_id` has some exchange offer and return it.
+    fn ensure_offer_exists(ca_id: CAId) -> Result<ExchangeOffer, DispatchError> {
+        Offers::get(ca_id).ok_or_else(|| Error::<T>::NoSuchOffer.into())
+    }
+}
@@ -45,15 +45,25 @@
 //! asset-level defaults may also be specified with `set_default_withholding_tax`
 //! and `set_did_withholding_tax`.
 //!
+//! Tax treaties between countries commonly reduce the tax withheld from foreign holders.
+//! To support this, treaty rates keyed by the issuer's and the holder's country
+//! may be set through `set_treaty_withholding_tax`, via a PIP.
+//! Once an asset's issuer jurisdiction is set with `set_issuer_tax_jurisdiction`,
+//! holders with a `Claim::Jurisdiction`, scoped to the ticker, by the designated claim issuer
+//! are withheld at the treaty rate for their country, unless a per-DID override exists.
+//!
 //! After having created a CA and some asset documents,
 //! such documents may also be linked to the CA.
 //! To do so, `link_ca_doc(ca_id, docs)` can be called,
 //! with the ID of the CA specified in `ca_id` as well the IDs of each document in `docs`.
 //!
-//! Beyond this module, two other modules exist dedicated to CAs. These are:
+//! Beyond this module, five other modules exist dedicated to CAs. These are:
 //!
 //! - The corporate ballots module, with which e.g., annual general meetings can be conducted on-chain.
 //! - The capital distributions module, with which e.g., dividends and other benefits may be distributed.
+//! - The corporate reorganization module, with which e.g., stock splits may be executed.
+//! - The corporate exchange module, with which e.g., holders may swap assets in mergers and tender offers.
+//! - The corporate coupon module, with which e.g., the coupons of bonds are paid on a schedule.
 //!
 //! For more details, consult the documentation in those modules.
 //!
@@ -79,6 +89,11 @@
 //! - `set_did_withholding_tax(origin, ticker, taxed_did, tax)` sets a withholding tax
 //!    for CAs associated with `ticker` and specific to `taxed_did` to `tax`,
 //!    or resets the tax of `taxed_did` to the default if `tax` is `None`.
+//! - `set_issuer_tax_jurisdiction(origin, ticker, jurisdiction)` sets the issuer's tax jurisdiction
+//!    for CAs associated with `ticker`, used to look up treaty withholding tax rates.
+//! - `set_treaty_withholding_tax(origin, issuer_country, holder_country, tax)` sets the treaty rate
+//!    withheld from holders in `holder_country` of assets issued in `issuer_country`.
+//!    Must be called via the PIP process.
 //! - `initiate_corporate_action(...)` initates a corporate action.
 //! - `link_ca_doc(origin, id, docs)` is called by external agents to associate `docs` to the CA with `id`.
 //! - `remove_ca(origin, id)` removes the CA identified by `id`.
@@ -89,7 +104,10 @@
 pub mod benchmarking;
 
 pub mod ballot;
+pub mod coupon;
 pub mod distribution;
+pub mod exchange;
+pub mod reorganization;
 
 use codec::{Decode, Encode};
 use distribution::WeightInfo as DistWeightInfoTrait;
@@ -109,8 +127,8 @@ use polymesh_common_utilities::{
     traits::checkpoint::ScheduleId, with_transaction, GC_DID,
 };
 use polymesh_primitives::{
-    asset::CheckpointId, impl_checked_inc, storage_migration_ver, Balance, DocumentId, EventDid,
-    IdentityId, Moment, PortfolioNumber, Ticker,
+    asset::CheckpointId, impl_checked_inc, storage_migration_ver, Balance, Claim, ClaimType,
+    CountryCode, DocumentId, EventDid, IdentityId, Moment, PortfolioNumber, Scope, Ticker,
 };
 use polymesh_primitives_derive::VecU8StrongTyped;
 use scale_info::TypeInfo;
@@ -263,16 +281,26 @@ pub struct CorporateAction {
 }
 
 impl CorporateAction {
-    /// Returns the tax of `did` in this CA.
-    fn tax_of(&self, did: &IdentityId) -> Tax {
+    /// Returns the per-DID tax override of `did` in this CA, if any.
+    fn did_tax(&self, did: &IdentityId) -> Option<Tax> {
         // N.B. we maintain a sorted list to enable O(log n) access here.
         self.withholding_tax
             .binary_search_by_key(&did, |(did, _)| did)
             .map(|idx| self.withholding_tax[idx].1)
-            .unwrap_or_else(|_| self.default_withholding_tax)
+            .ok()
     }
 }
 
+/// The tax jurisdiction of an asset's issuer, used to look up treaty withholding tax rates.
+#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Debug)]
+pub struct TaxJurisdiction {
+    /// The country the issuer is resident in for tax purposes.
+    pub country: CountryCode,
+    /// The identity whose `Claim::Jurisdiction` claims, scoped to the ticker,
+    /// determine the country of each holder.
+    pub claim_issuer: IdentityId,
+}
+
 /// A `Ticker`-local CA ID.
 /// By *local*, we mean that the same number might be used for a different `Ticker`
 /// to uniquely identify a different CA.
@@ -314,14 +342,21 @@ pub trait WeightInfo {
     fn remove_ca_with_dist() -> Weight;
     fn change_record_date_with_ballot() -> Weight;
     fn change_record_date_with_dist() -> Weight;
+    fn set_issuer_tax_jurisdiction() -> Weight;
+    fn set_treaty_withholding_tax() -> Weight;
 }
 
 /// The module's configuration trait.
-pub trait Config: frame_system::Config + BalancesConfig + IdentityConfig + asset::Config {
+pub trait Config:
+    frame_system::Config + BalancesConfig + IdentityConfig + asset::Config + pallet_settlement::Config
+{
     /// The overarching event type.
     type RuntimeEvent: From<Event>
         + From<ballot::Event>
         + From<distribution::Event>
+        + From<reorganization::Event>
+        + From<exchange::Event>
+        + From<coupon::Event>
         + Into<<Self as frame_system::Config>::RuntimeEvent>;
 
     /// Max number of DID specified in `TargetIdentities`.
@@ -338,13 +373,25 @@ pub trait Config: frame_system::Config + BalancesConfig + IdentityConfig + asset
 
     /// Weight information for extrinsics in the capital distribution pallet.
     type DistWeightInfo: distribution::WeightInfo;
+
+    /// Weight information for extrinsics in the corporate reorganization pallet.
+    type ReorgWeightInfo: reorganization::WeightInfo;
+
+    /// Weight information for extrinsics in the corporate exchange pallet.
+    type ExchangeWeightInfo: exchange::WeightInfo;
+
+    /// Weight information for extrinsics in the corporate coupon pallet.
+    type CouponWeightInfo: coupon::WeightInfo;
 }
 
 type Asset<T> = pallet_asset::Module<T>;
 type Ballot<T> = ballot::Module<T>;
 type Checkpoint<T> = checkpoint::Module<T>;
 type Distribution<T> = distribution::Module<T>;
+type Exchange<T> = exchange::Module<T>;
 type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type Identity<T> = pallet_identity::Module<T>;
+type Reorganization<T> = reorganization::Module<T>;
 
 decl_storage! {
     trait Store for Module<T: Config> as CorporateAction {
@@ -379,6 +426,19 @@ decl_storage! {
         /// (ticker => [(did, % to withhold)]
         pub DidWithholdingTax get(fn did_withholding_tax): map hasher(blake2_128_concat) Ticker => Vec<(IdentityId, Tax)>;
 
+        /// The tax jurisdiction of the issuer of a ticker, if any.
+        ///
+        /// (ticker => issuer's tax jurisdiction)
+        pub IssuerTaxJurisdiction get(fn issuer_tax_jurisdiction): map hasher(blake2_128_concat) Ticker => Option<TaxJurisdiction>;
+
+        /// The treaty withholding tax rates between countries.
+        /// If a rate exists for the issuer's and a holder's country,
+        /// it overrides the default in `DefaultWithholdingTax`, but not those in `DidWithholdingTax`.
+        ///
+        /// (issuer's country => holder's country => % to withhold)
+        pub TreatyWithholdingTax get(fn treaty_withholding_tax):
+            double_map hasher(blake2_128_concat) CountryCode, hasher(blake2_128_concat) CountryCode => Option<Tax>;
+
         /// The next per-`Ticker` CA ID in the sequence.
         /// The full ID is defined as a combination of `Ticker` and a number in this sequence.
         pub CAIdSequence get(fn ca_id_sequence): map hasher(blake2_128_concat) Ticker => LocalCAId;
@@ -604,7 +664,7 @@ decl_module! {
         /// Removes the CA identified by `ca_id`.
         ///
         /// Associated data, such as document links, ballots,
-        /// and capital distributions are also removed.
+        /// capital distributions, stock splits and exchange offers are also removed.
         ///
         /// Any schedule associated with the record date will see
         /// `strong_ref_count(schedule_id)` decremented.
@@ -620,7 +680,11 @@ decl_module! {
         /// # Permissions
         /// * Asset
         #[weight = <T as Config>::WeightInfo::remove_ca_with_ballot()
-            .max(<T as Config>::WeightInfo::remove_ca_with_dist())]
+            .max(<T as Config>::WeightInfo::remove_ca_with_dist())
+            .saturating_add(
+                <T as Config>::ReorgWeightInfo::remove_split()
+                    .max(<T as Config>::ExchangeWeightInfo::remove_offer()),
+            )]
         pub fn remove_ca(origin, ca_id: CAId) {
             // Ensure origin is a permissioned agent + CA exists.
             let agent = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
@@ -628,7 +692,15 @@ decl_module! {
 
             // Remove associated services.
             match ca.kind {
-                CAKind::Other | CAKind::Reorganization => {}
+                CAKind::Other => {}
+                CAKind::Reorganization => {
+                    if let Some(split) = <Reorganization<T>>::splits(ca_id) {
+                        <Reorganization<T>>::unverified_remove_split(agent, ca_id, &split)?;
+                    }
+                    if let Some(offer) = <Exchange<T>>::offers(ca_id) {
+                        <Exchange<T>>::unverified_remove_offer(agent, ca_id, &offer)?;
+                    }
+                }
                 CAKind::IssuerNotice => {
                     if let Some(range) = <Ballot<T>>::time_ranges(ca_id) {
                         <Ballot<T>>::remove_ballot_base(agent, ca_id, range)?;
@@ -681,7 +753,17 @@ decl_module! {
 
                 // Ensure associated services allow changing the date.
                 match ca.kind {
-                    CAKind::Other | CAKind::Reorganization => {}
+                    CAKind::Other => {}
+                    CAKind::Reorganization => {
+                        if let Some(split) = <Reorganization<T>>::splits(ca_id) {
+                            ensure!(ca.record_date.is_some(), Error::<T>::NoRecordDate);
+                            <Reorganization<T>>::ensure_split_not_started(&split)?;
+                        }
+                        if let Some(offer) = <Exchange<T>>::offers(ca_id) {
+                            Self::ensure_record_date_before_start(&ca, offer.deadline)?;
+                            <Exchange<T>>::ensure_offer_not_started(&offer)?;
+                        }
+                    }
                     CAKind::IssuerNotice => {
                         if let Some(range) = <Ballot<T>>::time_ranges(ca_id) {
                             Self::ensure_record_date_before_start(&ca, range.start)?;
@@ -759,6 +841,47 @@ decl_module! {
                 )
             })
         }
+
+        /// Set the tax jurisdiction of the issuer of `ticker` to `jurisdiction`,
+        /// or remove it if `None`, disabling treaty withholding tax rates for `ticker`.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be an external agent of `ticker` with relevant permissions.
+        /// - `ticker` that the jurisdiction will apply to.
+        /// - `jurisdiction` is the issuer's country and the trusted issuer of holders' jurisdiction claims.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::set_issuer_tax_jurisdiction()]
+        pub fn set_issuer_tax_jurisdiction(origin, ticker: Ticker, jurisdiction: Option<TaxJurisdiction>) {
+            let agent = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+            IssuerTaxJurisdiction::mutate(ticker, |slot| *slot = jurisdiction);
+            Self::deposit_event(Event::IssuerTaxJurisdictionChanged(agent, ticker, jurisdiction));
+        }
+
+        /// Set the treaty withholding tax for holders in `holder_country`
+        /// of assets whose issuer is in `issuer_country` to `tax`,
+        /// or remove the treaty rate if `None`.
+        /// May only be called via a PIP.
+        #[weight = <T as Config>::WeightInfo::set_treaty_withholding_tax()]
+        pub fn set_treaty_withholding_tax(
+            origin,
+            issuer_country: CountryCode,
+            holder_country: CountryCode,
+            tax: Option<Tax>,
+        ) {
+            ensure_root(origin)?;
+            TreatyWithholdingTax::mutate(issuer_country, holder_country, |slot| *slot = tax);
+            Self::deposit_event(Event::TreatyWithholdingTaxChanged(
+                GC_DID,
+                issuer_country,
+                holder_country,
+                tax,
+            ));
+        }
     }
 }
 
@@ -787,6 +910,12 @@ decl_event! {
         CARemoved(EventDid, CAId),
         /// A CA's record date changed.
         RecordDateChanged(EventDid, CAId, CorporateAction),
+        /// The issuer's tax jurisdiction for a ticker changed.
+        /// (Agent DID, Ticker, New jurisdiction)
+        IssuerTaxJurisdictionChanged(IdentityId, Ticker, Option<TaxJurisdiction>),
+        /// A treaty withholding tax rate changed.
+        /// (GC DID, Issuer's country, Holder's country, New Tax)
+        TreatyWithholdingTaxChanged(IdentityId, CountryCode, CountryCode, Option<Tax>),
     }
 }
 
@@ -905,6 +1034,38 @@ impl<T: Config> Module<T> {
         Ok(id)
     }
 
+    /// Returns the tax to withhold from `holder` in `ca`, a CA of `ticker`.
+    ///
+    /// Per-DID overrides take precedence, followed by any treaty rate
+    /// for the issuer's and `holder`'s countries, and finally the CA's default.
+    pub(crate) fn withholding_tax_of(
+        ca: &CorporateAction,
+        ticker: Ticker,
+        holder: IdentityId,
+    ) -> Tax {
+        ca.did_tax(&holder)
+            .or_else(|| Self::treaty_tax_of(ticker, holder))
+            .unwrap_or(ca.default_withholding_tax)
+    }
+
+    /// Returns the treaty rate for `holder` in `ticker`,
+    /// if the issuer's jurisdiction is set and `holder` has a valid jurisdiction claim.
+    fn treaty_tax_of(ticker: Ticker, holder: IdentityId) -> Option<Tax> {
+        let jurisdiction = IssuerTaxJurisdiction::get(ticker)?;
+        let claim = <Identity<T>>::fetch_claim(
+            holder,
+            ClaimType::Jurisdiction,
+            jurisdiction.claim_issuer,
+            Some(Scope::Ticker(ticker)),
+        )?;
+        match claim.claim {
+            Claim::Jurisdiction(country, _) => {
+                TreatyWithholdingTax::get(jurisdiction.country, country)
+            }
+            _ => None,
+        }
+    }
+
     /// Ensure number of identities in `TargetIdentities` are limited.
     fn ensure_target_ids_limited(targets: &TargetIdentities) -> DispatchResult {
         ensure!(
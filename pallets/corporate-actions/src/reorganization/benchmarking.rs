@@ -0,0 +1,69 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use core::iter;
+use frame_benchmarking::benchmarks;
+
+use polymesh_common_utilities::benchs::{user, AccountIdOf, User};
+use polymesh_common_utilities::TestUtilsFn;
+use polymesh_primitives::PortfolioName;
+
+use super::*;
+use crate::benchmarking::{setup_ca, SEED};
+
+const MAX_HOLDERS: u32 = 100;
+const MAX_PORTFOLIOS: u32 = 100;
+
+fn split<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> (User<T>, CAId) {
+    let (owner, ca_id) = setup_ca::<T>(CAKind::Reorganization);
+    <Module<T>>::split(owner.origin().into(), ca_id, 2, 1, SplitRounding::Down).unwrap();
+    (owner, ca_id)
+}
+
+benchmarks! {
+    where_clause { where T: TestUtilsFn<AccountIdOf<T>> }
+
+    split {
+        let (owner, ca_id) = setup_ca::<T>(CAKind::Reorganization);
+    }: _(owner.origin(), ca_id, 2, 1, SplitRounding::Down)
+    verify {
+        assert!(Splits::get(ca_id).is_some(), "split not created");
+    }
+
+    rescale_holders {
+        let h in 1..MAX_HOLDERS;
+        let p in 0..MAX_PORTFOLIOS;
+
+        let (owner, ca_id) = split::<T>();
+        for i in 0..p {
+            <Portfolio<T>>::create_portfolio(owner.origin().into(), PortfolioName(i.encode())).unwrap();
+        }
+        <Asset<T>>::freeze(owner.origin().into(), ca_id.ticker).unwrap();
+        <pallet_timestamp::Now<T>>::set(3000u32.into());
+        let holders = iter::once(owner.did())
+            .chain((1..h).map(|i| user::<T>("holder", SEED + i).did()))
+            .collect::<Vec<_>>();
+    }: _(owner.origin(), ca_id, holders, p)
+    verify {
+        assert_eq!(Splits::get(ca_id).unwrap().holders_rescaled, h, "not rescaled");
+    }
+
+    remove_split {
+        let (owner, ca_id) = split::<T>();
+    }: _(owner.origin(), ca_id)
+    verify {
+        assert!(Splits::get(ca_id).is_none(), "not removed");
+    }
+}
@@ -0,0 +1,510 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! # Corporate Reorganization Module
+//!
+//! The corporate reorganization module provides functionality for executing
+//! stock splits and reverse splits on-chain.
+//!
+//! The process works by first initiating a corporate action (CA) of kind `Reorganization`,
+//! with a record date, through `initiate_corporate_action`,
+//! and then attaching a split to it via `split`.
+//!
+//! A split is described by a ratio of `new_shares` for every `old_shares`,
+//! e.g., `2:1` doubles every balance, while `1:10` divides every balance by ten.
+//! Once the record date has passed, and the asset has been frozen by an external agent,
+//! the agent rescales the holders, in batches, through `rescale_holders`.
+//! From the first rescaled holder on, the asset can neither be unfrozen, issued nor redeemed
+//! until every holder with a balance has been rescaled.
+//! Every portfolio of a holder is rescaled, as are the total supply and the statistics of the asset.
+//! The checkpoint at the record date keeps the balances before the split,
+//! while checkpoints going forward record the rescaled balances.
+//! Unlike other CAs, a split applies to every holder of the asset, regardless of the CA's targets.
+//!
+//! Rescaled balances that are not a multiple of the asset's granularity,
+//! which is one whole unit for indivisible assets, are rounded according to the split's `SplitRounding`.
+//! Whenever a balance is rounded down, the fraction the holder lost is recorded in a `CashInLieu` event,
+//! to be settled off-chain by the issuer.
+//!
+//! Before the first holder is rescaled, a split can be removed by calling `remove_split`.
+//!
+//! The amounts of vesting schedules are not rescaled, so a split can neither be attached
+//! nor started while some tokens of the asset are vesting.
+//!
+//! ## Overview
+//!
+//! The module provides functions for:
+//!
+//! - Attaching a split to a reorganization CA.
+//! - Rescaling the balances of holders once the record date has passed.
+//! - Removing a split that hasn't started yet.
+//!
+//! ### Terminology
+//!
+//! - **Split ratio:** The number of shares held after the split for a number of shares held before it.
+//! - **Cash in lieu:** The fraction of a share that a holder is compensated for off-chain,
+//!   as their rescaled balance was rounded down.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `split` attaches a split to a reorganization CA.
+//! - `rescale_holders` rescales the balances of a batch of holders according to a split.
+//! - `remove_split` removes a split which hasn't rescaled any holder yet.
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+use crate as ca;
+use ca::{exchange, CAId, CAKind, Config};
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    weights::Weight,
+};
+use pallet_asset::{self as asset, checkpoint};
+use polymesh_common_utilities::{constants::currency::ONE_UNIT, with_transaction};
+use polymesh_primitives::{
+    storage_migration_ver, Balance, EventDid, IdentityId, PortfolioId, WeightMeter,
+};
+use scale_info::TypeInfo;
+use sp_runtime::traits::Zero;
+#[cfg(feature = "std")]
+use sp_runtime::{Deserialize, Serialize};
+use sp_std::prelude::*;
+
+type Asset<T> = asset::Module<T>;
+type Checkpoint<T> = checkpoint::Module<T>;
+type Exchange<T> = exchange::Module<T>;
+type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type CA<T> = ca::Module<T>;
+type Portfolio<T> = pallet_portfolio::Module<T>;
+
+/// How rescaled balances that aren't a multiple of the asset's granularity are rounded.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub enum SplitRounding {
+    /// Round down, compensating the fraction as cash in lieu.
+    Down,
+    /// Round up to the next multiple.
+    Up,
+    /// Round to the nearest multiple, with halves rounded up.
+    /// Fractions rounded down are compensated as cash in lieu.
+    Nearest,
+}
+
+/// A stock split's various details.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct Split {
+    /// The number of shares held after the split for every `old_shares`,
+    /// e.g., `2` in a `2:1` split.
+    pub new_shares: u32,
+    /// The number of shares held before the split for every `new_shares`,
+    /// e.g., `10` in a `1:10` reverse split.
+    pub old_shares: u32,
+    /// How rescaled balances are rounded.
+    pub rounding: SplitRounding,
+    /// The number of holders rescaled so far.
+    pub holders_rescaled: u32,
+}
+
+impl Split {
+    /// Returns `balance` rescaled by the split ratio and rounded to a multiple of `unit`,
+    /// along with the fraction owed to the holder as cash in lieu, if it was rounded down.
+    ///
+    /// Returns `None` on overflow.
+    pub fn rescale(&self, balance: Balance, unit: Balance) -> Option<(Balance, Balance)> {
+        let old_shares = Balance::from(self.old_shares);
+        let product = balance.checked_mul(self.new_shares.into())?;
+        let exact = product / old_shares;
+        let down = exact - exact % unit;
+        // The rounded off part, in `1 / old_shares`th of the smallest denomination.
+        // Cannot overflow as `exact - down < unit`.
+        let fraction = (exact - down) * old_shares + product % old_shares;
+        let round_up = match self.rounding {
+            SplitRounding::Down => false,
+            SplitRounding::Up => !fraction.is_zero(),
+            SplitRounding::Nearest => fraction * 2 >= unit * old_shares,
+        };
+        if round_up {
+            Some((down.checked_add(unit)?, 0))
+        } else {
+            Some((down, exact - down))
+        }
+    }
+}
+
+/// Weight abstraction for the corporate reorganization module.
+pub trait WeightInfo {
+    fn split() -> Weight;
+    fn rescale_holders(holders: u32, portfolios: u32) -> Weight;
+    fn remove_split() -> Weight;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as CorporateReorganization {
+        /// All stock splits, tied to their respective corporate actions (CAs).
+        ///
+        /// (CAId) => Split
+        Splits get(fn splits): map hasher(blake2_128_concat) CAId => Option<Split>;
+
+        /// Has an asset holder been rescaled yet?
+        ///
+        /// (CAId, DID) -> Was DID rescaled in the CAId?
+        HolderRescaled get(fn holder_rescaled): map hasher(blake2_128_concat) (CAId, IdentityId) => bool;
+
+        /// Storage version.
+        StorageVersion get(fn storage_version) build(|_| Version::new(0)): Version;
+    }
+}
+
+storage_migration_ver!(0);
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::RuntimeOrigin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Attach a stock split, of `new_shares` for every `old_shares`, to the CA identified by `ca_id`.
+        ///
+        /// Holders may be rescaled once the record date of the CA has passed.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ca_id.ticker`.
+        /// - `ca_id` identifies the CA to attach the split to.
+        /// - `new_shares` is the number of shares held after the split for every `old_shares`.
+        /// - `old_shares` is the number of shares held before the split for every `new_shares`.
+        /// - `rounding` specifies how rescaled balances are rounded to the asset's granularity.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `CANotReorganization` if the CA is not of kind Reorganization.
+        /// - `NoRecordDate` if the CA has no record date.
+        /// - `AlreadyExists` if a split or an exchange offer is already attached to the CA.
+        /// - `InvalidSplitRatio` if either side of the ratio is zero, or both are equal.
+        /// - `AssetHasVestingSchedules` if some tokens of the asset are vesting.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::ReorgWeightInfo::split()]
+        pub fn split(
+            origin,
+            ca_id: CAId,
+            new_shares: u32,
+            old_shares: u32,
+            rounding: SplitRounding,
+        ) {
+            let agent = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
+
+            // Ensure the ratio changes something.
+            ensure!(
+                !new_shares.is_zero() && !old_shares.is_zero() && new_shares != old_shares,
+                Error::<T>::InvalidSplitRatio
+            );
+
+            // Ensure that `ca_id` exists, is a reorganization, and has a record date.
+            let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+            ensure!(ca.kind == CAKind::Reorganization, Error::<T>::CANotReorganization);
+            ensure!(ca.record_date.is_some(), ca::Error::<T>::NoRecordDate);
+            ensure!(
+                !<Asset<T>>::has_vesting_schedules(&ca_id.ticker),
+                Error::<T>::AssetHasVestingSchedules
+            );
+
+            // Ensure CA doesn't have a split or an exchange offer yet.
+            ensure!(
+                !Splits::contains_key(ca_id) && <Exchange<T>>::offers(ca_id).is_none(),
+                Error::<T>::AlreadyExists
+            );
+
+            // Commit to storage + emit event.
+            let split = Split {
+                new_shares,
+                old_shares,
+                rounding,
+                holders_rescaled: 0,
+            };
+            Splits::insert(ca_id, split);
+            Self::deposit_event(Event::Created(agent, ca_id, split));
+        }
+
+        /// Rescale the balances of `holders` according to the split attached to `ca_id`.
+        ///
+        /// Every portfolio of a holder is rescaled and rounded separately.
+        /// Holders without any balance are marked as rescaled without any changes.
+        ///
+        /// Rescaling the first holder starts the split, after which the asset cannot be unfrozen,
+        /// issued, redeemed or controller transferred until every holder with a balance is rescaled.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ca_id.ticker`.
+        /// - `ca_id` identifies the CA with a split to rescale holders for.
+        /// - `holders` to rescale.
+        /// - `portfolios` is the number of user portfolios, across all `holders`, to rescale.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchSplit` if there's no split for `ca_id`.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `CannotRescaleBeforeRecordDate` if `now < record_date`.
+        /// - `AssetNotFrozen` if the asset isn't frozen.
+        /// - `HolderAlreadyRescaled` if a holder has already been rescaled.
+        /// - `HolderHasLockedAssets` if some assets are locked in a portfolio of a holder.
+        /// - `RescaledBalanceOverflowed` if a rescaled balance would overflow.
+        /// - `TooManyPortfolios` if `holders` have more than `portfolios` user portfolios.
+        /// - `RescaleInProgress` if another split of the asset is yet to rescale some holders.
+        /// - `AssetHasVestingSchedules` if the split hasn't started and some tokens of the asset are vesting.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::ReorgWeightInfo::rescale_holders(holders.len() as u32, *portfolios)]
+        pub fn rescale_holders(origin, ca_id: CAId, holders: Vec<IdentityId>, portfolios: u32) {
+            let agent = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?;
+            Self::base_rescale_holders(agent, ca_id, holders, portfolios)?;
+        }
+
+        /// Removes a split that hasn't rescaled any holder yet.
+        ///
+        /// ## Arguments
+        /// - `origin` is a signer that has permissions to act as an agent of `ca_id.ticker`.
+        /// - `ca_id` identifies the CA with a split to remove.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchSplit` if there's no split for `ca_id`.
+        /// - `SplitStarted` if some holders were already rescaled.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::ReorgWeightInfo::remove_split()]
+        pub fn remove_split(origin, ca_id: CAId) {
+            let agent = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
+            let split = Self::ensure_split_exists(ca_id)?;
+            Self::unverified_remove_split(agent, ca_id, &split)?;
+        }
+    }
+}
+
+decl_event! {
+    pub enum Event {
+        /// A stock split, with details included,
+        /// was created by the DID (permissioned agent) for the CA identified by `CAId`.
+        ///
+        /// (Agent DID, CA's ID, split details)
+        Created(EventDid, CAId, Split),
+
+        /// A holder's balance was rescaled by the split of the given `CAId`.
+        ///
+        /// (Agent DID, CA's ID, holder DID, balance before, balance after)
+        HolderRescaled(EventDid, CAId, IdentityId, Balance, Balance),
+
+        /// A holder's rescaled balance was rounded down,
+        /// and the holder is owed the fraction as cash in lieu.
+        ///
+        /// (CA's ID, holder DID, fraction in the smallest denomination of the asset)
+        CashInLieu(CAId, IdentityId, Balance),
+
+        /// A stock split was removed.
+        ///
+        /// (Agent DID, CA's ID)
+        Removed(EventDid, CAId),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// A stock split was made for a non-reorganization CA.
+        CANotReorganization,
+        /// A split or an exchange offer already exists for this CA.
+        AlreadyExists,
+        /// The split ratio has a zero side, or doesn't change any balance.
+        InvalidSplitRatio,
+        /// A split doesn't exist for this CA.
+        NoSuchSplit,
+        /// Holders cannot be rescaled as the current time is before the record date.
+        CannotRescaleBeforeRecordDate,
+        /// The asset must be frozen while holders are rescaled.
+        AssetNotFrozen,
+        /// The holder has already been rescaled.
+        HolderAlreadyRescaled,
+        /// Some assets are locked in a portfolio of the holder.
+        HolderHasLockedAssets,
+        /// Multiplication of a balance with the split ratio overflowed.
+        RescaledBalanceOverflowed,
+        /// Some holders have already been rescaled by the split.
+        SplitStarted,
+        /// The holders have more user portfolios than were given.
+        TooManyPortfolios,
+        /// Some tokens of the asset are vesting, and their schedules can't be rescaled.
+        AssetHasVestingSchedules,
+    }
+}
+
+impl<T: Config> Module<T> {
+    fn base_rescale_holders(
+        agent: IdentityId,
+        ca_id: CAId,
+        holders: Vec<IdentityId>,
+        portfolios: u32,
+    ) -> DispatchResult {
+        let mut split = Self::ensure_split_exists(ca_id)?;
+
+        // Ensure the record date has passed. The CA always has one when a split is attached.
+        let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+        let record_date = ca.record_date.ok_or(ca::Error::<T>::NoRecordDate)?;
+        ensure!(
+            <Checkpoint<T>>::now_unix() >= record_date.date,
+            Error::<T>::CannotRescaleBeforeRecordDate
+        );
+
+        // Balances must not move between holders while they're rescaled.
+        let ticker = ca_id.ticker;
+        ensure!(<Asset<T>>::frozen(ticker), Error::<T>::AssetNotFrozen);
+
+        let unit = if <Asset<T>>::is_divisible(&ticker) {
+            1
+        } else {
+            ONE_UNIT
+        };
+        with_transaction(|| {
+            // Track the supply left to rescale from the first holder on.
+            if split.holders_rescaled == 0 && !holders.is_empty() {
+                // Schedules may have been created since the split was attached.
+                ensure!(
+                    !<Asset<T>>::has_vesting_schedules(&ticker),
+                    Error::<T>::AssetHasVestingSchedules
+                );
+                <Asset<T>>::start_rescale(&ticker)?;
+            }
+            let mut portfolios_left = portfolios;
+            for holder in holders {
+                Self::rescale_holder(agent, ca_id, &split, unit, holder, &mut portfolios_left)?;
+                split.holders_rescaled = split.holders_rescaled.saturating_add(1);
+            }
+            Ok::<_, DispatchError>(())
+        })?;
+        Splits::insert(ca_id, split);
+        Ok(())
+    }
+
+    /// Rescale every portfolio of `holder` according to `split`,
+    /// deducting the user portfolios of `holder` from `portfolios_left`.
+    fn rescale_holder(
+        agent: IdentityId,
+        ca_id: CAId,
+        split: &Split,
+        unit: Balance,
+        holder: IdentityId,
+        portfolios_left: &mut u32,
+    ) -> DispatchResult {
+        ensure!(
+            !HolderRescaled::get((ca_id, holder)),
+            Error::<T>::HolderAlreadyRescaled
+        );
+
+        let ticker = ca_id.ticker;
+        let portfolios = Self::holder_portfolios(holder, portfolios_left)
+            .ok_or(Error::<T>::TooManyPortfolios)?;
+        let mut balances = Vec::new();
+        let mut cash_in_lieu: Balance = 0;
+        for pid in portfolios {
+            let balance = <Portfolio<T>>::portfolio_asset_balances(pid, ticker);
+            if balance.is_zero() {
+                continue;
+            }
+            ensure!(
+                <Portfolio<T>>::locked_assets(pid, ticker).is_zero(),
+                Error::<T>::HolderHasLockedAssets
+            );
+            let (rescaled, fraction) = split
+                .rescale(balance, unit)
+                .ok_or(Error::<T>::RescaledBalanceOverflowed)?;
+            cash_in_lieu = cash_in_lieu.saturating_add(fraction);
+            balances.push((pid, rescaled));
+        }
+
+        let before = <Asset<T>>::balance_of(ticker, holder);
+        let mut weight_meter = WeightMeter::max_limit_no_minimum();
+        <Asset<T>>::unsafe_set_portfolio_balances(
+            agent,
+            &ticker,
+            holder,
+            &balances,
+            &mut weight_meter,
+        )?;
+        let after = <Asset<T>>::balance_of(ticker, holder);
+
+        // Note that DID was rescaled + emit events.
+        HolderRescaled::insert((ca_id, holder), true);
+        Self::deposit_event(Event::HolderRescaled(
+            agent.for_event(),
+            ca_id,
+            holder,
+            before,
+            after,
+        ));
+        if !cash_in_lieu.is_zero() {
+            Self::deposit_event(Event::CashInLieu(ca_id, holder, cash_in_lieu));
+        }
+        Ok(())
+    }
+
+    /// Returns the default portfolio of `holder`, followed by its user portfolios,
+    /// deducting the latter from `portfolios_left`.
+    ///
+    /// Returns `None` if `holder` has more than `portfolios_left` user portfolios.
+    pub(crate) fn holder_portfolios(
+        holder: IdentityId,
+        portfolios_left: &mut u32,
+    ) -> Option<Vec<PortfolioId>> {
+        let mut portfolios = vec![PortfolioId::default_portfolio(holder)];
+        for num in pallet_portfolio::Portfolios::iter_key_prefix(holder) {
+            *portfolios_left = portfolios_left.checked_sub(1)?;
+            portfolios.push(PortfolioId::user_portfolio(holder, num));
+        }
+        Some(portfolios)
+    }
+
+    /// Kill the split identified by `ca_id`.
+    ///
+    /// Unlike `remove_split`, this won't check permissions and that the split exists.
+    pub(crate) fn unverified_remove_split(
+        agent: EventDid,
+        ca_id: CAId,
+        split: &Split,
+    ) -> DispatchResult {
+        Self::ensure_split_not_started(split)?;
+        Splits::remove(ca_id);
+        Self::deposit_event(Event::Removed(agent, ca_id));
+        Ok(())
+    }
+
+    /// Ensure that no holder has been rescaled by `split` yet.
+    pub(crate) fn ensure_split_not_started(split: &Split) -> DispatchResult {
+        ensure!(split.holders_rescaled == 0, Error::<T>::SplitStarted);
+        Ok(())
+    }
+
+    /// Ensure `ca_id` has some split and return it.
+    fn ensure_split_exists(ca_id: CAId) -> Result<Split, DispatchError> {
+        Splits::get(ca_id).ok_or_else(|| Error::<T>::NoSuchSplit.into())
+    }
+}
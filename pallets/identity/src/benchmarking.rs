@@ -18,12 +18,16 @@ use crate::*;
 use frame_benchmarking::{account, benchmarks};
 use frame_system::RawOrigin;
 use sp_core::H512;
+use sp_runtime::MultiSignature;
 use sp_std::prelude::*;
 
 use polymesh_common_utilities::benchs::{
     cdd_provider, user, user_without_did, AccountIdOf, UserBuilder,
 };
-use polymesh_common_utilities::traits::{identity::TargetIdAuthorization, TestUtilsFn};
+use polymesh_common_utilities::traits::{
+    identity::{ClaimAttestation, TargetIdAuthorization},
+    TestUtilsFn,
+};
 use polymesh_primitives::identity::limits::{
     MAX_ASSETS, MAX_EXTRINSICS, MAX_PALLETS, MAX_PORTFOLIOS, MAX_SECONDARY_KEYS,
 };
@@ -397,4 +401,33 @@ benchmarks! {
         assert_ne!(id, Module::<T>::custom_claim_id_seq());
     }
 
+    add_claim_with_attestation {
+        let caller = user::<T>("caller", 0);
+        let issuer = user::<T>("issuer", 0);
+        let scope = Scope::Identity(issuer.did());
+        let attestation = ClaimAttestation::<T::Moment, T::Hash> {
+            genesis_hash: frame_system::Pallet::<T>::block_hash(T::BlockNumber::from(0u32)),
+            target: caller.did(),
+            claim: Claim::Jurisdiction(CountryCode::BB, scope),
+            expiry: Some(666u32.into()),
+            issued_at: 0u32.into(),
+            valid_until: 666u32.into(),
+            nonce: 0,
+        };
+        let encoded_signature = MultiSignature::from(issuer.sign(&attestation.signing_payload()).unwrap()).encode();
+        let signature = T::OffChainSignature::decode(&mut &encoded_signature[..]).unwrap();
+    }: _(
+        caller.origin,
+        attestation.claim,
+        attestation.expiry,
+        attestation.issued_at,
+        attestation.valid_until,
+        attestation.nonce,
+        issuer.account(),
+        signature
+    )
+    verify {
+        assert!(Module::<T>::used_claim_attestation_nonce(issuer.did(), 0));
+    }
+
 }
@@ -15,7 +15,7 @@
 
 use crate::{
     Claim1stKey, Claim2ndKey, Claims, CustomClaimIdSequence, CustomClaims, CustomClaimsInverse,
-    DidRecords, Error, Event, Module, ParentDid,
+    DidRecords, Error, Event, Module, ParentDid, UsedClaimAttestationNonces,
 };
 use frame_support::{
     dispatch::{DispatchError, DispatchResult},
@@ -25,18 +25,20 @@ use frame_system::ensure_root;
 use pallet_base::{ensure_string_limited, try_next_pre};
 
 use polymesh_common_utilities::{
-    protocol_fee::ProtocolOp,
+    protocol_fee::{ChargeProtocolFee, ProtocolOp},
     traits::{
         group::{GroupTrait, InactiveMember},
-        identity::{Config, RawEvent},
+        identity::{AuthorizationNonce, ClaimAttestation, Config, RawEvent},
+        permissions::CheckAccountCallPermissions,
     },
     SystematicIssuers,
 };
 use polymesh_primitives::identity_claim::CustomClaimTypeId;
 use polymesh_primitives::{
-    CddId, Claim, ClaimType, IdentityClaim, IdentityId, Scope, SecondaryKey,
+    CddId, Claim, ClaimType, DispatchableName, IdentityClaim, IdentityId, PalletName, Scope,
+    SecondaryKey,
 };
-use sp_runtime::traits::{CheckedAdd, SaturatedConversion, Zero};
+use sp_runtime::traits::{CheckedAdd, SaturatedConversion, Verify, Zero};
 use sp_std::prelude::*;
 
 struct CddClaimChecker<T: Config> {
@@ -267,6 +269,17 @@ impl<T: Config> Module<T> {
         claim: Claim,
         issuer: IdentityId,
         expiry: Option<T::Moment>,
+    ) -> DispatchResult {
+        Self::base_add_claim_issued_at(target, claim, issuer, expiry, None)
+    }
+
+    /// It adds a new claim issued at `issued_at`, or now if `None`, without any previous security check.
+    fn base_add_claim_issued_at(
+        target: IdentityId,
+        claim: Claim,
+        issuer: IdentityId,
+        expiry: Option<T::Moment>,
+        issued_at: Option<T::Moment>,
     ) -> DispatchResult {
         let inner_scope = claim.as_scope().cloned();
         if let ClaimType::Custom(id) = claim.claim_type() {
@@ -275,7 +288,7 @@ impl<T: Config> Module<T> {
                 Error::<T>::CustomClaimTypeDoesNotExist
             );
         }
-        Self::unverified_add_claim_with_scope(target, claim, inner_scope, issuer, expiry);
+        Self::unverified_add_claim(target, claim, inner_scope, issuer, expiry, issued_at);
         Ok(())
     }
 
@@ -286,11 +299,27 @@ impl<T: Config> Module<T> {
         scope: Option<Scope>,
         issuer: IdentityId,
         expiry: Option<T::Moment>,
+    ) {
+        Self::unverified_add_claim(target, claim, scope, issuer, expiry, None)
+    }
+
+    /// Adds a claim issued at `issued_at`,
+    /// or when an existing claim was issued, or now, if `None`.
+    fn unverified_add_claim(
+        target: IdentityId,
+        claim: Claim,
+        scope: Option<Scope>,
+        issuer: IdentityId,
+        expiry: Option<T::Moment>,
+        issued_at: Option<T::Moment>,
     ) {
         let claim_type = claim.claim_type();
         let last_update_date = <pallet_timestamp::Pallet<T>>::get().saturated_into::<u64>();
-        let issuance_date = Self::fetch_claim(target, claim_type, issuer, scope.clone())
-            .map_or(last_update_date, |id_claim| id_claim.issuance_date);
+        let issuance_date = match issued_at {
+            Some(issued_at) => issued_at.saturated_into::<u64>(),
+            None => Self::fetch_claim(target, claim_type, issuer, scope.clone())
+                .map_or(last_update_date, |id_claim| id_claim.issuance_date),
+        };
 
         let expiry = expiry.map(|m| m.saturated_into::<u64>());
         let (pk, sk) = Self::get_claim_keys(target, claim_type, issuer, scope);
@@ -333,6 +362,64 @@ impl<T: Config> Module<T> {
         Self::base_add_claim(target, claim, issuer, expiry)
     }
 
+    /// Adds `claim` to the caller's identity once the issuer's off-chain attestation is verified.
+    pub(crate) fn base_add_claim_with_attestation(
+        origin: T::RuntimeOrigin,
+        claim: Claim,
+        expiry: Option<T::Moment>,
+        issued_at: T::Moment,
+        valid_until: T::Moment,
+        nonce: AuthorizationNonce,
+        signer: T::AccountId,
+        signature: T::OffChainSignature,
+    ) -> DispatchResult {
+        let target = Self::ensure_perms(origin)?;
+        let issuer =
+            Self::get_identity(&signer).ok_or(Error::<T>::ClaimAttestationSignerNotLinked)?;
+        // The signer must be the primary key, or a secondary key permitted to add claims.
+        Self::check_account_call_permissions(
+            &signer,
+            || PalletName::from("Identity"),
+            || DispatchableName::from("add_claim"),
+        )
+        .ok_or(Error::<T>::ClaimAttestationSignerNotPermitted)?;
+        ensure!(
+            !UsedClaimAttestationNonces::get(issuer, nonce),
+            Error::<T>::ClaimAttestationNonceUsed
+        );
+        let now = <pallet_timestamp::Pallet<T>>::get();
+        ensure!(issued_at <= now, Error::<T>::ClaimAttestationNotYetIssued);
+        ensure!(now < valid_until, Error::<T>::ClaimAttestationNoLongerValid);
+        if let Some(expiry) = expiry {
+            ensure!(now < expiry, Error::<T>::ClaimAttestationExpired);
+        }
+
+        let attestation = ClaimAttestation {
+            genesis_hash: <frame_system::Pallet<T>>::block_hash(T::BlockNumber::zero()),
+            target,
+            claim,
+            expiry,
+            issued_at,
+            valid_until,
+            nonce,
+        };
+        ensure!(
+            signature.verify(attestation.signing_payload().as_slice(), &signer),
+            Error::<T>::InvalidClaimAttestationSignature
+        );
+
+        let claim = attestation.claim;
+        if let Claim::CustomerDueDiligence(..) = &claim {
+            Self::ensure_authorized_cdd_provider(issuer)?;
+        } else {
+            Self::ensure_custom_scopes_limited(&claim)?;
+            T::ProtocolFee::charge_fee(ProtocolOp::IdentityAddClaim)?;
+        }
+        Self::base_add_claim_issued_at(target, claim, issuer, expiry, Some(issued_at))?;
+        UsedClaimAttestationNonces::insert(issuer, nonce, true);
+        Ok(())
+    }
+
     /// It removes a claim from `target` which was issued by `issuer` without any security check.
     pub(crate) fn base_revoke_claim(
         target: IdentityId,
@@ -66,6 +66,7 @@
 //! - `change_cdd_requirement_for_mk_rotation` - Sets if CDD authorization is required for updating primary key of an identity.
 //! - `join_identity_as_key` - Join an identity as a secondary key.
 //! - `add_claim` - Adds a new claim record or edits an existing one.
+//! - `add_claim_with_attestation` - Adds a claim attested off-chain by its issuer.
 //! - `revoke_claim` - Marks the specified claim as revoked.
 //! - `revoke_claim_by_index` - Revoke a claim identified by its index.
 //! - `set_secondary_key_permissions` - Sets permissions for a secondary key.
@@ -189,6 +190,10 @@ decl_storage! {
         /// All child identities of a parent (i.e ParentDID, ChildDID, true)
         pub ChildDid get(fn child_did):
             double_map hasher(identity) IdentityId, hasher(identity) IdentityId => bool;
+
+        /// Claim attestation nonces already used by an issuer (i.e IssuerDID, nonce, true)
+        pub UsedClaimAttestationNonces get(fn used_claim_attestation_nonce):
+            double_map hasher(identity) IdentityId, hasher(twox_64_concat) AuthorizationNonce => bool;
     }
     add_extra_genesis {
         // Identities at genesis.
@@ -575,6 +580,55 @@ decl_module! {
         pub fn unlink_child_identity(origin, child_did: IdentityId) {
             Self::base_unlink_child_identity(origin, child_did)?;
         }
+
+        /// Adds `claim` to the caller's identity, attested off-chain by its issuer.
+        ///
+        /// The issuer signs `CLAIM_ATTESTATION_PREFIX` followed by the encoded `ClaimAttestation`
+        /// (the chain's genesis hash, the caller's identity, `claim`, `expiry`, `issued_at`, `valid_until` and `nonce`)
+        /// with `signer`, which can be its sr25519, ed25519 or ECDSA primary key,
+        /// or a secondary key permitted to call `Identity::add_claim`.
+        /// The claim is issued at `issued_at`. The caller pays for the transaction and the protocol fee.
+        ///
+        /// # Arguments
+        /// - `claim` the attested claim.
+        /// - `expiry` the expiry of the claim, if any.
+        /// - `issued_at` when the issuer attested the claim.
+        /// - `valid_until` the time after which the attestation can no longer be registered.
+        /// - `nonce` a nonce not yet used by the issuer.
+        /// - `signer` the key of the issuer which signed the attestation.
+        /// - `signature` the signature of the attestation by `signer`.
+        ///
+        /// # Errors
+        /// - `ClaimAttestationSignerNotLinked` if `signer` isn't linked to an identity, or is a frozen secondary key.
+        /// - `ClaimAttestationSignerNotPermitted` if `signer` is a secondary key not permitted to add claims.
+        /// - `ClaimAttestationNonceUsed` if `nonce` was already used by the issuer.
+        /// - `ClaimAttestationNotYetIssued` if `issued_at` is in the future.
+        /// - `ClaimAttestationNoLongerValid` if `valid_until` has already passed.
+        /// - `ClaimAttestationExpired` if `expiry` has already passed.
+        /// - `InvalidClaimAttestationSignature` if `signature` is not valid.
+        /// - `UnAuthorizedCddProvider` if a CDD claim is attested by a non CDD provider.
+        #[weight = <T as Config>::WeightInfo::add_claim_with_attestation()]
+        pub fn add_claim_with_attestation(
+            origin,
+            claim: Claim,
+            expiry: Option<T::Moment>,
+            issued_at: T::Moment,
+            valid_until: T::Moment,
+            nonce: AuthorizationNonce,
+            signer: T::AccountId,
+            signature: T::OffChainSignature,
+        ) -> DispatchResult {
+            Self::base_add_claim_with_attestation(
+                origin,
+                claim,
+                expiry,
+                issued_at,
+                valid_until,
+                nonce,
+                signer,
+                signature,
+            )
+        }
     }
 }
 
@@ -647,6 +701,20 @@ decl_error! {
         DuplicateKey,
         /// Cannot use Except when specifying extrinsic permissions.
         ExceptNotAllowedForExtrinsics,
+        /// The signer of a claim attestation is not linked to an identity.
+        ClaimAttestationSignerNotLinked,
+        /// The claim attestation nonce has already been used by the issuer.
+        ClaimAttestationNonceUsed,
+        /// The attested claim has already expired.
+        ClaimAttestationExpired,
+        /// An invalid claim attestation signature.
+        InvalidClaimAttestationSignature,
+        /// The signer of a claim attestation is a secondary key not permitted to add claims.
+        ClaimAttestationSignerNotPermitted,
+        /// The claim attestation is issued in the future.
+        ClaimAttestationNotYetIssued,
+        /// The claim attestation can no longer be registered.
+        ClaimAttestationNoLongerValid,
     }
 }
 
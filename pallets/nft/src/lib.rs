@@ -19,8 +19,8 @@ use polymesh_primitives::nft::{
 };
 use polymesh_primitives::settlement::InstructionId;
 use polymesh_primitives::{
-    storage_migrate_on, storage_migration_ver, IdentityId, Memo, PortfolioId, PortfolioKind,
-    PortfolioUpdateReason, Ticker, WeightMeter,
+    storage_migrate_on, storage_migration_ver, Balance, IdentityId, Memo, PortfolioId,
+    PortfolioKind, PortfolioUpdateReason, Ticker, WeightMeter,
 };
 use sp_std::collections::btree_map::BTreeMap;
 use sp_std::collections::btree_set::BTreeSet;
@@ -224,7 +224,9 @@ decl_error! {
         /// An underflow while calculating the updated supply.
         SupplyUnderflow,
         /// Failed to transfer an NFT - nft is locked.
-        InvalidNFTTransferNFTIsLocked
+        InvalidNFTTransferNFTIsLocked,
+        /// Failed to transfer an NFT - the asset is frozen for the sender or the receiver.
+        InvalidNFTTransferFrozenHolder
     }
 }
 
@@ -357,6 +359,7 @@ impl<T: Config> Module<T> {
         let new_supply = NFTsInCollection::get(&ticker)
             .checked_add(1)
             .ok_or(Error::<T>::SupplyOverflow)?;
+        Asset::<T>::ensure_within_issuance_limit(&ticker, new_supply.into())?;
         let new_balance = NumberOfNFTs::get(&ticker, &caller_portfolio.did)
             .checked_add(1)
             .ok_or(Error::<T>::BalanceOverflow)?;
@@ -459,7 +462,7 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
-    /// Returns `Ok` if the asset is not frozen, if `sender_portfolio` owns all `nfts`, all arithmetic updates succeed,
+    /// Returns `Ok` if the asset is not frozen, for all holders or for either portfolio, if `sender_portfolio` owns all `nfts`, all arithmetic updates succeed,
     /// if `sender_portfolio` is different from `receiver_portfolio`, and if all compliance rules are being respected.
     pub fn validate_nft_transfer(
         sender_portfolio: &PortfolioId,
@@ -472,6 +475,12 @@ impl<T: Config> Module<T> {
             !Frozen::get(nfts.ticker()),
             Error::<T>::InvalidNFTTransferFrozenAsset
         );
+        // Verifies that the asset is not frozen for the sender nor the receiver
+        ensure!(
+            !Asset::<T>::holder_frozen(nfts.ticker(), sender_portfolio)
+                && !Asset::<T>::holder_frozen(nfts.ticker(), receiver_portfolio),
+            Error::<T>::InvalidNFTTransferFrozenHolder
+        );
         // Verifies that the sender_portfolio owns all nfts being transferred
         Self::validate_nft_ownership(sender_portfolio, receiver_portfolio, nfts)?;
         // Verifies that all compliance rules are being respected
@@ -479,6 +488,7 @@ impl<T: Config> Module<T> {
             nfts.ticker(),
             sender_portfolio.did,
             receiver_portfolio.did,
+            nfts.len() as Balance,
             weight_meter,
         )? {
             return Err(Error::<T>::InvalidNFTTransferComplianceFailure.into());
@@ -614,6 +624,10 @@ impl<T: Config> NFTTrait<T::RuntimeOrigin> for Module<T> {
         NFTOwner::insert(ticker, nft_id, new_owner_portfolio);
     }
 
+    fn nfts_in_collection(ticker: &Ticker) -> Balance {
+        Module::<T>::nfts_in_collection(ticker).into()
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     fn create_nft_collection(
         origin: T::RuntimeOrigin,
@@ -0,0 +1,172 @@
+use frame_benchmarking::benchmarks;
+use scale_info::prelude::format;
+
+use pallet_asset::benchmarking::setup_asset_transfer;
+use polymesh_common_utilities::benchs::{AccountIdOf, User, UserBuilder};
+use polymesh_common_utilities::TestUtilsFn;
+use polymesh_primitives::settlement::VenueDetails;
+use polymesh_primitives::Ticker;
+
+use crate::*;
+
+const BASE_TICKER: Ticker = Ticker::repeating(b'A');
+const QUOTE_TICKER: Ticker = Ticker::repeating(b'B');
+const PAIR: TradingPair = TradingPair {
+    base: BASE_TICKER,
+    quote: QUOTE_TICKER,
+};
+const PRICE: Balance = 1_000_000;
+
+pub type OrderBook<T> = crate::Module<T>;
+
+struct SetupBook {
+    pub venue_id: VenueId,
+    pub seller_portfolio: PortfolioId,
+    pub buyer_portfolio: PortfolioId,
+}
+
+/// Creates both assets, an exchange venue owned by `seller` and the portfolios of each side.
+fn setup_book<T>(seller: &User<T>, buyer: &User<T>) -> SetupBook
+where
+    T: Config + TestUtilsFn<AccountIdOf<T>>,
+{
+    let (seller_portfolio, _, _) = setup_asset_transfer(
+        seller,
+        buyer,
+        BASE_TICKER,
+        Some(&format!("SdrPortfolio{:?}", BASE_TICKER)),
+        Some(&format!("RcvPortfolio{:?}", BASE_TICKER)),
+        true,
+        true,
+        0,
+    );
+    let (buyer_portfolio, _, _) = setup_asset_transfer(
+        buyer,
+        seller,
+        QUOTE_TICKER,
+        Some(&format!("SdrPortfolio{:?}", QUOTE_TICKER)),
+        Some(&format!("RcvPortfolio{:?}", QUOTE_TICKER)),
+        true,
+        true,
+        0,
+    );
+
+    let venue_id = <Settlement<T>>::venue_counter();
+    <Settlement<T>>::create_venue(
+        seller.origin().into(),
+        VenueDetails::default(),
+        vec![seller.account()],
+        VenueType::Exchange,
+    )
+    .unwrap();
+
+    SetupBook {
+        venue_id,
+        seller_portfolio,
+        buyer_portfolio,
+    }
+}
+
+/// Fills the sell side of the book with `n` orders, worst price last.
+fn fill_sell_side<T: Config>(seller: &User<T>, setup: &SetupBook, n: u32) {
+    for i in 0..n {
+        <OrderBook<T>>::place_order(
+            seller.origin().into(),
+            setup.venue_id,
+            PAIR,
+            setup.seller_portfolio,
+            Side::Sell,
+            PRICE + i as Balance,
+            1,
+        )
+        .unwrap();
+    }
+}
+
+/// Replaces the pending books with `p - 1` books of other venues, so that the next queued book is the last one.
+fn fill_pending_books<T: Config>(p: u32) {
+    let books = (1..p)
+        .map(|i| (VenueId(u64::MAX - i as u64), PAIR))
+        .collect::<Vec<_>>();
+    <PendingBooks<T>>::put(BoundedVec::truncate_from(books));
+}
+
+benchmarks! {
+    where_clause { where T: TestUtilsFn<AccountIdOf<T>> }
+
+    place_order {
+        let n in 1 .. T::MaxOrdersPerBook::get();
+        let p in 1 .. T::MaxPendingBooks::get();
+
+        let alice = <UserBuilder<T>>::default().generate_did().build("Alice");
+        let bob = <UserBuilder<T>>::default().generate_did().build("Bob");
+        let setup = setup_book::<T>(&alice, &bob);
+        fill_sell_side::<T>(&alice, &setup, n - 1);
+        fill_pending_books::<T>(p);
+        // The best price is inserted before all the other orders.
+    }: _(alice.origin(), setup.venue_id, PAIR, setup.seller_portfolio, Side::Sell, PRICE - 1, 1)
+    verify {
+        assert_eq!(
+            BookOrders::decode_len((setup.venue_id, PAIR), Side::Sell),
+            Some(n as usize)
+        );
+        assert_eq!(<PendingBooks<T>>::decode_len(), Some(p as usize));
+    }
+
+    cancel_order {
+        let n in 1 .. T::MaxOrdersPerBook::get();
+
+        let alice = <UserBuilder<T>>::default().generate_did().build("Alice");
+        let bob = <UserBuilder<T>>::default().generate_did().build("Bob");
+        let setup = setup_book::<T>(&alice, &bob);
+        fill_sell_side::<T>(&alice, &setup, n);
+        let order_id = OrderId(OrderCounter::get().0 - 1);
+    }: _(alice.origin(), order_id)
+    verify {
+        assert!(Orders::get(order_id).is_none());
+    }
+
+    amend_order {
+        let n in 1 .. T::MaxOrdersPerBook::get();
+        let p in 1 .. T::MaxPendingBooks::get();
+
+        let alice = <UserBuilder<T>>::default().generate_did().build("Alice");
+        let bob = <UserBuilder<T>>::default().generate_did().build("Bob");
+        let setup = setup_book::<T>(&alice, &bob);
+        fill_sell_side::<T>(&alice, &setup, n);
+        fill_pending_books::<T>(p);
+        let order_id = OrderId(OrderCounter::get().0 - 1);
+        // The new price moves the worst order in front of all the others.
+    }: _(alice.origin(), order_id, PRICE - 1, 2)
+    verify {
+        assert_eq!(Orders::get(order_id).unwrap().remaining, 2);
+        assert_eq!(<PendingBooks<T>>::decode_len(), Some(p as usize));
+    }
+
+    match_orders {
+        let n in 1 .. T::MaxOrdersPerBook::get();
+
+        let alice = <UserBuilder<T>>::default().generate_did().build("Alice");
+        let bob = <UserBuilder<T>>::default().generate_did().build("Bob");
+        let setup = setup_book::<T>(&alice, &bob);
+        fill_sell_side::<T>(&alice, &setup, n);
+        <OrderBook<T>>::place_order(
+            bob.origin().into(),
+            setup.venue_id,
+            PAIR,
+            setup.buyer_portfolio,
+            Side::Buy,
+            PRICE,
+            2,
+        )
+        .unwrap();
+    }: {
+        assert!(<OrderBook<T>>::match_best_orders(setup.venue_id, PAIR));
+    }
+    verify {
+        assert_eq!(
+            BookOrders::decode_len((setup.venue_id, PAIR), Side::Sell),
+            Some(n as usize - 1)
+        );
+    }
+}
@@ -0,0 +1,550 @@
+// Copyright (c) 2020 Polymesh Association
+
+//! # Order Book Module
+//!
+//! Order book module runs limit order books on exchange venues.
+//!
+//! ## Overview
+//!
+//! Each order book trades a base asset against a quote asset and is attached to a venue of type `Exchange`.
+//! Placing an order locks the funds it needs in the owner's portfolio: the base asset for sell orders,
+//! and the quote asset at the limit price for buy orders.
+//! At the start of every block, crossing orders are matched by price-time priority within a weight budget.
+//! At most `MaxPendingBooks` order books can wait to be matched at once.
+//! Each match trades at the price of the order that was queued first, and is settled atomically
+//! through an instruction of the settlement pallet, created on behalf of the venue.
+//! If a match cannot be settled, the most recent of the two orders is cancelled.
+//! While the venue is halted or outside its trading window, its books are not matched and their orders are kept.
+//!
+//! ## Dispatchable Functions
+//!
+//! - `place_order` - Places a new limit order.
+//! - `cancel_order` - Cancels an order, releasing its locked funds.
+//! - `amend_order` - Changes the price and the amount of an order.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![recursion_limit = "256"]
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+use frame_support::dispatch::DispatchResult;
+use frame_support::traits::Get;
+use frame_support::weights::Weight;
+use frame_support::{decl_error, decl_event, decl_module, decl_storage, ensure, BoundedVec};
+use sp_runtime::DispatchError;
+use sp_std::collections::btree_set::BTreeSet;
+use sp_std::prelude::*;
+
+use pallet_base::try_next_post;
+use pallet_identity::PermissionedCallOriginData;
+use pallet_settlement::VenueInfo;
+use polymesh_common_utilities::portfolio::PortfolioSubTrait;
+use polymesh_common_utilities::traits::{identity, portfolio};
+use polymesh_common_utilities::with_transaction;
+use polymesh_primitives::order_book::{
+    quote_amount, Order, OrderBookDepth, OrderId, PriceLevel, Side, TradingPair,
+};
+use polymesh_primitives::settlement::{Leg, SettlementType, VenueId, VenueType};
+use polymesh_primitives::{Balance, IdentityId, PortfolioId, WeightMeter};
+
+type Identity<T> = pallet_identity::Module<T>;
+type Portfolio<T> = pallet_portfolio::Module<T>;
+type Settlement<T> = pallet_settlement::Module<T>;
+
+/// The key of an order book: the venue it is attached to and the traded pair.
+pub type BookKey = (VenueId, TradingPair);
+
+pub trait WeightInfo {
+    fn place_order(n: u32, p: u32) -> Weight;
+    fn cancel_order(n: u32) -> Weight;
+    fn amend_order(n: u32, p: u32) -> Weight;
+    fn match_orders(n: u32) -> Weight;
+}
+
+pub trait Config:
+    frame_system::Config
+    + identity::Config
+    + pallet_settlement::Config
+    + portfolio::Config
+    + pallet_base::Config
+{
+    /// The overarching event type.
+    type RuntimeEvent: From<Event> + Into<<Self as frame_system::Config>::RuntimeEvent>;
+    /// Weight information for extrinsic of the order book pallet.
+    type WeightInfo: WeightInfo;
+    /// Maximum number of orders on each side of an order book.
+    type MaxOrdersPerBook: Get<u32>;
+    /// Maximum number of order books waiting to be matched.
+    type MaxPendingBooks: Get<u32>;
+    /// Maximum weight spent matching orders at the start of a block.
+    type MatchingWeight: Get<Weight>;
+}
+
+decl_event!(
+    pub enum Event {
+        /// A new order has been placed.
+        /// (owner DID, order id, order details)
+        OrderPlaced(IdentityId, OrderId, Order),
+        /// An order has been cancelled and its remaining funds unlocked.
+        /// (owner DID, order id)
+        OrderCancelled(IdentityId, OrderId),
+        /// The price and amount of an order have been changed.
+        /// (owner DID, order id, new price, new amount)
+        OrderAmended(IdentityId, OrderId, Balance, Balance),
+        /// A buy and a sell order have been matched and settled.
+        /// (venue id, trading pair, buy order id, sell order id, price, base amount)
+        OrdersMatched(VenueId, TradingPair, OrderId, OrderId, Balance, Balance),
+        /// An order has been completely filled.
+        /// (order id)
+        OrderFilled(OrderId),
+        /// A match could not be settled and the most recent order has been cancelled.
+        /// (buy order id, sell order id, settlement error)
+        MatchFailed(OrderId, OrderId, DispatchError),
+    }
+);
+
+decl_error! {
+    /// Errors for the OrderBook module.
+    pub enum Error for Module<T: Config> {
+        /// Sender does not have required permissions.
+        Unauthorized,
+        /// An arithmetic operation overflowed.
+        Overflow,
+        /// The venue does not exist or is not an exchange.
+        InvalidVenue,
+        /// The base and quote assets of the trading pair are the same.
+        InvalidTradingPair,
+        /// The price or the amount of the order is zero.
+        InvalidOrder,
+        /// The order does not exist.
+        OrderNotFound,
+        /// The order book already holds the maximum number of orders on this side.
+        OrderBookFull,
+        /// Too many order books are already waiting to be matched.
+        TooManyPendingBooks,
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as OrderBook {
+        /// Details of all open orders.
+        /// order_id -> Order
+        Orders get(fn orders): map hasher(twox_64_concat) OrderId => Option<Order>;
+
+        /// The next order ID.
+        OrderCounter get(fn order_counter) build(|_| OrderId(1u64)): OrderId;
+
+        /// The time priority given to the next queued order.
+        NextPriority get(fn next_priority): u64;
+
+        /// Open orders of a book side, as (price, order id), best price first and then by time priority.
+        /// (venue_id, trading pair), side -> [(price, order_id)]
+        BookOrders get(fn book_orders):
+            double_map
+                hasher(blake2_128_concat) BookKey,
+                hasher(twox_64_concat) Side
+                => Vec<(Balance, OrderId)>;
+
+        /// Order books that may contain crossing orders and must be matched.
+        PendingBooks get(fn pending_books): BoundedVec<BookKey, T::MaxPendingBooks>;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: <T as frame_system::Config>::RuntimeOrigin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        const MaxOrdersPerBook: u32 = T::MaxOrdersPerBook::get();
+        const MaxPendingBooks: u32 = T::MaxPendingBooks::get();
+
+        fn on_initialize(_n: T::BlockNumber) -> Weight {
+            Self::match_pending_books(T::MatchingWeight::get())
+        }
+
+        /// Places a new limit order, locking the funds required to fill it.
+        ///
+        /// * `venue_id` - Exchange venue the order book is attached to.
+        /// * `pair` - The traded pair.
+        /// * `portfolio` - Portfolio funding the order and receiving its proceeds.
+        /// * `side` - Whether the order buys or sells the base asset.
+        /// * `price` - Limit price, as a multiple of 1_000_000.
+        /// * `amount` - Amount of base asset to buy or sell.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::place_order(T::MaxOrdersPerBook::get(), T::MaxPendingBooks::get())]
+        pub fn place_order(
+            origin,
+            venue_id: VenueId,
+            pair: TradingPair,
+            portfolio: PortfolioId,
+            side: Side,
+            price: Balance,
+            amount: Balance
+        ) {
+            let PermissionedCallOriginData {
+                primary_did: did,
+                secondary_key,
+                ..
+            } = Identity::<T>::ensure_origin_call_permissions(origin)?;
+
+            <Portfolio<T>>::ensure_portfolio_custody_and_permission(portfolio, did, secondary_key.as_ref())?;
+
+            VenueInfo::get(venue_id)
+                .filter(|v| v.venue_type == VenueType::Exchange)
+                .ok_or(Error::<T>::InvalidVenue)?;
+            ensure!(pair.base != pair.quote, Error::<T>::InvalidTradingPair);
+
+            let locked = Self::ensure_valid_order(side, price, amount)?;
+            let key = (venue_id, pair);
+            ensure!(
+                (BookOrders::decode_len(key, side).unwrap_or_default() as u32) < T::MaxOrdersPerBook::get(),
+                Error::<T>::OrderBookFull
+            );
+
+            let id = OrderCounter::try_mutate(try_next_post::<T, _>)?;
+            <Portfolio<T>>::lock_tokens(&portfolio, &pair.locked_ticker(side), locked)?;
+
+            let order = Order {
+                owner: did,
+                portfolio,
+                venue_id,
+                pair,
+                side,
+                price,
+                remaining: amount,
+                locked,
+                priority: Self::take_priority(),
+            };
+            Self::queue_order(id, &order)?;
+            Orders::insert(id, order.clone());
+
+            Self::deposit_event(Event::OrderPlaced(did, id, order));
+        }
+
+        /// Cancels an order, unlocking its remaining funds.
+        ///
+        /// * `order_id` - ID of the order to cancel.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::cancel_order(T::MaxOrdersPerBook::get())]
+        pub fn cancel_order(origin, order_id: OrderId) {
+            let (did, order) = Self::ensure_order_owner(origin, order_id)?;
+            Self::remove_order(order_id, &order);
+            Self::deposit_event(Event::OrderCancelled(did, order_id));
+        }
+
+        /// Changes the price and the amount of an order, adjusting its locked funds.
+        /// The order keeps its time priority only if its price is unchanged and its amount is not increased.
+        ///
+        /// * `order_id` - ID of the order to amend.
+        /// * `price` - New limit price, as a multiple of 1_000_000.
+        /// * `amount` - New amount of base asset to buy or sell.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::amend_order(T::MaxOrdersPerBook::get(), T::MaxPendingBooks::get())]
+        pub fn amend_order(origin, order_id: OrderId, price: Balance, amount: Balance) {
+            let (did, mut order) = Self::ensure_order_owner(origin, order_id)?;
+
+            let locked = Self::ensure_valid_order(order.side, price, amount)?;
+            let ticker = order.locked_ticker();
+            <Portfolio<T>>::unlock_tokens(&order.portfolio, &ticker, order.locked)?;
+            <Portfolio<T>>::lock_tokens(&order.portfolio, &ticker, locked)?;
+
+            let keeps_priority = price == order.price && amount <= order.remaining;
+            order.price = price;
+            order.remaining = amount;
+            order.locked = locked;
+            if !keeps_priority {
+                Self::dequeue_order(order_id, &order);
+                order.priority = Self::take_priority();
+                Self::queue_order(order_id, &order)?;
+            }
+            Orders::insert(order_id, order);
+
+            Self::deposit_event(Event::OrderAmended(did, order_id, price, amount));
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Returns the aggregated depth of an order book, with at most `levels` prices on each side.
+    pub fn order_book_depth(venue_id: VenueId, pair: TradingPair, levels: u32) -> OrderBookDepth {
+        let side_depth = |side: Side| {
+            let mut depth: Vec<PriceLevel> = Vec::new();
+            for (price, id) in BookOrders::get((venue_id, pair), side) {
+                let remaining = Orders::get(id).map(|o| o.remaining).unwrap_or_default();
+                match depth.last_mut() {
+                    Some(level) if level.price == price => {
+                        level.amount = level.amount.saturating_add(remaining);
+                        level.orders = level.orders.saturating_add(1);
+                    }
+                    _ if depth.len() >= levels as usize => break,
+                    _ => depth.push(PriceLevel {
+                        price,
+                        amount: remaining,
+                        orders: 1,
+                    }),
+                }
+            }
+            depth
+        };
+        OrderBookDepth {
+            bids: side_depth(Side::Buy),
+            asks: side_depth(Side::Sell),
+        }
+    }
+
+    /// Returns the open orders of a book side, best price first and then by time priority.
+    pub fn open_orders(venue_id: VenueId, pair: TradingPair, side: Side) -> Vec<(OrderId, Order)> {
+        BookOrders::get((venue_id, pair), side)
+            .into_iter()
+            .filter_map(|(_, id)| Orders::get(id).map(|order| (id, order)))
+            .collect()
+    }
+
+    /// Ensures `price` and `amount` are non-zero and returns the amount that must be locked for the order.
+    fn ensure_valid_order(
+        side: Side,
+        price: Balance,
+        amount: Balance,
+    ) -> Result<Balance, DispatchError> {
+        ensure!(price > 0 && amount > 0, Error::<T>::InvalidOrder);
+        let locked = Order::lock_amount(side, price, amount).ok_or(Error::<T>::Overflow)?;
+        ensure!(locked > 0, Error::<T>::InvalidOrder);
+        Ok(locked)
+    }
+
+    /// Ensures the signer is the owner of the order and has permission over its portfolio.
+    fn ensure_order_owner(
+        origin: T::RuntimeOrigin,
+        order_id: OrderId,
+    ) -> Result<(IdentityId, Order), DispatchError> {
+        let PermissionedCallOriginData {
+            primary_did: did,
+            secondary_key,
+            ..
+        } = Identity::<T>::ensure_origin_call_permissions(origin)?;
+        let order = Orders::get(order_id).ok_or(Error::<T>::OrderNotFound)?;
+        ensure!(order.owner == did, Error::<T>::Unauthorized);
+        <Portfolio<T>>::ensure_user_portfolio_permission(secondary_key.as_ref(), order.portfolio)?;
+        Ok((did, order))
+    }
+
+    fn take_priority() -> u64 {
+        NextPriority::mutate(|priority| {
+            let current = *priority;
+            *priority = priority.saturating_add(1);
+            current
+        })
+    }
+
+    /// Inserts the order in its book side after all orders with the same or a better price,
+    /// and marks the book for matching.
+    fn queue_order(id: OrderId, order: &Order) -> DispatchResult {
+        let key = (order.venue_id, order.pair);
+        <PendingBooks<T>>::try_mutate(|books| -> DispatchResult {
+            if !books.contains(&key) {
+                books
+                    .try_push(key)
+                    .map_err(|_| Error::<T>::TooManyPendingBooks)?;
+            }
+            Ok(())
+        })?;
+        BookOrders::mutate(key, order.side, |orders| {
+            let position = match order.side {
+                Side::Buy => orders.partition_point(|(price, _)| *price >= order.price),
+                Side::Sell => orders.partition_point(|(price, _)| *price <= order.price),
+            };
+            orders.insert(position, (order.price, id));
+        });
+        Ok(())
+    }
+
+    fn dequeue_order(id: OrderId, order: &Order) {
+        BookOrders::mutate((order.venue_id, order.pair), order.side, |orders| {
+            orders.retain(|(_, order_id)| *order_id != id)
+        });
+    }
+
+    /// Removes the order from storage and unlocks its remaining funds.
+    fn remove_order(id: OrderId, order: &Order) {
+        // The order book must've locked these tokens when the order was placed or last filled.
+        let _ =
+            <Portfolio<T>>::unlock_tokens(&order.portfolio, &order.locked_ticker(), order.locked);
+        Self::dequeue_order(id, order);
+        Orders::remove(id);
+    }
+
+    /// Matches the pending order books until they no longer cross or `weight_limit` is reached.
+    /// Books that still need matching, and the books of closed venues, are kept for the next block.
+    fn match_pending_books(weight_limit: Weight) -> Weight {
+        let pending = <PendingBooks<T>>::get();
+        let mut consumed = <T as frame_system::Config>::DbWeight::get().reads(1);
+        if pending.is_empty() {
+            return consumed;
+        }
+
+        let match_weight = <T as Config>::WeightInfo::match_orders(T::MaxOrdersPerBook::get());
+        let venue_check_weight = <T as frame_system::Config>::DbWeight::get().reads(2);
+        let mut still_pending = Vec::with_capacity(pending.len());
+        let mut out_of_weight = false;
+        for (venue_id, pair) in pending {
+            if out_of_weight
+                || consumed
+                    .saturating_add(venue_check_weight)
+                    .any_gt(weight_limit)
+            {
+                out_of_weight = true;
+                still_pending.push((venue_id, pair));
+                continue;
+            }
+            consumed = consumed.saturating_add(venue_check_weight);
+            // The orders of a halted or closed venue are kept until it reopens.
+            if Settlement::<T>::ensure_venue_open(venue_id).is_err() {
+                still_pending.push((venue_id, pair));
+                continue;
+            }
+            loop {
+                if consumed.saturating_add(match_weight).any_gt(weight_limit) {
+                    out_of_weight = true;
+                    still_pending.push((venue_id, pair));
+                    break;
+                }
+                consumed = consumed.saturating_add(match_weight);
+                if !Self::match_best_orders(venue_id, pair) {
+                    break;
+                }
+            }
+        }
+
+        // Only books taken from `pending` are kept, so none is truncated.
+        <PendingBooks<T>>::put(BoundedVec::truncate_from(still_pending));
+        consumed.saturating_add(<T as frame_system::Config>::DbWeight::get().writes(1))
+    }
+
+    /// Matches the best buy and sell orders of a book if they cross.
+    /// The venue must be open, so that a failed settlement is caused by one of the orders.
+    /// Returns `false` if the book does not cross.
+    fn match_best_orders(venue_id: VenueId, pair: TradingPair) -> bool {
+        let key = (venue_id, pair);
+        let best = |side: Side| {
+            BookOrders::get(key, side)
+                .first()
+                .and_then(|(_, id)| Orders::get(id).map(|order| (*id, order)))
+        };
+        let ((buy_id, mut buy), (sell_id, mut sell)) = match (best(Side::Buy), best(Side::Sell)) {
+            (Some(buy), Some(sell)) if buy.1.price >= sell.1.price => (buy, sell),
+            _ => return false,
+        };
+
+        // Trades at the price of the order that was queued first.
+        let price = if buy.priority < sell.priority {
+            buy.price
+        } else {
+            sell.price
+        };
+        let amount = buy.remaining.min(sell.remaining);
+        // Can't overflow, since the buy order could lock its remaining amount at a higher or equal price.
+        let cost = quote_amount(amount, price).unwrap_or_default();
+
+        if let Err(e) = Self::settle_match(venue_id, &buy, &sell, amount, cost) {
+            let (taker_id, taker) = if buy.priority < sell.priority {
+                (sell_id, sell)
+            } else {
+                (buy_id, buy)
+            };
+            Self::remove_order(taker_id, &taker);
+            Self::deposit_event(Event::MatchFailed(buy_id, sell_id, e));
+            Self::deposit_event(Event::OrderCancelled(taker.owner, taker_id));
+            return true;
+        }
+        Self::deposit_event(Event::OrdersMatched(
+            venue_id, pair, buy_id, sell_id, price, amount,
+        ));
+
+        buy.remaining -= amount;
+        buy.locked = buy.locked.saturating_sub(cost);
+        sell.remaining -= amount;
+        sell.locked = sell.locked.saturating_sub(amount);
+        Self::update_after_fill(buy_id, buy);
+        Self::update_after_fill(sell_id, sell);
+        true
+    }
+
+    /// Stores a partially filled order, or removes a completely filled one.
+    fn update_after_fill(id: OrderId, order: Order) {
+        if order.remaining == 0 {
+            // Releases the quote asset a buy order saved by trading below its limit price.
+            Self::remove_order(id, &order);
+            Self::deposit_event(Event::OrderFilled(id));
+        } else {
+            Orders::insert(id, order);
+        }
+    }
+
+    /// Settles a match through an instruction of the venue, affirmed on behalf of both owners.
+    fn settle_match(
+        venue_id: VenueId,
+        buy: &Order,
+        sell: &Order,
+        amount: Balance,
+        cost: Balance,
+    ) -> DispatchResult {
+        let venue = VenueInfo::get(venue_id).ok_or(Error::<T>::InvalidVenue)?;
+        let legs = vec![
+            Leg::Fungible {
+                sender: sell.portfolio,
+                receiver: buy.portfolio,
+                ticker: sell.pair.base,
+                amount,
+            },
+            Leg::Fungible {
+                sender: buy.portfolio,
+                receiver: sell.portfolio,
+                ticker: buy.pair.quote,
+                amount: cost,
+            },
+        ];
+
+        with_transaction(|| {
+            <Portfolio<T>>::unlock_tokens(&sell.portfolio, &sell.pair.base, amount)?;
+            <Portfolio<T>>::unlock_tokens(&buy.portfolio, &buy.pair.quote, cost)?;
+
+            let instruction_id = Settlement::<T>::base_add_instruction(
+                venue.creator,
+                venue_id,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                legs,
+                None,
+                None,
+                None,
+                None,
+            )?;
+
+            for order in [sell, buy] {
+                let portfolios: BTreeSet<PortfolioId> = [order.portfolio].into_iter().collect();
+                Settlement::<T>::unsafe_affirm_instruction(
+                    order.owner,
+                    instruction_id,
+                    portfolios,
+                    None,
+                    None,
+                )?;
+            }
+
+            Settlement::<T>::execute_instruction_now(
+                instruction_id,
+                venue.creator,
+                &mut WeightMeter::max_limit_no_minimum(),
+            )
+        })
+    }
+}
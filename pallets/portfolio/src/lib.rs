@@ -166,7 +166,9 @@ decl_error! {
         /// Trying to move an amount of zero assets.
         EmptyTransfer,
         /// The caller doesn't have permission to create portfolios on the owner's behalf.
-        MissingOwnersPermission
+        MissingOwnersPermission,
+        /// Transfers of the asset are frozen for one of the portfolios or for its owner.
+        HolderFrozen
     }
 }
 
@@ -311,6 +313,7 @@ decl_module! {
         /// * `NoDuplicateAssetsAllowed` the same ticker can't be repeated in the items vector.
         /// * `InvalidTransferNFTNotOwned` if the caller is trying to move an NFT he doesn't own.
         /// * `InvalidTransferNFTIsLocked` if the caller is trying to move a locked NFT.
+        /// * `HolderFrozen` if transfers of an asset are frozen for either portfolio or for their owner.
         ///
         /// # Permissions
         /// * Portfolio
@@ -326,7 +329,7 @@ decl_module! {
                 Self::ensure_portfolios_validity_and_permissions(origin, from.clone(), to.clone())?;
 
             // Verifies if the sender has all the funds
-            Self::ensure_valid_funds(&from, &funds)?;
+            Self::ensure_valid_funds(&from, &to, &funds)?;
 
             // Updates the portfolio of the sender and receiver
             Self::unchecked_move_funds(primary_did, from, to, funds);
@@ -713,10 +716,24 @@ impl<T: Config> Module<T> {
 
     /// Verifies if the sender has all funds for the transfer. For a fungible move to be valid, the sender must have sufficient balance, and for
     /// a non-fungible move, the NFTs must be owned by the sender and can't be locked.
-    fn ensure_valid_funds(sender_portfolio: &PortfolioId, funds: &[Fund]) -> DispatchResult {
+    /// In both cases, transfers of the asset must not be frozen for either portfolio.
+    fn ensure_valid_funds(
+        sender_portfolio: &PortfolioId,
+        receiver_portfolio: &PortfolioId,
+        funds: &[Fund],
+    ) -> DispatchResult {
         let mut unique_tickers = BTreeSet::new();
         // Ensure there are sufficient funds for all moves
         for fund in funds {
+            let ticker = match &fund.description {
+                FundDescription::Fungible { ticker, .. } => ticker,
+                FundDescription::NonFungible(nfts) => nfts.ticker(),
+            };
+            ensure!(
+                !T::Asset::holder_frozen(ticker, sender_portfolio)
+                    && !T::Asset::holder_frozen(ticker, receiver_portfolio),
+                Error::<T>::HolderFrozen
+            );
             match &fund.description {
                 FundDescription::Fungible { ticker, amount } => {
                     ensure!(*amount > 0, Error::<T>::EmptyTransfer);
@@ -980,4 +997,8 @@ impl<T: Config> PortfolioSubTrait<T::AccountId> for Module<T> {
         }
         PreApprovedPortfolios::get(portfolio_id, ticker)
     }
+
+    fn current_custodian(portfolio_id: &PortfolioId) -> IdentityId {
+        Self::custodian(portfolio_id)
+    }
 }
@@ -377,6 +377,10 @@ macro_rules! misc_pallet_impls {
             type WeightInfo = polymesh_weights::pallet_corporate_actions::SubstrateWeight;
             type BallotWeightInfo = polymesh_weights::pallet_corporate_ballot::SubstrateWeight;
             type DistWeightInfo = polymesh_weights::pallet_capital_distribution::SubstrateWeight;
+            type ReorgWeightInfo =
+                polymesh_weights::pallet_corporate_reorganization::SubstrateWeight;
+            type ExchangeWeightInfo = polymesh_weights::pallet_corporate_exchange::SubstrateWeight;
+            type CouponWeightInfo = polymesh_weights::pallet_corporate_coupon::SubstrateWeight;
         }
 
         impl pallet_statistics::Config for Runtime {
@@ -484,6 +488,8 @@ macro_rules! misc_pallet_impls {
             type MaxNumberOfOffChainAssets = MaxNumberOfOffChainAssets;
             type MaxNumberOfVenueSigners = MaxNumberOfVenueSigners;
             type MaxInstructionMediators = MaxInstructionMediators;
+            type MaxInstructionPrerequisites = MaxInstructionPrerequisites;
+            type MaxInstructionDependents = MaxInstructionDependents;
         }
 
         impl pallet_sto::Config for Runtime {
@@ -491,6 +497,14 @@ macro_rules! misc_pallet_impls {
             type WeightInfo = polymesh_weights::pallet_sto::SubstrateWeight;
         }
 
+        impl pallet_order_book::Config for Runtime {
+            type RuntimeEvent = RuntimeEvent;
+            type WeightInfo = polymesh_weights::pallet_order_book::SubstrateWeight;
+            type MaxOrdersPerBook = MaxOrdersPerBook;
+            type MaxPendingBooks = MaxPendingOrderBooks;
+            type MatchingWeight = OrderBookMatchingWeight;
+        }
+
         impl polymesh_common_utilities::traits::permissions::Config for Runtime {
             type Checker = Identity;
         }
@@ -575,13 +589,15 @@ macro_rules! runtime_apis {
         use frame_support::dispatch::result::Result as FrameResult;
         use node_rpc_runtime_api::asset as rpc_api_asset;
 
+        use pallet_corporate_actions::{ballot::MotionOutcome, CAId};
         use pallet_identity::types::{AssetDidResult, CddStatus, RpcDidRecords, DidStatus, KeyIdentityData};
         use pallet_pips::{Vote, VoteCount};
         use pallet_protocol_fee_rpc_runtime_api::CappedFee;
         use polymesh_primitives::asset::GranularCanTransferResult;
-        use polymesh_primitives::settlement::{InstructionId, ExecuteInstructionInfo, AffirmationCount};
+        use polymesh_primitives::order_book::{Order, OrderBookDepth, OrderId, Side, TradingPair};
+        use polymesh_primitives::settlement::{InstructionId, ExecuteInstructionInfo, AffirmationCount, InstructionExecutionReport, VenueId};
         use polymesh_primitives::{
-            asset::CheckpointId, compliance_manager::AssetComplianceResult, IdentityId, Index, NFTs,
+            asset::CheckpointId, compliance_manager::{AssetComplianceResult, AssetComplianceSimulation, ComplianceExpressionResult}, IdentityId, Index, NFTs,
             PortfolioId, Signatory, Ticker, WeightMeter, IdentityClaim
         };
 
@@ -974,6 +990,49 @@ macro_rules! runtime_apis {
                         &mut weight_meter
                     )
                 }
+
+                #[inline]
+                fn issuance_headroom(ticker: &Ticker) -> Balance {
+                    Asset::issuance_headroom(ticker)
+                }
+            }
+
+            impl node_rpc_runtime_api::compliance::ComplianceApi<Block> for Runtime {
+                #[inline]
+                fn compliance_expression_result(
+                    ticker: &Ticker,
+                    sender: IdentityId,
+                    receiver: IdentityId,
+                    amount: Balance
+                ) -> FrameResult<Option<ComplianceExpressionResult>, DispatchError>
+                {
+                    let mut weight_meter = WeightMeter::max_limit_no_minimum();
+                    ComplianceManager::compliance_expression_result(
+                        ticker,
+                        sender,
+                        receiver,
+                        amount,
+                        &mut weight_meter
+                    )
+                }
+
+                #[inline]
+                fn simulate_compliance(
+                    ticker: &Ticker,
+                    sender: IdentityId,
+                    receiver: IdentityId,
+                    amount: Balance
+                ) -> FrameResult<AssetComplianceSimulation, DispatchError>
+                {
+                    let mut weight_meter = WeightMeter::max_limit_no_minimum();
+                    ComplianceManager::simulate_compliance(
+                        ticker,
+                        sender,
+                        receiver,
+                        amount,
+                        &mut weight_meter
+                    )
+                }
             }
 
             impl pallet_group_rpc_runtime_api::GroupApi<Block> for Runtime {
@@ -1017,6 +1076,37 @@ macro_rules! runtime_apis {
                 ) -> AffirmationCount {
                     Settlement::affirmation_count(instruction_id, portfolios)
                 }
+
+                #[inline]
+                fn simulate_instruction_execution(
+                    instruction_id: InstructionId,
+                ) -> InstructionExecutionReport {
+                    Settlement::simulate_instruction_execution(instruction_id)
+                }
+            }
+
+            impl node_rpc_runtime_api::order_book::OrderBookApi<Block> for Runtime {
+                #[inline]
+                fn get_depth(venue_id: VenueId, pair: TradingPair, levels: u32) -> OrderBookDepth {
+                    OrderBook::order_book_depth(venue_id, pair, levels)
+                }
+
+                #[inline]
+                fn get_order(order_id: OrderId) -> Option<Order> {
+                    OrderBook::orders(order_id)
+                }
+
+                #[inline]
+                fn get_open_orders(venue_id: VenueId, pair: TradingPair, side: Side) -> Vec<(OrderId, Order)> {
+                    OrderBook::open_orders(venue_id, pair, side)
+                }
+            }
+
+            impl node_rpc_runtime_api::corporate_ballot::CorporateBallotApi<Block> for Runtime {
+                #[inline]
+                fn get_final_results(ca_id: CAId) -> Option<Vec<MotionOutcome>> {
+                    CorporateBallot::final_results(ca_id)
+                }
             }
 
             $($extra)*
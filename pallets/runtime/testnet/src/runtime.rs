@@ -12,7 +12,10 @@ use frame_support::{
 };
 use pallet_asset::checkpoint as pallet_checkpoint;
 use pallet_corporate_actions::ballot as pallet_corporate_ballot;
+use pallet_corporate_actions::coupon as pallet_corporate_coupon;
 use pallet_corporate_actions::distribution as pallet_capital_distribution;
+use pallet_corporate_actions::exchange as pallet_corporate_exchange;
+use pallet_corporate_actions::reorganization as pallet_corporate_reorganization;
 use pallet_session::historical as pallet_session_historical;
 pub use pallet_transaction_payment::{Multiplier, RuntimeDispatchInfo, TargetedFeeAdjustment};
 use polymesh_common_utilities::{
@@ -95,6 +98,13 @@ parameter_types! {
     pub const MaxNumberOfNFTs: u32 = 100;
     pub const MaxNumberOfVenueSigners: u32 = 50;
     pub const MaxInstructionMediators: u32 = 4;
+    pub const MaxInstructionPrerequisites: u32 = 10;
+    pub const MaxInstructionDependents: u32 = 10;
+
+    // Order book:
+    pub const MaxOrdersPerBook: u32 = 100;
+    pub const MaxPendingOrderBooks: u32 = 1_000;
+    pub OrderBookMatchingWeight: Weight = Perbill::from_percent(10) * MaximumBlockWeight::get();
 
     // I'm online:
     pub const ImOnlineUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
@@ -394,6 +404,11 @@ construct_runtime!(
         Nft: pallet_nft::{Pallet, Call, Storage, Event},
 
         TestUtils: pallet_test_utils::{Pallet, Call, Storage, Event<T> } = 50,
+
+        OrderBook: pallet_order_book::{Pallet, Call, Storage, Event},
+        CorporateReorganization: pallet_corporate_reorganization::{Pallet, Call, Storage, Event},
+        CorporateExchange: pallet_corporate_exchange::{Pallet, Call, Storage, Event},
+        CorporateCoupon: pallet_corporate_coupon::{Pallet, Call, Storage, Event},
     }
 );
 
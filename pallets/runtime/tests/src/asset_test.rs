@@ -21,14 +21,15 @@ use pallet_asset::{
 };
 use pallet_portfolio::{NextPortfolioNumber, PortfolioAssetBalances, PortfolioLockedAssets};
 use polymesh_common_utilities::asset::AssetFnTrait;
-use polymesh_common_utilities::constants::currency::ONE_UNIT;
+use polymesh_common_utilities::constants::currency::{MAX_SUPPLY, ONE_UNIT};
 use polymesh_common_utilities::constants::*;
 use polymesh_common_utilities::traits::checkpoint::{
     NextCheckpoints, ScheduleCheckpoints, ScheduleId,
 };
 use polymesh_primitives::agent::AgentGroup;
 use polymesh_primitives::asset::{
-    AssetName, AssetType, CheckpointId, CustomAssetTypeId, FundingRoundName, NonFungibleType,
+    AssetHolder, AssetName, AssetType, CheckpointId, CustomAssetTypeId, FundingRoundName,
+    IssuanceTranche, NonFungibleType, VestingRelease, VestingSchedule,
 };
 use polymesh_primitives::asset_metadata::{
     AssetMetadataKey, AssetMetadataLocalKey, AssetMetadataLockStatus, AssetMetadataName,
@@ -42,9 +43,9 @@ use polymesh_primitives::statistics::StatType;
 use polymesh_primitives::ticker::TICKER_LEN;
 use polymesh_primitives::{
     AccountId, AssetIdentifier, AssetPermissions, AuthorizationData, AuthorizationError, Document,
-    DocumentId, Fund, FundDescription, IdentityId, Memo, Moment, NFTCollectionKeys, Permissions,
-    PortfolioId, PortfolioKind, PortfolioName, PortfolioNumber, SecondaryKey, Signatory, Ticker,
-    WeightMeter,
+    DocumentHash, DocumentId, Fund, FundDescription, IdentityId, Memo, Moment, NFTCollectionKeys,
+    Permissions, PortfolioId, PortfolioKind, PortfolioName, PortfolioNumber, SecondaryKey,
+    Signatory, Ticker, WeightMeter,
 };
 use sp_keyring::AccountKeyring;
 
@@ -52,7 +53,7 @@ use crate::ext_builder::{ExtBuilder, IdentityRecord};
 use crate::nft::create_nft_collection;
 use crate::storage::{
     add_secondary_key, make_account_without_cdd, register_keyring_account, root, Checkpoint,
-    TestStorage, User,
+    EventTest, TestStorage, User,
 };
 
 type BaseError = pallet_base::Error<TestStorage>;
@@ -2870,3 +2871,289 @@ fn issue_tokens_user_portfolio() {
         assert_eq!(Tokens::get(&ticker).unwrap().total_supply, 1_000);
     });
 }
+
+#[test]
+fn vesting_schedule_locks_unvested_tokens() {
+    ExtBuilder::default().build().execute_with(|| {
+        let owner = User::new(AccountKeyring::Dave);
+        let alice = User::new(AccountKeyring::Alice);
+        let (ticker, token) = a_token(owner.did);
+        assert_ok!(basic_asset(owner, ticker, &token));
+        allow_all_transfers(ticker, owner);
+        assert_ok!(transfer(ticker, owner, alice, 1_000));
+
+        let portfolio = PortfolioId::default_portfolio(alice.did);
+        let schedule = VestingSchedule {
+            amount: 1_000,
+            start: 1_000,
+            cliff: 2_000,
+            end: 5_000,
+            release: VestingRelease::Linear,
+            released: 0,
+        };
+        let create = |user: User, schedule: VestingSchedule| {
+            Asset::create_vesting_schedule(user.origin(), ticker, portfolio, schedule)
+        };
+        set_timestamp(1_000);
+
+        // Only agents can create schedules, which must be consistent and fully backed by free tokens.
+        assert_noop!(create(alice, schedule.clone()), EAError::UnauthorizedAgent);
+        assert_noop!(
+            create(
+                owner,
+                VestingSchedule {
+                    cliff: 500,
+                    ..schedule.clone()
+                }
+            ),
+            AssetError::InvalidVestingSchedule
+        );
+        assert_noop!(
+            create(
+                owner,
+                VestingSchedule {
+                    amount: 1_001,
+                    ..schedule.clone()
+                }
+            ),
+            PortfolioError::InsufficientPortfolioBalance
+        );
+        assert_ok!(create(owner, schedule.clone()));
+        assert_eq!(PortfolioLockedAssets::get(&portfolio, &ticker), 1_000);
+        assert_noop!(
+            transfer(ticker, alice, owner, 1),
+            AssetError::InvalidTransfer
+        );
+
+        // Nothing vests before the cliff.
+        set_timestamp(1_500);
+        assert_noop!(
+            Asset::release_vested_tokens(alice.origin(), ticker, portfolio),
+            AssetError::NoVestedTokens
+        );
+
+        // Half of the tokens vest linearly by 3_000.
+        set_timestamp(3_000);
+        assert_ok!(Asset::release_vested_tokens(
+            alice.origin(),
+            ticker,
+            portfolio
+        ));
+        assert_eq!(PortfolioLockedAssets::get(&portfolio, &ticker), 500);
+        assert_eq!(Asset::vesting_schedules(ticker, portfolio)[0].released, 500);
+        assert_ok!(transfer(ticker, alice, owner, 500));
+        assert_noop!(
+            transfer(ticker, alice, owner, 1),
+            AssetError::InvalidTransfer
+        );
+
+        // Revoking at 4_000 frees the 250 vested tokens and claws back the 250 unvested ones.
+        set_timestamp(4_000);
+        assert_noop!(
+            Asset::revoke_vesting_schedule(owner.origin(), ticker, portfolio, 1),
+            AssetError::NoSuchVestingSchedule
+        );
+        assert_ok!(Asset::revoke_vesting_schedule(
+            owner.origin(),
+            ticker,
+            portfolio,
+            0
+        ));
+        assert!(Asset::vesting_schedules(ticker, portfolio).is_empty());
+        assert_eq!(PortfolioLockedAssets::get(&portfolio, &ticker), 0);
+        assert_eq!(Asset::balance_of(&ticker, alice.did), 250);
+        assert_eq!(Asset::balance_of(&ticker, owner.did), TOTAL_SUPPLY - 250);
+
+        // Steps release whole periods only.
+        let step = VestingSchedule {
+            release: VestingRelease::Step(1_000),
+            ..schedule
+        };
+        assert_eq!(step.vested_at(3_500), 500);
+        assert_eq!(step.vested_at(5_000), 1_000);
+    });
+}
+
+#[test]
+fn holder_freeze_and_forced_transfer() {
+    ExtBuilder::default().build().execute_with(|| {
+        let owner = User::new(AccountKeyring::Dave);
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let (ticker, token) = a_token(owner.did);
+        assert_ok!(basic_asset(owner, ticker, &token));
+        allow_all_transfers(ticker, owner);
+        assert_ok!(transfer(ticker, owner, alice, 1_000));
+
+        let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+        let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+        let freeze = |holder| Asset::freeze_holder(owner.origin(), ticker, holder);
+        let unfreeze = |holder| Asset::unfreeze_holder(owner.origin(), ticker, holder);
+
+        // Freezing an identity halts transfers from and to all of its portfolios.
+        let alice_id = AssetHolder::Identity(alice.did);
+        assert_noop!(
+            Asset::freeze_holder(alice.origin(), ticker, alice_id),
+            EAError::UnauthorizedAgent
+        );
+        assert_ok!(freeze(alice_id));
+        assert_noop!(freeze(alice_id), AssetError::HolderAlreadyFrozen);
+        assert_noop!(
+            transfer(ticker, alice, bob, 100),
+            AssetError::InvalidTransfer
+        );
+        assert_noop!(
+            transfer(ticker, owner, alice, 100),
+            AssetError::InvalidTransfer
+        );
+        assert_ok!(transfer(ticker, owner, bob, 100));
+
+        // A legal order moves the tokens of a frozen holder.
+        let legal_order = DocumentHash::H256([7; 32]);
+        assert_ok!(Asset::force_transfer(
+            owner.origin(),
+            ticker,
+            alice_portfolio,
+            bob_portfolio,
+            400,
+            legal_order
+        ));
+        assert_eq!(Asset::balance_of(&ticker, alice.did), 600);
+        assert_eq!(Asset::balance_of(&ticker, bob.did), 500);
+        assert!(System::events().iter().any(|e| e.event
+            == EventTest::Asset(pallet_asset::RawEvent::ForcedTransfer(
+                owner.did,
+                ticker,
+                alice_portfolio,
+                bob_portfolio,
+                400,
+                legal_order
+            ))));
+        assert_noop!(
+            Asset::force_transfer(
+                owner.origin(),
+                ticker,
+                alice_portfolio,
+                bob_portfolio,
+                601,
+                legal_order
+            ),
+            PortfolioError::InsufficientPortfolioBalance
+        );
+
+        assert_ok!(unfreeze(alice_id));
+        assert_noop!(unfreeze(alice_id), AssetError::HolderNotFrozen);
+        assert_ok!(transfer(ticker, alice, bob, 100));
+
+        // Freezing a single portfolio.
+        assert_ok!(freeze(AssetHolder::Portfolio(alice_portfolio)));
+        assert_noop!(
+            transfer(ticker, alice, bob, 100),
+            AssetError::InvalidTransfer
+        );
+
+        // The frozen portfolio can't be emptied into another portfolio of its owner either.
+        assert_ok!(Portfolio::create_portfolio(
+            alice.origin(),
+            PortfolioName(b"unfrozen".to_vec())
+        ));
+        let move_funds = || {
+            Portfolio::move_portfolio_funds(
+                alice.origin(),
+                alice_portfolio,
+                PortfolioId::user_portfolio(alice.did, PortfolioNumber(1)),
+                vec![Fund {
+                    description: FundDescription::Fungible {
+                        ticker,
+                        amount: 100,
+                    },
+                    memo: None,
+                }],
+            )
+        };
+        assert_noop!(move_funds(), PortfolioError::HolderFrozen);
+        assert_ok!(unfreeze(AssetHolder::Portfolio(alice_portfolio)));
+        assert_ok!(move_funds());
+        assert_ok!(transfer(ticker, alice, bob, 100));
+    });
+}
+
+#[test]
+fn max_supply_and_issuance_tranches() {
+    ExtBuilder::default().build().execute_with(|| {
+        let owner = User::new(AccountKeyring::Dave);
+        let (ticker, token) = a_token(owner.did);
+        assert_ok!(basic_asset(owner, ticker, &token));
+        let issue = |amount| Asset::issue(owner.origin(), ticker, amount, PortfolioKind::Default);
+        let lower = |cap| Asset::lower_max_supply(owner.origin(), ticker, cap);
+        let raise = |cap| Asset::raise_max_supply(owner.origin(), ticker, cap);
+        assert_eq!(Asset::issuance_headroom(&ticker), MAX_SUPPLY - TOTAL_SUPPLY);
+
+        // The cap can be lowered down to the issued supply, but not raised that way.
+        assert_noop!(
+            raise(Some(TOTAL_SUPPLY + 1_000)),
+            AssetError::MaxSupplyNotRaised
+        );
+        assert_noop!(
+            lower(TOTAL_SUPPLY - 1),
+            AssetError::MaxSupplyBelowTotalSupply
+        );
+        assert_ok!(lower(TOTAL_SUPPLY + 1_000));
+        assert!(System::events().iter().any(|e| e.event
+            == EventTest::Asset(pallet_asset::RawEvent::MaxSupplyChanged(
+                owner.did,
+                ticker,
+                None,
+                Some(TOTAL_SUPPLY + 1_000)
+            ))));
+        assert_noop!(lower(TOTAL_SUPPLY + 1_000), AssetError::MaxSupplyNotLowered);
+        assert_noop!(issue(1_001), AssetError::MaxSupplyExceeded);
+        assert_ok!(issue(500));
+        assert_eq!(Asset::issuance_headroom(&ticker), 500);
+
+        // Raising is a separate call.
+        assert_noop!(
+            raise(Some(TOTAL_SUPPLY + 1_000)),
+            AssetError::MaxSupplyNotRaised
+        );
+        assert_noop!(
+            raise(Some(MAX_SUPPLY + 1)),
+            AssetError::TotalSupplyAboveLimit
+        );
+        assert_ok!(raise(Some(TOTAL_SUPPLY + 2_000)));
+        assert_eq!(Asset::issuance_headroom(&ticker), 1_500);
+
+        // Tranches release the cap over time.
+        let tranche = |start, supply| IssuanceTranche { start, supply };
+        let set_tranches =
+            |tranches| Asset::set_issuance_tranches(owner.origin(), ticker, tranches);
+        assert_noop!(
+            set_tranches(vec![
+                tranche(3_000, TOTAL_SUPPLY),
+                tranche(2_000, TOTAL_SUPPLY)
+            ]),
+            AssetError::IssuanceTranchesNotOrdered
+        );
+        assert_ok!(set_tranches(vec![
+            tranche(2_000, TOTAL_SUPPLY + 1_000),
+            tranche(3_000, TOTAL_SUPPLY + 5_000),
+        ]));
+        set_timestamp(1_000);
+        assert_eq!(Asset::issuance_headroom(&ticker), 0);
+        assert_noop!(issue(1), AssetError::MaxSupplyExceeded);
+        set_timestamp(2_000);
+        assert_eq!(Asset::issuance_headroom(&ticker), 500);
+        assert_noop!(issue(501), AssetError::MaxSupplyExceeded);
+        set_timestamp(3_000);
+        assert_eq!(Asset::issuance_headroom(&ticker), 1_500);
+        assert_ok!(set_tranches(vec![]));
+        assert_eq!(Asset::issuance_headroom(&ticker), 1_500);
+
+        // Removing the cap.
+        assert_ok!(raise(None));
+        assert_eq!(
+            Asset::issuance_headroom(&ticker),
+            MAX_SUPPLY - TOTAL_SUPPLY - 500
+        );
+    });
+}
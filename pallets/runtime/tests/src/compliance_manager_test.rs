@@ -3,16 +3,19 @@ use frame_support::traits::Currency;
 use frame_support::{assert_noop, assert_ok};
 use sp_std::prelude::*;
 
-use pallet_compliance_manager::Error as CMError;
+use pallet_compliance_manager::{Error as CMError, MAX_COMPLIANCE_EXPRESSION_DEPTH};
 use polymesh_common_utilities::compliance_manager::ComplianceFnConfig;
 use polymesh_common_utilities::constants::ERC1400_TRANSFER_SUCCESS;
 use polymesh_primitives::agent::AgentGroup;
 use polymesh_primitives::compliance_manager::{
-    AssetComplianceResult, ComplianceRequirement, ComplianceRequirementResult,
+    AssetComplianceResult, ComplianceExpression, ComplianceExpressionResult, ComplianceRequirement,
+    ComplianceRequirementResult, ComplianceTemplateId, ComplianceTemplateName, MissingClaim,
+    TransferParty,
 };
 use polymesh_primitives::{
-    AuthorizationData, Claim, ClaimType, Condition, ConditionType, CountryCode, IdentityId,
-    PortfolioId, Scope, Signatory, TargetIdentity, Ticker, TrustedFor, WeightMeter,
+    AmountBounds, AuthorizationData, Claim, ClaimAge, ClaimDate, ClaimType, Condition,
+    ConditionType, CountryCode, IdentityId, PortfolioId, Scope, Signatory, TargetIdentity, Ticker,
+    TrustedFor, TrustedIssuer, WeightMeter,
 };
 use sp_keyring::AccountKeyring;
 
@@ -160,6 +163,7 @@ fn should_add_and_verify_compliance_requirement_we() {
             &ticker,
             Some(owner.did),
             Some(token_rec.did),
+            0,
             &mut WeightMeter::max_limit_no_minimum(),
         )
         .unwrap()
@@ -857,6 +861,462 @@ fn scope_asset_compliance_we() {
     assert_valid_transfer!(ticker, owner.did, user.did, 10);
 }
 
+#[test]
+fn claim_age_and_amount_asset_compliance() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(claim_age_and_amount_asset_compliance_we);
+}
+fn claim_age_and_amount_asset_compliance_we() {
+    // 0. Create accounts
+    let owner = User::new(AccountKeyring::Alice);
+    let cdd = User::new(AccountKeyring::Bob);
+    let user = User::new(AccountKeyring::Charlie);
+
+    // 1. Create & mint token
+    let (ticker, _) = create_token(owner);
+
+    // 2. Receiver needs a KYC claim updated within the last 500ms, and at most 100 tokens.
+    let scope = Scope::from(IdentityId::from(0));
+    let age = ClaimAge {
+        date: ClaimDate::LastUpdate,
+        max_age: 500,
+    };
+    let bounds = AmountBounds {
+        min: Some(5),
+        max: Some(100),
+    };
+    let receiver_conditions = vec![
+        Condition::from_dids(
+            ConditionType::IsRecent(Claim::KnowYourCustomer(scope.clone()), age),
+            &[cdd.did],
+        ),
+        Condition::from_dids(ConditionType::IsAmountWithin(bounds), &[]),
+    ];
+    assert_ok!(ComplianceManager::add_compliance_requirement(
+        owner.origin(),
+        ticker,
+        vec![],
+        receiver_conditions
+    ));
+
+    // 3. Validate behaviour.
+    // 3.1. Invalid transfer because missing KYC.
+    set_timestamp(1_000);
+    assert_invalid_transfer!(ticker, owner.did, user.did, 10);
+    // 3.2. Add KYC and transfers within bounds will be OK.
+    assert_ok!(Identity::add_claim(
+        cdd.origin(),
+        user.did,
+        Claim::KnowYourCustomer(scope.clone()),
+        None
+    ));
+    assert_valid_transfer!(ticker, owner.did, user.did, 10);
+    assert_valid_transfer!(ticker, owner.did, user.did, 100);
+    assert_invalid_transfer!(ticker, owner.did, user.did, 4);
+    assert_invalid_transfer!(ticker, owner.did, user.did, 101);
+    // 3.3. The KYC claim becomes stale.
+    set_timestamp(1_500);
+    assert_valid_transfer!(ticker, owner.did, user.did, 10);
+    set_timestamp(1_501);
+    assert_invalid_transfer!(ticker, owner.did, user.did, 10);
+    // 3.4. Refreshing the claim makes it recent again.
+    assert_ok!(Identity::add_claim(
+        cdd.origin(),
+        user.did,
+        Claim::KnowYourCustomer(scope.clone()),
+        None
+    ));
+    assert_valid_transfer!(ticker, owner.did, user.did, 10);
+
+    // 4. The granular result reports each condition.
+    let result = ComplianceManager::verify_restriction_granular(
+        &ticker,
+        Some(owner.did),
+        Some(user.did),
+        101,
+        &mut WeightMeter::max_limit_no_minimum(),
+    )
+    .unwrap();
+    assert!(!result.result);
+    let conditions = &result.requirements[0].receiver_conditions;
+    assert!(conditions[0].result);
+    assert!(!conditions[1].result);
+}
+
+#[test]
+fn compliance_expression() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(compliance_expression_we);
+}
+fn compliance_expression_we() {
+    // 0. Create accounts
+    let owner = User::new(AccountKeyring::Alice);
+    let cdd = User::new(AccountKeyring::Bob);
+    let user = User::new(AccountKeyring::Charlie);
+
+    // 1. Create & mint token
+    let (ticker, _) = create_token(owner);
+
+    // 2. Sender and receiver must share a jurisdiction, and the receiver must not be blocked.
+    let scope = Scope::from(IdentityId::from(0));
+    let issuers = vec![TrustedIssuer::from(cdd.did)];
+    let not_blocked = ComplianceExpression::Not(Box::new(ComplianceExpression::Condition(
+        TransferParty::Receiver,
+        Condition::from_dids(
+            ConditionType::IsPresent(Claim::Blocked(scope.clone())),
+            &[owner.did],
+        ),
+    )));
+    let expression = ComplianceExpression::And(vec![
+        ComplianceExpression::SameJurisdiction(scope.clone(), issuers),
+        not_blocked,
+    ]);
+    assert_ok!(ComplianceManager::set_compliance_expression(
+        owner.origin(),
+        ticker,
+        Some(expression.clone())
+    ));
+    assert_eq!(
+        ComplianceManager::compliance_expression(ticker),
+        Some(expression.clone())
+    );
+
+    // 3. Validate behaviour.
+    // 3.1. Invalid transfer because of missing jurisdictions.
+    assert_invalid_transfer!(ticker, owner.did, user.did, 10);
+    // 3.2. Different jurisdictions are invalid, the same one is valid.
+    let add_jurisdiction = |did, country| {
+        assert_ok!(Identity::add_claim(
+            cdd.origin(),
+            did,
+            Claim::Jurisdiction(country, scope.clone()),
+            None
+        ));
+    };
+    add_jurisdiction(owner.did, CountryCode::CA);
+    add_jurisdiction(user.did, CountryCode::ES);
+    assert_invalid_transfer!(ticker, owner.did, user.did, 10);
+    add_jurisdiction(user.did, CountryCode::CA);
+    assert_valid_transfer!(ticker, owner.did, user.did, 10);
+    // 3.3. Block the receiver.
+    assert_ok!(Identity::add_claim(
+        owner.origin(),
+        user.did,
+        Claim::Blocked(scope.clone()),
+        None,
+    ));
+    assert_invalid_transfer!(ticker, owner.did, user.did, 10);
+    // 3.4. The expression is an alternative to the requirements, so a requirement that holds
+    // makes the transfer valid, even though the expression does not hold.
+    assert_ok!(ComplianceManager::add_compliance_requirement(
+        owner.origin(),
+        ticker,
+        vec![],
+        vec![]
+    ));
+    assert_valid_transfer!(ticker, owner.did, user.did, 10);
+    let id = get_latest_requirement_id(ticker);
+    assert_ok!(ComplianceManager::remove_compliance_requirement(
+        owner.origin(),
+        ticker,
+        id
+    ));
+    assert_invalid_transfer!(ticker, owner.did, user.did, 10);
+
+    // 4. The result of each node is returned.
+    let result = ComplianceManager::compliance_expression_result(
+        &ticker,
+        owner.did,
+        user.did,
+        10,
+        &mut WeightMeter::max_limit_no_minimum(),
+    )
+    .unwrap()
+    .unwrap();
+    assert!(!result.result());
+    match result {
+        ComplianceExpressionResult::And(results, false) => {
+            assert!(results[0].result());
+            match &results[1] {
+                ComplianceExpressionResult::Not(inner, false) => assert!(inner.result()),
+                r => panic!("unexpected result {:?}", r),
+            }
+        }
+        r => panic!("unexpected result {:?}", r),
+    }
+
+    // 5. Expressions nested too deeply are rejected.
+    let deep = (0..MAX_COMPLIANCE_EXPRESSION_DEPTH).fold(expression, |expr, _| {
+        ComplianceExpression::Not(Box::new(expr))
+    });
+    assert_noop!(
+        ComplianceManager::set_compliance_expression(owner.origin(), ticker, Some(deep)),
+        CMError::<TestStorage>::ComplianceExpressionTooDeep
+    );
+
+    // 6. Remove the expression.
+    assert_ok!(ComplianceManager::set_compliance_expression(
+        owner.origin(),
+        ticker,
+        None
+    ));
+    assert_eq!(ComplianceManager::compliance_expression(ticker), None);
+}
+
+#[test]
+fn compliance_templates() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(compliance_templates_we);
+}
+fn compliance_templates_we() {
+    // 0. Create accounts
+    let owner = User::new(AccountKeyring::Alice);
+    let cdd = User::new(AccountKeyring::Bob);
+    let user = User::new(AccountKeyring::Charlie);
+    let template_owner = User::new(AccountKeyring::Dave);
+
+    // 1. Create & mint token
+    let (ticker, _) = create_token(owner);
+
+    // 2. Create a template requiring a claim from its trusted issuers.
+    let scope = Scope::from(IdentityId::from(0));
+    let rules = |claim: Claim| {
+        vec![ComplianceRequirement {
+            sender_conditions: vec![],
+            receiver_conditions: vec![Condition::from_dids(ConditionType::IsPresent(claim), &[])],
+            id: 1,
+        }]
+    };
+    let issuers = vec![TrustedIssuer::from(cdd.did)];
+    assert_ok!(ComplianceManager::create_compliance_template(
+        template_owner.origin(),
+        ComplianceTemplateName(b"KYC".to_vec()),
+        rules(Claim::KnowYourCustomer(scope.clone())),
+        issuers.clone(),
+    ));
+    let id = ComplianceTemplateId(1);
+    assert_ok!(ComplianceManager::subscribe_to_compliance_template(
+        owner.origin(),
+        ticker,
+        id
+    ));
+
+    // 3. The template's requirements and trusted issuers apply.
+    assert_invalid_transfer!(ticker, owner.did, user.did, 10);
+    assert_ok!(Identity::add_claim(
+        cdd.origin(),
+        user.did,
+        Claim::KnowYourCustomer(scope.clone()),
+        None
+    ));
+    assert_valid_transfer!(ticker, owner.did, user.did, 10);
+
+    // 4. Only the owner can update the template.
+    assert_noop!(
+        ComplianceManager::update_compliance_template(
+            owner.origin(),
+            id,
+            rules(Claim::Accredited(scope.clone())),
+            issuers.clone(),
+            true
+        ),
+        CMError::<TestStorage>::Unauthorized
+    );
+
+    // 5. A version requiring acceptance only applies once accepted.
+    assert_ok!(ComplianceManager::update_compliance_template(
+        template_owner.origin(),
+        id,
+        rules(Claim::Accredited(scope.clone())),
+        issuers.clone(),
+        true
+    ));
+    assert_valid_transfer!(ticker, owner.did, user.did, 10);
+    assert_ok!(ComplianceManager::accept_compliance_template_version(
+        owner.origin(),
+        ticker
+    ));
+    assert_invalid_transfer!(ticker, owner.did, user.did, 10);
+
+    // 6. A version not requiring acceptance applies immediately.
+    assert_ok!(ComplianceManager::update_compliance_template(
+        template_owner.origin(),
+        id,
+        rules(Claim::KnowYourCustomer(scope.clone())),
+        issuers.clone(),
+        false
+    ));
+    assert_valid_transfer!(ticker, owner.did, user.did, 10);
+    assert_noop!(
+        ComplianceManager::accept_compliance_template_version(owner.origin(), ticker),
+        CMError::<TestStorage>::ComplianceTemplateVersionAlreadyAccepted
+    );
+
+    // 6.1. A version applying immediately must be within the complexity limit
+    // along with the compliance expression of every subscriber.
+    assert_eq!(ComplianceManager::template_subscriber_count(id), 1);
+    let accredited = ComplianceExpression::Condition(
+        TransferParty::Receiver,
+        Condition::from_dids(
+            ConditionType::IsPresent(Claim::Accredited(scope.clone())),
+            &[],
+        ),
+    );
+    assert_ok!(ComplianceManager::set_compliance_expression(
+        owner.origin(),
+        ticker,
+        Some(ComplianceExpression::And(vec![accredited; 40]))
+    ));
+    let complex_rules = vec![ComplianceRequirement {
+        sender_conditions: vec![],
+        receiver_conditions: vec![Condition::from_dids(
+            ConditionType::IsAnyOf(vec![Claim::KnowYourCustomer(scope.clone()); 10]),
+            &[],
+        )],
+        id: 1,
+    }];
+    assert_noop!(
+        ComplianceManager::update_compliance_template(
+            template_owner.origin(),
+            id,
+            complex_rules.clone(),
+            issuers.clone(),
+            false
+        ),
+        CMError::<TestStorage>::ComplianceRequirementTooComplex
+    );
+    assert_ok!(ComplianceManager::update_compliance_template(
+        template_owner.origin(),
+        id,
+        complex_rules,
+        issuers,
+        true
+    ));
+    assert_noop!(
+        ComplianceManager::accept_compliance_template_version(owner.origin(), ticker),
+        CMError::<TestStorage>::ComplianceRequirementTooComplex
+    );
+    assert_valid_transfer!(ticker, owner.did, user.did, 10);
+
+    // 7. Unsubscribing restores the asset's own (empty) compliance.
+    assert_ok!(ComplianceManager::unsubscribe_from_compliance_template(
+        owner.origin(),
+        ticker
+    ));
+    assert_invalid_transfer!(ticker, owner.did, user.did, 10);
+    assert_eq!(ComplianceManager::template_subscriber_count(id), 0);
+    assert_noop!(
+        ComplianceManager::unsubscribe_from_compliance_template(owner.origin(), ticker),
+        CMError::<TestStorage>::NotSubscribedToComplianceTemplate
+    );
+}
+
+#[test]
+fn simulate_compliance() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(simulate_compliance_we);
+}
+fn simulate_compliance_we() {
+    // 0. Create accounts
+    let owner = User::new(AccountKeyring::Alice);
+    let cdd = User::new(AccountKeyring::Bob);
+    let user = User::new(AccountKeyring::Charlie);
+    let other_issuer = User::new(AccountKeyring::Dave);
+
+    // 1. Create & mint token
+    let (ticker, _) = create_token(owner);
+
+    // 2. Receiver needs a KYC claim, or to be accredited by the default issuer.
+    let scope = Scope::from(IdentityId::from(0));
+    let kyc = Claim::KnowYourCustomer(scope.clone());
+    let accredited = Claim::Accredited(scope.clone());
+    assert_ok!(ComplianceManager::add_default_trusted_claim_issuer(
+        owner.origin(),
+        ticker,
+        TrustedIssuer {
+            issuer: other_issuer.did,
+            trusted_for: TrustedFor::Specific(vec![ClaimType::Accredited]),
+        }
+    ));
+    assert_ok!(ComplianceManager::add_compliance_requirement(
+        owner.origin(),
+        ticker,
+        vec![],
+        vec![
+            Condition::from_dids(ConditionType::IsPresent(kyc.clone()), &[cdd.did]),
+            Condition::from_dids(ConditionType::IsAbsent(Claim::Blocked(scope.clone())), &[]),
+        ]
+    ));
+    assert_ok!(ComplianceManager::add_compliance_requirement(
+        owner.origin(),
+        ticker,
+        vec![],
+        vec![Condition::from_dids(
+            ConditionType::IsAnyOf(vec![accredited.clone(), kyc.clone()]),
+            &[]
+        )]
+    ));
+
+    // 3. The simulation lists the missing claims and who can issue them.
+    let simulate = || {
+        ComplianceManager::simulate_compliance(
+            &ticker,
+            owner.did,
+            user.did,
+            10,
+            &mut WeightMeter::max_limit_no_minimum(),
+        )
+        .unwrap()
+    };
+    let result = simulate();
+    assert!(!result.result);
+    assert_eq!(result.expression, None);
+    let missing = |req: usize, cond: usize| {
+        result.requirements[req].receiver_conditions[cond]
+            .missing_claims
+            .clone()
+    };
+    assert_eq!(
+        missing(0, 0),
+        vec![MissingClaim {
+            claim: kyc.clone(),
+            issuers: vec![cdd.did],
+        }]
+    );
+    assert_eq!(missing(0, 1), vec![]);
+    assert_eq!(
+        missing(1, 0),
+        vec![
+            MissingClaim {
+                claim: accredited,
+                issuers: vec![other_issuer.did],
+            },
+            MissingClaim {
+                claim: kyc.clone(),
+                issuers: vec![],
+            },
+        ]
+    );
+
+    // 4. Once the claim is added, nothing is missing anymore for the first requirement.
+    assert_ok!(Identity::add_claim(cdd.origin(), user.did, kyc, None));
+    let result = simulate();
+    assert!(result.result);
+    assert!(result.requirements[0].result);
+    assert!(result.requirements[0]
+        .receiver_conditions
+        .iter()
+        .all(|cond| cond.missing_claims.is_empty()));
+}
+
 #[test]
 fn ensure_custom_scopes_limited() {
     ExtBuilder::default().build().execute_with(|| {
@@ -972,6 +1432,7 @@ fn cm_test_case_9_we() {
             &ticker,
             Some(owner.did),
             Some(user.did),
+            0,
             &mut WeightMeter::max_limit_no_minimum(),
         )
         .unwrap()
@@ -1000,6 +1461,7 @@ fn cm_test_case_9_we() {
         &ticker,
         Some(owner.did),
         Some(ferdie.did),
+        0,
         &mut WeightMeter::max_limit_no_minimum(),
     )
     .unwrap();
@@ -1068,6 +1530,7 @@ fn cm_test_case_11_we() {
         &ticker,
         Some(owner.did),
         Some(charlie.did),
+        0,
         &mut WeightMeter::max_limit_no_minimum(),
     )
     .unwrap();
@@ -1095,6 +1558,7 @@ fn cm_test_case_11_we() {
         &ticker,
         Some(owner.did),
         Some(dave.did),
+        0,
         &mut WeightMeter::max_limit_no_minimum(),
     )
     .unwrap();
@@ -1122,6 +1586,7 @@ fn cm_test_case_11_we() {
         &ticker,
         Some(owner.did),
         Some(eve.did),
+        0,
         &mut WeightMeter::max_limit_no_minimum(),
     )
     .unwrap();
@@ -1196,6 +1661,7 @@ fn cm_test_case_13_we() {
         &ticker,
         None,
         Some(charlie.did),
+        0,
         &mut WeightMeter::max_limit_no_minimum(),
     )
     .unwrap();
@@ -1231,6 +1697,7 @@ fn cm_test_case_13_we() {
         &ticker,
         None,
         Some(dave.did),
+        0,
         &mut WeightMeter::max_limit_no_minimum(),
     )
     .unwrap();
@@ -1265,6 +1732,7 @@ fn cm_test_case_13_we() {
         &ticker,
         Some(owner.did),
         Some(eve.did),
+        0,
         &mut WeightMeter::max_limit_no_minimum(),
     )
     .unwrap();
@@ -1305,7 +1773,7 @@ fn can_verify_restriction_with_primary_issuance_agent_we() {
     // No compliance requirement is present, compliance should fail
     let mut weight_meter = WeightMeter::max_limit_no_minimum();
     assert_ok!(
-        ComplianceManager::is_compliant(&ticker, issuer.did, other.did, &mut weight_meter),
+        ComplianceManager::is_compliant(&ticker, issuer.did, other.did, 0, &mut weight_meter),
         false
     );
 
@@ -1326,7 +1794,7 @@ fn can_verify_restriction_with_primary_issuance_agent_we() {
     ));
 
     let verify = |from: User, to: User, weight_meter: &mut WeightMeter| {
-        ComplianceManager::is_compliant(&ticker, from.did, to.did, weight_meter)
+        ComplianceManager::is_compliant(&ticker, from.did, to.did, 0, weight_meter)
     };
 
     // From primary issuance agent to the random guy should succeed
@@ -1416,6 +1884,7 @@ fn check_new_return_type_of_rpc() {
             &ticker,
             Some(owner.did),
             Some(receiver.did),
+            0,
             &mut WeightMeter::max_limit_no_minimum(),
         )
         .unwrap();
@@ -1,6 +1,6 @@
 use super::{
     asset_test::max_len_bytes,
-    storage::{root, Balance, Checkpoint, MaxDidWhts, MaxTargetIds, TestStorage, User},
+    storage::{root, Balance, Checkpoint, EventTest, MaxDidWhts, MaxTargetIds, TestStorage, User},
     ExtBuilder,
 };
 use crate::asset_test::{
@@ -11,24 +11,38 @@ use core::iter;
 use frame_support::{
     assert_noop, assert_ok,
     dispatch::{DispatchError, DispatchResult},
+    traits::OnInitialize,
     IterableStorageDoubleMap, StorageDoubleMap, StorageMap,
 };
 use pallet_asset::Tokens;
 use pallet_corporate_actions::{
-    ballot::{BallotMeta, BallotTimeRange, BallotVote, Motion, Votes},
-    distribution::{self, Distribution, PER_SHARE_PRECISION},
+    ballot::{
+        BallotMeta, BallotTimeRange, BallotVote, DelegationScope, Motion, MotionOutcome,
+        TallyRound, Votes,
+    },
+    coupon::{self, CouponTerms},
+    distribution::{
+        self, CashOption, Distribution, Election, ElectiveOptions, MAX_CASH_OPTIONS,
+        PER_SHARE_PRECISION,
+    },
+    exchange::{self, OfferKind},
+    reorganization::{self, Split, SplitRounding},
     CACheckpoint, CADetails, CAId, CAIdSequence, CAKind, CorporateAction, CorporateActions,
     Details, LocalCAId, RecordDate, RecordDateSpec, TargetIdentities, TargetTreatment,
     TargetTreatment::{Exclude, Include},
-    Tax,
+    Tax, TaxJurisdiction,
 };
 use polymesh_common_utilities::{
     constants::currency::ONE_UNIT,
     traits::checkpoint::{ScheduleCheckpoints, ScheduleId},
 };
+use polymesh_primitives::asset::{VestingRelease, VestingSchedule};
+use polymesh_primitives::calendar::{CalendarPeriod, CalendarUnit, DayCount};
+use polymesh_primitives::settlement::{VenueDetails, VenueId, VenueType};
 use polymesh_primitives::{
-    agent::AgentGroup, asset::CheckpointId, AuthorizationData, Document, DocumentId, IdentityId,
-    Moment, PortfolioId, PortfolioNumber, Signatory, Ticker,
+    agent::AgentGroup, asset::CheckpointId, AuthorizationData, Claim, CountryCode, Document,
+    DocumentHash, DocumentId, Fund, FundDescription, IdentityId, Moment, PortfolioId,
+    PortfolioKind, PortfolioName, PortfolioNumber, Scope, Signatory, Ticker,
 };
 use sp_arithmetic::Permill;
 use sp_keyring::AccountKeyring;
@@ -47,10 +61,20 @@ type CA = pallet_corporate_actions::Module<TestStorage>;
 type Ballot = pallet_corporate_actions::ballot::Module<TestStorage>;
 type Dist = distribution::Module<TestStorage>;
 type Portfolio = pallet_portfolio::Module<TestStorage>;
+type Settlement = pallet_settlement::Module<TestStorage>;
 type BaseError = pallet_base::Error<TestStorage>;
 type Error = pallet_corporate_actions::Error<TestStorage>;
 type BallotError = pallet_corporate_actions::ballot::Error<TestStorage>;
+type BallotEvent = pallet_corporate_actions::ballot::Event;
+type Coupon = coupon::Module<TestStorage>;
+type CouponError = coupon::Error<TestStorage>;
+type CouponEvent = coupon::Event;
 type DistError = distribution::Error<TestStorage>;
+type Exchange = exchange::Module<TestStorage>;
+type ExchangeError = exchange::Error<TestStorage>;
+type Reorg = reorganization::Module<TestStorage>;
+type ReorgError = reorganization::Error<TestStorage>;
+type ReorgEvent = reorganization::Event;
 type PError = pallet_portfolio::Error<TestStorage>;
 type CPError = pallet_asset::checkpoint::Error<TestStorage>;
 type EAError = pallet_external_agents::Error<TestStorage>;
@@ -1720,6 +1744,147 @@ fn vote_scheduled_checkpoint() {
     vote_cp_test(|ticker, owner| notice_ca(owner, ticker, Some(2000)).unwrap());
 }
 
+#[test]
+fn finalize_ballot_rcv_works() {
+    test(|ticker, [owner, other, voter]| {
+        set_schedule_complexity();
+        let baz = User::new(AccountKeyring::Dave);
+        transfer_amount(&ticker, owner, other, 400);
+        transfer_amount(&ticker, owner, voter, 300);
+        transfer_amount(&ticker, owner, baz, 200);
+
+        let id = notice_ca(owner, ticker, Some(1)).unwrap();
+        assert_ok!(attach(owner, id, true));
+        set_timestamp(T_RANGE.start);
+
+        // In motion A, `baz` falls back from "baz" to "bar".
+        let vote = |user: User, vs: &[(Balance, Option<u16>)]| {
+            let vs = vs
+                .iter()
+                .map(|&(power, fallback)| BallotVote { power, fallback })
+                .collect();
+            assert_ok!(Ballot::vote(user.origin(), id, vs));
+        };
+        vote(other, &[(400, None), (0, None), (0, None), (400, None)]);
+        vote(voter, &[(0, None), (300, None), (0, None), (0, None)]);
+        vote(baz, &[(0, None), (0, None), (200, Some(1)), (0, None)]);
+        assert_eq!(Ballot::results(id), vec![400, 300, 200, 400]);
+
+        let finalize =
+            |voters, choices| Ballot::finalize_ballot(owner.origin(), id, voters, choices);
+        assert_noop!(finalize(3, 4), BallotError::VotingNotEnded);
+        set_timestamp(T_RANGE.end + 1);
+        assert_noop!(finalize(2, 4), BallotError::VoterCountExceeded);
+        assert_noop!(finalize(3, 3), BallotError::ChoiceCountExceeded);
+        assert_ok!(finalize(3, 4));
+        assert_noop!(finalize(3, 4), BallotError::AlreadyFinalized);
+
+        // "baz" is eliminated, and its votes transferred to "bar", which then has a majority.
+        assert_eq!(
+            Ballot::final_results(id).unwrap(),
+            vec![
+                MotionOutcome {
+                    winner: Some(1),
+                    rounds: vec![
+                        TallyRound {
+                            tallies: vec![400, 300, 200],
+                            eliminated: vec![2],
+                        },
+                        TallyRound {
+                            tallies: vec![400, 500, 0],
+                            eliminated: vec![],
+                        },
+                    ],
+                },
+                MotionOutcome {
+                    winner: Some(0),
+                    rounds: vec![TallyRound {
+                        tallies: vec![400],
+                        eliminated: vec![],
+                    }],
+                },
+            ]
+        );
+    });
+}
+
+#[test]
+fn proxy_vote_works() {
+    test(|ticker, [owner, other, voter]| {
+        set_schedule_complexity();
+        let proxy = User::new(AccountKeyring::Dave);
+        transfer(&ticker, owner, other);
+        transfer(&ticker, owner, voter);
+
+        let id = notice_ca(owner, ticker, Some(1)).unwrap();
+        assert_ok!(attach(owner, id, false));
+
+        // Delegate on the ticker, and on the ballot, with the latter taking precedence.
+        let delegate = |user: User, scope, proxy| Ballot::delegate(user.origin(), scope, proxy);
+        let on_ticker = DelegationScope::Ticker(ticker);
+        let on_ballot = DelegationScope::Ballot(id);
+        assert_noop!(
+            delegate(other, on_ticker, other.did),
+            BallotError::SelfDelegation
+        );
+        assert_noop!(
+            delegate(other, on_ticker, IdentityId::from(999)),
+            BallotError::NoSuchProxy
+        );
+        assert_noop!(
+            delegate(
+                other,
+                DelegationScope::Ballot(next_ca_id(ticker)),
+                proxy.did
+            ),
+            BallotError::NoSuchBallot
+        );
+        assert_ok!(delegate(other, on_ticker, proxy.did));
+        assert_ok!(delegate(voter, on_ticker, proxy.did));
+        assert_ok!(delegate(voter, on_ballot, other.did));
+        assert_eq!(Ballot::proxy_of(id, other.did), Some(proxy.did));
+        assert_eq!(Ballot::proxy_of(id, voter.did), Some(other.did));
+        set_timestamp(T_RANGE.start);
+
+        // The proxy votes with the delegator's balance at the record date.
+        let proxy_vote =
+            |delegator: User, vs| Ballot::proxy_vote(proxy.origin(), id, delegator.did, votes(vs));
+        assert_noop!(proxy_vote(voter, &[0; 4]), BallotError::NotProxy);
+        assert_noop!(
+            proxy_vote(other, &[AMOUNT + 1, 0, 0, 0]),
+            BallotError::InsufficientVotes
+        );
+        let vs = &[200, 300, 0, AMOUNT];
+        assert_ok!(proxy_vote(other, vs));
+        assert_eq!(Ballot::votes(id, other.did), votes(vs));
+        assert_eq!(Ballot::proxy_votes(id, other.did), Some(proxy.did));
+        assert_eq!(Ballot::results(id), vs.to_vec());
+        assert!(System::events().iter().any(|e| e.event
+            == EventTest::CorporateBallot(BallotEvent::ProxyVoteCast(
+                proxy.did,
+                other.did,
+                id,
+                votes(vs)
+            ))));
+
+        // The delegator overrides the proxy, which then cannot vote for them again.
+        let own = &[AMOUNT, 0, 0, 0];
+        assert_ok!(Ballot::vote(other.origin(), id, votes(own)));
+        assert_eq!(Ballot::proxy_votes(id, other.did), None);
+        assert_eq!(Ballot::results(id), own.to_vec());
+        assert_noop!(proxy_vote(other, vs), BallotError::DelegatorAlreadyVoted);
+
+        // Once the ballot delegation is revoked, the ticker delegation applies.
+        assert_ok!(Ballot::revoke_delegation(voter.origin(), on_ballot));
+        assert_noop!(
+            Ballot::revoke_delegation(voter.origin(), on_ballot),
+            BallotError::NoSuchDelegation
+        );
+        assert_ok!(proxy_vote(voter, vs));
+        assert_eq!(Ballot::proxy_votes(id, voter.did), Some(proxy.did));
+    });
+}
+
 fn dist_ca(owner: User, ticker: Ticker, rd: Option<Moment>) -> Result<CAId, DispatchError> {
     let id = next_ca_id(ticker);
     moment_ca(owner, ticker, CAKind::UnpredictableBenefit, rd)?;
@@ -2284,3 +2449,901 @@ fn dist_claim_existing_checkpoint() {
 fn dist_claim_scheduled_checkpoint() {
     dist_claim_cp_test(|ticker, owner| dist_ca(owner, ticker, Some(2000)).unwrap());
 }
+
+fn elective_options(
+    cash: &[(Ticker, Balance, Balance)],
+    scrip_per_share: Option<Balance>,
+    default_election: Election,
+) -> ElectiveOptions {
+    let cash = cash
+        .iter()
+        .map(|&(currency, per_share, amount)| CashOption {
+            currency,
+            per_share,
+            amount,
+        })
+        .collect();
+    ElectiveOptions {
+        cash,
+        scrip_per_share,
+        default_election,
+    }
+}
+
+#[test]
+fn dist_elective_options_works() {
+    currency_test(|ticker, currency, [owner, foo, _]| {
+        let alt = create_asset(b"GAMMA", owner);
+        transfer(&ticker, owner, foo);
+
+        let id = dist_ca(owner, ticker, Some(1)).unwrap();
+        let distribute = |expires_at| {
+            Dist::distribute(
+                owner.origin(),
+                id,
+                None,
+                currency,
+                PER_SHARE_PRECISION,
+                1000,
+                5,
+                expires_at,
+            )
+        };
+        assert_ok!(distribute(None));
+        let set = |user: User, options| Dist::set_elective_options(user.origin(), id, options);
+        let elect = |election| Dist::elect(foo.origin(), id, election);
+
+        // Not elective yet.
+        assert_noop!(elect(Election::Scrip), DistError::NotElective);
+
+        // Only distributions that expire can be made elective.
+        let cash_alt = (alt, 2 * PER_SHARE_PRECISION, 2000);
+        let options = elective_options(&[cash_alt], Some(PER_SHARE_PRECISION / 2), Election::Scrip);
+        assert_noop!(
+            set(owner, options.clone()),
+            DistError::ElectiveWithoutExpiry
+        );
+        assert_ok!(Dist::remove_distribution(owner.origin(), id));
+        assert_ok!(distribute(Some(10)));
+
+        // Only agents can set options, and options must be valid.
+        assert_noop!(set(foo, options.clone()), EAError::UnauthorizedAgent);
+        assert_noop!(
+            set(owner, elective_options(&[], None, Election::Cash(currency))),
+            DistError::NoElectiveOptions
+        );
+        let too_many = vec![cash_alt; MAX_CASH_OPTIONS as usize + 1];
+        assert_noop!(
+            set(owner, elective_options(&too_many, None, Election::Scrip)),
+            DistError::TooManyCashOptions
+        );
+        assert_noop!(
+            set(owner, elective_options(&[], Some(0), Election::Scrip)),
+            DistError::DistributionPerShareIsZero
+        );
+        assert_noop!(
+            set(
+                owner,
+                elective_options(&[(alt, 0, 2000)], None, Election::Scrip)
+            ),
+            DistError::DistributionPerShareIsZero
+        );
+        assert_noop!(
+            set(
+                owner,
+                elective_options(&[(alt, 1, 0)], None, Election::Scrip)
+            ),
+            DistError::DistributionAmountIsZero
+        );
+        assert_noop!(
+            set(
+                owner,
+                elective_options(&[cash_alt, cash_alt], None, Election::Scrip)
+            ),
+            DistError::DuplicateCurrency
+        );
+        assert_noop!(
+            set(
+                owner,
+                elective_options(&[(currency, 1, 1)], None, Election::Scrip)
+            ),
+            DistError::DuplicateCurrency
+        );
+        assert_noop!(
+            set(owner, elective_options(&[cash_alt], None, Election::Scrip)),
+            DistError::InvalidElection
+        );
+
+        // Set the options, locking the `alt` amount.
+        assert_ok!(set(owner, options.clone()));
+        assert_eq!(Dist::electives(id), Some(options.clone()));
+        assert_eq!(Dist::elective_remaining(id, alt), 2000);
+        let pid = PortfolioId::default_portfolio(owner.did);
+        let free = Asset::total_supply(alt) - 2000;
+        assert_ok!(Portfolio::ensure_sufficient_balance(&pid, &alt, free));
+        assert_noop!(
+            Portfolio::ensure_sufficient_balance(&pid, &alt, free + 1),
+            PError::InsufficientPortfolioBalance,
+        );
+        assert_noop!(set(owner, options.clone()), DistError::AlreadyElective);
+
+        // Elect, and change one's mind.
+        assert_noop!(elect(Election::Cash(ticker)), DistError::InvalidElection);
+        assert_ok!(elect(Election::Cash(alt)));
+        assert_ok!(elect(Election::Cash(currency)));
+        assert_eq!(
+            Dist::holder_election(id, foo.did),
+            Some(Election::Cash(currency))
+        );
+
+        // Elections close at `payment_at`.
+        set_timestamp(5);
+        assert_noop!(elect(Election::Scrip), DistError::DistributionStarted);
+
+        // Removing the distribution unlocks `alt` and clears the elections.
+        set_timestamp(4);
+        assert_ok!(Dist::remove_distribution(owner.origin(), id));
+        assert_eq!(Dist::electives(id), None);
+        assert_eq!(Dist::elective_remaining(id, alt), 0);
+        assert_eq!(Dist::holder_election(id, foo.did), None);
+        assert_ok!(Portfolio::ensure_sufficient_balance(
+            &pid,
+            &alt,
+            Asset::total_supply(alt)
+        ));
+    });
+}
+
+#[test]
+fn dist_elective_claim_works() {
+    currency_test(|ticker, currency, [owner, foo, bar]| {
+        let baz = User::new(AccountKeyring::Dave);
+        let alt = create_asset(b"GAMMA", owner);
+
+        // Transfer 500 to each of `foo`, `bar`, and `baz`.
+        transfer(&ticker, owner, foo);
+        transfer(&ticker, owner, bar);
+        transfer(&ticker, owner, baz);
+
+        // Create the dist, paying 1 `currency`, 2 `alt`, or 1/2 share per share.
+        let id = dist_ca(owner, ticker, Some(1)).unwrap();
+        assert_ok!(Dist::distribute(
+            owner.origin(),
+            id,
+            None,
+            currency,
+            PER_SHARE_PRECISION,
+            1000,
+            5,
+            Some(10),
+        ));
+        let options = elective_options(
+            &[(alt, 2 * PER_SHARE_PRECISION, 1000)],
+            Some(PER_SHARE_PRECISION / 2),
+            Election::Scrip,
+        );
+        assert_ok!(Dist::set_elective_options(owner.origin(), id, options));
+
+        // `foo` elects `alt`, `bar` elects `currency`, and `baz` gets the default scrip.
+        assert_ok!(Dist::elect(foo.origin(), id, Election::Cash(alt)));
+        assert_ok!(Dist::elect(bar.origin(), id, Election::Cash(currency)));
+        set_timestamp(5);
+
+        CorporateActions::mutate(ticker, id.local_id, |ca| {
+            ca.as_mut().unwrap().default_withholding_tax = P25;
+        });
+        let post_tax = |benefit: Balance| benefit - P25 * benefit;
+
+        // `foo` claims in `alt`, using up its whole amount.
+        assert_ok!(Dist::claim(foo.origin(), id));
+        assert_eq!(Asset::balance_of(&alt, foo.did), post_tax(2 * AMOUNT));
+        assert_eq!(Dist::elective_remaining(id, alt), 0);
+        assert_eq!(Dist::distributions(id).unwrap().remaining, 1000);
+
+        // `bar` is pushed to in `currency`.
+        assert_ok!(Dist::push_benefit(owner.origin(), id, bar.did));
+        assert_eq!(Asset::balance_of(&currency, bar.did), post_tax(AMOUNT));
+        assert_eq!(Dist::distributions(id).unwrap().remaining, 1000 - AMOUNT);
+
+        // `baz` claims newly issued shares.
+        let supply = Asset::total_supply(ticker);
+        assert_ok!(Dist::claim(baz.origin(), id));
+        let scrip = post_tax(AMOUNT / 2);
+        assert_eq!(Asset::balance_of(&ticker, baz.did), AMOUNT + scrip);
+        assert_eq!(Asset::total_supply(ticker), supply + scrip);
+        assert_noop!(Dist::claim(baz.origin(), id), DistError::HolderAlreadyPaid);
+
+        // Reclaiming unlocks what remains of all currencies.
+        set_timestamp(10);
+        assert_ok!(Dist::reclaim(owner.origin(), id));
+        let pid = PortfolioId::default_portfolio(owner.did);
+        let wht = 2 * AMOUNT - post_tax(2 * AMOUNT);
+        let free = Asset::total_supply(alt) - 2 * AMOUNT + wht;
+        assert_ok!(Portfolio::ensure_sufficient_balance(&pid, &alt, free));
+    });
+}
+
+#[test]
+fn dist_treaty_tax_and_refund_works() {
+    currency_test(|ticker, currency, [owner, foo, bar]| {
+        transfer(&ticker, owner, foo);
+        transfer(&ticker, owner, bar);
+
+        // Only root may set treaty rates, and only agents the issuer's jurisdiction.
+        let (us, de) = (CountryCode::US, CountryCode::DE);
+        assert_noop!(
+            CA::set_treaty_withholding_tax(owner.origin(), us, de, Some(P25)),
+            DispatchError::BadOrigin,
+        );
+        assert_ok!(CA::set_treaty_withholding_tax(root(), us, de, Some(P25)));
+        assert_eq!(CA::treaty_withholding_tax(us, de), Some(P25));
+        let jurisdiction = Some(TaxJurisdiction {
+            country: us,
+            claim_issuer: owner.did,
+        });
+        assert_noop!(
+            CA::set_issuer_tax_jurisdiction(foo.origin(), ticker, jurisdiction),
+            EAError::UnauthorizedAgent
+        );
+        assert_ok!(CA::set_issuer_tax_jurisdiction(
+            owner.origin(),
+            ticker,
+            jurisdiction
+        ));
+        assert_eq!(CA::issuer_tax_jurisdiction(ticker), jurisdiction);
+
+        // `foo` is German per the trusted issuer, while `bar`'s claim is from an untrusted one.
+        let german = Claim::Jurisdiction(de, Scope::Ticker(ticker));
+        assert_ok!(Identity::add_claim(
+            owner.origin(),
+            foo.did,
+            german.clone(),
+            None
+        ));
+        assert_ok!(Identity::add_claim(foo.origin(), bar.did, german, None));
+
+        // Create the dist, with a 50% default tax.
+        let id = dist_ca(owner, ticker, Some(1)).unwrap();
+        assert_ok!(Dist::distribute(
+            owner.origin(),
+            id,
+            None,
+            currency,
+            PER_SHARE_PRECISION,
+            1000,
+            5,
+            None,
+        ));
+        set_timestamp(5);
+        CorporateActions::mutate(ticker, id.local_id, |ca| {
+            ca.as_mut().unwrap().default_withholding_tax = P50;
+        });
+
+        // `foo` is withheld at the treaty rate, `bar` at the default.
+        assert_ok!(Dist::claim(foo.origin(), id));
+        assert_ok!(Dist::claim(bar.origin(), id));
+        let balance = |user: User| Asset::balance_of(&currency, user.did);
+        assert_eq!(balance(foo), AMOUNT - P25 * AMOUNT);
+        assert_eq!(balance(bar), AMOUNT - P50 * AMOUNT);
+        assert_eq!(Dist::withheld_tax(id, foo.did), P25 * AMOUNT);
+        assert_eq!(Dist::withheld_tax(id, bar.did), P50 * AMOUNT);
+
+        // `bar` reclaims part of the tax withheld.
+        let refund =
+            |user: User, amount| Dist::refund_withheld_tax(user.origin(), id, bar.did, amount);
+        assert_noop!(refund(foo, 100), EAError::UnauthorizedAgent);
+        assert_noop!(
+            refund(owner, P50 * AMOUNT + 1),
+            DistError::RefundExceedsWithheld
+        );
+        assert_ok!(refund(owner, 100));
+        assert_eq!(balance(bar), AMOUNT - P50 * AMOUNT + 100);
+        assert_eq!(Dist::withheld_tax(id, bar.did), P50 * AMOUNT - 100);
+    });
+}
+
+fn reorg_ca(owner: User, ticker: Ticker, rd: Option<Moment>) -> Result<CAId, DispatchError> {
+    let id = next_ca_id(ticker);
+    moment_ca(owner, ticker, CAKind::Reorganization, rd)?;
+    Ok(id)
+}
+
+#[test]
+fn reorg_split_works() {
+    test(|ticker, [owner, foo, bar]| {
+        set_schedule_complexity();
+        transfer(&ticker, owner, foo);
+        transfer(&ticker, owner, bar);
+        let supply = token_details(&ticker).total_supply;
+
+        // Splits can only be attached to reorganizations with a record date.
+        let split = |id, new_shares, old_shares| {
+            Reorg::split(
+                owner.origin(),
+                id,
+                new_shares,
+                old_shares,
+                SplitRounding::Down,
+            )
+        };
+        let id = dist_ca(owner, ticker, Some(1000)).unwrap();
+        assert_noop!(split(id, 2, 1), ReorgError::CANotReorganization);
+        let id = reorg_ca(owner, ticker, None).unwrap();
+        assert_noop!(split(id, 2, 1), Error::NoRecordDate);
+        let id = reorg_ca(owner, ticker, Some(1000)).unwrap();
+        assert_noop!(split(id, 2, 2), ReorgError::InvalidSplitRatio);
+        assert_noop!(split(id, 0, 1), ReorgError::InvalidSplitRatio);
+        assert_ok!(split(id, 2, 1));
+        assert_noop!(split(id, 2, 1), ReorgError::AlreadyExists);
+
+        // `foo` holds half of its balance in a user portfolio.
+        assert_ok!(Portfolio::create_portfolio(
+            foo.origin(),
+            PortfolioName(b"split".to_vec())
+        ));
+        let foo_user = PortfolioId::user_portfolio(foo.did, PortfolioNumber(1));
+        assert_ok!(Portfolio::move_portfolio_funds(
+            foo.origin(),
+            PortfolioId::default_portfolio(foo.did),
+            foo_user,
+            vec![Fund {
+                description: FundDescription::Fungible {
+                    ticker,
+                    amount: AMOUNT / 2
+                },
+                memo: None,
+            }],
+        ));
+
+        // Holders are rescaled after the record date, while the asset is frozen.
+        let rescale = |holders: &[User], portfolios| {
+            let holders = holders.iter().map(|u| u.did).collect();
+            Reorg::rescale_holders(owner.origin(), id, holders, portfolios)
+        };
+        assert_noop!(
+            rescale(&[foo], 1),
+            ReorgError::CannotRescaleBeforeRecordDate
+        );
+        set_timestamp(1000);
+        assert_noop!(rescale(&[foo], 1), ReorgError::AssetNotFrozen);
+        assert_ok!(Asset::freeze(owner.origin(), ticker));
+
+        // The user portfolios of the holders must be accounted for.
+        assert_noop!(rescale(&[foo], 0), ReorgError::TooManyPortfolios);
+        assert_ok!(rescale(&[foo], 1));
+
+        // Until every holder is rescaled, the asset cannot be unfrozen, issued, redeemed or force transferred.
+        assert_noop!(
+            Asset::unfreeze(owner.origin(), ticker),
+            AssetError::RescaleInProgress
+        );
+        assert_noop!(
+            Asset::issue(owner.origin(), ticker, AMOUNT, PortfolioKind::Default),
+            AssetError::RescaleInProgress
+        );
+        assert_noop!(
+            Asset::redeem(owner.origin(), ticker, AMOUNT),
+            AssetError::RescaleInProgress
+        );
+        // Moving `bar`'s tokens to the rescaled `foo` would leave them unscaled.
+        assert_noop!(
+            Asset::force_transfer(
+                owner.origin(),
+                ticker,
+                PortfolioId::default_portfolio(bar.did),
+                PortfolioId::default_portfolio(foo.did),
+                AMOUNT,
+                DocumentHash::H256([7; 32])
+            ),
+            AssetError::RescaleInProgress
+        );
+        assert_ok!(rescale(&[owner], 0));
+        assert_noop!(
+            Asset::unfreeze(owner.origin(), ticker),
+            AssetError::RescaleInProgress
+        );
+        assert_ok!(rescale(&[bar], 0));
+        assert_eq!(Asset::balance_to_rescale(ticker), 0);
+        assert_ok!(Asset::unfreeze(owner.origin(), ticker));
+
+        let balance = |u: User| Asset::balance_of(&ticker, u.did);
+        assert_eq!(balance(foo), 2 * AMOUNT);
+        assert_eq!(balance(bar), 2 * AMOUNT);
+        assert_eq!(balance(owner), 2 * (supply - 2 * AMOUNT));
+        assert_eq!(
+            Portfolio::portfolio_asset_balances(PortfolioId::default_portfolio(foo.did), ticker),
+            AMOUNT
+        );
+        assert_eq!(
+            Portfolio::portfolio_asset_balances(foo_user, ticker),
+            AMOUNT
+        );
+        assert_eq!(token_details(&ticker).total_supply, 2 * supply);
+        assert_eq!(Reorg::splits(id).unwrap().holders_rescaled, 3);
+
+        // The record date checkpoint keeps the balances before the split.
+        let cp = Checkpoint::checkpoint_id_sequence(ticker);
+        assert_eq!(Asset::get_balance_at(ticker, foo.did, cp), AMOUNT);
+
+        // Holders are rescaled once, and started splits cannot be removed.
+        assert_ok!(Asset::freeze(owner.origin(), ticker));
+        assert_noop!(rescale(&[foo], 1), ReorgError::HolderAlreadyRescaled);
+        assert_noop!(
+            Reorg::remove_split(owner.origin(), id),
+            ReorgError::SplitStarted
+        );
+        assert_noop!(CA::remove_ca(owner.origin(), id), ReorgError::SplitStarted);
+    });
+}
+
+#[test]
+fn reorg_split_rejects_vesting_schedules() {
+    test(|ticker, [owner, foo, _]| {
+        set_schedule_complexity();
+        transfer(&ticker, owner, foo);
+
+        let owner_portfolio = PortfolioId::default_portfolio(owner.did);
+        let vest = || {
+            let schedule = VestingSchedule {
+                amount: AMOUNT,
+                start: 1_000,
+                cliff: 2_000,
+                end: 5_000,
+                release: VestingRelease::Linear,
+                released: 0,
+            };
+            Asset::create_vesting_schedule(owner.origin(), ticker, owner_portfolio, schedule)
+        };
+        let revoke = || Asset::revoke_vesting_schedule(owner.origin(), ticker, owner_portfolio, 0);
+        let rescale =
+            |id, holder: User| Reorg::rescale_holders(owner.origin(), id, vec![holder.did], 0);
+
+        // A split can't be attached while some tokens are vesting.
+        assert_ok!(vest());
+        let id = reorg_ca(owner, ticker, Some(1000)).unwrap();
+        let split = || Reorg::split(owner.origin(), id, 2, 1, SplitRounding::Down);
+        assert_noop!(split(), ReorgError::AssetHasVestingSchedules);
+        assert_ok!(revoke());
+        assert_ok!(split());
+
+        // Nor started, if a schedule was created since.
+        assert_ok!(vest());
+        set_timestamp(1000);
+        assert_ok!(Asset::freeze(owner.origin(), ticker));
+        assert_noop!(rescale(id, owner), ReorgError::AssetHasVestingSchedules);
+        assert_ok!(revoke());
+
+        // Once started, no schedule can be created until every holder is rescaled.
+        assert_ok!(rescale(id, owner));
+        assert_noop!(vest(), AssetError::RescaleInProgress);
+        assert_ok!(rescale(id, foo));
+        assert_ok!(vest());
+    });
+}
+
+#[test]
+fn reorg_split_rounding_indivisible() {
+    let split = |new_shares, old_shares, rounding| Split {
+        new_shares,
+        old_shares,
+        rounding,
+        holders_rescaled: 0,
+    };
+    let unit = ONE_UNIT;
+
+    // 4 units in a 1:3 reverse split are 1.33 units.
+    let rescale = |rounding| split(1, 3, rounding).rescale(4 * unit, unit).unwrap();
+    assert_eq!(rescale(SplitRounding::Down), (unit, unit / 3));
+    assert_eq!(rescale(SplitRounding::Up), (2 * unit, 0));
+    assert_eq!(rescale(SplitRounding::Nearest), (unit, unit / 3));
+    // 5 units in a 1:3 reverse split are 1.67 units.
+    let rescale = |rounding| split(1, 3, rounding).rescale(5 * unit, unit).unwrap();
+    assert_eq!(rescale(SplitRounding::Nearest), (2 * unit, 0));
+    // Divisible assets are rounded to the smallest denomination.
+    let rescale = |rounding| split(3, 2, rounding).rescale(5, 1).unwrap();
+    assert_eq!(rescale(SplitRounding::Down), (7, 0));
+    assert_eq!(rescale(SplitRounding::Nearest), (8, 0));
+    assert_eq!(split(2, 1, SplitRounding::Down).rescale(u128::MAX, 1), None);
+
+    test(|ticker, [owner, foo, _]| {
+        set_schedule_complexity();
+        Tokens::mutate(ticker, |t| {
+            if let Some(t) = t {
+                t.divisible = false;
+            }
+        });
+        transfer_amount(&ticker, owner, foo, 4 * unit);
+        let supply = token_details(&ticker).total_supply;
+
+        let id = reorg_ca(owner, ticker, Some(1000)).unwrap();
+        assert_ok!(Reorg::split(owner.origin(), id, 1, 3, SplitRounding::Down));
+        set_timestamp(1000);
+        assert_ok!(Asset::freeze(owner.origin(), ticker));
+        assert_ok!(Reorg::rescale_holders(owner.origin(), id, vec![foo.did], 0));
+
+        // `foo` is left with one unit, and is owed the rest as cash in lieu.
+        assert_eq!(Asset::balance_of(&ticker, foo.did), unit);
+        assert_eq!(token_details(&ticker).total_supply, supply - 3 * unit);
+        assert!(System::events().iter().any(|e| e.event
+            == EventTest::CorporateReorganization(ReorgEvent::CashInLieu(id, foo.did, unit / 3))));
+    });
+}
+
+fn exchange_venue(owner: User) -> VenueId {
+    let venue_id = Settlement::venue_counter();
+    assert_ok!(Settlement::create_venue(
+        owner.origin(),
+        VenueDetails::default(),
+        vec![owner.acc()],
+        VenueType::Other
+    ));
+    venue_id
+}
+
+#[test]
+fn exchange_voluntary_works() {
+    currency_test(|ticker, currency, [owner, foo, bar]| {
+        transfer(&ticker, owner, foo);
+        transfer(&ticker, owner, bar);
+        let id = reorg_ca(owner, ticker, Some(1000)).unwrap();
+        let venue_id = exchange_venue(owner);
+        let foo_venue_id = exchange_venue(foo);
+        let create = |new_ticker, per_share, amount, venue_id| {
+            Exchange::create_offer(
+                owner.origin(),
+                id,
+                OfferKind::Voluntary,
+                None,
+                new_ticker,
+                per_share,
+                amount,
+                venue_id,
+                2000,
+            )
+        };
+        let per_share = 2 * PER_SHARE_PRECISION;
+        assert_noop!(
+            create(currency, 0, 2_000, venue_id),
+            ExchangeError::OfferAmountIsZero
+        );
+        assert_noop!(
+            create(ticker, per_share, 2_000, venue_id),
+            ExchangeError::SameTicker
+        );
+        assert_noop!(
+            create(currency, per_share, 2_000, foo_venue_id),
+            ExchangeError::InvalidVenue
+        );
+        assert_ok!(create(currency, per_share, 2_000, venue_id));
+        assert_noop!(
+            create(currency, per_share, 2_000, venue_id),
+            ExchangeError::AlreadyExists
+        );
+        assert_noop!(
+            Reorg::split(owner.origin(), id, 2, 1, SplitRounding::Down),
+            ReorgError::AlreadyExists
+        );
+        let owner_portfolio = PortfolioId::default_portfolio(owner.did);
+        assert_eq!(Portfolio::locked_assets(owner_portfolio, currency), 2_000);
+
+        // Holders elect after the record date, and before the deadline.
+        assert_noop!(
+            Exchange::elect(foo.origin(), id, vec![]),
+            ExchangeError::CannotExchangeBeforeRecordDate
+        );
+        set_timestamp(1000);
+        let owner_balance = Asset::balance_of(&ticker, owner.did);
+        assert_ok!(Exchange::elect(foo.origin(), id, vec![]));
+        assert_eq!(Asset::balance_of(&ticker, foo.did), 0);
+        assert_eq!(Asset::balance_of(&currency, foo.did), 2 * AMOUNT);
+        assert_eq!(
+            Asset::balance_of(&ticker, owner.did),
+            owner_balance + AMOUNT
+        );
+        assert_eq!(Exchange::offers(id).unwrap().remaining, 2_000 - 2 * AMOUNT);
+        assert_eq!(
+            Portfolio::locked_assets(owner_portfolio, currency),
+            2_000 - 2 * AMOUNT
+        );
+        assert_noop!(
+            Exchange::elect(foo.origin(), id, vec![]),
+            ExchangeError::HolderAlreadyExchanged
+        );
+        assert_noop!(
+            Exchange::push_exchange(owner.origin(), id, bar.did, vec![]),
+            ExchangeError::OfferNotMandatory
+        );
+        assert_noop!(
+            Exchange::remove_offer(owner.origin(), id),
+            ExchangeError::OfferStarted
+        );
+
+        // After the deadline, `bar` keeps the old asset and the rest of the deposit is reclaimed.
+        assert_noop!(
+            Exchange::reclaim(owner.origin(), id),
+            ExchangeError::DeadlineNotPassed
+        );
+        set_timestamp(2000);
+        assert_noop!(
+            Exchange::elect(bar.origin(), id, vec![]),
+            ExchangeError::CannotElectAfterDeadline
+        );
+        assert_ok!(Exchange::reclaim(owner.origin(), id));
+        assert_eq!(Portfolio::locked_assets(owner_portfolio, currency), 0);
+        assert_eq!(Asset::balance_of(&ticker, bar.did), AMOUNT);
+        assert_noop!(
+            Exchange::reclaim(owner.origin(), id),
+            ExchangeError::AlreadyReclaimed
+        );
+    });
+}
+
+#[test]
+fn exchange_mandatory_works() {
+    currency_test(|ticker, currency, [owner, foo, bar]| {
+        let baz = User::new(AccountKeyring::Dave);
+        transfer(&ticker, owner, foo);
+        transfer(&ticker, owner, bar);
+        let id = reorg_ca(owner, ticker, Some(1000)).unwrap();
+        let venue_id = exchange_venue(owner);
+        assert_ok!(Exchange::create_offer(
+            owner.origin(),
+            id,
+            OfferKind::Mandatory,
+            None,
+            currency,
+            PER_SHARE_PRECISION / 2,
+            AMOUNT,
+            venue_id,
+            2000,
+        ));
+        let push = |holder: User, portfolios| {
+            Exchange::push_exchange(owner.origin(), id, holder.did, portfolios)
+        };
+
+        // Unelected positions are exchanged after the deadline.
+        set_timestamp(1000);
+        assert_noop!(push(foo, vec![]), ExchangeError::CannotPushBeforeDeadline);
+        set_timestamp(2000);
+        assert_noop!(
+            Exchange::reclaim(owner.origin(), id),
+            ExchangeError::HoldersNotExchanged
+        );
+        assert_noop!(
+            push(foo, vec![PortfolioNumber(1); 11]),
+            ExchangeError::TooManyPortfolios
+        );
+        assert_ok!(push(foo, vec![]));
+        assert_eq!(Asset::balance_of(&ticker, foo.did), 0);
+        assert_eq!(Asset::balance_of(&currency, foo.did), AMOUNT / 2);
+        assert!(Exchange::holder_exchanged((id, foo.did)));
+        assert_noop!(push(baz, vec![]), ExchangeError::NothingToExchange);
+        assert_noop!(
+            CA::remove_ca(owner.origin(), id),
+            ExchangeError::OfferStarted
+        );
+
+        // `bar` moved its position out of the default portfolio after the record date.
+        assert_ok!(Portfolio::create_portfolio(
+            bar.origin(),
+            PortfolioName(b"moved".to_vec())
+        ));
+        let bar_user = PortfolioId::user_portfolio(bar.did, PortfolioNumber(1));
+        assert_ok!(Portfolio::move_portfolio_funds(
+            bar.origin(),
+            PortfolioId::default_portfolio(bar.did),
+            bar_user,
+            vec![Fund {
+                description: FundDescription::Fungible {
+                    ticker,
+                    amount: AMOUNT
+                },
+                memo: None,
+            }],
+        ));
+        assert_noop!(
+            Exchange::reclaim(owner.origin(), id),
+            ExchangeError::HoldersNotExchanged
+        );
+        assert_noop!(push(bar, vec![]), ExchangeError::InsufficientHolderBalance);
+        assert_ok!(push(bar, vec![PortfolioNumber(1)]));
+        assert_eq!(Portfolio::portfolio_asset_balances(bar_user, ticker), 0);
+        assert_eq!(Asset::balance_of(&currency, bar.did), AMOUNT / 2);
+
+        // Every holder was exchanged, so the deposit can be reclaimed.
+        assert_ok!(Exchange::reclaim(owner.origin(), id));
+        assert_eq!(Exchange::offers(id).unwrap().old_exchanged, 2 * AMOUNT);
+    });
+}
+
+const DAY: Moment = 86_400_000;
+
+fn coupon_terms(currency: Ticker) -> CouponTerms {
+    CouponTerms {
+        currency,
+        face_value: PER_SHARE_PRECISION,
+        rate: Permill::from_percent(12),
+        day_count: DayCount::Actual360,
+        start: 0,
+        period: CalendarPeriod {
+            unit: CalendarUnit::Day,
+            amount: 30,
+        },
+        maturity: 60 * DAY,
+        claim_period: None,
+    }
+}
+
+#[test]
+fn coupon_schedule_works() {
+    currency_test(|ticker, currency, [owner, foo, bar]| {
+        transfer(&ticker, owner, foo);
+        let terms = coupon_terms(currency);
+        let create = |terms| Coupon::create_schedule(owner.origin(), ticker, None, terms);
+
+        // Only agents may attach valid schedules.
+        assert_noop!(
+            Coupon::create_schedule(foo.origin(), ticker, None, terms),
+            EAError::UnauthorizedAgent
+        );
+        assert_noop!(
+            create(CouponTerms {
+                face_value: 0,
+                ..terms
+            }),
+            CouponError::FaceValueIsZero
+        );
+        assert_noop!(
+            create(CouponTerms {
+                period: CalendarPeriod::default(),
+                ..terms
+            }),
+            CouponError::PeriodNotRecurring
+        );
+        assert_noop!(
+            create(CouponTerms {
+                maturity: 0,
+                ..terms
+            }),
+            CouponError::MaturityBeforeStart
+        );
+        assert_ok!(create(terms));
+        assert_noop!(create(terms), CouponError::AlreadyExists);
+        assert_eq!(Coupon::schedules(ticker).unwrap().next_coupon, 30 * DAY);
+        assert_noop!(
+            Coupon::pay_coupon(owner.origin(), ticker),
+            CouponError::CouponNotOutstanding
+        );
+        assert_noop!(
+            Coupon::redeem_matured(owner.origin(), ticker, vec![foo.did], 0),
+            CouponError::NotMatured
+        );
+
+        // The first coupon creates a distribution at 12% over 30 / 360 days.
+        let coupon_id = next_ca_id(ticker);
+        set_timestamp(30 * DAY);
+        Coupon::on_initialize(System::block_number());
+        let per_share = PER_SHARE_PRECISION / 100;
+        let dist = Dist::distributions(coupon_id).unwrap();
+        assert_eq!(dist.currency, currency);
+        assert_eq!(dist.per_share, per_share);
+        assert_eq!(dist.amount, Asset::total_supply(ticker) / 100);
+        assert_eq!(
+            CA::corporate_actions(ticker, coupon_id.local_id)
+                .unwrap()
+                .kind,
+            CAKind::PredictableBenefit
+        );
+        assert_ok!(Dist::claim(foo.origin(), coupon_id));
+        assert_eq!(Asset::balance_of(&currency, foo.did), AMOUNT / 100);
+        let schedule = Coupon::schedules(ticker).unwrap();
+        assert_eq!(schedule.coupons_paid, 1);
+        assert_eq!(schedule.next_coupon, 60 * DAY);
+
+        // The owner cannot fund the coupon at maturity, which also redeems the principal.
+        let maturity_id = next_ca_id(ticker);
+        let amount = Asset::total_supply(ticker) / 100 + Asset::total_supply(ticker);
+        set_timestamp(60 * DAY);
+        Coupon::on_initialize(System::block_number());
+        assert!(System::events().iter().any(|e| e.event
+            == EventTest::CorporateCoupon(CouponEvent::CouponFundingInsufficient(
+                ticker,
+                60 * DAY,
+                PortfolioId::default_portfolio(owner.did),
+                amount
+            ))));
+        assert_eq!(Dist::distributions(maturity_id), None);
+        let schedule = Coupon::schedules(ticker).unwrap();
+        assert_eq!(schedule.coupons_paid, 1);
+        let cp_id = schedule.record_checkpoint.unwrap();
+        assert_eq!(Coupon::due_coupons().len(), 0);
+        assert_eq!(Coupon::next_coupon_due(), None);
+
+        // Balances changing after the due date don't affect the outstanding coupon.
+        assert_ok!(Asset::issue(
+            owner.origin(),
+            ticker,
+            AMOUNT,
+            PortfolioKind::Default
+        ));
+        assert_ok!(Asset::issue(
+            owner.origin(),
+            currency,
+            amount,
+            PortfolioKind::Default
+        ));
+
+        // The coupon is not paid from the portfolio of a removed agent.
+        assert_ok!(transfer_caa(ticker, owner, bar));
+        assert_noop!(
+            Coupon::pay_coupon(bar.origin(), ticker),
+            CouponError::ScheduleAgentRemoved
+        );
+        assert_ok!(transfer_caa(ticker, bar, owner));
+
+        // Once funded, the outstanding coupon is paid and the schedule ends.
+        assert_noop!(
+            Coupon::pay_coupon(foo.origin(), ticker),
+            EAError::UnauthorizedAgent
+        );
+        assert_ok!(Coupon::pay_coupon(owner.origin(), ticker));
+        let dist = Dist::distributions(maturity_id).unwrap();
+        assert_eq!(dist.per_share, per_share + PER_SHARE_PRECISION);
+        assert_eq!(dist.amount, amount);
+        assert_eq!(
+            CA::corporate_actions(ticker, maturity_id.local_id)
+                .unwrap()
+                .record_date
+                .unwrap()
+                .checkpoint,
+            CACheckpoint::Existing(cp_id)
+        );
+        assert_eq!(Coupon::schedules(ticker), None);
+        assert!(Coupon::matured(ticker));
+        assert_noop!(
+            Coupon::pay_coupon(owner.origin(), ticker),
+            CouponError::NoSuchSchedule
+        );
+
+        // The principal was repaid, so the holders' balances are burnt, including user portfolios.
+        assert_ok!(Portfolio::create_portfolio(
+            foo.origin(),
+            PortfolioName(b"bonds".to_vec())
+        ));
+        let foo_user = PortfolioId::user_portfolio(foo.did, PortfolioNumber(1));
+        assert_ok!(Portfolio::move_portfolio_funds(
+            foo.origin(),
+            PortfolioId::default_portfolio(foo.did),
+            foo_user,
+            vec![Fund {
+                description: FundDescription::Fungible {
+                    ticker,
+                    amount: AMOUNT / 2
+                },
+                memo: None,
+            }],
+        ));
+        let redeem = |holders, portfolios| {
+            Coupon::redeem_matured(owner.origin(), ticker, holders, portfolios)
+        };
+        assert_noop!(
+            Coupon::redeem_matured(foo.origin(), ticker, vec![foo.did], 1),
+            EAError::UnauthorizedAgent
+        );
+        assert_noop!(redeem(vec![foo.did], 0), CouponError::TooManyPortfolios);
+        let supply = Asset::total_supply(ticker);
+        assert_ok!(redeem(vec![foo.did], 1));
+        assert_eq!(Asset::balance_of(&ticker, foo.did), 0);
+        assert_eq!(Portfolio::portfolio_asset_balances(foo_user, ticker), 0);
+        assert_eq!(Asset::total_supply(ticker), supply - AMOUNT);
+        assert!(System::events().iter().any(|e| e.event
+            == EventTest::CorporateCoupon(CouponEvent::MaturedRedeemed(
+                owner.did.for_event(),
+                ticker,
+                foo.did,
+                AMOUNT
+            ))));
+        assert_ok!(redeem(vec![owner.did], 0));
+        assert_eq!(Asset::total_supply(ticker), 0);
+    });
+}
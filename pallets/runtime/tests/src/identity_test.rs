@@ -23,8 +23,8 @@ use polymesh_common_utilities::{
     traits::{
         group::GroupTrait,
         identity::{
-            Config as IdentityConfig, CreateChildIdentityWithAuth, RawEvent, SecondaryKeyWithAuth,
-            TargetIdAuthorization,
+            ClaimAttestation, Config as IdentityConfig, CreateChildIdentityWithAuth, RawEvent,
+            SecondaryKeyWithAuth, TargetIdAuthorization,
         },
         transaction_payment::CddAndFeeDetails,
     },
@@ -37,9 +37,11 @@ use polymesh_primitives::{
     SecondaryKey, Signatory, SubsetRestriction, Ticker, TransactionError,
 };
 use polymesh_runtime_develop::runtime::{CddHandler, RuntimeCall};
-use sp_core::H512;
+use sp_core::{ecdsa, ed25519, Pair, H512};
 use sp_keyring::AccountKeyring;
+use sp_runtime::traits::IdentifyAccount;
 use sp_runtime::transaction_validity::InvalidTransaction;
+use sp_runtime::{MultiSignature, MultiSigner};
 use std::convert::From;
 
 type AuthorizationsGiven = pallet_identity::AuthorizationsGiven<TestStorage>;
@@ -2255,3 +2257,161 @@ fn do_create_child_identities_with_auth_test() {
         expires_at
     ));
 }
+
+#[test]
+fn add_claim_with_attestation_test() {
+    ExtBuilder::default()
+        .monied(true)
+        .build()
+        .execute_with(&do_add_claim_with_attestation_test);
+}
+
+fn do_add_claim_with_attestation_test() {
+    let alice = User::new(AccountKeyring::Alice);
+    let bob = User::new(AccountKeyring::Bob);
+    let scope = Scope::from(IdentityId::from(0));
+    set_timestamp(10);
+
+    // Link an ed25519 and an ECDSA key to Bob's identity,
+    // and an sr25519 key which isn't permitted to add claims.
+    let ed_pair = ed25519::Pair::from_seed(&[1u8; 32]);
+    let ed_key = MultiSigner::from(ed_pair.public()).into_account();
+    add_secondary_key(bob.did, ed_key.clone());
+    let ecdsa_pair = ecdsa::Pair::from_seed(&[2u8; 32]);
+    let ecdsa_key = MultiSigner::from(ecdsa_pair.public()).into_account();
+    add_secondary_key(bob.did, ecdsa_key.clone());
+    let restricted = User::new_with(bob.did, AccountKeyring::Charlie);
+    add_secondary_key_with_perms(
+        bob.did,
+        restricted.acc(),
+        Permissions::from_pallet_permissions(vec![PalletPermissions::new(
+            "Identity".into(),
+            SubsetRestriction::elem("remove_claim".into()),
+        )]),
+    );
+
+    let attestation =
+        |claim: Claim, expiry: Option<u64>, issued_at, valid_until, nonce| ClaimAttestation {
+            genesis_hash: System::block_hash(0),
+            target: alice.did,
+            claim,
+            expiry,
+            issued_at,
+            valid_until,
+            nonce,
+        };
+    let payload = |claim, expiry, nonce| attestation(claim, expiry, 5, 50, nonce).signing_payload();
+    let add = |claim, expiry, issued_at, valid_until, nonce, signer, signature| {
+        Identity::add_claim_with_attestation(
+            alice.origin(),
+            claim,
+            expiry,
+            issued_at,
+            valid_until,
+            nonce,
+            signer,
+            signature,
+        )
+    };
+    let fetch =
+        |claim_type| Identity::fetch_claim(alice.did, claim_type, bob.did, Some(scope.clone()));
+
+    // Alice registers a claim attested with Bob's sr25519 primary key.
+    let claim = Claim::Accredited(scope.clone());
+    let signature = MultiSignature::from(bob.ring.sign(&payload(claim.clone(), Some(100), 0)));
+    assert_noop!(
+        add(claim.clone(), None, 5, 50, 0, bob.acc(), signature.clone()),
+        Error::InvalidClaimAttestationSignature
+    );
+    assert_ok!(add(
+        claim.clone(),
+        Some(100),
+        5,
+        50,
+        0,
+        bob.acc(),
+        signature.clone()
+    ));
+    let id_claim = fetch(ClaimType::Accredited).unwrap();
+    assert_eq!(id_claim.claim, claim);
+    assert_eq!(id_claim.expiry, Some(100));
+    assert_eq!(id_claim.issuance_date, 5);
+
+    // The attestation can't be replayed.
+    assert_noop!(
+        add(claim, Some(100), 5, 50, 0, bob.acc(), signature),
+        Error::ClaimAttestationNonceUsed
+    );
+
+    // The signature must cover the domain tag and the genesis hash.
+    let claim = Claim::Affiliate(scope.clone());
+    let untagged = attestation(claim.clone(), None, 5, 50, 1).encode();
+    let signature = MultiSignature::from(bob.ring.sign(&untagged));
+    assert_noop!(
+        add(claim.clone(), None, 5, 50, 1, bob.acc(), signature),
+        Error::InvalidClaimAttestationSignature
+    );
+    let mut other_chain = attestation(claim.clone(), None, 5, 50, 1);
+    other_chain.genesis_hash = [1u8; 32].into();
+    let signature = MultiSignature::from(bob.ring.sign(&other_chain.signing_payload()));
+    assert_noop!(
+        add(claim.clone(), None, 5, 50, 1, bob.acc(), signature),
+        Error::InvalidClaimAttestationSignature
+    );
+
+    // Ed25519 and ECDSA secondary keys permitted to add claims can attest claims too.
+    let signature = MultiSignature::from(ed_pair.sign(&payload(claim.clone(), None, 1)));
+    assert_ok!(add(claim, None, 5, 50, 1, ed_key, signature));
+    assert!(fetch(ClaimType::Affiliate).is_some());
+
+    let claim = Claim::Exempted(scope.clone());
+    let signature = MultiSignature::from(ecdsa_pair.sign(&payload(claim.clone(), None, 2)));
+    assert_ok!(add(claim, None, 5, 50, 2, ecdsa_key, signature));
+    assert!(fetch(ClaimType::Exempted).is_some());
+
+    // Secondary keys not permitted to add claims can't attest claims.
+    let claim = Claim::Blocked(scope.clone());
+    let signature = MultiSignature::from(restricted.ring.sign(&payload(claim.clone(), None, 3)));
+    assert_noop!(
+        add(claim.clone(), None, 5, 50, 3, restricted.acc(), signature),
+        Error::ClaimAttestationSignerNotPermitted
+    );
+
+    // Keys not linked to an identity can't attest claims.
+    let unlinked_pair = ed25519::Pair::from_seed(&[3u8; 32]);
+    let signature = MultiSignature::from(unlinked_pair.sign(&payload(claim.clone(), None, 3)));
+    assert_noop!(
+        add(
+            claim.clone(),
+            None,
+            5,
+            50,
+            3,
+            MultiSigner::from(unlinked_pair.public()).into_account(),
+            signature
+        ),
+        Error::ClaimAttestationSignerNotLinked
+    );
+
+    // Attestations issued in the future can't be registered.
+    let signed = attestation(claim.clone(), None, 20, 50, 3).signing_payload();
+    let signature = MultiSignature::from(bob.ring.sign(&signed));
+    assert_noop!(
+        add(claim.clone(), None, 20, 50, 3, bob.acc(), signature),
+        Error::ClaimAttestationNotYetIssued
+    );
+
+    // Attestations can't be registered after `valid_until`, nor expired claims.
+    set_timestamp(60);
+    let signature = MultiSignature::from(bob.ring.sign(&payload(claim.clone(), None, 3)));
+    assert_noop!(
+        add(claim.clone(), None, 5, 50, 3, bob.acc(), signature),
+        Error::ClaimAttestationNoLongerValid
+    );
+    let signed = attestation(claim.clone(), Some(55), 5, 100, 3).signing_payload();
+    let signature = MultiSignature::from(bob.ring.sign(&signed));
+    assert_noop!(
+        add(claim, Some(55), 5, 100, 3, bob.acc(), signature),
+        Error::ClaimAttestationExpired
+    );
+}
@@ -27,6 +27,7 @@ mod group_test;
 mod identity_test;
 mod multisig;
 mod nft;
+mod order_book_test;
 mod pips_test;
 mod portfolio;
 mod protocol_fee;
@@ -7,16 +7,16 @@ use pallet_nft::{
 use pallet_portfolio::PortfolioNFT;
 use polymesh_common_utilities::traits::nft::Event;
 use polymesh_common_utilities::with_transaction;
-use polymesh_primitives::asset::{AssetType, NonFungibleType};
+use polymesh_primitives::asset::{AssetHolder, AssetType, NonFungibleType};
 use polymesh_primitives::asset_metadata::{
     AssetMetadataKey, AssetMetadataLocalKey, AssetMetadataName, AssetMetadataSpec,
     AssetMetadataValue,
 };
 use polymesh_primitives::settlement::InstructionId;
 use polymesh_primitives::{
-    AuthorizationData, IdentityId, NFTCollectionId, NFTCollectionKeys, NFTId, NFTMetadataAttribute,
-    NFTs, PortfolioId, PortfolioKind, PortfolioNumber, PortfolioUpdateReason, Signatory, Ticker,
-    WeightMeter,
+    AuthorizationData, Fund, FundDescription, IdentityId, NFTCollectionId, NFTCollectionKeys,
+    NFTId, NFTMetadataAttribute, NFTs, PortfolioId, PortfolioKind, PortfolioName, PortfolioNumber,
+    PortfolioUpdateReason, Signatory, Ticker, WeightMeter,
 };
 use sp_keyring::AccountKeyring;
 
@@ -25,6 +25,7 @@ use crate::ext_builder::ExtBuilder;
 use crate::storage::{TestStorage, User};
 
 type Asset = pallet_asset::Module<TestStorage>;
+type AssetError = pallet_asset::Error<TestStorage>;
 type ComplianceManager = pallet_compliance_manager::Module<TestStorage>;
 type EAError = pallet_external_agents::Error<TestStorage>;
 type Identity = pallet_identity::Module<TestStorage>;
@@ -1097,3 +1098,127 @@ fn controller_transfer_nft_not_owned() {
         );
     });
 }
+
+/// An NFT can only be minted within the max supply of its asset.
+#[test]
+fn mint_nft_max_supply_exceeded() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_timestamp(Utc::now().timestamp() as _);
+
+        let alice: User = User::new(AccountKeyring::Alice);
+        let ticker: Ticker = Ticker::from_slice_truncated(b"TICKER".as_ref());
+        let collection_keys: NFTCollectionKeys =
+            vec![AssetMetadataKey::Local(AssetMetadataLocalKey(1))].into();
+        let attributes = vec![NFTMetadataAttribute {
+            key: AssetMetadataKey::Local(AssetMetadataLocalKey(1)),
+            value: AssetMetadataValue(b"test".to_vec()),
+        }];
+
+        create_nft_collection(
+            alice.clone(),
+            ticker.clone(),
+            AssetType::NonFungible(NonFungibleType::Derivative),
+            collection_keys,
+        );
+        assert_ok!(Asset::lower_max_supply(alice.origin(), ticker, 1));
+        mint_nft(
+            alice.clone(),
+            ticker,
+            attributes.clone(),
+            PortfolioKind::Default,
+        );
+        assert_eq!(Asset::issuance_headroom(&ticker), 0);
+        assert_noop!(
+            NFT::issue_nft(alice.origin(), ticker, attributes, PortfolioKind::Default),
+            AssetError::MaxSupplyExceeded
+        );
+    });
+}
+
+/// NFTs can't be transferred or moved from or to a frozen holder.
+#[test]
+fn transfer_nft_frozen_holder() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_timestamp(Utc::now().timestamp() as _);
+
+        let alice: User = User::new(AccountKeyring::Alice);
+        let bob: User = User::new(AccountKeyring::Bob);
+        let ticker: Ticker = Ticker::from_slice_truncated(b"TICKER".as_ref());
+        let mut weight_meter = WeightMeter::max_limit_no_minimum();
+        let collection_keys: NFTCollectionKeys =
+            vec![AssetMetadataKey::Local(AssetMetadataLocalKey(1))].into();
+        create_nft_collection(
+            alice.clone(),
+            ticker.clone(),
+            AssetType::NonFungible(NonFungibleType::Derivative),
+            collection_keys,
+        );
+        let nfts_metadata: Vec<NFTMetadataAttribute> = vec![NFTMetadataAttribute {
+            key: AssetMetadataKey::Local(AssetMetadataLocalKey(1)),
+            value: AssetMetadataValue(b"test".to_vec()),
+        }];
+        mint_nft(
+            alice.clone(),
+            ticker.clone(),
+            nfts_metadata,
+            PortfolioKind::Default,
+        );
+        ComplianceManager::pause_asset_compliance(alice.origin(), ticker.clone()).unwrap();
+
+        let sender_portfolio = PortfolioId::default_portfolio(alice.did);
+        let receiver_portfolio = PortfolioId::default_portfolio(bob.did);
+        let nfts = NFTs::new(ticker, vec![NFTId(1)]).unwrap();
+        let transfer = |weight_meter: &mut WeightMeter| {
+            with_transaction(|| {
+                NFT::base_nft_transfer(
+                    sender_portfolio,
+                    receiver_portfolio,
+                    nfts.clone(),
+                    InstructionId(0),
+                    None,
+                    IdentityId::default(),
+                    weight_meter,
+                )
+            })
+        };
+
+        // The receiver is frozen.
+        let bob_id = AssetHolder::Identity(bob.did);
+        assert_ok!(Asset::freeze_holder(alice.origin(), ticker, bob_id));
+        assert_noop!(
+            transfer(&mut weight_meter),
+            NFTError::InvalidNFTTransferFrozenHolder
+        );
+        assert_ok!(Asset::unfreeze_holder(alice.origin(), ticker, bob_id));
+
+        // The sender's portfolio is frozen, so the NFT can't be moved out of it either.
+        let alice_default = AssetHolder::Portfolio(sender_portfolio);
+        assert_ok!(Asset::freeze_holder(alice.origin(), ticker, alice_default));
+        assert_noop!(
+            transfer(&mut weight_meter),
+            NFTError::InvalidNFTTransferFrozenHolder
+        );
+        assert_ok!(Portfolio::create_portfolio(
+            alice.origin(),
+            PortfolioName(b"MyOwnPortfolio".to_vec())
+        ));
+        let move_nft = || {
+            Portfolio::move_portfolio_funds(
+                alice.origin(),
+                sender_portfolio,
+                PortfolioId::user_portfolio(alice.did, PortfolioNumber(1)),
+                vec![Fund {
+                    description: FundDescription::NonFungible(nfts.clone()),
+                    memo: None,
+                }],
+            )
+        };
+        assert_noop!(move_nft(), PortfolioError::HolderFrozen);
+        assert_ok!(Asset::unfreeze_holder(
+            alice.origin(),
+            ticker,
+            alice_default
+        ));
+        assert_ok!(move_nft());
+    });
+}
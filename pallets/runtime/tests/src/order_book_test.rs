@@ -0,0 +1,414 @@
+use frame_support::traits::{Get, OnInitialize};
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+
+use pallet_order_book::{Event, PendingBooks};
+use polymesh_primitives::order_book::{
+    Order, OrderBookDepth, OrderId, PriceLevel, Side, TradingPair,
+};
+use polymesh_primitives::settlement::{VenueDetails, VenueId, VenueType};
+use polymesh_primitives::{IdentityId, PortfolioId, Ticker, WeightMeter};
+use sp_keyring::AccountKeyring;
+
+use super::asset_test::allow_all_transfers;
+use super::sto_test::create_asset;
+use super::storage::{make_account_with_portfolio, EventTest, TestStorage, User};
+use super::{exec_ok, ExtBuilder};
+
+type Asset = pallet_asset::Module<TestStorage>;
+type OrderBook = pallet_order_book::Module<TestStorage>;
+type Error = pallet_order_book::Error<TestStorage>;
+type Portfolio = pallet_portfolio::Module<TestStorage>;
+type Settlement = pallet_settlement::Module<TestStorage>;
+type System = frame_system::Pallet<TestStorage>;
+
+const BASE: Ticker = Ticker::new_unchecked([b'A', b'C', b'M', b'E', 0, 0, 0, 0, 0, 0, 0, 0]);
+const QUOTE: Ticker = Ticker::new_unchecked([b'U', b'S', b'D', b'C', 0, 0, 0, 0, 0, 0, 0, 0]);
+const PAIR: TradingPair = TradingPair {
+    base: BASE,
+    quote: QUOTE,
+};
+
+#[track_caller]
+fn test(logic: impl FnOnce()) {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(logic);
+}
+
+#[test]
+fn match_orders_at_maker_price_ext() {
+    test(match_orders_at_maker_price);
+}
+
+#[test]
+fn cancel_and_amend_order_ext() {
+    test(cancel_and_amend_order);
+}
+
+#[test]
+fn failed_match_cancels_newer_order_ext() {
+    test(failed_match_cancels_newer_order);
+}
+
+#[test]
+fn halted_venue_keeps_crossing_orders_ext() {
+    test(halted_venue_keeps_crossing_orders);
+}
+
+#[test]
+fn pending_books_are_bounded_ext() {
+    test(pending_books_are_bounded);
+}
+
+struct BookContext {
+    alice: User,
+    alice_portfolio: PortfolioId,
+    bob: User,
+    bob_portfolio: PortfolioId,
+    venue_id: VenueId,
+}
+
+/// Alice holds the base asset, Bob holds the quote asset and Charlie runs the exchange venue.
+fn init_book_context() -> BookContext {
+    System::set_block_number(1);
+    let (alice, alice_portfolio) = make_account_with_portfolio(AccountKeyring::Alice);
+    let (bob, bob_portfolio) = make_account_with_portfolio(AccountKeyring::Bob);
+    let charlie = User::new(AccountKeyring::Charlie);
+
+    create_asset(alice.origin(), BASE, 1_000);
+    create_asset(alice.origin(), QUOTE, 1_000);
+    allow_all_transfers(BASE, alice);
+    allow_all_transfers(QUOTE, alice);
+    assert_ok!(Asset::unsafe_transfer(
+        alice_portfolio,
+        bob_portfolio,
+        &QUOTE,
+        1_000,
+        None,
+        None,
+        IdentityId::default(),
+        &mut WeightMeter::max_limit_no_minimum()
+    ));
+
+    let venue_id = Settlement::venue_counter();
+    exec_ok!(Settlement::create_venue(
+        charlie.origin(),
+        VenueDetails::default(),
+        vec![charlie.acc()],
+        VenueType::Exchange
+    ));
+
+    BookContext {
+        alice,
+        alice_portfolio,
+        bob,
+        bob_portfolio,
+        venue_id,
+    }
+}
+
+fn place(user: User, ctx: &BookContext, side: Side, price: u128, amount: u128) -> OrderId {
+    let order_id = OrderBook::order_counter();
+    let portfolio = PortfolioId::default_portfolio(user.did);
+    exec_ok!(OrderBook::place_order(
+        user.origin(),
+        ctx.venue_id,
+        PAIR,
+        portfolio,
+        side,
+        price,
+        amount
+    ));
+    order_id
+}
+
+fn match_orders() {
+    OrderBook::on_initialize(System::block_number());
+}
+
+fn assert_event(event: Event) {
+    assert!(System::events()
+        .iter()
+        .any(|e| e.event == EventTest::OrderBook(event.clone())));
+}
+
+fn open_order_ids(ctx: &BookContext, side: Side) -> Vec<OrderId> {
+    OrderBook::open_orders(ctx.venue_id, PAIR, side)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect()
+}
+
+fn match_orders_at_maker_price() {
+    let ctx = init_book_context();
+    let (alice, bob) = (ctx.alice, ctx.bob);
+
+    // Bob is the maker, so the trade happens at his price.
+    let buy = place(bob, &ctx, Side::Buy, 1_200_000, 100);
+    assert_eq!(Portfolio::locked_assets(ctx.bob_portfolio, QUOTE), 120);
+    let sell = place(alice, &ctx, Side::Sell, 1_000_000, 60);
+    assert_eq!(Portfolio::locked_assets(ctx.alice_portfolio, BASE), 60);
+
+    match_orders();
+    assert_event(Event::OrdersMatched(
+        ctx.venue_id,
+        PAIR,
+        buy,
+        sell,
+        1_200_000,
+        60,
+    ));
+    assert_event(Event::OrderFilled(sell));
+    assert_eq!(OrderBook::orders(sell), None);
+    assert_eq!(Asset::balance_of(&BASE, bob.did), 60);
+    assert_eq!(Asset::balance_of(&QUOTE, alice.did), 72);
+    assert_eq!(Portfolio::locked_assets(ctx.alice_portfolio, BASE), 0);
+    let bid = OrderBook::orders(buy).unwrap();
+    assert_eq!((bid.remaining, bid.locked), (40, 48));
+    assert_eq!(Portfolio::locked_assets(ctx.bob_portfolio, QUOTE), 48);
+
+    // The books don't cross anymore.
+    let ask = place(alice, &ctx, Side::Sell, 1_300_000, 40);
+    match_orders();
+    assert!(OrderBook::pending_books().is_empty());
+    assert_eq!(
+        OrderBook::order_book_depth(ctx.venue_id, PAIR, 10),
+        OrderBookDepth {
+            bids: vec![PriceLevel {
+                price: 1_200_000,
+                amount: 40,
+                orders: 1
+            }],
+            asks: vec![PriceLevel {
+                price: 1_300_000,
+                amount: 40,
+                orders: 1
+            }],
+        }
+    );
+
+    // Lowering the ask crosses the book again, still at Bob's price.
+    exec_ok!(OrderBook::amend_order(alice.origin(), ask, 1_100_000, 40));
+    match_orders();
+    assert_event(Event::OrdersMatched(
+        ctx.venue_id,
+        PAIR,
+        buy,
+        ask,
+        1_200_000,
+        40,
+    ));
+    assert_eq!(OrderBook::orders(buy), None);
+    assert_eq!(OrderBook::orders(ask), None);
+    assert_eq!(
+        OrderBook::order_book_depth(ctx.venue_id, PAIR, 10),
+        OrderBookDepth::default()
+    );
+    assert_eq!(Asset::balance_of(&BASE, bob.did), 100);
+    assert_eq!(Asset::balance_of(&QUOTE, alice.did), 120);
+    assert_eq!(Portfolio::locked_assets(ctx.bob_portfolio, QUOTE), 0);
+    assert_eq!(Portfolio::locked_assets(ctx.alice_portfolio, BASE), 0);
+}
+
+fn cancel_and_amend_order() {
+    let ctx = init_book_context();
+    let (alice, bob) = (ctx.alice, ctx.bob);
+
+    let sto_venue = Settlement::venue_counter();
+    exec_ok!(Settlement::create_venue(
+        alice.origin(),
+        VenueDetails::default(),
+        vec![alice.acc()],
+        VenueType::Sto
+    ));
+    let place_order = |venue_id, pair, price, amount| {
+        OrderBook::place_order(
+            bob.origin(),
+            venue_id,
+            pair,
+            ctx.bob_portfolio,
+            Side::Buy,
+            price,
+            amount,
+        )
+    };
+    assert_noop!(
+        place_order(sto_venue, PAIR, 1_000_000, 10),
+        Error::InvalidVenue
+    );
+    let same_asset_pair = TradingPair {
+        base: QUOTE,
+        quote: QUOTE,
+    };
+    assert_noop!(
+        place_order(ctx.venue_id, same_asset_pair, 1_000_000, 10),
+        Error::InvalidTradingPair
+    );
+    assert_noop!(
+        place_order(ctx.venue_id, PAIR, 1_000_000, 0),
+        Error::InvalidOrder
+    );
+
+    let first = place(bob, &ctx, Side::Buy, 1_000_000, 10);
+    let second = place(bob, &ctx, Side::Buy, 1_000_000, 10);
+    assert_eq!(open_order_ids(&ctx, Side::Buy), vec![first, second]);
+    assert_eq!(Portfolio::locked_assets(ctx.bob_portfolio, QUOTE), 20);
+
+    // Increasing the amount loses the time priority, decreasing it does not.
+    exec_ok!(OrderBook::amend_order(bob.origin(), first, 1_000_000, 30));
+    assert_eq!(open_order_ids(&ctx, Side::Buy), vec![second, first]);
+    exec_ok!(OrderBook::amend_order(bob.origin(), second, 1_000_000, 5));
+    assert_eq!(open_order_ids(&ctx, Side::Buy), vec![second, first]);
+    assert_eq!(Portfolio::locked_assets(ctx.bob_portfolio, QUOTE), 35);
+
+    // A better price goes first.
+    exec_ok!(OrderBook::amend_order(bob.origin(), first, 1_500_000, 30));
+    assert_eq!(open_order_ids(&ctx, Side::Buy), vec![first, second]);
+    assert_eq!(
+        OrderBook::orders(first),
+        Some(Order {
+            owner: bob.did,
+            portfolio: ctx.bob_portfolio,
+            venue_id: ctx.venue_id,
+            pair: PAIR,
+            side: Side::Buy,
+            price: 1_500_000,
+            remaining: 30,
+            locked: 45,
+            priority: 3,
+        })
+    );
+    assert_eq!(Portfolio::locked_assets(ctx.bob_portfolio, QUOTE), 50);
+
+    // Only the owner can cancel.
+    assert_noop!(
+        OrderBook::cancel_order(alice.origin(), first),
+        Error::Unauthorized
+    );
+    exec_ok!(OrderBook::cancel_order(bob.origin(), first));
+    assert_event(Event::OrderCancelled(bob.did, first));
+    exec_ok!(OrderBook::cancel_order(bob.origin(), second));
+    assert_noop!(
+        OrderBook::cancel_order(bob.origin(), second),
+        Error::OrderNotFound
+    );
+    assert!(open_order_ids(&ctx, Side::Buy).is_empty());
+    assert_eq!(Portfolio::locked_assets(ctx.bob_portfolio, QUOTE), 0);
+}
+
+fn failed_match_cancels_newer_order() {
+    let ctx = init_book_context();
+    let (alice, bob) = (ctx.alice, ctx.bob);
+
+    let buy = place(bob, &ctx, Side::Buy, 1_000_000, 10);
+    let sell = place(alice, &ctx, Side::Sell, 1_000_000, 10);
+    assert_ok!(Asset::freeze(alice.origin(), BASE));
+
+    match_orders();
+    assert!(System::events().iter().any(|e| matches!(
+        e.event,
+        EventTest::OrderBook(Event::MatchFailed(b, s, _)) if b == buy && s == sell
+    )));
+    assert_event(Event::OrderCancelled(alice.did, sell));
+    assert_eq!(OrderBook::orders(sell), None);
+    assert_eq!(Portfolio::locked_assets(ctx.alice_portfolio, BASE), 0);
+    assert_eq!(open_order_ids(&ctx, Side::Buy), vec![buy]);
+    assert_eq!(Portfolio::locked_assets(ctx.bob_portfolio, QUOTE), 10);
+    assert!(OrderBook::pending_books().is_empty());
+    assert_eq!(Asset::balance_of(&BASE, bob.did), 0);
+}
+
+fn halted_venue_keeps_crossing_orders() {
+    let ctx = init_book_context();
+    let (alice, bob) = (ctx.alice, ctx.bob);
+    let charlie = User::new(AccountKeyring::Charlie);
+
+    let buy = place(bob, &ctx, Side::Buy, 1_000_000, 10);
+    let sell = place(alice, &ctx, Side::Sell, 1_000_000, 10);
+    exec_ok!(Settlement::set_venue_halt(
+        charlie.origin(),
+        ctx.venue_id,
+        true
+    ));
+
+    // Nothing is matched or cancelled while the venue is halted.
+    for _ in 0..2 {
+        match_orders();
+    }
+    assert_eq!(open_order_ids(&ctx, Side::Buy), vec![buy]);
+    assert_eq!(open_order_ids(&ctx, Side::Sell), vec![sell]);
+    assert_eq!(Portfolio::locked_assets(ctx.alice_portfolio, BASE), 10);
+    assert_eq!(Portfolio::locked_assets(ctx.bob_portfolio, QUOTE), 10);
+    assert_eq!(
+        OrderBook::pending_books().into_inner(),
+        vec![(ctx.venue_id, PAIR)]
+    );
+
+    // The book is matched once the venue resumes.
+    exec_ok!(Settlement::set_venue_halt(
+        charlie.origin(),
+        ctx.venue_id,
+        false
+    ));
+    match_orders();
+    assert_event(Event::OrdersMatched(
+        ctx.venue_id,
+        PAIR,
+        buy,
+        sell,
+        1_000_000,
+        10,
+    ));
+    assert!(OrderBook::pending_books().is_empty());
+    assert_eq!(Asset::balance_of(&BASE, bob.did), 10);
+}
+
+fn pending_books_are_bounded() {
+    let ctx = init_book_context();
+    let bob = ctx.bob;
+    place(bob, &ctx, Side::Buy, 1_000_000, 10);
+
+    // Fill the remaining slots with the books of other venues.
+    let max = <TestStorage as pallet_order_book::Config>::MaxPendingBooks::get();
+    let mut books = OrderBook::pending_books().into_inner();
+    books.extend((1..max).map(|i| (VenueId(u64::MAX - i as u64), PAIR)));
+    PendingBooks::<TestStorage>::put(BoundedVec::truncate_from(books));
+
+    // Orders can still be queued in a book that is already pending, but not in a new one.
+    place(bob, &ctx, Side::Buy, 1_000_000, 10);
+    let reversed_pair = TradingPair {
+        base: QUOTE,
+        quote: BASE,
+    };
+    assert_noop!(
+        OrderBook::place_order(
+            bob.origin(),
+            ctx.venue_id,
+            reversed_pair,
+            ctx.bob_portfolio,
+            Side::Sell,
+            1_000_000,
+            10,
+        ),
+        Error::TooManyPendingBooks
+    );
+
+    // Matching frees the slots of the books that no longer cross, over as many blocks as needed.
+    for _ in 0..max {
+        if OrderBook::pending_books().is_empty() {
+            break;
+        }
+        match_orders();
+    }
+    assert!(OrderBook::pending_books().is_empty());
+    exec_ok!(OrderBook::place_order(
+        bob.origin(),
+        ctx.venue_id,
+        reversed_pair,
+        ctx.bob_portfolio,
+        Side::Sell,
+        1_000_000,
+        10
+    ));
+}
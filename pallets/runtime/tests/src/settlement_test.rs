@@ -5,21 +5,24 @@ use std::ops::Deref;
 use codec::Encode;
 use frame_support::dispatch::DispatchErrorWithPostInfo;
 use frame_support::{
-    assert_err_ignore_postinfo, assert_noop, assert_ok, assert_storage_noop,
+    assert_err_ignore_postinfo, assert_noop, assert_ok, assert_storage_noop, traits::Get,
     IterableStorageDoubleMap, StorageDoubleMap, StorageMap,
 };
 use rand::{prelude::*, thread_rng};
-use sp_runtime::{AccountId32, AnySignature};
+use sp_core::H256;
+use sp_io::hashing::keccak_256;
+use sp_runtime::{AccountId32, AnySignature, Perbill};
 use sp_std::collections::btree_set::BTreeSet;
 
 use pallet_asset::BalanceOf;
 use pallet_nft::NumberOfNFTs;
-use pallet_portfolio::{PortfolioLockedNFT, PortfolioNFT};
+use pallet_portfolio::{PortfolioLockedAssets, PortfolioLockedNFT, PortfolioNFT};
 use pallet_scheduler as scheduler;
 use pallet_settlement::{
-    AffirmsReceived, InstructionAffirmsPending, InstructionLegs, InstructionMediatorsAffirmations,
-    InstructionMemos, NumberOfVenueSigners, OffChainAffirmations, RawEvent, UserAffirmations,
-    UserVenues, VenueInstructions,
+    AffirmsReceived, InstructionAffirmsPending, InstructionDependents, InstructionLegs,
+    InstructionMediatorsAffirmations, InstructionMemos, InstructionPrerequisites,
+    NumberOfVenueSigners, OffChainAffirmations, RawEvent, UserAffirmations, UserVenues,
+    VenueInstructions,
 };
 use polymesh_common_utilities::constants::currency::ONE_UNIT;
 use polymesh_common_utilities::constants::ERC1400_TRANSFER_SUCCESS;
@@ -27,11 +30,16 @@ use polymesh_primitives::asset::{AssetType, NonFungibleType};
 use polymesh_primitives::asset_metadata::{
     AssetMetadataKey, AssetMetadataLocalKey, AssetMetadataValue,
 };
+use polymesh_primitives::calendar::{
+    CalendarPeriod, CalendarUnit, CalendarWindow, CheckpointSchedule,
+};
 use polymesh_primitives::checked_inc::CheckedInc;
+use polymesh_primitives::ethereum::{self, EthereumAddress};
 use polymesh_primitives::settlement::{
-    AffirmationCount, AffirmationStatus, AssetCount, Instruction, InstructionId, InstructionStatus,
-    Leg, LegId, LegStatus, MediatorAffirmationStatus, Receipt, ReceiptDetails, SettlementType,
-    VenueDetails, VenueId, VenueType,
+    AffirmationCount, AffirmationStatus, AssetCount, EcdsaReceipt, EcdsaReceiptDetails,
+    Instruction, InstructionId, InstructionStatus, InstructionVersion, Leg, LegId, LegStatus,
+    LegTransferReport, MediatorAffirmationStatus, Receipt, ReceiptDetails, RepoStatus,
+    SettlementType, VenueDetails, VenueId, VenueInstructionLimits, VenueType, ECDSA_RECEIPT_PREFIX,
 };
 use polymesh_primitives::{
     AccountId, AuthorizationData, Balance, Claim, Condition, ConditionType, Fund, FundDescription,
@@ -40,7 +48,7 @@ use polymesh_primitives::{
 };
 use sp_keyring::AccountKeyring;
 
-use super::asset_test::{allow_all_transfers, max_len_bytes};
+use super::asset_test::{allow_all_transfers, max_len_bytes, transfer};
 use super::nft::{create_nft_collection, mint_nft};
 use super::storage::{
     default_portfolio_vec, make_account_without_cdd, user_portfolio_vec, TestStorage, User,
@@ -502,6 +510,7 @@ fn token_swap() {
             created_at: Some(Timestamp::get()),
             trade_date: None,
             value_date: None,
+            expiry: None,
         };
         assert_instruction_status(instruction_id, InstructionStatus::Pending);
         assert_instruction_details(instruction_id, instruction_details);
@@ -625,6 +634,7 @@ fn settle_on_block() {
             created_at: Some(Timestamp::get()),
             trade_date: None,
             value_date: None,
+            expiry: None,
         };
         assert_instruction_status(instruction_id, InstructionStatus::Pending);
         assert_eq!(
@@ -738,6 +748,7 @@ fn failed_execution() {
             created_at: Some(Timestamp::get()),
             trade_date: None,
             value_date: None,
+            expiry: None,
         };
         assert_instruction_status(instruction_id, InstructionStatus::Pending);
         assert_eq!(
@@ -1272,6 +1283,114 @@ fn claim_multiple_receipts_during_authorization() {
     });
 }
 
+#[test]
+fn claim_ecdsa_receipts() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let venue_id = create_venue(alice);
+        let id = Settlement::instruction_counter();
+        let amount = 100;
+        let custodian_key = libsecp256k1::SecretKey::parse(&keccak_256(b"Custodian")).unwrap();
+        let custodian = ethereum::address(&custodian_key);
+        Timestamp::set_timestamp(1_000);
+
+        assert_ok!(Settlement::add_instruction(
+            alice.origin(),
+            venue_id,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            vec![Leg::OffChain {
+                sender_identity: alice.did,
+                receiver_identity: bob.did,
+                ticker: TICKER,
+                amount,
+            }],
+            None,
+        ));
+
+        let genesis_hash = System::block_hash(0);
+        let receipt = Receipt::new(0, id, LegId(0), alice.did, bob.did, TICKER, amount);
+        let receipt_details = |signer: EthereumAddress, genesis_hash: H256, expiry| {
+            let ecdsa_receipt = EcdsaReceipt::new(genesis_hash, receipt.clone(), expiry);
+            let signature = ethereum::eth_msg(&ecdsa_receipt, ECDSA_RECEIPT_PREFIX, &custodian_key);
+            EcdsaReceiptDetails::new(0, id, LegId(0), signer, signature, expiry, None)
+        };
+        let affirm = |receipt_details| {
+            Settlement::affirm_with_ecdsa_receipts(
+                alice.origin(),
+                id,
+                vec![receipt_details],
+                Vec::new(),
+                None,
+            )
+        };
+
+        // The signer must be registered for the venue by its creator.
+        assert_noop!(
+            affirm(receipt_details(custodian, genesis_hash, None)),
+            Error::UnauthorizedSigner
+        );
+        assert_noop!(
+            Settlement::update_venue_ecdsa_signers(bob.origin(), venue_id, vec![custodian], true),
+            Error::Unauthorized
+        );
+        assert_ok!(Settlement::update_venue_ecdsa_signers(
+            alice.origin(),
+            venue_id,
+            vec![custodian],
+            true
+        ));
+        assert_eq!(NumberOfVenueSigners::get(venue_id), 2);
+
+        // Receipts are bound to the chain, to their signer and to their expiry.
+        assert_noop!(
+            affirm(receipt_details(custodian, H256::repeat_byte(1), None)),
+            Error::InvalidSignature
+        );
+        assert_noop!(
+            affirm(receipt_details(
+                EthereumAddress([1; 20]),
+                genesis_hash,
+                None
+            )),
+            Error::UnauthorizedSigner
+        );
+        assert_noop!(
+            affirm(receipt_details(custodian, genesis_hash, Some(1_000))),
+            Error::ReceiptExpired
+        );
+        let signature = receipt_details(custodian, genesis_hash, Some(1_000))
+            .signature()
+            .clone();
+        let extended_expiry =
+            EcdsaReceiptDetails::new(0, id, LegId(0), custodian, signature, Some(2_000), None);
+        assert_noop!(affirm(extended_expiry), Error::InvalidSignature);
+
+        assert_ok!(affirm(receipt_details(
+            custodian,
+            genesis_hash,
+            Some(2_000)
+        )));
+        assert_affirms_pending(id, 0);
+        assert_eq!(
+            OffChainAffirmations::get(id, LegId(0)),
+            AffirmationStatus::Affirmed
+        );
+        assert_leg_status(
+            id,
+            LegId(0),
+            LegStatus::ExecutionToBeSkippedWithEcdsaReceipt(custodian, 0),
+        );
+        assert!(Settlement::ecdsa_receipts_used(custodian, 0));
+        assert_noop!(
+            affirm(receipt_details(custodian, genesis_hash, Some(2_000))),
+            Error::ReceiptAlreadyClaimed
+        );
+    });
+}
+
 #[test]
 fn overload_instruction() {
     test_with_cdd_provider(|_eve| {
@@ -3989,6 +4108,1649 @@ fn reject_instruction_as_mediator() {
     });
 }
 
+#[test]
+fn partially_fill_instruction() {
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER, TICKER2]);
+        let mut bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER, TICKER2]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        create_token(TICKER2, bob.user);
+        let instruction_id = Settlement::instruction_counter();
+        alice.refresh_init_balances();
+        bob.refresh_init_balances();
+
+        let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+        let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+        let legs = vec![
+            Leg::Fungible {
+                sender: alice_portfolio,
+                receiver: bob_portfolio,
+                ticker: TICKER,
+                amount: 1_000,
+            },
+            Leg::Fungible {
+                sender: bob_portfolio,
+                receiver: alice_portfolio,
+                ticker: TICKER2,
+                amount: 500,
+            },
+        ];
+        assert_ok!(Settlement::add_partially_fillable_instruction(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleManual(0),
+            None,
+            None,
+            legs,
+            None,
+        ));
+        assert!(Settlement::partially_fillable(instruction_id));
+        set_current_block_number(10);
+
+        // All affirmations must be received before filling the instruction
+        assert_err_ignore_postinfo!(
+            Settlement::execute_partial_fill(
+                alice.origin(),
+                instruction_id,
+                Perbill::from_percent(60),
+                2,
+                None
+            ),
+            Error::NotAllAffirmationsHaveBeenReceived
+        );
+        assert_affirm_instruction!(alice.origin(), instruction_id, alice.did);
+        assert_affirm_instruction!(bob.origin(), instruction_id, bob.did);
+
+        // Only the venue creator can fill the instruction
+        assert_err_ignore_postinfo!(
+            Settlement::execute_partial_fill(
+                bob.origin(),
+                instruction_id,
+                Perbill::from_percent(60),
+                2,
+                None
+            ),
+            Error::Unauthorized
+        );
+        assert_err_ignore_postinfo!(
+            Settlement::execute_partial_fill(
+                alice.origin(),
+                instruction_id,
+                Perbill::zero(),
+                2,
+                None
+            ),
+            Error::InvalidFillRatio
+        );
+
+        assert_ok!(Settlement::execute_partial_fill(
+            alice.origin(),
+            instruction_id,
+            Perbill::from_percent(60),
+            2,
+            None
+        ));
+        assert_instruction_status(instruction_id, InstructionStatus::Pending);
+        alice.assert_balance_decreased(&TICKER, 600);
+        alice.assert_balance_increased(&TICKER2, 300);
+        bob.assert_balance_increased(&TICKER, 600);
+        bob.assert_balance_decreased(&TICKER2, 300);
+        // The remaining amounts are still locked
+        assert_locked_assets(&TICKER, &alice, 400);
+        assert_locked_assets(&TICKER2, &bob, 200);
+        assert_eq!(
+            InstructionLegs::get(instruction_id, LegId(0)),
+            Some(Leg::Fungible {
+                sender: alice_portfolio,
+                receiver: bob_portfolio,
+                ticker: TICKER,
+                amount: 400,
+            })
+        );
+        assert_eq!(
+            Settlement::partial_fill_original_amount(instruction_id, LegId(0)),
+            Some(1_000)
+        );
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::InstructionPartiallyFilled(
+                alice.did,
+                instruction_id,
+                Perbill::from_percent(60),
+                vec![(LegId(0), 600), (LegId(1), 300)]
+            ))));
+
+        // Filling everything that is left executes the instruction
+        assert_ok!(Settlement::execute_partial_fill(
+            alice.origin(),
+            instruction_id,
+            Perbill::one(),
+            2,
+            None
+        ));
+        assert_instruction_status(
+            instruction_id,
+            InstructionStatus::Success(System::block_number()),
+        );
+        assert!(!Settlement::partially_fillable(instruction_id));
+        assert_locked_assets(&TICKER, &alice, 0);
+        assert_locked_assets(&TICKER2, &bob, 0);
+        alice.assert_balance_decreased(&TICKER, 1_000);
+        alice.assert_balance_increased(&TICKER2, 500);
+        bob.assert_balance_increased(&TICKER, 1_000);
+        bob.assert_balance_decreased(&TICKER2, 500);
+    });
+}
+
+#[test]
+fn partially_fillable_instruction_only_fungible_legs() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let venue_counter = create_token_and_venue(TICKER, alice);
+
+        assert_noop!(
+            Settlement::add_partially_fillable_instruction(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleManual(0),
+                None,
+                None,
+                vec![Leg::NonFungible {
+                    sender: PortfolioId::default_portfolio(alice.did),
+                    receiver: PortfolioId::default_portfolio(bob.did),
+                    nfts: NFTs::new_unverified(TICKER, vec![NFTId(1)]),
+                }],
+                None,
+            ),
+            Error::InvalidLegForPartialFill
+        );
+    });
+}
+
+#[test]
+fn partially_fillable_instruction_only_manual_settlement() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let venue_counter = create_token_and_venue(TICKER, alice);
+        let legs = vec![Leg::Fungible {
+            sender: PortfolioId::default_portfolio(alice.did),
+            receiver: PortfolioId::default_portfolio(bob.did),
+            ticker: TICKER,
+            amount: 1_000,
+        }];
+
+        for settlement_type in [
+            SettlementType::SettleOnAffirmation,
+            SettlementType::SettleOnBlock(100),
+        ] {
+            assert_noop!(
+                Settlement::add_partially_fillable_instruction(
+                    alice.origin(),
+                    venue_counter,
+                    settlement_type,
+                    None,
+                    None,
+                    legs.clone(),
+                    None,
+                ),
+                Error::InvalidSettlementTypeForPartialFill
+            );
+        }
+    });
+}
+
+#[test]
+fn expired_instruction_is_rejected() {
+    test_with_cdd_provider(|_eve| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let venue_counter = create_token_and_venue(TICKER, alice);
+        let instruction_id = Settlement::instruction_counter();
+        let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+        let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+        let legs = vec![Leg::Fungible {
+            sender: alice_portfolio,
+            receiver: bob_portfolio,
+            ticker: TICKER,
+            amount: 1_000,
+        }];
+        set_current_block_number(10);
+        Timestamp::set_timestamp(1_000);
+
+        // The expiry date must be in the future
+        assert_noop!(
+            Settlement::add_instruction_with_expiry(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                1_000,
+                legs.clone(),
+                None,
+                BTreeSet::new().try_into().unwrap()
+            ),
+            Error::InvalidExpiryDate
+        );
+        // With a block time of 6ms the instruction is expected to expire at block 20
+        assert_ok!(Settlement::add_instruction_with_expiry(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            1_060,
+            legs,
+            None,
+            BTreeSet::new().try_into().unwrap()
+        ));
+        assert_eq!(
+            Settlement::instruction_details(instruction_id).expiry,
+            Some(1_060)
+        );
+        assert_affirm_instruction!(alice.origin(), instruction_id, alice.did);
+        assert_locked_assets(&TICKER, &alice, 1_000);
+
+        for _ in 0..10 {
+            Timestamp::set_timestamp(Timestamp::get() + 6);
+            next_block();
+        }
+        assert_instruction_status(instruction_id, InstructionStatus::Rejected(20));
+        assert_locked_assets(&TICKER, &alice, 0);
+        assert_eq!(InstructionLegs::iter_prefix(instruction_id).count(), 0);
+        assert_eq!(
+            UserAffirmations::get(alice_portfolio, instruction_id),
+            AffirmationStatus::Unknown
+        );
+        assert_eq!(
+            UserAffirmations::get(bob_portfolio, instruction_id),
+            AffirmationStatus::Unknown
+        );
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::InstructionExpired(
+                instruction_id
+            ))));
+    });
+}
+
+#[test]
+fn expired_instruction_cannot_be_affirmed() {
+    test_with_cdd_provider(|_eve| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let venue_counter = create_token_and_venue(TICKER, alice);
+        let instruction_id = Settlement::instruction_counter();
+        set_current_block_number(10);
+        Timestamp::set_timestamp(1_000);
+
+        assert_ok!(Settlement::add_instruction_with_expiry(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            1_060,
+            vec![Leg::Fungible {
+                sender: PortfolioId::default_portfolio(alice.did),
+                receiver: PortfolioId::default_portfolio(bob.did),
+                ticker: TICKER,
+                amount: 1_000,
+            }],
+            None,
+            BTreeSet::new().try_into().unwrap()
+        ));
+
+        // Blocks were produced faster than expected, so the rejection is rescheduled
+        for _ in 0..10 {
+            next_block();
+        }
+        assert_instruction_status(instruction_id, InstructionStatus::Pending);
+        // The rescheduled rejection keeps the instruction's expiry name
+        let expiry_task = sp_io::hashing::blake2_256(&instruction_id.expiry_name());
+        assert!(pallet_scheduler::Lookup::<TestStorage>::contains_key(
+            expiry_task
+        ));
+
+        Timestamp::set_timestamp(1_060);
+        assert_noop!(
+            Settlement::affirm_instruction(
+                alice.origin(),
+                instruction_id,
+                default_portfolio_vec(alice.did)
+            ),
+            Error::InstructionExpired
+        );
+        for _ in 0..10 {
+            next_block();
+        }
+        assert_instruction_status(instruction_id, InstructionStatus::Rejected(30));
+        assert!(!pallet_scheduler::Lookup::<TestStorage>::contains_key(
+            expiry_task
+        ));
+    });
+}
+
+#[test]
+fn execute_netted_instructions() {
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER, TICKER2]);
+        let mut bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER, TICKER2]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        create_token(TICKER2, bob.user);
+        let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+        let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+        assert_ok!(transfer(TICKER, alice.user, bob.user, 1_000));
+        alice.refresh_init_balances();
+        bob.refresh_init_balances();
+
+        let first_instruction = Settlement::instruction_counter();
+        let second_instruction = first_instruction.checked_inc().unwrap();
+        for legs in [
+            vec![
+                Leg::Fungible {
+                    sender: alice_portfolio,
+                    receiver: bob_portfolio,
+                    ticker: TICKER,
+                    amount: 1_000,
+                },
+                Leg::Fungible {
+                    sender: bob_portfolio,
+                    receiver: alice_portfolio,
+                    ticker: TICKER2,
+                    amount: 200,
+                },
+            ],
+            vec![Leg::Fungible {
+                sender: bob_portfolio,
+                receiver: alice_portfolio,
+                ticker: TICKER,
+                amount: 400,
+            }],
+        ] {
+            assert_ok!(Settlement::add_instruction(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleManual(0),
+                None,
+                None,
+                legs,
+                None,
+            ));
+        }
+        set_current_block_number(10);
+
+        // Only fully affirmed instructions can be netted
+        assert_err_ignore_postinfo!(
+            Settlement::execute_netted_instructions(
+                alice.origin(),
+                venue_counter,
+                vec![first_instruction, second_instruction],
+                3,
+                0,
+                0,
+                None
+            ),
+            Error::NotAllAffirmationsHaveBeenReceived
+        );
+        for id in [first_instruction, second_instruction] {
+            assert_affirm_instruction!(alice.origin(), id, alice.did);
+            assert_affirm_instruction!(bob.origin(), id, bob.did);
+        }
+        assert_locked_assets(&TICKER, &alice, 1_000);
+        assert_locked_assets(&TICKER, &bob, 400);
+
+        // Only the venue creator can net the instructions
+        assert_err_ignore_postinfo!(
+            Settlement::execute_netted_instructions(
+                bob.origin(),
+                venue_counter,
+                vec![first_instruction, second_instruction],
+                3,
+                0,
+                0,
+                None
+            ),
+            Error::Unauthorized
+        );
+        assert_err_ignore_postinfo!(
+            Settlement::execute_netted_instructions(
+                alice.origin(),
+                venue_counter,
+                vec![first_instruction, first_instruction],
+                3,
+                0,
+                0,
+                None
+            ),
+            Error::DuplicateInstructionInBatch
+        );
+        assert_err_ignore_postinfo!(
+            Settlement::execute_netted_instructions(
+                alice.origin(),
+                venue_counter,
+                vec![first_instruction, second_instruction],
+                2,
+                0,
+                0,
+                None
+            ),
+            Error::NumberOfFungibleTransfersUnderestimated
+        );
+
+        assert_ok!(Settlement::execute_netted_instructions(
+            alice.origin(),
+            venue_counter,
+            vec![first_instruction, second_instruction],
+            3,
+            0,
+            0,
+            None
+        ));
+        assert_instruction_status(first_instruction, InstructionStatus::Success(10));
+        assert_instruction_status(second_instruction, InstructionStatus::Success(10));
+        alice.assert_balance_decreased(&TICKER, 600);
+        alice.assert_balance_increased(&TICKER2, 200);
+        bob.assert_balance_increased(&TICKER, 600);
+        bob.assert_balance_decreased(&TICKER2, 200);
+        assert_locked_assets(&TICKER, &alice, 0);
+        assert_locked_assets(&TICKER, &bob, 0);
+        assert_locked_assets(&TICKER2, &bob, 0);
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::NettedInstructionsExecuted(
+                alice.did,
+                venue_counter,
+                vec![first_instruction, second_instruction],
+                vec![
+                    Leg::Fungible {
+                        sender: alice_portfolio,
+                        receiver: bob_portfolio,
+                        ticker: TICKER,
+                        amount: 600,
+                    },
+                    Leg::Fungible {
+                        sender: bob_portfolio,
+                        receiver: alice_portfolio,
+                        ticker: TICKER2,
+                        amount: 200,
+                    },
+                ]
+            ))));
+        // Each net transfer lists the instructions it settles
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::NetTransferExecuted(
+                alice.did,
+                venue_counter,
+                Leg::Fungible {
+                    sender: alice_portfolio,
+                    receiver: bob_portfolio,
+                    ticker: TICKER,
+                    amount: 600,
+                },
+                vec![first_instruction, second_instruction],
+            ))));
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::NetTransferExecuted(
+                alice.did,
+                venue_counter,
+                Leg::Fungible {
+                    sender: bob_portfolio,
+                    receiver: alice_portfolio,
+                    ticker: TICKER2,
+                    amount: 200,
+                },
+                vec![first_instruction],
+            ))));
+    });
+}
+
+#[test]
+fn netting_batch_with_prerequisites() {
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER]);
+        let mut bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+        let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+        assert_ok!(transfer(TICKER, alice.user, bob.user, 1_000));
+        alice.refresh_init_balances();
+        bob.refresh_init_balances();
+
+        let first_instruction = Settlement::instruction_counter();
+        let second_instruction = first_instruction.checked_inc().unwrap();
+        assert_ok!(Settlement::add_instruction(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleManual(0),
+            None,
+            None,
+            vec![Leg::Fungible {
+                sender: alice_portfolio,
+                receiver: bob_portfolio,
+                ticker: TICKER,
+                amount: 100,
+            }],
+            None,
+        ));
+        assert_ok!(Settlement::add_instruction_with_prerequisites(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleManual(0),
+            None,
+            None,
+            vec![Leg::Fungible {
+                sender: bob_portfolio,
+                receiver: alice_portfolio,
+                ticker: TICKER,
+                amount: 40,
+            }],
+            None,
+            BTreeSet::new().try_into().unwrap(),
+            BTreeSet::from([first_instruction]).try_into().unwrap()
+        ));
+        set_current_block_number(10);
+        for id in [first_instruction, second_instruction] {
+            assert_affirm_instruction!(alice.origin(), id, alice.did);
+            assert_affirm_instruction!(bob.origin(), id, bob.did);
+        }
+
+        // The dependent can't be netted without its prerequisite
+        assert_err_ignore_postinfo!(
+            Settlement::execute_netted_instructions(
+                alice.origin(),
+                venue_counter,
+                vec![second_instruction],
+                1,
+                0,
+                0,
+                None
+            ),
+            Error::PrerequisitesNotExecuted
+        );
+
+        // A prerequisite in the same batch is executed together with its dependent, in any order
+        assert_ok!(Settlement::execute_netted_instructions(
+            alice.origin(),
+            venue_counter,
+            vec![second_instruction, first_instruction],
+            2,
+            0,
+            0,
+            None
+        ));
+        assert_instruction_status(first_instruction, InstructionStatus::Success(10));
+        assert_instruction_status(second_instruction, InstructionStatus::Success(10));
+        alice.assert_balance_decreased(&TICKER, 60);
+        bob.assert_balance_increased(&TICKER, 60);
+        assert_locked_assets(&TICKER, &alice, 0);
+        assert_locked_assets(&TICKER, &bob, 0);
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::NetTransferExecuted(
+                alice.did,
+                venue_counter,
+                Leg::Fungible {
+                    sender: alice_portfolio,
+                    receiver: bob_portfolio,
+                    ticker: TICKER,
+                    amount: 60,
+                },
+                vec![first_instruction, second_instruction],
+            ))));
+
+        // The dependent was executed with the batch, so nothing is left scheduled for it
+        next_block();
+        alice.assert_balance_decreased(&TICKER, 60);
+    });
+}
+
+#[test]
+fn netting_keeps_original_counterparties() {
+    test_with_cdd_provider(|_eve| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let charlie = User::new(AccountKeyring::Charlie);
+        let venue_counter = create_token_and_venue(TICKER, alice);
+        let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+        let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+        let charlie_portfolio = PortfolioId::default_portfolio(charlie.did);
+        assert_ok!(transfer(TICKER, alice, bob, 1_000));
+
+        let first_instruction = Settlement::instruction_counter();
+        let second_instruction = first_instruction.checked_inc().unwrap();
+        for (sender, receiver) in [
+            (alice_portfolio, bob_portfolio),
+            (bob_portfolio, charlie_portfolio),
+        ] {
+            assert_ok!(Settlement::add_instruction(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleManual(0),
+                None,
+                None,
+                vec![Leg::Fungible {
+                    sender,
+                    receiver,
+                    ticker: TICKER,
+                    amount: 300,
+                }],
+                None,
+            ));
+        }
+        set_current_block_number(10);
+        assert_affirm_instruction!(alice.origin(), first_instruction, alice.did);
+        assert_affirm_instruction!(bob.origin(), first_instruction, bob.did);
+        assert_affirm_instruction!(bob.origin(), second_instruction, bob.did);
+        assert_affirm_instruction!(charlie.origin(), second_instruction, charlie.did);
+
+        assert_ok!(Settlement::execute_netted_instructions(
+            alice.origin(),
+            venue_counter,
+            vec![first_instruction, second_instruction],
+            2,
+            0,
+            0,
+            None
+        ));
+        // Alice never traded with Charlie, so her transfer to Bob is not redirected to Charlie
+        let net_legs =
+            System::events()
+                .into_iter()
+                .find_map(|e| match e.event {
+                    super::storage::EventTest::Settlement(
+                        RawEvent::NettedInstructionsExecuted(_, _, _, net_legs),
+                    ) => Some(net_legs),
+                    _ => None,
+                })
+                .unwrap();
+        assert_eq!(net_legs.len(), 2);
+        for (sender, receiver) in [
+            (alice_portfolio, bob_portfolio),
+            (bob_portfolio, charlie_portfolio),
+        ] {
+            assert!(net_legs.contains(&Leg::Fungible {
+                sender,
+                receiver,
+                ticker: TICKER,
+                amount: 300,
+            }));
+        }
+    });
+}
+
+#[test]
+fn instruction_waits_for_prerequisites() {
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER]);
+        let mut bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        alice.refresh_init_balances();
+        bob.refresh_init_balances();
+        let legs = vec![Leg::Fungible {
+            sender: PortfolioId::default_portfolio(alice.did),
+            receiver: PortfolioId::default_portfolio(bob.did),
+            ticker: TICKER,
+            amount: 100,
+        }];
+        let first_instruction = Settlement::instruction_counter();
+        let second_instruction = first_instruction.checked_inc().unwrap();
+        assert_ok!(Settlement::add_instruction(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            legs.clone(),
+            None,
+        ));
+        assert_noop!(
+            Settlement::add_instruction_with_prerequisites(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                legs.clone(),
+                None,
+                BTreeSet::new().try_into().unwrap(),
+                BTreeSet::from([InstructionId(1_000)]).try_into().unwrap()
+            ),
+            Error::UnknownInstruction
+        );
+        assert_ok!(Settlement::add_instruction_with_prerequisites(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            legs,
+            None,
+            BTreeSet::new().try_into().unwrap(),
+            BTreeSet::from([first_instruction]).try_into().unwrap()
+        ));
+        set_current_block_number(10);
+
+        // The second instruction is not scheduled until its prerequisite has been executed
+        assert_affirm_instruction!(alice.origin(), second_instruction, alice.did);
+        assert_affirm_instruction!(bob.origin(), second_instruction, bob.did);
+        assert_err_ignore_postinfo!(
+            Settlement::execute_manual_instruction(
+                alice.origin(),
+                second_instruction,
+                None,
+                1,
+                0,
+                0,
+                None
+            ),
+            Error::PrerequisitesNotExecuted
+        );
+        next_block();
+        alice.assert_balance_decreased(&TICKER, 0);
+
+        assert_affirm_instruction!(alice.origin(), first_instruction, alice.did);
+        assert_affirm_instruction!(bob.origin(), first_instruction, bob.did);
+        next_block();
+        assert_instruction_status(first_instruction, InstructionStatus::Success(12));
+        alice.assert_balance_decreased(&TICKER, 100);
+
+        // The second instruction is scheduled as soon as the first one has been executed
+        next_block();
+        assert_instruction_status(second_instruction, InstructionStatus::Success(13));
+        alice.assert_balance_decreased(&TICKER, 200);
+        bob.assert_balance_increased(&TICKER, 200);
+    });
+}
+
+#[test]
+fn rejection_cascades_to_dependent_instructions() {
+    test_with_cdd_provider(|_eve| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let venue_counter = create_token_and_venue(TICKER, alice);
+        let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+        let legs = vec![Leg::Fungible {
+            sender: alice_portfolio,
+            receiver: PortfolioId::default_portfolio(bob.did),
+            ticker: TICKER,
+            amount: 100,
+        }];
+        let first_instruction = Settlement::instruction_counter();
+        let second_instruction = first_instruction.checked_inc().unwrap();
+        let third_instruction = second_instruction.checked_inc().unwrap();
+        assert_ok!(Settlement::add_instruction(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            legs.clone(),
+            None,
+        ));
+        for prerequisite in [first_instruction, second_instruction] {
+            assert_ok!(Settlement::add_instruction_with_prerequisites(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                legs.clone(),
+                None,
+                BTreeSet::new().try_into().unwrap(),
+                BTreeSet::from([prerequisite]).try_into().unwrap()
+            ));
+        }
+        set_current_block_number(10);
+        assert_affirm_instruction!(alice.origin(), second_instruction, alice.did);
+        assert_affirm_instruction!(alice.origin(), third_instruction, alice.did);
+        assert_locked_assets(&TICKER, &alice, 200);
+
+        assert_ok!(Settlement::reject_instruction(
+            alice.origin(),
+            first_instruction,
+            alice_portfolio
+        ));
+        assert_instruction_status(first_instruction, InstructionStatus::Rejected(10));
+        assert_instruction_status(second_instruction, InstructionStatus::Rejected(10));
+        assert_locked_assets(&TICKER, &alice, 100);
+
+        // Indirect dependents are rejected in the next block
+        assert_instruction_status(third_instruction, InstructionStatus::Pending);
+        next_block();
+        assert_instruction_status(third_instruction, InstructionStatus::Rejected(11));
+        assert_locked_assets(&TICKER, &alice, 0);
+        assert_eq!(
+            Settlement::instruction_dependents_count(second_instruction),
+            0
+        );
+        assert_eq!(
+            InstructionDependents::iter_prefix(first_instruction).count(),
+            0
+        );
+        assert_eq!(
+            InstructionPrerequisites::iter_prefix(third_instruction).count(),
+            0
+        );
+        for (dependent, prerequisite) in [
+            (second_instruction, first_instruction),
+            (third_instruction, second_instruction),
+        ] {
+            assert!(System::events().iter().any(|e| e.event
+                == super::storage::EventTest::Settlement(RawEvent::DependentInstructionRejected(
+                    dependent,
+                    prerequisite
+                ))));
+        }
+
+        // Rejected instructions can't be prerequisites
+        assert_noop!(
+            Settlement::add_instruction_with_prerequisites(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                legs,
+                None,
+                BTreeSet::new().try_into().unwrap(),
+                BTreeSet::from([first_instruction]).try_into().unwrap()
+            ),
+            Error::PrerequisiteRejected
+        );
+    });
+}
+
+#[test]
+fn prerequisites_are_restricted_to_the_creator_venues() {
+    test_with_cdd_provider(|_eve| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let alice_venue = create_token_and_venue(TICKER, alice);
+        let bob_venue = create_token_and_venue(TICKER2, bob);
+        let alice_legs = vec![Leg::Fungible {
+            sender: PortfolioId::default_portfolio(alice.did),
+            receiver: PortfolioId::default_portfolio(bob.did),
+            ticker: TICKER,
+            amount: 100,
+        }];
+        let bob_legs = vec![Leg::Fungible {
+            sender: PortfolioId::default_portfolio(bob.did),
+            receiver: PortfolioId::default_portfolio(alice.did),
+            ticker: TICKER2,
+            amount: 100,
+        }];
+        let alice_instruction = Settlement::instruction_counter();
+        assert_ok!(Settlement::add_instruction(
+            alice.origin(),
+            alice_venue,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            alice_legs.clone(),
+            None,
+        ));
+
+        // Bob can't make his instructions depend on an instruction of Alice's venue
+        assert_noop!(
+            Settlement::add_instruction_with_prerequisites(
+                bob.origin(),
+                bob_venue,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                bob_legs,
+                None,
+                BTreeSet::new().try_into().unwrap(),
+                BTreeSet::from([alice_instruction]).try_into().unwrap()
+            ),
+            Error::PrerequisiteNotInCreatorVenue
+        );
+
+        // The number of dependents of an instruction is limited
+        let max_dependents =
+            <TestStorage as pallet_settlement::Config>::MaxInstructionDependents::get();
+        for _ in 0..max_dependents {
+            assert_ok!(Settlement::add_instruction_with_prerequisites(
+                alice.origin(),
+                alice_venue,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                alice_legs.clone(),
+                None,
+                BTreeSet::new().try_into().unwrap(),
+                BTreeSet::from([alice_instruction]).try_into().unwrap()
+            ));
+        }
+        assert_eq!(
+            Settlement::instruction_dependents_count(alice_instruction),
+            max_dependents
+        );
+        assert_noop!(
+            Settlement::add_instruction_with_prerequisites(
+                alice.origin(),
+                alice_venue,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                alice_legs,
+                None,
+                BTreeSet::new().try_into().unwrap(),
+                BTreeSet::from([alice_instruction]).try_into().unwrap()
+            ),
+            Error::TooManyDependents
+        );
+    });
+}
+
+#[test]
+fn failed_prerequisite_keeps_dependents_until_rejected() {
+    test_with_cdd_provider(|_eve| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let venue_counter = create_token_and_venue(TICKER, alice);
+        let legs = vec![Leg::Fungible {
+            sender: PortfolioId::default_portfolio(alice.did),
+            receiver: PortfolioId::default_portfolio(bob.did),
+            ticker: TICKER,
+            amount: 100,
+        }];
+        let first_instruction = Settlement::instruction_counter();
+        let second_instruction = first_instruction.checked_inc().unwrap();
+        assert_ok!(Settlement::add_instruction(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            legs.clone(),
+            None,
+        ));
+        assert_ok!(Settlement::add_instruction_with_prerequisites(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            legs,
+            None,
+            BTreeSet::new().try_into().unwrap(),
+            BTreeSet::from([first_instruction]).try_into().unwrap()
+        ));
+        set_current_block_number(10);
+        assert_affirm_instruction!(alice.origin(), second_instruction, alice.did);
+        assert_affirm_instruction!(alice.origin(), first_instruction, alice.did);
+        assert_affirm_instruction!(bob.origin(), first_instruction, bob.did);
+        assert_ok!(Asset::freeze(alice.origin(), TICKER));
+
+        // The first instruction fails in block 11, but can still be retried, so its dependent is kept
+        next_block();
+        assert_instruction_status(first_instruction, InstructionStatus::Failed);
+        next_block();
+        assert_instruction_status(second_instruction, InstructionStatus::Pending);
+        assert_locked_assets(&TICKER, &alice, 200);
+
+        // Its dependent is rejected once the failed instruction is rejected
+        assert_ok!(Settlement::reject_instruction(
+            alice.origin(),
+            first_instruction,
+            PortfolioId::default_portfolio(alice.did),
+        ));
+        assert_instruction_status(first_instruction, InstructionStatus::Rejected(12));
+        assert_instruction_status(second_instruction, InstructionStatus::Rejected(12));
+        assert_locked_assets(&TICKER, &alice, 0);
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::DependentInstructionRejected(
+                second_instruction,
+                first_instruction
+            ))));
+    });
+}
+
+#[test]
+fn simulate_instruction_execution() {
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER]);
+        let bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        alice.refresh_init_balances();
+        let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+        let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+
+        let report = Settlement::simulate_instruction_execution(InstructionId(1_000));
+        assert_eq!(report.instruction_errors, vec!["UnknownInstruction"]);
+        assert!(report.legs.is_empty());
+        assert!(!report.result);
+
+        let instruction_id = Settlement::instruction_counter();
+        assert_ok!(Settlement::add_instruction(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleManual(0),
+            None,
+            None,
+            vec![
+                Leg::Fungible {
+                    sender: alice_portfolio,
+                    receiver: bob_portfolio,
+                    ticker: TICKER,
+                    amount: 100,
+                },
+                Leg::Fungible {
+                    sender: bob_portfolio,
+                    receiver: alice_portfolio,
+                    ticker: TICKER,
+                    amount: 150,
+                },
+            ],
+            None,
+        ));
+        set_current_block_number(10);
+
+        // Bob only receives 100 tokens from the first leg
+        let report = Settlement::simulate_instruction_execution(instruction_id);
+        assert_eq!(
+            report.instruction_errors,
+            vec!["NotAllAffirmationsHaveBeenReceived"]
+        );
+        assert_eq!(report.legs.len(), 2);
+        assert_eq!(report.legs[0].0, LegId(0));
+        assert!(report.legs[0].1.result());
+        match &report.legs[1].1 {
+            LegTransferReport::Fungible(granular_result) => {
+                assert!(granular_result.sender_insufficient_balance);
+                assert!(granular_result.compliance_result.result);
+                assert!(!granular_result.result);
+            }
+            _ => panic!("Expected a fungible leg report"),
+        }
+        assert!(!report.result);
+
+        // Nothing was committed by the simulation
+        alice.assert_all_balances_unchanged();
+        bob.assert_all_balances_unchanged();
+        assert_instruction_status(instruction_id, InstructionStatus::Pending);
+    });
+}
+
+#[test]
+fn venue_halt_trading_window_and_limits() {
+    const HOUR: u64 = 60 * 60 * 1000;
+    const DAY: u64 = 24 * HOUR;
+
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER]);
+        let bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        alice.refresh_init_balances();
+        let legs = vec![Leg::Fungible {
+            sender: PortfolioId::default_portfolio(alice.did),
+            receiver: PortfolioId::default_portfolio(bob.did),
+            ticker: TICKER,
+            amount: 100,
+        }];
+        let add_instruction = |legs: Vec<Leg>| {
+            Settlement::add_instruction(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleManual(0),
+                None,
+                None,
+                legs,
+                None,
+            )
+        };
+        set_current_block_number(10);
+
+        // Only the venue creator and the venue signers can manage the venue
+        assert_noop!(
+            Settlement::set_venue_halt(bob.origin(), venue_counter, true),
+            Error::Unauthorized
+        );
+        assert_ok!(Settlement::update_venue_signers(
+            alice.origin(),
+            venue_counter,
+            vec![bob.acc()],
+            true
+        ));
+        assert_ok!(Settlement::set_venue_halt(
+            bob.origin(),
+            venue_counter,
+            true
+        ));
+        assert_noop!(add_instruction(legs.clone()), Error::VenueHalted);
+        assert_ok!(Settlement::set_venue_halt(
+            alice.origin(),
+            venue_counter,
+            false
+        ));
+
+        let limits = VenueInstructionLimits {
+            max_legs: Some(1),
+            max_leg_amount: Some(50),
+        };
+        assert_ok!(Settlement::set_venue_instruction_limits(
+            alice.origin(),
+            venue_counter,
+            limits
+        ));
+        assert_eq!(Settlement::venue_instruction_limits(venue_counter), limits);
+        assert_noop!(
+            add_instruction(legs.clone()),
+            Error::InstructionExceedsVenueLimits
+        );
+        assert_ok!(Settlement::set_venue_instruction_limits(
+            alice.origin(),
+            venue_counter,
+            VenueInstructionLimits::default()
+        ));
+
+        // The venue is open for one hour every day
+        let mut trading_window = CalendarWindow {
+            schedule: CheckpointSchedule {
+                start: DAY,
+                period: CalendarPeriod {
+                    unit: CalendarUnit::Day,
+                    amount: 1,
+                },
+            },
+            duration: 0,
+        };
+        assert_noop!(
+            Settlement::set_venue_trading_window(
+                alice.origin(),
+                venue_counter,
+                Some(trading_window)
+            ),
+            Error::InvalidTradingWindow
+        );
+        trading_window.duration = HOUR;
+        assert_ok!(Settlement::set_venue_trading_window(
+            alice.origin(),
+            venue_counter,
+            Some(trading_window)
+        ));
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::VenueTradingWindowUpdated(
+                alice.did,
+                venue_counter,
+                Some(trading_window)
+            ))));
+        Timestamp::set_timestamp(DAY + 2 * HOUR);
+        assert_noop!(
+            add_instruction(legs.clone()),
+            Error::VenueOutsideTradingWindow
+        );
+        Timestamp::set_timestamp(2 * DAY + HOUR / 2);
+        let instruction_id = Settlement::instruction_counter();
+        assert_ok!(add_instruction(legs));
+        assert_affirm_instruction!(alice.origin(), instruction_id, alice.did);
+        assert_affirm_instruction!(bob.origin(), instruction_id, bob.did);
+
+        // A halted venue can't execute instructions
+        assert_ok!(Settlement::set_venue_halt(
+            alice.origin(),
+            venue_counter,
+            true
+        ));
+        assert_err_ignore_postinfo!(
+            Settlement::execute_manual_instruction(
+                alice.origin(),
+                instruction_id,
+                None,
+                1,
+                0,
+                0,
+                None
+            ),
+            Error::VenueHalted
+        );
+        assert_ok!(Settlement::set_venue_halt(
+            alice.origin(),
+            venue_counter,
+            false
+        ));
+        assert_ok!(Settlement::execute_manual_instruction(
+            alice.origin(),
+            instruction_id,
+            None,
+            1,
+            0,
+            0,
+            None
+        ));
+        alice.assert_balance_decreased(&TICKER, 100);
+        bob.assert_balance_increased(&TICKER, 100);
+    });
+}
+
+/// Alice lends 100 `TICKER` to Bob, who posts 50 `TICKER2` as collateral.
+fn repo_legs(alice: &User, bob: &User) -> Vec<Leg> {
+    vec![
+        Leg::Fungible {
+            sender: PortfolioId::default_portfolio(alice.did),
+            receiver: PortfolioId::default_portfolio(bob.did),
+            ticker: TICKER,
+            amount: 100,
+        },
+        Leg::Fungible {
+            sender: PortfolioId::default_portfolio(bob.did),
+            receiver: PortfolioId::default_portfolio(alice.did),
+            ticker: TICKER2,
+            amount: 50,
+        },
+    ]
+}
+
+#[test]
+fn repo_instruction_unwinds_at_maturity() {
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER, TICKER2]);
+        let mut bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER, TICKER2]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        create_token(TICKER2, bob.user);
+        alice.refresh_init_balances();
+        bob.refresh_init_balances();
+        set_current_block_number(10);
+        Timestamp::set_timestamp(1_000);
+
+        assert_noop!(
+            Settlement::add_repo_instruction(
+                alice.origin(),
+                venue_counter,
+                repo_legs(&alice, &bob),
+                1_000,
+                None
+            ),
+            Error::InvalidMaturityDate
+        );
+        let instruction_id = Settlement::instruction_counter();
+        assert_ok!(Settlement::add_repo_instruction(
+            alice.origin(),
+            venue_counter,
+            repo_legs(&alice, &bob),
+            1_060,
+            None
+        ));
+        assert_affirm_instruction!(alice.origin(), instruction_id, alice.did);
+        assert_affirm_instruction!(bob.origin(), instruction_id, bob.did);
+
+        // The opening legs are executed in the next block and the returned assets are locked
+        Timestamp::set_timestamp(Timestamp::get() + 6);
+        next_block();
+        assert_instruction_status(instruction_id, InstructionStatus::Success(11));
+        alice.assert_balance_decreased(&TICKER, 100);
+        bob.assert_balance_increased(&TICKER, 100);
+        assert_locked_assets(&TICKER, &bob, 100);
+        assert_locked_assets(&TICKER2, &alice, 50);
+        assert_eq!(
+            Settlement::repo_agreement(instruction_id).unwrap().status,
+            RepoStatus::Open
+        );
+
+        // With a block time of 6ms the agreement is unwound at block 20
+        for _ in 0..9 {
+            Timestamp::set_timestamp(Timestamp::get() + 6);
+            next_block();
+        }
+        assert_eq!(Settlement::repo_agreement(instruction_id), None);
+        alice.assert_all_balances_unchanged();
+        bob.assert_all_balances_unchanged();
+        assert_locked_assets(&TICKER, &bob, 0);
+        assert_locked_assets(&TICKER2, &alice, 0);
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::RepoUnwound(instruction_id))));
+    });
+}
+
+#[test]
+fn repo_instruction_early_close_and_default() {
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER, TICKER2]);
+        let mut bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER, TICKER2]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        create_token(TICKER2, bob.user);
+        alice.refresh_init_balances();
+        bob.refresh_init_balances();
+        set_current_block_number(10);
+        Timestamp::set_timestamp(1_000);
+
+        let open_repo = || {
+            let instruction_id = Settlement::instruction_counter();
+            assert_ok!(Settlement::add_repo_instruction(
+                alice.origin(),
+                venue_counter,
+                repo_legs(&alice, &bob),
+                Timestamp::get() + 60,
+                None
+            ));
+            assert_affirm_instruction!(alice.origin(), instruction_id, alice.did);
+            assert_affirm_instruction!(bob.origin(), instruction_id, bob.did);
+            Timestamp::set_timestamp(Timestamp::get() + 6);
+            next_block();
+            instruction_id
+        };
+
+        // Blocks were produced faster than expected, so the unwind is rescheduled under the same name
+        let instruction_id = open_repo();
+        for _ in 0..10 {
+            next_block();
+        }
+        assert_eq!(
+            Settlement::repo_agreement(instruction_id).unwrap().status,
+            RepoStatus::Open
+        );
+        let unwind_task = sp_io::hashing::blake2_256(&instruction_id.repo_unwind_name());
+        assert!(pallet_scheduler::Lookup::<TestStorage>::contains_key(
+            unwind_task
+        ));
+
+        // Only the venue creator or the custodian of all returning portfolios can close the repo early
+        assert_noop!(
+            Settlement::close_repo_early(bob.origin(), instruction_id, 2, 0),
+            PortfolioError::UnauthorizedCustodian
+        );
+        assert_noop!(
+            Settlement::close_repo_early(alice.origin(), instruction_id, 1, 0),
+            Error::NumberOfFungibleTransfersUnderestimated
+        );
+        // A halted venue can't close agreements early
+        assert_ok!(Settlement::set_venue_halt(
+            alice.origin(),
+            venue_counter,
+            true
+        ));
+        assert_noop!(
+            Settlement::close_repo_early(alice.origin(), instruction_id, 2, 0),
+            Error::VenueHalted
+        );
+        assert_ok!(Settlement::set_venue_halt(
+            alice.origin(),
+            venue_counter,
+            false
+        ));
+        assert_ok!(Settlement::close_repo_early(
+            alice.origin(),
+            instruction_id,
+            2,
+            0
+        ));
+        assert_eq!(Settlement::repo_agreement(instruction_id), None);
+        // The rescheduled unwind has been cancelled
+        assert!(!pallet_scheduler::Lookup::<TestStorage>::contains_key(
+            unwind_task
+        ));
+        alice.assert_all_balances_unchanged();
+        bob.assert_all_balances_unchanged();
+        assert_locked_assets(&TICKER, &bob, 0);
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::RepoClosedEarly(
+                alice.did,
+                instruction_id
+            ))));
+        assert_noop!(
+            Settlement::close_repo_early(alice.origin(), instruction_id, 2, 0),
+            Error::RepoNotOpen
+        );
+
+        // The agreement defaults if the collateral can't be returned at maturity
+        let instruction_id = open_repo();
+        assert_ok!(Asset::freeze(bob.origin(), TICKER2));
+        for _ in 0..10 {
+            Timestamp::set_timestamp(Timestamp::get() + 6);
+            next_block();
+        }
+        assert_eq!(Settlement::repo_agreement(instruction_id), None);
+        assert_locked_assets(&TICKER, &bob, 0);
+        assert_locked_assets(&TICKER2, &alice, 0);
+        bob.assert_balance_increased(&TICKER, 100);
+        alice.assert_balance_increased(&TICKER2, 50);
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::RepoDefaulted(instruction_id))));
+    });
+}
+
+#[test]
+fn repo_opening_legs_are_not_executed_without_collateral() {
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER, TICKER2]);
+        let mut bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER, TICKER2]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        create_token(TICKER2, bob.user);
+        alice.refresh_init_balances();
+        bob.refresh_init_balances();
+        set_current_block_number(10);
+        Timestamp::set_timestamp(1_000);
+
+        let instruction_id = Settlement::instruction_counter();
+        assert_ok!(Settlement::add_repo_instruction(
+            alice.origin(),
+            venue_counter,
+            repo_legs(&alice, &bob),
+            1_060,
+            None
+        ));
+        assert_affirm_instruction!(alice.origin(), instruction_id, alice.did);
+        assert_affirm_instruction!(bob.origin(), instruction_id, bob.did);
+
+        // The lent assets can't be locked in Bob's portfolio once received
+        let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+        let bob_balance = Asset::balance_of(&TICKER, bob.did);
+        PortfolioLockedAssets::insert(bob_portfolio, TICKER, bob_balance + 1);
+
+        // The opening legs are rolled back together with the collateral
+        Timestamp::set_timestamp(Timestamp::get() + 6);
+        next_block();
+        assert_instruction_status(instruction_id, InstructionStatus::Failed);
+        alice.assert_all_balances_unchanged();
+        bob.assert_all_balances_unchanged();
+        assert_locked_assets(&TICKER, &alice, 100);
+        assert_locked_assets(&TICKER2, &bob, 50);
+        assert_eq!(
+            Settlement::repo_agreement(instruction_id).unwrap().status,
+            RepoStatus::Pending
+        );
+    });
+}
+
+#[test]
+fn amend_instruction_resets_affirmations() {
+    test_with_cdd_provider(|_eve| {
+        let mut alice = UserWithBalance::new(AccountKeyring::Alice, &[TICKER]);
+        let mut bob = UserWithBalance::new(AccountKeyring::Bob, &[TICKER]);
+        let venue_counter = create_token_and_venue(TICKER, alice.user);
+        alice.refresh_init_balances();
+        bob.refresh_init_balances();
+        set_current_block_number(1);
+
+        let instruction_id = Settlement::instruction_counter();
+        let leg = |amount| Leg::Fungible {
+            sender: PortfolioId::default_portfolio(alice.did),
+            receiver: PortfolioId::default_portfolio(bob.did),
+            ticker: TICKER,
+            amount,
+        };
+        assert_ok!(Settlement::add_instruction(
+            alice.origin(),
+            venue_counter,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            vec![leg(100)],
+            None,
+        ));
+        assert_affirm_instruction!(alice.origin(), instruction_id, alice.did);
+        assert_locked_assets(&TICKER, &alice, 100);
+
+        // Only the venue creator can amend the instruction
+        assert_noop!(
+            Settlement::amend_instruction(bob.origin(), instruction_id, vec![leg(60)], None, None),
+            Error::Unauthorized
+        );
+        assert_ok!(Settlement::amend_instruction(
+            alice.origin(),
+            instruction_id,
+            vec![leg(60)],
+            Some(1_000),
+            Some(2_000)
+        ));
+        assert_eq!(Settlement::instruction_version(instruction_id), 1);
+        assert_eq!(
+            Settlement::instruction_version_history(instruction_id, 0),
+            Some(InstructionVersion {
+                legs: vec![leg(100)],
+                trade_date: None,
+                value_date: None,
+                amended_at: Timestamp::get(),
+            })
+        );
+        let details = Settlement::instruction_details(instruction_id);
+        assert_eq!(
+            (details.trade_date, details.value_date),
+            (Some(1_000), Some(2_000))
+        );
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::InstructionAmended(
+                alice.did,
+                instruction_id,
+                1,
+                Some(1_000),
+                Some(2_000),
+                vec![(LegId(0), Some(leg(100)), Some(leg(60)))]
+            ))));
+
+        // All affirmations have been reset
+        assert_locked_assets(&TICKER, &alice, 0);
+        assert_user_affirms(instruction_id, &alice, AffirmationStatus::Pending);
+        assert_user_affirms(instruction_id, &bob, AffirmationStatus::Pending);
+        assert_affirms_pending(instruction_id, 2);
+
+        // Parties affirm the current version
+        assert_noop!(
+            Settlement::affirm_instruction(
+                bob.origin(),
+                instruction_id,
+                default_portfolio_vec(bob.did)
+            ),
+            Error::InstructionVersionMismatch
+        );
+        assert_noop!(
+            Settlement::affirm_instruction_version(
+                bob.origin(),
+                instruction_id,
+                0,
+                default_portfolio_vec(bob.did)
+            ),
+            Error::InstructionVersionMismatch
+        );
+        assert_ok!(Settlement::affirm_instruction_version(
+            bob.origin(),
+            instruction_id,
+            1,
+            default_portfolio_vec(bob.did)
+        ));
+        assert_ok!(Settlement::affirm_instruction_version(
+            alice.origin(),
+            instruction_id,
+            1,
+            default_portfolio_vec(alice.did)
+        ));
+        next_block();
+        assert_instruction_status(
+            instruction_id,
+            InstructionStatus::Success(System::block_number()),
+        );
+        alice.assert_balance_decreased(&TICKER, 60);
+        bob.assert_balance_increased(&TICKER, 60);
+        assert_noop!(
+            Settlement::amend_instruction(
+                alice.origin(),
+                instruction_id,
+                vec![leg(10)],
+                None,
+                None
+            ),
+            Error::InstructionCannotBeAmended
+        );
+    });
+}
+
+#[test]
+fn amend_instruction_with_multiple_legs() {
+    test_with_cdd_provider(|_eve| {
+        let alice = User::new(AccountKeyring::Alice);
+        let bob = User::new(AccountKeyring::Bob);
+        let venue_counter = create_token_and_venue(TICKER, alice);
+        set_current_block_number(1);
+
+        let leg = |amount| Leg::Fungible {
+            sender: PortfolioId::default_portfolio(alice.did),
+            receiver: PortfolioId::default_portfolio(bob.did),
+            ticker: TICKER,
+            amount,
+        };
+        let add_instruction = |settlement_type| {
+            let instruction_id = Settlement::instruction_counter();
+            assert_ok!(Settlement::add_instruction(
+                alice.origin(),
+                venue_counter,
+                settlement_type,
+                None,
+                None,
+                (1..=12).map(|i| leg(i * 10)).collect(),
+                None,
+            ));
+            instruction_id
+        };
+
+        // The history and the diff follow the order of the legs
+        let instruction_id = add_instruction(SettlementType::SettleOnAffirmation);
+        let mut new_legs: Vec<Leg> = (1..=12).map(|i| leg(i * 10)).collect();
+        new_legs[1] = leg(25);
+        new_legs[10] = leg(115);
+        new_legs.pop();
+        assert_ok!(Settlement::amend_instruction(
+            alice.origin(),
+            instruction_id,
+            new_legs.clone(),
+            None,
+            None
+        ));
+        assert_eq!(
+            Settlement::instruction_version_history(instruction_id, 0)
+                .unwrap()
+                .legs,
+            (1..=12).map(|i| leg(i * 10)).collect::<Vec<_>>()
+        );
+        assert!(System::events().iter().any(|e| e.event
+            == super::storage::EventTest::Settlement(RawEvent::InstructionAmended(
+                alice.did,
+                instruction_id,
+                1,
+                None,
+                None,
+                vec![
+                    (LegId(1), Some(leg(20)), Some(leg(25))),
+                    (LegId(10), Some(leg(110)), Some(leg(115))),
+                    (LegId(11), Some(leg(120)), None),
+                ]
+            ))));
+        let mut legs: Vec<(LegId, Leg)> = InstructionLegs::iter_prefix(instruction_id).collect();
+        legs.sort_by_key(|(leg_id, _)| *leg_id);
+        assert_eq!(
+            legs.into_iter().map(|(_, leg)| leg).collect::<Vec<_>>(),
+            new_legs
+        );
+
+        // Instructions settling on a block that has been reached can't be amended
+        let instruction_id = add_instruction(SettlementType::SettleOnBlock(5));
+        set_current_block_number(5);
+        assert_noop!(
+            Settlement::amend_instruction(alice.origin(), instruction_id, new_legs, None, None),
+            Error::SettleOnPastBlock
+        );
+    });
+}
+
 /// Asserts the storage has been updated after adding an instruction.
 /// While each portfolio in `portfolios_pending_approval` must have a pending `AffirmationStatus`, each portfolio in `portfolios_pre_approved`
 /// must have an affirmed status. The number of pending affirmations must be equal to the number of portfolios in `portfolios_pending_approval` + the number of offchain legs,
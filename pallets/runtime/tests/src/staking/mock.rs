@@ -485,6 +485,10 @@ impl PortfolioSubTrait<AccountId> for Test {
     fn skip_portfolio_affirmation(_: &PortfolioId, _: &Ticker) -> bool {
         unimplemented!()
     }
+
+    fn current_custodian(_: &PortfolioId) -> IdentityId {
+        unimplemented!()
+    }
 }
 
 impl CheckCdd<AccountId> for Test {
@@ -17,7 +17,10 @@ use pallet_balances as balances;
 use pallet_committee as committee;
 use pallet_corporate_actions as corporate_actions;
 use pallet_corporate_actions::ballot as corporate_ballots;
+use pallet_corporate_actions::coupon as corporate_coupon;
 use pallet_corporate_actions::distribution as capital_distributions;
+use pallet_corporate_actions::exchange as corporate_exchange;
+use pallet_corporate_actions::reorganization as corporate_reorganization;
 use pallet_group as group;
 use pallet_identity as identity;
 use pallet_multisig as multisig;
@@ -241,6 +244,11 @@ parameter_types! {
     pub const MaxNumberOfOffChainAssets: u32 = 10;
     pub const MaxNumberOfVenueSigners: u32 = 50;
     pub const MaxInstructionMediators: u32 = 4;
+    pub const MaxInstructionPrerequisites: u32 = 10;
+    pub const MaxInstructionDependents: u32 = 10;
+    pub const MaxOrdersPerBook: u32 = 100;
+    pub const MaxPendingOrderBooks: u32 = 1_000;
+    pub OrderBookMatchingWeight: Weight = Perbill::from_percent(10) * MaximumBlockWeight::get();
     pub const MaxAssetMediators: u32 = 4;
 }
 
@@ -337,6 +345,12 @@ frame_support::construct_runtime!(
 
         Nft: pallet_nft::{Pallet, Call, Storage, Event} = 51,
 
+        OrderBook: pallet_order_book::{Pallet, Call, Storage, Event} = 52,
+
+        CorporateReorganization: corporate_reorganization::{Pallet, Call, Storage, Event} = 53,
+        CorporateExchange: corporate_exchange::{Pallet, Call, Storage, Event} = 54,
+        CorporateCoupon: corporate_coupon::{Pallet, Call, Storage, Event} = 55,
+
         // Testing only.
         Example: example::{Pallet, Call} = 201,
     }
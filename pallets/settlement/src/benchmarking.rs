@@ -27,6 +27,9 @@ use polymesh_common_utilities::benchs::{make_asset, AccountIdOf, User, UserBuild
 use polymesh_common_utilities::constants::currency::ONE_UNIT;
 use polymesh_common_utilities::constants::ENSURED_MAX_LEN;
 use polymesh_common_utilities::TestUtilsFn;
+use polymesh_primitives::calendar::{
+    CalendarPeriod, CalendarUnit, CalendarWindow, CheckpointSchedule,
+};
 use polymesh_primitives::checked_inc::CheckedInc;
 use polymesh_primitives::settlement::ReceiptMetadata;
 use polymesh_primitives::{IdentityId, Memo, NFTId, NFTs, PortfolioId, Ticker};
@@ -366,6 +369,40 @@ benchmarks! {
         assert_eq!(Module::<T>::venue_info(VenueId(1)).unwrap().venue_type, ty, "Incorrect venue type value");
     }
 
+    set_venue_trading_window {
+        let window = CalendarWindow {
+            schedule: CheckpointSchedule {
+                start: 0,
+                period: CalendarPeriod { unit: CalendarUnit::Day, amount: 1 },
+            },
+            duration: 8 * 60 * 60 * 1000,
+        };
+
+        let User { origin, did, .. } = creator::<T>();
+        let venue_id = create_venue_::<T>(did.unwrap(), vec![]);
+    }: _(origin, venue_id, Some(window))
+    verify {
+        assert_eq!(Module::<T>::venue_trading_window(venue_id), Some(window));
+    }
+
+    set_venue_halt {
+        let User { origin, did, .. } = creator::<T>();
+        let venue_id = create_venue_::<T>(did.unwrap(), vec![]);
+    }: _(origin, venue_id, true)
+    verify {
+        assert!(Module::<T>::venue_halted(venue_id));
+    }
+
+    set_venue_instruction_limits {
+        let limits = VenueInstructionLimits { max_legs: Some(10), max_leg_amount: Some(ONE_UNIT) };
+
+        let User { origin, did, .. } = creator::<T>();
+        let venue_id = create_venue_::<T>(did.unwrap(), vec![]);
+    }: _(origin, venue_id, limits)
+    verify {
+        assert_eq!(Module::<T>::venue_instruction_limits(venue_id), limits);
+    }
+
     update_venue_signers {
         // Variations for the no. of signers allowed.
         let s in 0 .. MAX_SIGNERS_ALLOWED;
@@ -793,4 +830,89 @@ benchmarks! {
 
         let parameters = setup_execute_instruction::<T>(&alice, &bob, settlement_type, venue_id, f, n, o, m, false, false);
     }: _(parameters.asset_mediators[0].origin.clone(), InstructionId(1), None)
+
+    expire_instruction {
+        // Number of fungible, non-fungible and offchain LEGS in the instruction
+        let f in 1..T::MaxNumberOfFungibleAssets::get();
+        let n in 0..T::MaxNumberOfNFTs::get();
+        let o in 0..T::MaxNumberOfOffChainAssets::get();
+
+        let m = T::MaxInstructionMediators::get();
+
+        let alice = UserBuilder::<T>::default().generate_did().build("Alice");
+        let bob = UserBuilder::<T>::default().generate_did().build("Bob");
+        let settlement_type = SettlementType::SettleOnBlock(100u32.into());
+        let venue_id = create_venue_::<T>(alice.did(), vec![alice.account(), bob.account()]);
+
+        setup_execute_instruction::<T>(&alice, &bob, settlement_type, venue_id, f, n, o, m, false, false);
+        <InstructionDetails<T>>::mutate(InstructionId(1), |details| details.expiry = Some(1u32.into()));
+        <pallet_timestamp::Now<T>>::set(2u32.into());
+    }: expire_scheduled_instruction(RawOrigin::Root, InstructionId(1), Weight::MAX)
+    verify {
+        assert_eq!(Module::<T>::instruction_status(InstructionId(1)), InstructionStatus::Rejected(frame_system::Pallet::<T>::block_number()));
+    }
+
+    reject_dependents {
+        // Number of instructions depending on the rejected instruction
+        let d in 0..T::MaxInstructionDependents::get();
+
+        let f = T::MaxNumberOfFungibleAssets::get();
+        let alice = UserBuilder::<T>::default().generate_did().build("Alice");
+        let bob = UserBuilder::<T>::default().generate_did().build("Bob");
+        let venue_id = create_venue_::<T>(alice.did(), vec![alice.account(), bob.account()]);
+
+        let parameters = setup_execute_instruction::<T>(&alice, &bob, SettlementType::SettleOnAffirmation, venue_id, f, 0, 0, 0, false, false);
+        for i in 0..d {
+            Module::<T>::add_instruction_with_prerequisites(
+                alice.origin.clone().into(),
+                venue_id,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                parameters.legs.clone(),
+                None,
+                BTreeSet::new().try_into().unwrap(),
+                BTreeSet::from([InstructionId(1)]).try_into().unwrap(),
+            )
+            .unwrap();
+            Module::<T>::affirm_instruction(
+                alice.origin.clone().into(),
+                InstructionId(2 + i as u64),
+                parameters.portfolios.sdr_portfolios.clone(),
+            )
+            .unwrap();
+        }
+        InstructionStatuses::<T>::insert(InstructionId(1), InstructionStatus::Rejected(frame_system::Pallet::<T>::block_number()));
+    }: reject_scheduled_dependents(RawOrigin::Root, InstructionId(1))
+    verify {
+        assert_eq!(InstructionDependents::iter_prefix(InstructionId(1)).count(), 0);
+    }
+
+    close_repo_early {
+        // Number of fungible and non-fungible LEGS in the agreement
+        let f in 1..T::MaxNumberOfFungibleAssets::get();
+        let n in 0..T::MaxNumberOfNFTs::get();
+
+        let alice = UserBuilder::<T>::default().generate_did().build("Alice");
+        let bob = UserBuilder::<T>::default().generate_did().build("Bob");
+        let venue_id = create_venue_::<T>(alice.did(), vec![alice.account(), bob.account()]);
+
+        // The assets returned by the agreement are locked in the returning portfolios
+        let parameters = setup_legs::<T>(&alice, &bob, f, n, 0, false, false);
+        for leg in &parameters.legs {
+            Module::<T>::lock_via_leg(leg).unwrap();
+        }
+        RepoAgreements::<T>::insert(
+            InstructionId(1),
+            RepoAgreement {
+                venue_id,
+                status: RepoStatus::Open,
+                closing_legs: parameters.legs,
+                maturity: 1_000u32.into(),
+            },
+        );
+    }: _(alice.origin, InstructionId(1), f, n)
+    verify {
+        assert!(Module::<T>::repo_agreement(InstructionId(1)).is_none());
+    }
 }
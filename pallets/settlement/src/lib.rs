@@ -42,6 +42,15 @@
 //! - `set_venue_filtering` - Enables or disabled venue filtering for a token.
 //! - `allow_venues` - Allows additional venues to create instructions involving an asset.
 //! - `disallow_venues` - Revokes permission given to venues for creating instructions involving a particular asset.
+//! - `add_partially_fillable_instruction` - Adds a new instruction whose legs can be filled pro rata in multiple steps.
+//! - `execute_partial_fill` - Transfers a fraction of the remaining amount of all legs of a partially fillable instruction.
+//! - `add_instruction_with_expiry` - Adds a new instruction that is automatically rejected if it has not been executed before its expiry date.
+//! - `execute_netted_instructions` - Executes a batch of instructions of a venue by transferring only the net amount between each pair of portfolios.
+//! - `add_instruction_with_prerequisites` - Adds a new instruction that can only be executed after all its prerequisite instructions have been executed.
+//! - `update_venue_ecdsa_signers` - Adds or removes the ECDSA signers allowed to sign receipts for a venue.
+//! - `affirm_with_ecdsa_receipts` - Affirms an instruction using receipts signed with ECDSA keys, bound to the chain and to an expiry.
+//! - `amend_instruction` - Proposes a new version of a pending instruction, resetting all its affirmations.
+//! - `affirm_instruction_version` - Affirms a specific version of an instruction.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![recursion_limit = "256"]
@@ -64,7 +73,10 @@ use frame_support::{
     decl_error, decl_module, decl_storage, ensure, BoundedBTreeSet, IterableStorageDoubleMap,
 };
 use frame_system::{ensure_root, RawOrigin};
-use sp_runtime::traits::{One, Verify};
+use sp_runtime::traits::{One, SaturatedConversion, Saturating, Verify, Zero};
+use sp_runtime::Perbill;
+use sp_std::cmp::Ordering;
+use sp_std::collections::btree_map::BTreeMap;
 use sp_std::collections::btree_set::BTreeSet;
 use sp_std::convert::TryFrom;
 use sp_std::prelude::*;
@@ -79,15 +91,19 @@ pub use polymesh_common_utilities::traits::settlement::{Event, RawEvent, WeightI
 use polymesh_common_utilities::traits::{asset, compliance_manager, identity, nft, CommonConfig};
 use polymesh_common_utilities::with_transaction;
 use polymesh_common_utilities::SystematicIssuers::Settlement as SettlementDID;
+use polymesh_primitives::calendar::CalendarWindow;
+use polymesh_primitives::ethereum::{eth_check, EthereumAddress};
 use polymesh_primitives::settlement::{
-    AffirmationCount, AffirmationStatus, AssetCount, ExecuteInstructionInfo, FilteredLegs,
-    Instruction, InstructionId, InstructionInfo, InstructionStatus, Leg, LegId, LegStatus,
-    MediatorAffirmationStatus, Receipt, ReceiptDetails, SettlementType, Venue, VenueDetails,
-    VenueId, VenueType,
+    AffirmationCount, AffirmationStatus, AssetCount, EcdsaReceipt, EcdsaReceiptDetails,
+    ExecuteInstructionInfo, FilteredLegs, Instruction, InstructionExecutionReport, InstructionId,
+    InstructionInfo, InstructionStatus, InstructionVersion, Leg, LegId, LegStatus,
+    LegTransferReport, MediatorAffirmationStatus, Receipt, ReceiptDetails, ReceiptMetadata,
+    ReceiptSigner, RepoAgreement, RepoStatus, SettlementType, Venue, VenueDetails, VenueId,
+    VenueInstructionLimits, VenueType, ECDSA_RECEIPT_PREFIX,
 };
 use polymesh_primitives::{
-    storage_migration_ver, Balance, IdentityId, Memo, NFTs, PortfolioId, SecondaryKey, Ticker,
-    WeightMeter,
+    storage_migrate_on, storage_migration_ver, Balance, IdentityId, Memo, NFTs, PortfolioId,
+    SecondaryKey, Ticker, WeightMeter,
 };
 
 type Identity<T> = pallet_identity::Module<T>;
@@ -142,6 +158,12 @@ pub trait Config:
 
     /// Maximum number mediators in the instruction level (this does not include asset mediators).
     type MaxInstructionMediators: Get<u32>;
+
+    /// Maximum number of prerequisite instructions of a single instruction.
+    type MaxInstructionPrerequisites: Get<u32>;
+
+    /// Maximum number of instructions that can depend on a single instruction.
+    type MaxInstructionDependents: Get<u32>;
 }
 
 decl_error! {
@@ -229,14 +251,58 @@ decl_error! {
         NumberOfVenueSignersExceeded,
         /// The caller is not a mediator in the instruction.
         CallerIsNotAMediator,
-        /// The mediator's expiry date must be in the future.
+        /// The expiry date must be in the future.
         InvalidExpiryDate,
         /// The expiry date for the mediator's affirmation has passed.
-        MediatorAffirmationExpired
+        MediatorAffirmationExpired,
+        /// The instruction does not allow partial fills.
+        PartialFillNotAllowed,
+        /// Only fungible legs are allowed in a partially fillable instruction.
+        InvalidLegForPartialFill,
+        /// The fill ratio must be greater than zero.
+        InvalidFillRatio,
+        /// The instruction's expiry date has passed.
+        InstructionExpired,
+        /// The netting batch must contain at least one instruction.
+        EmptyNettingBatch,
+        /// An instruction was included more than once in the netting batch.
+        DuplicateInstructionInBatch,
+        /// All instructions in the netting batch must belong to the given venue.
+        InstructionNotInVenue,
+        /// Not all prerequisite instructions have been executed.
+        PrerequisitesNotExecuted,
+        /// A rejected instruction can't be a prerequisite.
+        PrerequisiteRejected,
+        /// The venue has been halted.
+        VenueHalted,
+        /// The venue is outside of its trading window.
+        VenueOutsideTradingWindow,
+        /// The trading window must have a non-zero duration.
+        InvalidTradingWindow,
+        /// The instruction exceeds the venue's instruction limits.
+        InstructionExceedsVenueLimits,
+        /// Only fungible and non-fungible legs are allowed in a repo agreement.
+        InvalidLegForRepo,
+        /// The maturity date must be in the future.
+        InvalidMaturityDate,
+        /// The repo agreement does not exist or its opening legs have not been executed.
+        RepoNotOpen,
+        /// The receipt's expiry date has passed.
+        ReceiptExpired,
+        /// Only pending instructions that are not partially fillable nor a repo agreement can be amended.
+        InstructionCannotBeAmended,
+        /// The given version is not the current version of the instruction.
+        InstructionVersionMismatch,
+        /// Partially fillable instructions can only be settled manually.
+        InvalidSettlementTypeForPartialFill,
+        /// Prerequisites must belong to a venue of the instruction's creator.
+        PrerequisiteNotInCreatorVenue,
+        /// The prerequisite has reached the maximum number of dependent instructions.
+        TooManyDependents
     }
 }
 
-storage_migration_ver!(2);
+storage_migration_ver!(3);
 
 decl_storage! {
     trait Store for Module<T: Config> as Settlement {
@@ -291,7 +357,7 @@ decl_storage! {
         /// Number of instructions in the system (It's one more than the actual number)
         InstructionCounter get(fn instruction_counter) build(|_| InstructionId(1u64)): InstructionId;
         /// Storage version.
-        StorageVersion get(fn storage_version) build(|_| Version::new(2)): Version;
+        StorageVersion get(fn storage_version) build(|_| Version::new(3)): Version;
         /// Instruction memo
         pub InstructionMemos get(fn memo): map hasher(twox_64_concat) InstructionId => Option<Memo>;
         /// Instruction statuses. instruction_id -> InstructionStatus
@@ -308,6 +374,48 @@ decl_storage! {
         /// The status for the mediators affirmation.
         pub InstructionMediatorsAffirmations get(fn venue_mediators_affirmations):
             double_map hasher(twox_64_concat) InstructionId, hasher(identity) IdentityId => MediatorAffirmationStatus<T::Moment>;
+        /// Instructions that can be partially filled. instruction_id -> partially_fillable
+        pub PartiallyFillableInstructions get(fn partially_fillable):
+            map hasher(twox_64_concat) InstructionId => bool;
+        /// The amount of each leg of a partially fillable instruction when it was added.
+        /// (instruction_id, leg_id) -> amount
+        pub PartialFillOriginalAmounts get(fn partial_fill_original_amount):
+            double_map hasher(twox_64_concat) InstructionId, hasher(twox_64_concat) LegId => Option<Balance>;
+        /// Instructions that must be executed before an instruction can be executed.
+        /// (instruction_id, prerequisite_id) -> ()
+        pub InstructionPrerequisites get(fn instruction_prerequisites):
+            double_map hasher(twox_64_concat) InstructionId, hasher(twox_64_concat) InstructionId => ();
+        /// Instructions that depend on the execution of an instruction.
+        /// (prerequisite_id, instruction_id) -> ()
+        pub InstructionDependents get(fn instruction_dependents):
+            double_map hasher(twox_64_concat) InstructionId, hasher(twox_64_concat) InstructionId => ();
+        /// Number of instructions that depend on the execution of an instruction. instruction_id -> number of dependents
+        pub InstructionDependentsCount get(fn instruction_dependents_count):
+            map hasher(twox_64_concat) InstructionId => u32;
+        /// The recurring window during which a venue accepts and executes instructions.
+        /// venue_id -> trading_window
+        pub VenueTradingWindows get(fn venue_trading_window):
+            map hasher(twox_64_concat) VenueId => Option<CalendarWindow>;
+        /// Venues that have been halted. venue_id -> halted
+        pub HaltedVenues get(fn venue_halted): map hasher(twox_64_concat) VenueId => bool;
+        /// Limits on the size of the instructions added to a venue. venue_id -> limits
+        pub VenueLimits get(fn venue_instruction_limits):
+            map hasher(twox_64_concat) VenueId => VenueInstructionLimits;
+        /// Repo and securities lending agreements. opening_instruction_id -> agreement
+        pub RepoAgreements get(fn repo_agreement):
+            map hasher(twox_64_concat) InstructionId => Option<RepoAgreement<T::Moment>>;
+        /// ECDSA signers allowed by the venue. (venue_id, signer) -> bool
+        pub VenueEcdsaSigners get(fn venue_ecdsa_signers):
+            double_map hasher(twox_64_concat) VenueId, hasher(twox_64_concat) EthereumAddress => bool;
+        /// Tracks redemption of ECDSA receipts. (signer, receipt_uid) -> receipt_used
+        pub EcdsaReceiptsUsed get(fn ecdsa_receipts_used):
+            double_map hasher(twox_64_concat) EthereumAddress, hasher(blake2_128_concat) u64 => bool;
+        /// The current version of an instruction, incremented on each amendment. instruction_id -> version
+        pub InstructionVersions get(fn instruction_version):
+            map hasher(twox_64_concat) InstructionId => u32;
+        /// Superseded versions of amended instructions. (instruction_id, version) -> InstructionVersion
+        pub InstructionVersionHistory get(fn instruction_version_history):
+            double_map hasher(twox_64_concat) InstructionId, hasher(twox_64_concat) u32 => Option<InstructionVersion<T::Moment>>;
     }
 }
 
@@ -317,6 +425,13 @@ decl_module! {
 
         fn deposit_event() = default;
 
+        fn on_runtime_upgrade() -> Weight {
+            storage_migrate_on!(StorageVersion, 3, {
+                migration::migrate_to_v3::<T>();
+            });
+            Weight::zero()
+        }
+
         /// Registers a new venue.
         ///
         /// * `details` - Extra details about a venue
@@ -383,6 +498,10 @@ decl_module! {
 
         /// Affirms an instruction using receipts for offchain transfers.
         ///
+        /// Receipts signed with sr25519 or ed25519 keys have no expiry and don't commit to the chain,
+        /// so they stay valid until claimed, on any chain where the venue has the same signer.
+        /// Use `affirm_with_ecdsa_receipts` for receipts bound to this chain and to an expiry date.
+        ///
         /// # Arguments
         /// * `id` - the [`InstructionId`] of the instruction being affirmed.
         /// * `receipt_details` - a vector of [`ReceiptDetails`], which contain the details about the offchain transfer.
@@ -397,6 +516,7 @@ decl_module! {
             receipt_details: Vec<ReceiptDetails<T::AccountId, T::OffChainSignature>>,
             portfolios: Vec<PortfolioId>,
         ) -> DispatchResultWithPostInfo {
+            Self::ensure_instruction_version(id, 0)?;
             Self::affirm_with_receipts_and_maybe_schedule_instruction(
                 origin,
                 id,
@@ -535,6 +655,8 @@ decl_module! {
                 value_date,
                 legs,
                 instruction_memo,
+                None,
+                None,
                 None
             )?;
         }
@@ -573,6 +695,8 @@ decl_module! {
                 value_date,
                 legs,
                 instruction_memo,
+                None,
+                None,
                 None
             )?;
             Self::affirm_and_maybe_schedule_instruction(
@@ -594,6 +718,7 @@ decl_module! {
         /// * Portfolio
         #[weight = <T as Config>::WeightInfo::affirm_instruction_input(None)]
         pub fn affirm_instruction(origin, id: InstructionId, portfolios: Vec<PortfolioId>) -> DispatchResultWithPostInfo {
+            Self::ensure_instruction_version(id, 0)?;
             Self::affirm_and_maybe_schedule_instruction(
                 origin,
                 id,
@@ -623,7 +748,9 @@ decl_module! {
         ///
         /// # Permissions
         /// * Portfolio
-        #[weight = <T as Config>::WeightInfo::reject_instruction_input(None, false)]
+        #[weight = <T as Config>::WeightInfo::reject_instruction_input(None, false)
+            .saturating_add(<T as Config>::WeightInfo::reject_dependents(T::MaxInstructionDependents::get()))
+        ]
         pub fn reject_instruction(origin, id: InstructionId, portfolio: PortfolioId) -> DispatchResultWithPostInfo {
             Self::base_reject_instruction(origin, id, Some(portfolio), None)
         }
@@ -644,6 +771,7 @@ decl_module! {
         }
 
         /// Affirms an instruction using receipts for offchain transfers.
+        /// As with `affirm_with_receipts`, the receipts have no expiry and aren't bound to this chain.
         ///
         /// # Arguments
         /// * `id` - the [`InstructionId`] of the instruction being affirmed.
@@ -663,6 +791,7 @@ decl_module! {
             portfolios: Vec<PortfolioId>,
             number_of_assets: Option<AffirmationCount>
         ) {
+            Self::ensure_instruction_version(id, 0)?;
             Self::affirm_with_receipts_and_maybe_schedule_instruction(
                 origin,
                 id,
@@ -691,6 +820,7 @@ decl_module! {
             portfolios: Vec<PortfolioId>,
             number_of_assets: Option<AffirmationCount>
         ) {
+            Self::ensure_instruction_version(id, 0)?;
             Self::affirm_and_maybe_schedule_instruction(
                 origin,
                 id,
@@ -711,7 +841,9 @@ decl_module! {
         ///
         /// # Permissions
         /// * Portfolio
-        #[weight = <T as Config>::WeightInfo::reject_instruction_input(*number_of_assets, false)]
+        #[weight = <T as Config>::WeightInfo::reject_instruction_input(*number_of_assets, false)
+            .saturating_add(<T as Config>::WeightInfo::reject_dependents(T::MaxInstructionDependents::get()))
+        ]
         pub fn reject_instruction_with_count(
             origin,
             id: InstructionId,
@@ -774,7 +906,9 @@ decl_module! {
                 value_date,
                 legs,
                 instruction_memo,
-                Some(mediators)
+                Some(mediators),
+                None,
+                None
             )?;
         }
 
@@ -813,7 +947,9 @@ decl_module! {
                 value_date,
                 legs,
                 instruction_memo,
-                Some(mediators)
+                Some(mediators),
+                None,
+                None
             )?;
             let portfolios_set = portfolios.into_iter().collect::<BTreeSet<_>>();
             Self::affirm_and_maybe_schedule_instruction(
@@ -837,6 +973,7 @@ decl_module! {
             instruction_id: InstructionId,
             expiry: Option<T::Moment>
         ) {
+            Self::ensure_instruction_version(instruction_id, 0)?;
             Self::base_affirm_instruction_as_mediator(origin, instruction_id, expiry)?;
         }
 
@@ -857,7 +994,9 @@ decl_module! {
         /// * `number_of_assets` - an optional [`AssetCount`] that will be used for a precise fee estimation before executing the extrinsic.
         ///
         /// Note: calling the rpc method `get_execute_instruction_info` returns an instance of [`ExecuteInstructionInfo`], which contain the asset count.
-        #[weight = <T as Config>::WeightInfo::reject_instruction_input(None, true)]
+        #[weight = <T as Config>::WeightInfo::reject_instruction_input(None, true)
+            .saturating_add(<T as Config>::WeightInfo::reject_dependents(T::MaxInstructionDependents::get()))
+        ]
         pub fn reject_instruction_as_mediator(
             origin,
             instruction_id: InstructionId,
@@ -865,186 +1004,964 @@ decl_module! {
         ) -> DispatchResultWithPostInfo {
             Self::base_reject_instruction(origin, instruction_id, None, number_of_assets)
         }
-    }
-}
-
-impl<T: Config> Module<T> {
-    fn lock_via_leg(leg: &Leg) -> DispatchResult {
-        match leg {
-            Leg::Fungible {
-                sender,
-                ticker,
-                amount,
-                ..
-            } => T::Portfolio::lock_tokens(&sender, &ticker, *amount),
-            Leg::NonFungible { sender, nfts, .. } => with_transaction(|| {
-                for nft_id in nfts.ids() {
-                    T::Portfolio::lock_nft(&sender, nfts.ticker(), &nft_id)?;
-                }
-                Ok(())
-            }),
-            Leg::OffChain { .. } => Err(Error::<T>::OffChainAssetCantBeLocked.into()),
-        }
-    }
-
-    fn unlock_via_leg(leg: &Leg) -> DispatchResult {
-        match leg {
-            Leg::Fungible {
-                sender,
-                ticker,
-                amount,
-                ..
-            } => T::Portfolio::unlock_tokens(&sender, &ticker, *amount),
-            Leg::NonFungible { sender, nfts, .. } => with_transaction(|| {
-                for nft_id in nfts.ids() {
-                    T::Portfolio::unlock_nft(&sender, nfts.ticker(), &nft_id)?;
-                }
-                Ok(())
-            }),
-            Leg::OffChain { .. } => Err(Error::<T>::OffChainAssetCantBeLocked.into()),
-        }
-    }
-
-    /// Ensure origin call permission and the given instruction validity.
-    fn ensure_origin_perm_and_instruction_validity(
-        origin: <T as frame_system::Config>::RuntimeOrigin,
-        id: InstructionId,
-        is_execute: bool,
-    ) -> EnsureValidInstructionResult<T::AccountId, T::Moment, T::BlockNumber> {
-        let origin_data = Identity::<T>::ensure_origin_call_permissions(origin)?;
-        Ok((
-            origin_data.primary_did,
-            origin_data.secondary_key,
-            Self::ensure_instruction_validity(id, is_execute)?,
-        ))
-    }
-
-    // Extract `Venue` with `id`, assuming it was created by `did`, or error.
-    fn venue_for_management(id: VenueId, did: IdentityId) -> Result<Venue, DispatchError> {
-        // Ensure venue exists & that DID created it.
-        let venue = Self::venue_info(id).ok_or(Error::<T>::InvalidVenue)?;
-        ensure!(venue.creator == did, Error::<T>::Unauthorized);
-        Ok(venue)
-    }
 
-    pub fn base_add_instruction(
-        did: IdentityId,
-        venue_id: VenueId,
-        settlement_type: SettlementType<T::BlockNumber>,
-        trade_date: Option<T::Moment>,
-        value_date: Option<T::Moment>,
-        legs: Vec<Leg>,
-        memo: Option<Memo>,
-        mediators: Option<BoundedBTreeSet<IdentityId, T::MaxInstructionMediators>>,
-    ) -> Result<InstructionId, DispatchError> {
-        // Verifies if the block number is in the future so that `T::Scheduler::schedule_named` doesn't fail.
-        if let SettlementType::SettleOnBlock(block_number) = &settlement_type {
+        /// Adds a new instruction that can be partially filled.
+        ///
+        /// By affirming the instruction, the counter parties agree that the venue creator may settle it
+        /// in multiple steps, where each step transfers the same fraction of the remaining amount of every leg.
+        /// The original amount of each leg is kept in [`PartialFillOriginalAmounts`].
+        ///
+        /// # Arguments
+        /// * `venue_id`: The [`VenueId`] of the venue this instruction belongs to.
+        /// * `settlement_type`: Must be [`SettlementType::SettleManual`], since other types execute the instruction in full.
+        /// * `trade_date`: Optional date from which people can interact with this instruction.
+        /// * `value_date`: Optional date after which the instruction should be settled (not enforced).
+        /// * `legs`: A vector of all [`Leg`] included in this instruction. Only [`Leg::Fungible`] is allowed.
+        /// * `instruction_memo`: An optional [`Memo`] field for this instruction.
+        #[weight = <T as Config>::WeightInfo::add_instruction_legs(legs)]
+        pub fn add_partially_fillable_instruction(
+            origin,
+            venue_id: VenueId,
+            settlement_type: SettlementType<T::BlockNumber>,
+            trade_date: Option<T::Moment>,
+            value_date: Option<T::Moment>,
+            legs: Vec<Leg>,
+            instruction_memo: Option<Memo>,
+        ) {
+            let did = Identity::<T>::ensure_perms(origin)?;
             ensure!(
-                *block_number > System::<T>::block_number(),
-                Error::<T>::SettleOnPastBlock
+                matches!(settlement_type, SettlementType::SettleManual(_)),
+                Error::<T>::InvalidSettlementTypeForPartialFill
             );
-        }
-
-        // Ensure that instruction dates are valid.
-        if let (Some(trade_date), Some(value_date)) = (trade_date, value_date) {
             ensure!(
-                value_date >= trade_date,
-                Error::<T>::InstructionDatesInvalid
+                legs.iter().all(|leg| matches!(leg, Leg::Fungible { .. })),
+                Error::<T>::InvalidLegForPartialFill
             );
+            let instruction_id = Self::base_add_instruction(
+                did,
+                venue_id,
+                settlement_type,
+                trade_date,
+                value_date,
+                legs,
+                instruction_memo,
+                None,
+                None,
+                None
+            )?;
+            PartiallyFillableInstructions::insert(instruction_id, true);
+            for (leg_id, leg) in InstructionLegs::iter_prefix(instruction_id) {
+                if let Leg::Fungible { amount, .. } = leg {
+                    PartialFillOriginalAmounts::insert(instruction_id, leg_id, amount);
+                }
+            }
         }
 
-        // Ensure venue exists & sender is its creator.
-        Self::venue_for_management(venue_id, did)?;
-
-        // Verifies if all legs are valid.
-        let mut instruction_info = Self::ensure_valid_legs(&legs, &venue_id)?;
-
-        // Adds the instruction mediators
-        if let Some(mediators) = mediators {
-            instruction_info.extend_mediators(mediators.into())
+        /// Transfers `fill` of the remaining amount of each leg in a partially fillable instruction.
+        /// The amounts that were not transferred stay locked until the instruction is filled, rejected or executed.
+        ///
+        /// # Arguments
+        /// * `id`: The [`InstructionId`] of the instruction to be partially filled.
+        /// * `fill`: The fraction of the remaining amount of each leg that will be transferred.
+        /// Filling [`Perbill::one`] executes the instruction.
+        /// * `fungible_transfers`: The number of fungible legs in the instruction.
+        /// * `weight_limit`: An optional maximum [`Weight`] value to be charged for filling the instruction.
+        ///
+        /// # Permissions
+        /// * The caller must be the creator of the instruction's venue.
+        #[weight = <T as Config>::WeightInfo::execute_manual_weight_limit(weight_limit, fungible_transfers, &0, &0)]
+        pub fn execute_partial_fill(
+            origin,
+            id: InstructionId,
+            fill: Perbill,
+            fungible_transfers: u32,
+            weight_limit: Option<Weight>
+        ) -> DispatchResultWithPostInfo {
+            let mut weight_meter = Self::ensure_valid_weight_meter(
+                Self::execute_manual_instruction_minimum_weight(),
+                weight_limit.unwrap_or(Self::execute_manual_instruction_weight_limit(
+                    fungible_transfers,
+                    0,
+                    0,
+                )),
+            )?;
+            let input_cost = AssetCount::new(fungible_transfers, 0, 0);
+            Self::base_execute_partial_fill(origin, id, fill, &input_cost, &mut weight_meter)
+                .map_err(|e| DispatchErrorWithPostInfo {
+                    post_info: Some(weight_meter.consumed()).into(),
+                    error: e.error,
+                })
         }
 
-        // Advance and get next `instruction_id`.
-        let instruction_id = InstructionCounter::try_mutate(try_next_post::<T, _>)?;
-
-        // All checks have been made - Write data to storage.
-        InstructionStatuses::<T>::insert(instruction_id, InstructionStatus::Pending);
-
-        for portfolio_id in instruction_info.portfolios_pending_approval() {
-            UserAffirmations::insert(portfolio_id, instruction_id, AffirmationStatus::Pending);
-        }
-        for portfolio_id in instruction_info.portfolios_pre_approved_difference() {
-            UserAffirmations::insert(portfolio_id, instruction_id, AffirmationStatus::Affirmed);
-            AffirmsReceived::insert(instruction_id, portfolio_id, AffirmationStatus::Affirmed);
-            Self::deposit_event(RawEvent::InstructionAutomaticallyAffirmed(
+        /// Adds a new instruction that is automatically rejected if it has not been executed before `expiry`.
+        /// When the instruction expires, all affirmations are withdrawn and all locked assets are released.
+        ///
+        /// # Arguments
+        /// * `venue_id`: The [`VenueId`] of the venue this instruction belongs to.
+        /// * `settlement_type`: The [`SettlementType`] specifying when the instruction should be settled.
+        /// * `trade_date`: Optional date from which people can interact with this instruction.
+        /// * `value_date`: Optional date after which the instruction should be settled (not enforced).
+        /// * `expiry`: The date after which the instruction can no longer be affirmed or executed.
+        /// * `legs`: A vector of all [`Leg`] included in this instruction.
+        /// * `instruction_memo`: An optional [`Memo`] field for this instruction.
+        /// * `mediators`: A set of [`IdentityId`] of all the mandatory mediators for the instruction.
+        #[weight = <T as Config>::WeightInfo::add_instruction_with_mediators_legs(legs, mediators.len() as u32)]
+        pub fn add_instruction_with_expiry(
+            origin,
+            venue_id: VenueId,
+            settlement_type: SettlementType<T::BlockNumber>,
+            trade_date: Option<T::Moment>,
+            value_date: Option<T::Moment>,
+            expiry: T::Moment,
+            legs: Vec<Leg>,
+            instruction_memo: Option<Memo>,
+            mediators: BoundedBTreeSet<IdentityId, T::MaxInstructionMediators>,
+        ) {
+            let did = Identity::<T>::ensure_perms(origin)?;
+            Self::base_add_instruction(
                 did,
-                *portfolio_id,
-                instruction_id,
-            ));
-        }
-        for mediator_id in instruction_info.mediators() {
-            InstructionMediatorsAffirmations::<T>::insert(
-                instruction_id,
-                mediator_id,
-                MediatorAffirmationStatus::Pending,
-            );
-        }
-        InstructionAffirmsPending::insert(
-            instruction_id,
-            instruction_info.number_of_pending_affirmations(),
-        );
-
-        legs.iter().enumerate().for_each(|(index, leg)| {
-            let leg_id = LegId(index as u64);
-            InstructionLegs::insert(instruction_id, leg_id, leg.clone());
-            if leg.is_off_chain() {
-                OffChainAffirmations::insert(instruction_id, leg_id, AffirmationStatus::Pending);
-            }
-        });
-
-        <InstructionDetails<T>>::insert(
-            instruction_id,
-            Instruction {
-                instruction_id,
                 venue_id,
                 settlement_type,
-                created_at: Some(<pallet_timestamp::Pallet<T>>::get()),
                 trade_date,
                 value_date,
-            },
-        );
-        if let Some(ref memo) = memo {
-            InstructionMemos::insert(instruction_id, &memo);
+                legs,
+                instruction_memo,
+                Some(mediators),
+                Some(expiry),
+                None
+            )?;
         }
-        VenueInstructions::insert(venue_id, instruction_id, ());
 
-        if let SettlementType::SettleOnBlock(block_number) = settlement_type {
-            let weight_limit = Self::execute_scheduled_instruction_weight_limit(
-                instruction_info.fungible_transfers(),
-                instruction_info.nfts_transferred(),
-                instruction_info.off_chain(),
-            );
-            Self::schedule_instruction(instruction_id, block_number, weight_limit);
+        /// Root callable extrinsic, used as an internal call to reject an expired instruction.
+        #[weight = (*weight_limit).max(<T as Config>::WeightInfo::ensure_root_origin())]
+        fn expire_scheduled_instruction(
+            origin,
+            id: InstructionId,
+            weight_limit: Weight
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_root_origin(origin)?;
+            Self::base_expire_instruction(id, weight_limit);
+            Ok(().into())
         }
 
-        Self::deposit_event(RawEvent::InstructionCreated(
-            did,
-            venue_id,
-            instruction_id,
-            settlement_type,
-            trade_date,
-            value_date,
-            legs,
-            memo,
-        ));
-        Ok(instruction_id)
-    }
-
-    /// Returns [`InstructionInfo`] if all legs are valid, otherwise returns an error.
-    /// See also: [`Module::ensure_valid_fungible_leg`], [`Module::ensure_valid_nft_leg`] and [`Module::ensure_valid_off_chain_leg`].
-    fn ensure_valid_legs(
-        legs: &[Leg],
-        venue_id: &VenueId,
+        /// Executes a batch of fully affirmed instructions of the same venue. Instead of settling each leg,
+        /// the legs between the same two portfolios are offset for every ticker and only the net amounts are transferred.
+        /// Non-fungible legs are transferred as they are. Either all instructions are executed or none is.
+        /// Prerequisites of an instruction that are part of the batch are executed together with it.
+        /// A [`RawEvent::NetTransferExecuted`] event is emitted for each net transfer, with the instructions it settles.
+        ///
+        /// # Arguments
+        /// * `venue_id`: The [`VenueId`] of the venue all instructions belong to.
+        /// * `instruction_ids`: The [`InstructionId`] of all instructions in the batch.
+        /// * `fungible_transfers`: The number of fungible legs in all instructions.
+        /// * `nfts_transfers`: The number of nfts being transferred in all instructions.
+        /// * `offchain_transfers`: The number of offchain legs in all instructions.
+        /// * `weight_limit`: An optional maximum [`Weight`] value to be charged for executing the batch.
+        ///
+        /// # Permissions
+        /// * The caller must be the creator of the venue.
+        #[weight = <T as Config>::WeightInfo::execute_netted_instructions_weight_limit(
+            weight_limit,
+            instruction_ids.len() as u32,
+            fungible_transfers,
+            nfts_transfers,
+            offchain_transfers
+        )]
+        pub fn execute_netted_instructions(
+            origin,
+            venue_id: VenueId,
+            instruction_ids: Vec<InstructionId>,
+            fungible_transfers: u32,
+            nfts_transfers: u32,
+            offchain_transfers: u32,
+            weight_limit: Option<Weight>
+        ) -> DispatchResultWithPostInfo {
+            let mut weight_meter = Self::ensure_valid_weight_meter(
+                Self::execute_manual_instruction_minimum_weight(),
+                <T as Config>::WeightInfo::execute_netted_instructions_weight_limit(
+                    &weight_limit,
+                    instruction_ids.len() as u32,
+                    &fungible_transfers,
+                    &nfts_transfers,
+                    &offchain_transfers,
+                ),
+            )?;
+            let input_cost = AssetCount::new(fungible_transfers, nfts_transfers, offchain_transfers);
+            Self::base_execute_netted_instructions(
+                origin,
+                venue_id,
+                instruction_ids,
+                &input_cost,
+                &mut weight_meter
+            )
+            .map_err(|e| DispatchErrorWithPostInfo {
+                post_info: Some(weight_meter.consumed()).into(),
+                error: e.error,
+            })
+        }
+
+        /// Adds a new instruction that can only be executed after all `prerequisites` have been executed.
+        /// If any of the prerequisites is rejected or fails to execute, the instruction is also rejected. The rejection
+        /// of the instructions that depend on it is scheduled for the next block.
+        /// Since all prerequisites must exist when the instruction is created, the dependencies can never form a cycle.
+        ///
+        /// Pending prerequisites must belong to a venue of the caller and can have at most
+        /// `MaxInstructionDependents` dependents each.
+        ///
+        /// # Arguments
+        /// * `venue_id`: The [`VenueId`] of the venue this instruction belongs to.
+        /// * `settlement_type`: The [`SettlementType`] specifying when the instruction should be settled.
+        /// * `trade_date`: Optional date from which people can interact with this instruction.
+        /// * `value_date`: Optional date after which the instruction should be settled (not enforced).
+        /// * `legs`: A vector of all [`Leg`] included in this instruction.
+        /// * `instruction_memo`: An optional [`Memo`] field for this instruction.
+        /// * `mediators`: A set of [`IdentityId`] of all the mandatory mediators for the instruction.
+        /// * `prerequisites`: A set of [`InstructionId`] of all instructions that must be executed before this instruction.
+        #[weight = <T as Config>::WeightInfo::add_instruction_with_mediators_legs(legs, mediators.len() as u32)
+            .saturating_add(<T as frame_system::Config>::DbWeight::get().reads_writes(
+                4 * prerequisites.len() as u64,
+                3 * prerequisites.len() as u64,
+            ))]
+        pub fn add_instruction_with_prerequisites(
+            origin,
+            venue_id: VenueId,
+            settlement_type: SettlementType<T::BlockNumber>,
+            trade_date: Option<T::Moment>,
+            value_date: Option<T::Moment>,
+            legs: Vec<Leg>,
+            instruction_memo: Option<Memo>,
+            mediators: BoundedBTreeSet<IdentityId, T::MaxInstructionMediators>,
+            prerequisites: BoundedBTreeSet<InstructionId, T::MaxInstructionPrerequisites>,
+        ) {
+            let did = Identity::<T>::ensure_perms(origin)?;
+            Self::base_add_instruction(
+                did,
+                venue_id,
+                settlement_type,
+                trade_date,
+                value_date,
+                legs,
+                instruction_memo,
+                Some(mediators),
+                None,
+                Some(prerequisites)
+            )?;
+        }
+
+        /// Sets the recurring trading window of a venue. Instructions can only be added to and executed
+        /// in the venue while the window is open.
+        ///
+        /// # Arguments
+        /// * `venue_id`: The [`VenueId`] of the venue.
+        /// * `trading_window`: The new [`CalendarWindow`], or `None` to allow trading at any time.
+        ///
+        /// # Permissions
+        /// * Venue creator or venue signer.
+        #[weight = <T as Config>::WeightInfo::set_venue_trading_window()]
+        pub fn set_venue_trading_window(
+            origin,
+            venue_id: VenueId,
+            trading_window: Option<CalendarWindow>,
+        ) {
+            let did = Self::ensure_venue_manager(origin, venue_id)?;
+            if let Some(trading_window) = &trading_window {
+                ensure!(trading_window.duration > 0, Error::<T>::InvalidTradingWindow);
+            }
+
+            VenueTradingWindows::mutate(venue_id, |window| *window = trading_window);
+            Self::deposit_event(RawEvent::VenueTradingWindowUpdated(did, venue_id, trading_window));
+        }
+
+        /// Halts or resumes a venue. While halted, no instruction can be added to or executed in the venue.
+        ///
+        /// # Arguments
+        /// * `venue_id`: The [`VenueId`] of the venue.
+        /// * `halted`: `true` to halt the venue, `false` to resume it.
+        ///
+        /// # Permissions
+        /// * Venue creator or venue signer.
+        #[weight = <T as Config>::WeightInfo::set_venue_halt()]
+        pub fn set_venue_halt(origin, venue_id: VenueId, halted: bool) {
+            let did = Self::ensure_venue_manager(origin, venue_id)?;
+
+            HaltedVenues::insert(venue_id, halted);
+            Self::deposit_event(RawEvent::VenueHaltUpdated(did, venue_id, halted));
+        }
+
+        /// Sets the limits on the size of the instructions that can be added to a venue.
+        ///
+        /// # Arguments
+        /// * `venue_id`: The [`VenueId`] of the venue.
+        /// * `limits`: The new [`VenueInstructionLimits`].
+        ///
+        /// # Permissions
+        /// * Venue creator or venue signer.
+        #[weight = <T as Config>::WeightInfo::set_venue_instruction_limits()]
+        pub fn set_venue_instruction_limits(origin, venue_id: VenueId, limits: VenueInstructionLimits) {
+            let did = Self::ensure_venue_manager(origin, venue_id)?;
+
+            VenueLimits::insert(venue_id, limits);
+            Self::deposit_event(RawEvent::VenueInstructionLimitsUpdated(did, venue_id, limits));
+        }
+
+        /// Adds a repo or securities lending instruction. The opening `legs` are executed as soon as all parties
+        /// have affirmed the instruction, and the reversed legs are automatically executed at `maturity`.
+        /// Until then, the assets received through the opening legs are locked in the receivers' portfolios.
+        /// If the opening legs have not been executed before `maturity`, the instruction expires.
+        ///
+        /// # Arguments
+        /// * `venue_id`: The [`VenueId`] of the venue this instruction belongs to.
+        /// * `legs`: The opening legs. Only fungible and non-fungible legs are allowed.
+        /// * `maturity`: The date at which all assets are returned.
+        /// * `instruction_memo`: An optional [`Memo`] field for this instruction.
+        #[weight = <T as Config>::WeightInfo::add_instruction_legs(legs)]
+        pub fn add_repo_instruction(
+            origin,
+            venue_id: VenueId,
+            legs: Vec<Leg>,
+            maturity: T::Moment,
+            instruction_memo: Option<Memo>,
+        ) {
+            let did = Identity::<T>::ensure_perms(origin)?;
+            Self::base_add_repo_instruction(did, venue_id, legs, maturity, instruction_memo)?;
+        }
+
+        /// Root callable extrinsic, used as an internal call to unwind a repo agreement at its maturity date.
+        #[weight = (*weight_limit).max(<T as Config>::WeightInfo::ensure_root_origin())]
+        fn unwind_scheduled_repo(
+            origin,
+            id: InstructionId,
+            weight_limit: Weight
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_root_origin(origin)?;
+            let mut weight_meter = Self::ensure_valid_weight_meter(
+                Self::execute_scheduled_instruction_minimum_weight(),
+                weight_limit,
+            )?;
+            Self::base_unwind_scheduled_repo(id, weight_limit, &mut weight_meter);
+            Ok(PostDispatchInfo::from(Some(weight_meter.consumed())))
+        }
+
+        /// Unwinds an open repo agreement before its maturity date by executing all its closing legs.
+        /// The venue must not be halted and its trading window, if any, must be open.
+        ///
+        /// # Arguments
+        /// * `id`: The [`InstructionId`] of the opening instruction of the agreement.
+        /// * `fungible_transfers`: The number of fungible legs in the agreement.
+        /// * `nfts_transfers`: The number of nfts being transferred in the agreement.
+        ///
+        /// # Permissions
+        /// * The venue creator or the custodian of all portfolios returning assets.
+        #[weight = <T as Config>::WeightInfo::close_repo_early(*fungible_transfers, *nfts_transfers)]
+        pub fn close_repo_early(
+            origin,
+            id: InstructionId,
+            fungible_transfers: u32,
+            nfts_transfers: u32,
+        ) -> DispatchResultWithPostInfo {
+            let mut weight_meter = Self::ensure_valid_weight_meter(
+                <T as Config>::WeightInfo::close_repo_early(0, 0),
+                <T as Config>::WeightInfo::close_repo_early(fungible_transfers, nfts_transfers),
+            )?;
+            let input_cost = AssetCount::new(fungible_transfers, nfts_transfers, 0);
+            Self::base_close_repo_early(origin, id, &input_cost, &mut weight_meter)?;
+            Ok(PostDispatchInfo::from(Some(weight_meter.consumed())))
+        }
+
+        /// Edit a venue's ECDSA signers, which sign receipts with secp256k1 keys.
+        /// * `id` specifies the ID of the venue to edit.
+        /// * `signers` specifies the Ethereum addresses of the signers to add/remove.
+        /// * `add_signers` specifies the update type add/remove of venue where add is true and remove is false.
+        #[weight = <T as Config>::WeightInfo::update_venue_signers(signers.len() as u32)]
+        pub fn update_venue_ecdsa_signers(origin, id: VenueId, signers: Vec<EthereumAddress>, add_signers: bool) {
+            let did = Identity::<T>::ensure_perms(origin)?;
+
+            Self::base_update_venue_ecdsa_signers(did, id, signers, add_signers)?;
+        }
+
+        /// Affirms an instruction using receipts signed with ECDSA (secp256k1) keys for offchain transfers.
+        ///
+        /// Each receipt signs an [`EcdsaReceipt`], which binds it to this chain's genesis hash and
+        /// to an optional expiry date.
+        ///
+        /// # Arguments
+        /// * `id` - the [`InstructionId`] of the instruction being affirmed.
+        /// * `receipt_details` - a vector of [`EcdsaReceiptDetails`], which contain the details about the offchain transfer.
+        /// * `portfolios` - a vector of [`PortfolioId`] under the caller's control and intended for affirmation.
+        /// * `number_of_assets` - an optional [`AffirmationCount`] that will be used for a precise fee estimation before executing the extrinsic.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::affirm_with_receipts_input(*number_of_assets)]
+        pub fn affirm_with_ecdsa_receipts(
+            origin,
+            id: InstructionId,
+            receipt_details: Vec<EcdsaReceiptDetails<T::Moment>>,
+            portfolios: Vec<PortfolioId>,
+            number_of_assets: Option<AffirmationCount>
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_instruction_version(id, 0)?;
+            let filtered_legs = Self::base_affirm_with_ecdsa_receipts(
+                origin,
+                id,
+                receipt_details,
+                portfolios,
+                number_of_assets
+            )?;
+            Self::maybe_schedule_affirmed_with_receipts(id, filtered_legs)
+        }
+
+        /// Proposes a new version of a pending instruction, replacing its legs, trade date and value date.
+        ///
+        /// All affirmations are reset and all assets locked for the current version are released.
+        /// The current version is kept in [`InstructionVersionHistory`]. Once amended, the instruction can only be
+        /// affirmed through the extrinsics that take the version being affirmed, e.g. `affirm_instruction_version`.
+        ///
+        /// # Arguments
+        /// * `id` - the [`InstructionId`] of the instruction being amended.
+        /// * `legs` - the legs of the new version.
+        /// * `trade_date` - optional date from which people can interact with the new version.
+        /// * `value_date` - optional date after which the new version should be settled (not enforced).
+        ///
+        /// # Permissions
+        /// * The venue creator.
+        #[weight = <T as Config>::WeightInfo::add_instruction_legs(legs)
+            .saturating_add(<T as Config>::WeightInfo::reject_instruction_input(None, false))
+        ]
+        pub fn amend_instruction(
+            origin,
+            id: InstructionId,
+            legs: Vec<Leg>,
+            trade_date: Option<T::Moment>,
+            value_date: Option<T::Moment>,
+        ) -> DispatchResultWithPostInfo {
+            let did = Identity::<T>::ensure_perms(origin)?;
+            Self::base_amend_instruction(did, id, legs, trade_date, value_date)
+        }
+
+        /// Affirms the given version of an instruction, failing if the instruction has been amended since.
+        ///
+        /// # Arguments
+        /// * `id` - the [`InstructionId`] of the instruction being affirmed.
+        /// * `version` - the version of the instruction being affirmed.
+        /// * `portfolios` - a vector of [`PortfolioId`] under the caller's control and intended for affirmation.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::affirm_instruction_input(None)]
+        pub fn affirm_instruction_version(
+            origin,
+            id: InstructionId,
+            version: u32,
+            portfolios: Vec<PortfolioId>,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_instruction_version(id, version)?;
+            Self::affirm_and_maybe_schedule_instruction(origin, id, portfolios.into_iter(), None)
+        }
+
+        /// Root callable extrinsic, used as an internal call to reject the dependents of a rejected or expired instruction.
+        #[weight = <T as Config>::WeightInfo::reject_dependents(T::MaxInstructionDependents::get())]
+        fn reject_scheduled_dependents(origin, id: InstructionId) -> DispatchResultWithPostInfo {
+            Self::ensure_root_origin(origin)?;
+            Ok(PostDispatchInfo::from(Some(Self::base_reject_scheduled_dependents(id))))
+        }
+
+        /// Affirms the given version of an instruction using receipts for offchain transfers,
+        /// failing if the instruction has been amended since.
+        /// As with `affirm_with_receipts`, the receipts have no expiry and aren't bound to this chain.
+        ///
+        /// # Arguments
+        /// * `id` - the [`InstructionId`] of the instruction being affirmed.
+        /// * `version` - the version of the instruction being affirmed.
+        /// * `receipt_details` - a vector of [`ReceiptDetails`], which contain the details about the offchain transfer.
+        /// * `portfolios` - a vector of [`PortfolioId`] under the caller's control and intended for affirmation.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::affirm_with_receipts_input(None)]
+        pub fn affirm_with_receipts_version(
+            origin,
+            id: InstructionId,
+            version: u32,
+            receipt_details: Vec<ReceiptDetails<T::AccountId, T::OffChainSignature>>,
+            portfolios: Vec<PortfolioId>,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_instruction_version(id, version)?;
+            Self::affirm_with_receipts_and_maybe_schedule_instruction(
+                origin,
+                id,
+                receipt_details,
+                portfolios,
+                None
+            )
+        }
+
+        /// Affirms the given version of an instruction using receipts signed with ECDSA (secp256k1) keys
+        /// for offchain transfers, failing if the instruction has been amended since.
+        ///
+        /// # Arguments
+        /// * `id` - the [`InstructionId`] of the instruction being affirmed.
+        /// * `version` - the version of the instruction being affirmed.
+        /// * `receipt_details` - a vector of [`EcdsaReceiptDetails`], which contain the details about the offchain transfer.
+        /// * `portfolios` - a vector of [`PortfolioId`] under the caller's control and intended for affirmation.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::affirm_with_receipts_input(None)]
+        pub fn affirm_with_ecdsa_receipts_version(
+            origin,
+            id: InstructionId,
+            version: u32,
+            receipt_details: Vec<EcdsaReceiptDetails<T::Moment>>,
+            portfolios: Vec<PortfolioId>,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_instruction_version(id, version)?;
+            let filtered_legs = Self::base_affirm_with_ecdsa_receipts(
+                origin,
+                id,
+                receipt_details,
+                portfolios,
+                None
+            )?;
+            Self::maybe_schedule_affirmed_with_receipts(id, filtered_legs)
+        }
+
+        /// Affirms the given version of an instruction as a mediator, failing if the instruction has been amended since.
+        ///
+        /// # Arguments
+        /// * `instruction_id`: The [`InstructionId`] that will be affirmed by the mediator.
+        /// * `version` - the version of the instruction being affirmed.
+        /// * `expiry`: An Optional value for defining when the affirmation will expire (None means it will always be valid).
+        #[weight = <T as Config>::WeightInfo::affirm_instruction_as_mediator()]
+        pub fn affirm_instruction_as_mediator_version(
+            origin,
+            instruction_id: InstructionId,
+            version: u32,
+            expiry: Option<T::Moment>
+        ) {
+            Self::ensure_instruction_version(instruction_id, version)?;
+            Self::base_affirm_instruction_as_mediator(origin, instruction_id, expiry)?;
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    fn lock_via_leg(leg: &Leg) -> DispatchResult {
+        match leg {
+            Leg::Fungible {
+                sender,
+                ticker,
+                amount,
+                ..
+            } => T::Portfolio::lock_tokens(&sender, &ticker, *amount),
+            Leg::NonFungible { sender, nfts, .. } => with_transaction(|| {
+                for nft_id in nfts.ids() {
+                    T::Portfolio::lock_nft(&sender, nfts.ticker(), &nft_id)?;
+                }
+                Ok(())
+            }),
+            Leg::OffChain { .. } => Err(Error::<T>::OffChainAssetCantBeLocked.into()),
+        }
+    }
+
+    fn unlock_via_leg(leg: &Leg) -> DispatchResult {
+        match leg {
+            Leg::Fungible {
+                sender,
+                ticker,
+                amount,
+                ..
+            } => T::Portfolio::unlock_tokens(&sender, &ticker, *amount),
+            Leg::NonFungible { sender, nfts, .. } => with_transaction(|| {
+                for nft_id in nfts.ids() {
+                    T::Portfolio::unlock_nft(&sender, nfts.ticker(), &nft_id)?;
+                }
+                Ok(())
+            }),
+            Leg::OffChain { .. } => Err(Error::<T>::OffChainAssetCantBeLocked.into()),
+        }
+    }
+
+    /// Ensure origin call permission and the given instruction validity.
+    fn ensure_origin_perm_and_instruction_validity(
+        origin: <T as frame_system::Config>::RuntimeOrigin,
+        id: InstructionId,
+        is_execute: bool,
+    ) -> EnsureValidInstructionResult<T::AccountId, T::Moment, T::BlockNumber> {
+        let origin_data = Identity::<T>::ensure_origin_call_permissions(origin)?;
+        Ok((
+            origin_data.primary_did,
+            origin_data.secondary_key,
+            Self::ensure_instruction_validity(id, is_execute)?,
+        ))
+    }
+
+    /// Returns the [`IdentityId`] of the caller if the caller is the creator or a signer of the venue.
+    fn ensure_venue_manager(
+        origin: T::RuntimeOrigin,
+        venue_id: VenueId,
+    ) -> Result<IdentityId, DispatchError> {
+        let origin_data = Identity::<T>::ensure_origin_call_permissions(origin)?;
+        let venue = Self::venue_info(venue_id).ok_or(Error::<T>::InvalidVenue)?;
+        ensure!(
+            venue.creator == origin_data.primary_did
+                || Self::venue_signers(venue_id, &origin_data.sender),
+            Error::<T>::Unauthorized
+        );
+        Ok(origin_data.primary_did)
+    }
+
+    /// Returns `Ok` if the venue has not been halted and its trading window, if any, is open.
+    pub fn ensure_venue_open(venue_id: VenueId) -> DispatchResult {
+        ensure!(!Self::venue_halted(venue_id), Error::<T>::VenueHalted);
+        if let Some(trading_window) = Self::venue_trading_window(venue_id) {
+            let now: u64 = <pallet_timestamp::Pallet<T>>::get().saturated_into();
+            ensure!(
+                trading_window.is_open(now),
+                Error::<T>::VenueOutsideTradingWindow
+            );
+        }
+        Ok(())
+    }
+
+    // Extract `Venue` with `id`, assuming it was created by `did`, or error.
+    fn venue_for_management(id: VenueId, did: IdentityId) -> Result<Venue, DispatchError> {
+        // Ensure venue exists & that DID created it.
+        let venue = Self::venue_info(id).ok_or(Error::<T>::InvalidVenue)?;
+        ensure!(venue.creator == did, Error::<T>::Unauthorized);
+        Ok(venue)
+    }
+
+    pub fn base_add_instruction(
+        did: IdentityId,
+        venue_id: VenueId,
+        settlement_type: SettlementType<T::BlockNumber>,
+        trade_date: Option<T::Moment>,
+        value_date: Option<T::Moment>,
+        legs: Vec<Leg>,
+        memo: Option<Memo>,
+        mediators: Option<BoundedBTreeSet<IdentityId, T::MaxInstructionMediators>>,
+        expiry: Option<T::Moment>,
+        prerequisites: Option<BoundedBTreeSet<InstructionId, T::MaxInstructionPrerequisites>>,
+    ) -> Result<InstructionId, DispatchError> {
+        // Verifies if the block number is in the future so that `T::Scheduler::schedule_named` doesn't fail.
+        if let SettlementType::SettleOnBlock(block_number) = &settlement_type {
+            ensure!(
+                *block_number > System::<T>::block_number(),
+                Error::<T>::SettleOnPastBlock
+            );
+        }
+
+        // Ensure that instruction dates are valid.
+        if let (Some(trade_date), Some(value_date)) = (trade_date, value_date) {
+            ensure!(
+                value_date >= trade_date,
+                Error::<T>::InstructionDatesInvalid
+            );
+        }
+
+        // Ensure that the expiry date is in the future.
+        if let Some(expiry) = expiry {
+            ensure!(
+                expiry > <pallet_timestamp::Pallet<T>>::get(),
+                Error::<T>::InvalidExpiryDate
+            );
+        }
+
+        // Ensure all prerequisites exist, have not been rejected and belong to a venue of the caller.
+        let prerequisites = prerequisites.map(|p| p.into_inner()).unwrap_or_default();
+        for prerequisite in &prerequisites {
+            match Self::instruction_status(prerequisite) {
+                InstructionStatus::Unknown => return Err(Error::<T>::UnknownInstruction.into()),
+                InstructionStatus::Rejected(_) => {
+                    return Err(Error::<T>::PrerequisiteRejected.into())
+                }
+                InstructionStatus::Pending | InstructionStatus::Failed => {
+                    let prerequisite_venue = Self::instruction_details(prerequisite).venue_id;
+                    ensure!(
+                        Self::venue_info(prerequisite_venue)
+                            .map_or(false, |venue| venue.creator == did),
+                        Error::<T>::PrerequisiteNotInCreatorVenue
+                    );
+                    ensure!(
+                        Self::instruction_dependents_count(prerequisite)
+                            < T::MaxInstructionDependents::get(),
+                        Error::<T>::TooManyDependents
+                    );
+                }
+                InstructionStatus::Success(_) => {}
+            }
+        }
+
+        // Ensure venue exists & sender is its creator.
+        Self::venue_for_management(venue_id, did)?;
+        // Ensure the venue accepts new instructions and the instruction is within its limits.
+        Self::ensure_venue_open(venue_id)?;
+        ensure!(
+            Self::venue_instruction_limits(venue_id).allows(&legs),
+            Error::<T>::InstructionExceedsVenueLimits
+        );
+
+        // Verifies if all legs are valid.
+        let mut instruction_info = Self::ensure_valid_legs(&legs, &venue_id)?;
+
+        // Adds the instruction mediators
+        if let Some(mediators) = mediators {
+            instruction_info.extend_mediators(mediators.into())
+        }
+
+        // Advance and get next `instruction_id`.
+        let instruction_id = InstructionCounter::try_mutate(try_next_post::<T, _>)?;
+
+        // All checks have been made - Write data to storage.
+        InstructionStatuses::<T>::insert(instruction_id, InstructionStatus::Pending);
+        Self::unsafe_insert_legs(did, instruction_id, &legs, &instruction_info);
+
+        <InstructionDetails<T>>::insert(
+            instruction_id,
+            Instruction {
+                instruction_id,
+                venue_id,
+                settlement_type,
+                created_at: Some(<pallet_timestamp::Pallet<T>>::get()),
+                trade_date,
+                value_date,
+                expiry,
+            },
+        );
+        if let Some(ref memo) = memo {
+            InstructionMemos::insert(instruction_id, &memo);
+        }
+        VenueInstructions::insert(venue_id, instruction_id, ());
+        for prerequisite in prerequisites {
+            InstructionPrerequisites::insert(instruction_id, prerequisite, ());
+            // Executed prerequisites have already been pruned, there is nothing left to cascade
+            if let InstructionStatus::Success(_) = Self::instruction_status(prerequisite) {
+                continue;
+            }
+            InstructionDependents::insert(prerequisite, instruction_id, ());
+            InstructionDependentsCount::mutate(prerequisite, |count| *count += 1);
+        }
+
+        if let SettlementType::SettleOnBlock(block_number) = settlement_type {
+            let weight_limit = Self::execute_scheduled_instruction_weight_limit(
+                instruction_info.fungible_transfers(),
+                instruction_info.nfts_transferred(),
+                instruction_info.off_chain(),
+            );
+            Self::schedule_instruction(instruction_id, block_number, weight_limit);
+        }
+
+        if let Some(expiry) = expiry {
+            let weight_limit = <T as Config>::WeightInfo::expire_instruction(
+                instruction_info.fungible_transfers(),
+                instruction_info.nfts_transferred(),
+                instruction_info.off_chain(),
+            )
+            .saturating_add(<T as Config>::WeightInfo::reject_dependents(
+                T::MaxInstructionDependents::get(),
+            ));
+            Self::schedule_instruction_expiry(instruction_id, expiry, weight_limit);
+        }
+
+        Self::deposit_event(RawEvent::InstructionCreated(
+            did,
+            venue_id,
+            instruction_id,
+            settlement_type,
+            trade_date,
+            value_date,
+            legs,
+            memo,
+        ));
+        Ok(instruction_id)
+    }
+
+    /// Inserts the `legs` of the instruction along with the pending affirmations of all its parties and mediators.
+    fn unsafe_insert_legs(
+        did: IdentityId,
+        instruction_id: InstructionId,
+        legs: &[Leg],
+        instruction_info: &InstructionInfo,
+    ) {
+        for portfolio_id in instruction_info.portfolios_pending_approval() {
+            UserAffirmations::insert(portfolio_id, instruction_id, AffirmationStatus::Pending);
+        }
+        for portfolio_id in instruction_info.portfolios_pre_approved_difference() {
+            UserAffirmations::insert(portfolio_id, instruction_id, AffirmationStatus::Affirmed);
+            AffirmsReceived::insert(instruction_id, portfolio_id, AffirmationStatus::Affirmed);
+            Self::deposit_event(RawEvent::InstructionAutomaticallyAffirmed(
+                did,
+                *portfolio_id,
+                instruction_id,
+            ));
+        }
+        for mediator_id in instruction_info.mediators() {
+            InstructionMediatorsAffirmations::<T>::insert(
+                instruction_id,
+                mediator_id,
+                MediatorAffirmationStatus::Pending,
+            );
+        }
+        InstructionAffirmsPending::insert(
+            instruction_id,
+            instruction_info.number_of_pending_affirmations(),
+        );
+
+        legs.iter().enumerate().for_each(|(index, leg)| {
+            let leg_id = LegId(index as u64);
+            InstructionLegs::insert(instruction_id, leg_id, leg.clone());
+            if leg.is_off_chain() {
+                OffChainAffirmations::insert(instruction_id, leg_id, AffirmationStatus::Pending);
+            }
+        });
+    }
+
+    /// Replaces the legs and dates of the pending instruction of the given `id` with a new version.
+    /// The current version is moved to [`InstructionVersionHistory`], its locks are released and all its affirmations are reset.
+    fn base_amend_instruction(
+        did: IdentityId,
+        id: InstructionId,
+        legs: Vec<Leg>,
+        trade_date: Option<T::Moment>,
+        value_date: Option<T::Moment>,
+    ) -> DispatchResultWithPostInfo {
+        let mut details = Self::ensure_instruction_validity(id, false)?;
+        ensure!(
+            Self::instruction_status(id) == InstructionStatus::Pending
+                && !Self::partially_fillable(id)
+                && Self::repo_agreement(id).is_none(),
+            Error::<T>::InstructionCannotBeAmended
+        );
+
+        // The new version is scheduled again, so the block number must still be in the future.
+        if let SettlementType::SettleOnBlock(block_number) = details.settlement_type {
+            ensure!(
+                block_number > System::<T>::block_number(),
+                Error::<T>::SettleOnPastBlock
+            );
+        }
+
+        // Ensure that instruction dates are valid.
+        if let (Some(trade_date), Some(value_date)) = (trade_date, value_date) {
+            ensure!(
+                value_date >= trade_date,
+                Error::<T>::InstructionDatesInvalid
+            );
+        }
+
+        // Ensure venue exists & sender is its creator.
+        Self::venue_for_management(details.venue_id, did)?;
+        // Ensure the venue accepts instructions and the new version is within its limits.
+        Self::ensure_venue_open(details.venue_id)?;
+        ensure!(
+            Self::venue_instruction_limits(details.venue_id).allows(&legs),
+            Error::<T>::InstructionExceedsVenueLimits
+        );
+
+        // Verifies if all legs are valid and keeps the mediators of the current version.
+        let mut instruction_info = Self::ensure_valid_legs(&legs, &details.venue_id)?;
+        instruction_info.extend_mediators(
+            InstructionMediatorsAffirmations::<T>::iter_prefix(id)
+                .map(|(mediator, _)| mediator)
+                .collect(),
+        );
+        let version = Self::instruction_version(id)
+            .checked_add(1)
+            .ok_or(Error::<T>::InstructionCannotBeAmended)?;
+
+        // All checks have been made - Release the current version.
+        let mut old_legs: Vec<(LegId, Leg)> = InstructionLegs::iter_prefix(id).collect();
+        old_legs.sort_by_key(|leg_id_leg| leg_id_leg.0);
+        Self::unchecked_release_locks(id, &old_legs);
+        let _ = T::Scheduler::cancel_named(id.execution_name());
+        #[allow(deprecated)]
+        InstructionLegs::remove_prefix(id, None);
+        #[allow(deprecated)]
+        <InstructionLegStatus<T>>::remove_prefix(id, None);
+        #[allow(deprecated)]
+        OffChainAffirmations::remove_prefix(id, None);
+        #[allow(deprecated)]
+        AffirmsReceived::remove_prefix(id, None);
+        #[allow(deprecated)]
+        InstructionMediatorsAffirmations::<T>::remove_prefix(id, None);
+        for (_, leg) in &old_legs {
+            if let Leg::Fungible {
+                sender, receiver, ..
+            }
+            | Leg::NonFungible {
+                sender, receiver, ..
+            } = leg
+            {
+                UserAffirmations::remove(sender, id);
+                UserAffirmations::remove(receiver, id);
+            }
+        }
+        InstructionVersionHistory::<T>::insert(
+            id,
+            version - 1,
+            InstructionVersion {
+                legs: old_legs.iter().map(|(_, leg)| leg.clone()).collect(),
+                trade_date: details.trade_date,
+                value_date: details.value_date,
+                amended_at: <pallet_timestamp::Pallet<T>>::get(),
+            },
+        );
+
+        // Write the new version.
+        InstructionVersions::insert(id, version);
+        Self::unsafe_insert_legs(did, id, &legs, &instruction_info);
+        details.trade_date = trade_date;
+        details.value_date = value_date;
+        <InstructionDetails<T>>::insert(id, details.clone());
+        if let SettlementType::SettleOnBlock(block_number) = details.settlement_type {
+            let weight_limit = Self::execute_scheduled_instruction_weight_limit(
+                instruction_info.fungible_transfers(),
+                instruction_info.nfts_transferred(),
+                instruction_info.off_chain(),
+            );
+            Self::schedule_instruction(id, block_number, weight_limit);
+        }
+
+        let leg_count = old_legs.len().max(legs.len());
+        let leg_changes = (0..leg_count)
+            .filter_map(|index| {
+                let old_leg = old_legs.get(index).map(|(_, leg)| leg.clone());
+                let new_leg = legs.get(index).cloned();
+                (old_leg != new_leg).then(|| (LegId(index as u64), old_leg, new_leg))
+            })
+            .collect();
+        Self::deposit_event(RawEvent::InstructionAmended(
+            did,
+            id,
+            version,
+            trade_date,
+            value_date,
+            leg_changes,
+        ));
+        // The cost of releasing the current version depends on the number of its legs
+        Ok(PostDispatchInfo::from(Some(
+            <T as Config>::WeightInfo::add_instruction_legs(&legs).saturating_add(
+                Self::reject_instruction_weight(AssetCount::from_legs(&old_legs), false, 0),
+            ),
+        )))
+    }
+
+    /// Returns `Ok` if `version` is the current version of the instruction of the given `id`.
+    /// Affirmations that don't specify a version can only be given to instructions that have never been amended.
+    fn ensure_instruction_version(id: InstructionId, version: u32) -> DispatchResult {
+        ensure!(
+            Self::instruction_version(id) == version,
+            Error::<T>::InstructionVersionMismatch
+        );
+        Ok(())
+    }
+
+    /// Returns [`InstructionInfo`] if all legs are valid, otherwise returns an error.
+    /// See also: [`Module::ensure_valid_fungible_leg`], [`Module::ensure_valid_nft_leg`] and [`Module::ensure_valid_off_chain_leg`].
+    fn ensure_valid_legs(
+        legs: &[Leg],
+        venue_id: &VenueId,
     ) -> Result<InstructionInfo, DispatchError> {
         // Tracks the number of fungible, non-fungible and offchain assets across the legs
         let mut instruction_asset_count = AssetCount::default();
@@ -1115,7 +2032,8 @@ impl<T: Config> Module<T> {
         }
         for (leg_id, leg) in filtered_legs.sender_subset() {
             match Self::instruction_leg_status(id, leg_id) {
-                LegStatus::ExecutionToBeSkipped(_, _) => {
+                LegStatus::ExecutionToBeSkipped(_, _)
+                | LegStatus::ExecutionToBeSkippedWithEcdsaReceipt(_, _) => {
                     return Err(Error::<T>::UnexpectedLegStatus.into())
                 }
                 LegStatus::ExecutionPending => {
@@ -1177,12 +2095,27 @@ impl<T: Config> Module<T> {
             }
             (_, _) => {}
         }
+        Self::ensure_instruction_not_expired(&details)?;
 
         Ok(details)
     }
 
+    /// Returns `Ok` if the instruction has no expiry date or if its expiry date has not been reached.
+    fn ensure_instruction_not_expired(
+        instruction: &Instruction<T::Moment, T::BlockNumber>,
+    ) -> DispatchResult {
+        if let Some(expiry) = instruction.expiry {
+            ensure!(
+                expiry > <pallet_timestamp::Pallet<T>>::get(),
+                Error::<T>::InstructionExpired
+            );
+        }
+        Ok(())
+    }
+
     /// Executes the instruction of the given `id`. If the execution succeeds, the instruction gets pruned,
     /// otherwise the instruction status is set to failed.
+    /// A failed instruction can still be retried, so its dependents are only rejected once it is rejected or expires.
     fn execute_instruction_retryable(
         id: InstructionId,
         caller_did: IdentityId,
@@ -1196,10 +2129,17 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
-    fn execute_instruction(
+    /// Returns `Ok` if all affirmations have been received and the instruction is [`InstructionStatus::Pending`]
+    /// or [`InstructionStatus::Failed`]. Otherwise, returns an error.
+    fn ensure_ready_for_execution(instruction_id: InstructionId) -> DispatchResult {
+        Self::ensure_ready_for_batch_execution(instruction_id, &BTreeSet::new())
+    }
+
+    /// Same as [`Self::ensure_ready_for_execution`], but the prerequisites in `batch` are considered
+    /// executed, since they are executed together with the instruction.
+    fn ensure_ready_for_batch_execution(
         instruction_id: InstructionId,
-        caller_did: IdentityId,
-        weight_meter: &mut WeightMeter,
+        batch: &BTreeSet<InstructionId>,
     ) -> DispatchResult {
         // Verifies that there are no pending affirmations for the given instruction
         ensure!(
@@ -1208,6 +2148,15 @@ impl<T: Config> Module<T> {
         );
         // Verifies that all mediator's affirmations are still valid
         Self::ensure_non_expired_affirmations(&instruction_id)?;
+        // Verifies that the instruction itself has not expired and that its venue is open
+        let details = Self::instruction_details(instruction_id);
+        Self::ensure_instruction_not_expired(&details)?;
+        Self::ensure_venue_open(details.venue_id)?;
+        // Verifies that all instructions this instruction depends on have been executed
+        ensure!(
+            Self::prerequisites_executed_or_in(instruction_id, batch),
+            Error::<T>::PrerequisitesNotExecuted
+        );
 
         // Ensures the instruction is pending or has failed at least one time
         let instruction_status = Self::instruction_status(instruction_id);
@@ -1216,6 +2165,15 @@ impl<T: Config> Module<T> {
                 || instruction_status == InstructionStatus::Failed,
             Error::<T>::InvalidInstructionStatusForExecution
         );
+        Ok(())
+    }
+
+    fn execute_instruction(
+        instruction_id: InstructionId,
+        caller_did: IdentityId,
+        weight_meter: &mut WeightMeter,
+    ) -> DispatchResult {
+        Self::ensure_ready_for_execution(instruction_id)?;
 
         let venue_id = Self::instruction_details(instruction_id).venue_id;
 
@@ -1241,15 +2199,24 @@ impl<T: Config> Module<T> {
         Self::ensure_allowed_venue(&instruction_legs, venue_id)?;
 
         let instruction_memo = InstructionMemos::get(&instruction_id);
-        // Attempts to release the locks and transfer all fungible an non fungible assets
+        // Attempts to release the locks and transfer all fungible an non fungible assets.
+        // The assets that must be returned by a repo agreement are locked in the same transaction.
         if let Err(leg_id) = frame_storage_with_transaction(|| {
-            Self::release_asset_locks_and_transfer_pending_legs(
+            match Self::release_asset_locks_and_transfer_pending_legs(
                 instruction_id,
                 &instruction_legs,
                 instruction_memo,
                 caller_did,
                 weight_meter,
-            )
+            ) {
+                TransactionOutcome::Commit(Ok(Ok(()))) => {
+                    match Self::lock_repo_collateral(instruction_id) {
+                        Ok(()) => TransactionOutcome::Commit(Ok(Ok(()))),
+                        Err(leg_id) => TransactionOutcome::Rollback(Ok(Err(leg_id))),
+                    }
+                }
+                outcome => outcome,
+            }
         })? {
             Self::deposit_event(RawEvent::LegFailedExecution(
                 caller_did,
@@ -1345,11 +2312,87 @@ impl<T: Config> Module<T> {
         TransactionOutcome::Commit(Ok(Ok(())))
     }
 
+    /// Transfers `fill` of the remaining amount of each pending leg and updates the legs to hold the amounts that are still open.
+    /// Returns the amount transferred for each leg, or the [`LegId`] of the first leg that failed.
+    fn transfer_partial_fill(
+        instruction_id: InstructionId,
+        instruction_legs: &[(LegId, Leg)],
+        fill: Perbill,
+        instruction_memo: Option<Memo>,
+        caller_did: IdentityId,
+        weight_meter: &mut WeightMeter,
+    ) -> TransactionOutcome<Result<Result<Vec<(LegId, Balance)>, LegId>, DispatchError>> {
+        let mut filled_amounts = Vec::with_capacity(instruction_legs.len());
+        for (leg_id, leg) in instruction_legs {
+            if Self::instruction_leg_status(instruction_id, leg_id) != LegStatus::ExecutionPending {
+                continue;
+            }
+            if let Leg::Fungible {
+                sender,
+                receiver,
+                ticker,
+                amount,
+            } = leg
+            {
+                // Rounding down guarantees that some amount is always left open
+                let fill_amount = fill.mul_floor(*amount);
+                if fill_amount.is_zero() {
+                    continue;
+                }
+                if T::Portfolio::unlock_tokens(sender, ticker, fill_amount).is_err()
+                    || <Asset<T>>::base_transfer(
+                        *sender,
+                        *receiver,
+                        ticker,
+                        fill_amount,
+                        Some(instruction_id),
+                        instruction_memo.clone(),
+                        caller_did,
+                        weight_meter,
+                    )
+                    .is_err()
+                {
+                    return TransactionOutcome::Rollback(Ok(Err(*leg_id)));
+                }
+                InstructionLegs::insert(
+                    instruction_id,
+                    leg_id,
+                    Leg::Fungible {
+                        sender: *sender,
+                        receiver: *receiver,
+                        ticker: *ticker,
+                        amount: amount.saturating_sub(fill_amount),
+                    },
+                );
+                filled_amounts.push((*leg_id, fill_amount));
+            }
+        }
+        TransactionOutcome::Commit(Ok(Ok(filled_amounts)))
+    }
+
+    /// Removes the instruction of the given `id` from storage. If the instruction has been executed,
+    /// the instructions that depend on it are scheduled if they are ready, otherwise they are also rejected.
     fn prune_instruction(id: InstructionId, executed: bool) {
+        Self::prune_instruction_storage(id, executed);
+        if executed {
+            Self::open_repo(id);
+            Self::schedule_ready_dependents(id);
+        } else {
+            Self::reject_dependents(id);
+        }
+    }
+
+    fn prune_instruction_storage(id: InstructionId, executed: bool) {
         let drained_legs: Vec<(LegId, Leg)> = InstructionLegs::drain_prefix(&id).collect();
         let details = <InstructionDetails<T>>::take(id);
         VenueInstructions::remove(details.venue_id, id);
+        if details.expiry.is_some() {
+            let _ = T::Scheduler::cancel_named(id.expiry_name());
+        }
         InstructionAffirmsPending::remove(id);
+        PartiallyFillableInstructions::remove(id);
+        #[allow(deprecated)]
+        PartialFillOriginalAmounts::remove_prefix(id, None);
         #[allow(deprecated)]
         <InstructionLegStatus<T>>::remove_prefix(id, None);
         #[allow(deprecated)]
@@ -1358,6 +2401,17 @@ impl<T: Config> Module<T> {
         AffirmsReceived::remove_prefix(id, None);
         #[allow(deprecated)]
         InstructionMediatorsAffirmations::<T>::remove_prefix(id, None);
+        for (prerequisite, _) in InstructionPrerequisites::drain_prefix(id) {
+            if InstructionDependents::contains_key(prerequisite, id) {
+                InstructionDependents::remove(prerequisite, id);
+                InstructionDependentsCount::mutate(prerequisite, |count| {
+                    *count = count.saturating_sub(1)
+                });
+            }
+        }
+        if !executed {
+            RepoAgreements::<T>::remove(id);
+        }
 
         if executed {
             InstructionStatuses::<T>::insert(
@@ -1387,6 +2441,103 @@ impl<T: Config> Module<T> {
         }
     }
 
+    /// Returns `true` if all prerequisites of the given instruction have been executed.
+    fn prerequisites_executed(id: InstructionId) -> bool {
+        Self::prerequisites_executed_or_in(id, &BTreeSet::new())
+    }
+
+    /// Returns `true` if all prerequisites of the given instruction have been executed or are part of `batch`.
+    fn prerequisites_executed_or_in(id: InstructionId, batch: &BTreeSet<InstructionId>) -> bool {
+        InstructionPrerequisites::iter_prefix(id).all(|(prerequisite, _)| {
+            batch.contains(&prerequisite)
+                || matches!(
+                    Self::instruction_status(prerequisite),
+                    InstructionStatus::Success(_)
+                )
+        })
+    }
+
+    /// Schedules all [`SettlementType::SettleOnAffirmation`] instructions that depend on the executed
+    /// instruction of the given `id`, and that can now be executed.
+    fn schedule_ready_dependents(id: InstructionId) {
+        InstructionDependentsCount::remove(id);
+        for (dependent, _) in InstructionDependents::drain_prefix(id) {
+            let instruction_status = Self::instruction_status(dependent);
+            if instruction_status != InstructionStatus::Pending
+                && instruction_status != InstructionStatus::Failed
+            {
+                continue;
+            }
+            let instruction_asset_count = Self::get_instruction_asset_count(&dependent);
+            let weight_limit = Self::execute_scheduled_instruction_weight_limit(
+                instruction_asset_count.fungible(),
+                instruction_asset_count.non_fungible(),
+                instruction_asset_count.off_chain(),
+            );
+            Self::maybe_schedule_instruction(
+                Self::instruction_affirms_pending(dependent),
+                dependent,
+                weight_limit,
+            );
+        }
+    }
+
+    /// Rejects all instructions that directly depend on the instruction of the given `id`, releasing their locked assets.
+    /// The rejection of the instructions that depend on the rejected dependents is scheduled for the next block.
+    /// Returns the number of rejected dependents.
+    fn reject_dependents(id: InstructionId) -> u32 {
+        let mut rejected = 0;
+        InstructionDependentsCount::remove(id);
+        for (dependent, _) in InstructionDependents::drain_prefix(id) {
+            let instruction_status = Self::instruction_status(dependent);
+            if instruction_status != InstructionStatus::Pending
+                && instruction_status != InstructionStatus::Failed
+            {
+                continue;
+            }
+            let legs: Vec<(LegId, Leg)> = InstructionLegs::iter_prefix(&dependent).collect();
+            Self::unchecked_release_locks(dependent, &legs);
+            let _ = T::Scheduler::cancel_named(dependent.execution_name());
+            Self::prune_instruction_storage(dependent, false);
+            Self::deposit_event(RawEvent::DependentInstructionRejected(dependent, id));
+            Self::schedule_dependents_rejection(dependent);
+            rejected += 1;
+        }
+        rejected
+    }
+
+    /// Schedules the rejection of all instructions that depend on the instruction of the given `id` for the next block.
+    /// Nothing is scheduled if the instruction has no dependents or if their rejection has already been scheduled.
+    fn schedule_dependents_rejection(id: InstructionId) {
+        if Self::instruction_dependents_count(id) == 0
+            || T::Scheduler::next_dispatch_time(id.dependents_rejection_name()).is_ok()
+        {
+            return;
+        }
+        let call = Call::<T>::reject_scheduled_dependents { id }.into();
+        if let Err(_) = T::Scheduler::schedule_named(
+            id.dependents_rejection_name(),
+            DispatchTime::At(System::<T>::block_number() + One::one()),
+            None,
+            SETTLEMENT_INSTRUCTION_EXECUTION_PRIORITY,
+            RawOrigin::Root.into(),
+            call,
+        ) {
+            Self::deposit_event(RawEvent::SchedulingFailed(
+                Error::<T>::FailedToSchedule.into(),
+            ));
+        }
+    }
+
+    /// Rejects the dependents of the instruction of the given `id`, unless the instruction has been executed in the meantime.
+    fn base_reject_scheduled_dependents(id: InstructionId) -> Weight {
+        if let InstructionStatus::Success(_) = Self::instruction_status(id) {
+            return <T as Config>::WeightInfo::reject_dependents(0);
+        }
+        let rejected = Self::reject_dependents(id);
+        <T as Config>::WeightInfo::reject_dependents(rejected)
+    }
+
     pub fn unsafe_affirm_instruction(
         did: IdentityId,
         id: InstructionId,
@@ -1436,16 +2587,19 @@ impl<T: Config> Module<T> {
                     // must've locked these tokens when instruction was affirmed
                     let _ = Self::unlock_via_leg(&leg);
                 }
-                LegStatus::ExecutionToBeSkipped(_, _) | LegStatus::PendingTokenLock => {}
+                LegStatus::ExecutionToBeSkipped(_, _)
+                | LegStatus::ExecutionToBeSkippedWithEcdsaReceipt(_, _)
+                | LegStatus::PendingTokenLock => {}
             }
         }
     }
 
-    /// Schedule a given instruction to be executed on the next block only if the
-    /// settlement type is `SettleOnAffirmation` and no. of affirms pending is 0.
+    /// Schedule a given instruction to be executed on the next block only if the settlement type
+    /// is `SettleOnAffirmation`, no. of affirms pending is 0 and all prerequisites have been executed.
     fn maybe_schedule_instruction(affirms_pending: u64, id: InstructionId, weight_limit: Weight) {
         if affirms_pending == 0
             && Self::instruction_details(id).settlement_type == SettlementType::SettleOnAffirmation
+            && Self::prerequisites_executed(id)
         {
             // Schedule instruction to be executed in the next block.
             let execution_at = System::<T>::block_number() + One::one();
@@ -1478,6 +2632,301 @@ impl<T: Config> Module<T> {
         }
     }
 
+    /// Schedules the rejection of the given instruction at the block in which `expiry` is expected to be reached.
+    ///
+    /// NB - The block is estimated from the expected block time, the expiry date is checked again when the call is dispatched.
+    fn schedule_instruction_expiry(id: InstructionId, expiry: T::Moment, weight_limit: Weight) {
+        let call = Call::<T>::expire_scheduled_instruction { id, weight_limit }.into();
+        if let Err(_) = T::Scheduler::schedule_named(
+            id.expiry_name(),
+            DispatchTime::At(Self::expiry_block(expiry)),
+            None,
+            SETTLEMENT_INSTRUCTION_EXECUTION_PRIORITY,
+            RawOrigin::Root.into(),
+            call,
+        ) {
+            Self::deposit_event(RawEvent::SchedulingFailed(
+                Error::<T>::FailedToSchedule.into(),
+            ));
+        }
+    }
+
+    /// Returns the first block number in which the `expiry` date is expected to have been reached.
+    fn expiry_block(expiry: T::Moment) -> T::BlockNumber {
+        let block_time = <T as pallet_timestamp::Config>::MinimumPeriod::get()
+            .saturating_mul(2u32.into())
+            .max(One::one());
+        let remaining_time = expiry.saturating_sub(<pallet_timestamp::Pallet<T>>::get());
+        // Rounds up, so that the expiry date has been reached in the returned block
+        let remaining_blocks =
+            (remaining_time.saturating_add(block_time.saturating_sub(One::one())) / block_time)
+                .saturated_into::<u64>()
+                .max(1);
+        System::<T>::block_number().saturating_add(remaining_blocks.saturated_into())
+    }
+
+    /// Rejects the instruction of the given `id` if its expiry date has been reached, releasing all locked assets.
+    /// If the expiry date has not been reached yet, a new rejection attempt is scheduled.
+    fn base_expire_instruction(id: InstructionId, weight_limit: Weight) {
+        // Executed and rejected instructions have already been pruned
+        let instruction_status = Self::instruction_status(id);
+        if instruction_status != InstructionStatus::Pending
+            && instruction_status != InstructionStatus::Failed
+        {
+            return;
+        }
+        let expiry = match Self::instruction_details(id).expiry {
+            Some(expiry) => expiry,
+            None => return,
+        };
+
+        if expiry > <pallet_timestamp::Pallet<T>>::get() {
+            // The scheduler releases the name of a task before dispatching it, in which case
+            // there is nothing left to reschedule and the expiry is scheduled again under the same name.
+            let retry_at = DispatchTime::At(Self::expiry_block(expiry));
+            if T::Scheduler::reschedule_named(id.expiry_name(), retry_at).is_err() {
+                Self::schedule_instruction_expiry(id, expiry, weight_limit);
+            }
+            return;
+        }
+
+        let legs: Vec<(LegId, Leg)> = InstructionLegs::iter_prefix(&id).collect();
+        Self::unchecked_release_locks(id, &legs);
+        let _ = T::Scheduler::cancel_named(id.execution_name());
+        Self::prune_instruction(id, false);
+        Self::deposit_event(RawEvent::InstructionExpired(id));
+    }
+
+    fn base_add_repo_instruction(
+        did: IdentityId,
+        venue_id: VenueId,
+        legs: Vec<Leg>,
+        maturity: T::Moment,
+        instruction_memo: Option<Memo>,
+    ) -> DispatchResult {
+        ensure!(
+            legs.iter().all(|leg| !leg.is_off_chain()),
+            Error::<T>::InvalidLegForRepo
+        );
+        ensure!(
+            maturity > <pallet_timestamp::Pallet<T>>::get(),
+            Error::<T>::InvalidMaturityDate
+        );
+
+        let closing_legs = legs.iter().map(|leg| leg.reversed()).collect();
+        // The opening legs can't be executed after the maturity date
+        let instruction_id = Self::base_add_instruction(
+            did,
+            venue_id,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            legs,
+            instruction_memo,
+            None,
+            Some(maturity),
+            None,
+        )?;
+        RepoAgreements::<T>::insert(
+            instruction_id,
+            RepoAgreement {
+                venue_id,
+                status: RepoStatus::Pending,
+                closing_legs,
+                maturity,
+            },
+        );
+        Ok(())
+    }
+
+    /// Locks the assets that must be returned by the pending repo agreement of the given `id`, if any.
+    /// Must be called in the same transaction as the execution of the opening legs, so that they are never
+    /// executed without their collateral. Returns the [`LegId`] of the first closing leg that can't be locked.
+    fn lock_repo_collateral(id: InstructionId) -> Result<(), LegId> {
+        let agreement = match Self::repo_agreement(id) {
+            Some(agreement) if agreement.status == RepoStatus::Pending => agreement,
+            _ => return Ok(()),
+        };
+        for (leg_id, leg) in Self::repo_closing_legs(&agreement) {
+            Self::lock_via_leg(&leg).map_err(|_| leg_id)?;
+        }
+        Ok(())
+    }
+
+    /// Schedules the execution of the closing legs of the repo agreement of the given `id`, if any, at the maturity date.
+    /// Called once the opening legs have been executed and the assets to be returned have been locked.
+    fn open_repo(id: InstructionId) {
+        let mut agreement = match Self::repo_agreement(id) {
+            Some(agreement) if agreement.status == RepoStatus::Pending => agreement,
+            _ => return,
+        };
+
+        let asset_count = AssetCount::from_legs(&Self::repo_closing_legs(&agreement));
+        let weight_limit = Self::execute_scheduled_instruction_weight_limit(
+            asset_count.fungible(),
+            asset_count.non_fungible(),
+            0,
+        );
+        let maturity = agreement.maturity;
+        agreement.status = RepoStatus::Open;
+        RepoAgreements::<T>::insert(id, agreement);
+        Self::schedule_repo_unwind(id, maturity, weight_limit);
+        Self::deposit_event(RawEvent::RepoOpened(id, maturity));
+    }
+
+    /// Schedules the unwind of the repo agreement of the given `id` at the block in which `maturity` is expected to be reached.
+    fn schedule_repo_unwind(id: InstructionId, maturity: T::Moment, weight_limit: Weight) {
+        let call = Call::<T>::unwind_scheduled_repo { id, weight_limit }.into();
+        if let Err(_) = T::Scheduler::schedule_named(
+            id.repo_unwind_name(),
+            DispatchTime::At(Self::expiry_block(maturity)),
+            None,
+            SETTLEMENT_INSTRUCTION_EXECUTION_PRIORITY,
+            RawOrigin::Root.into(),
+            call,
+        ) {
+            Self::deposit_event(RawEvent::SchedulingFailed(
+                Error::<T>::FailedToSchedule.into(),
+            ));
+        }
+    }
+
+    /// Executes the closing legs of the repo agreement of the given `id` if its maturity date has been reached.
+    /// If the closing legs can't be executed, the agreement defaults and all its locked assets are released.
+    fn base_unwind_scheduled_repo(
+        id: InstructionId,
+        weight_limit: Weight,
+        weight_meter: &mut WeightMeter,
+    ) {
+        let agreement = match Self::repo_agreement(id) {
+            Some(agreement) if agreement.status == RepoStatus::Open => agreement,
+            _ => return,
+        };
+
+        if agreement.maturity > <pallet_timestamp::Pallet<T>>::get() {
+            // The scheduler releases the name of a task before dispatching it, in which case
+            // there is nothing left to reschedule and the unwind is scheduled again under the same name.
+            let retry_at = DispatchTime::At(Self::expiry_block(agreement.maturity));
+            if T::Scheduler::reschedule_named(id.repo_unwind_name(), retry_at).is_err() {
+                Self::schedule_repo_unwind(id, agreement.maturity, weight_limit);
+            }
+            return;
+        }
+
+        let closing_legs = Self::repo_closing_legs(&agreement);
+        RepoAgreements::<T>::remove(id);
+        match Self::execute_repo_closing_legs(
+            id,
+            &closing_legs,
+            SettlementDID.as_id(),
+            weight_meter,
+        ) {
+            Ok(_) => Self::deposit_event(RawEvent::RepoUnwound(id)),
+            Err(_) => {
+                for (_, leg) in &closing_legs {
+                    let _ = Self::unlock_via_leg(leg);
+                }
+                Self::deposit_event(RawEvent::RepoDefaulted(id));
+            }
+        }
+    }
+
+    fn base_close_repo_early(
+        origin: T::RuntimeOrigin,
+        id: InstructionId,
+        input_cost: &AssetCount,
+        weight_meter: &mut WeightMeter,
+    ) -> DispatchResult {
+        let origin_data = Identity::<T>::ensure_origin_call_permissions(origin)?;
+        let did = origin_data.primary_did;
+        let agreement = Self::repo_agreement(id)
+            .filter(|agreement| agreement.status == RepoStatus::Open)
+            .ok_or(Error::<T>::RepoNotOpen)?;
+        Self::ensure_venue_open(agreement.venue_id)?;
+
+        let closing_legs = Self::repo_closing_legs(&agreement);
+        Self::ensure_valid_cost(&AssetCount::from_legs(&closing_legs), input_cost)?;
+        // If the caller is not the venue creator, they must have custody of all portfolios returning assets
+        if Self::venue_for_management(agreement.venue_id, did).is_err() {
+            for (_, leg) in &closing_legs {
+                match leg {
+                    Leg::Fungible { sender, .. } | Leg::NonFungible { sender, .. } => {
+                        T::Portfolio::ensure_portfolio_custody_and_permission(
+                            *sender,
+                            did,
+                            origin_data.secondary_key.as_ref(),
+                        )?;
+                    }
+                    Leg::OffChain { .. } => {}
+                }
+            }
+        }
+
+        Self::execute_repo_closing_legs(id, &closing_legs, did, weight_meter)?;
+        RepoAgreements::<T>::remove(id);
+        let _ = T::Scheduler::cancel_named(id.repo_unwind_name());
+        Self::deposit_event(RawEvent::RepoClosedEarly(did, id));
+        Ok(())
+    }
+
+    /// Releases the locks and transfers the assets of all closing legs. Either all legs are executed or none is.
+    fn execute_repo_closing_legs(
+        id: InstructionId,
+        closing_legs: &[(LegId, Leg)],
+        caller_did: IdentityId,
+        weight_meter: &mut WeightMeter,
+    ) -> DispatchResult {
+        frame_storage_with_transaction(|| {
+            for (_, leg) in closing_legs {
+                let result = Self::unlock_via_leg(leg).and_then(|_| match leg {
+                    Leg::Fungible {
+                        sender,
+                        receiver,
+                        ticker,
+                        amount,
+                    } => <Asset<T>>::base_transfer(
+                        *sender,
+                        *receiver,
+                        ticker,
+                        *amount,
+                        Some(id),
+                        None,
+                        caller_did,
+                        weight_meter,
+                    ),
+                    Leg::NonFungible {
+                        sender,
+                        receiver,
+                        nfts,
+                    } => <Nft<T>>::base_nft_transfer(
+                        *sender,
+                        *receiver,
+                        nfts.clone(),
+                        id,
+                        None,
+                        caller_did,
+                        weight_meter,
+                    ),
+                    Leg::OffChain { .. } => Ok(()),
+                });
+                if let Err(e) = result {
+                    return TransactionOutcome::Rollback(Err(e));
+                }
+            }
+            TransactionOutcome::Commit(Ok(()))
+        })
+    }
+
+    /// Returns the closing legs of the repo `agreement` in execution order.
+    fn repo_closing_legs(agreement: &RepoAgreement<T::Moment>) -> Vec<(LegId, Leg)> {
+        agreement
+            .closing_legs
+            .iter()
+            .enumerate()
+            .map(|(index, leg)| (LegId(index as u64), leg.clone()))
+            .collect()
+    }
+
     /// Affirms all legs from the instruction of the given `instruction_id`, where `portfolios` are a counter party.
     /// If the portfolio is the sender, the asset is also locked.
     pub fn base_affirm_with_receipts(
@@ -1487,30 +2936,114 @@ impl<T: Config> Module<T> {
         portfolios: Vec<PortfolioId>,
         affirmation_count: Option<AffirmationCount>,
     ) -> Result<FilteredLegs, DispatchError> {
+        let portfolios_set = portfolios.into_iter().collect::<BTreeSet<_>>();
+        let (did, venue_id) = Self::ensure_can_affirm_with_receipts(
+            origin,
+            instruction_id,
+            receipts_details.len(),
+            &portfolios_set,
+        )?;
+        Self::ensure_valid_receipts_details(venue_id, instruction_id, &receipts_details)?;
+
+        let claimed_receipts = receipts_details
+            .into_iter()
+            .map(|receipt_details| {
+                (
+                    receipt_details.leg_id(),
+                    receipt_details.uid(),
+                    ReceiptSigner::Account(receipt_details.signer().clone()),
+                    receipt_details.metadata().clone(),
+                )
+            })
+            .collect();
+        Self::unsafe_affirm_with_receipts(
+            did,
+            instruction_id,
+            portfolios_set,
+            claimed_receipts,
+            affirmation_count,
+        )
+    }
+
+    /// Affirms all legs from the instruction of the given `instruction_id`, where `portfolios` are a counter party,
+    /// using receipts signed with ECDSA keys. If the portfolio is the sender, the asset is also locked.
+    pub fn base_affirm_with_ecdsa_receipts(
+        origin: <T as frame_system::Config>::RuntimeOrigin,
+        instruction_id: InstructionId,
+        receipts_details: Vec<EcdsaReceiptDetails<T::Moment>>,
+        portfolios: Vec<PortfolioId>,
+        affirmation_count: Option<AffirmationCount>,
+    ) -> Result<FilteredLegs, DispatchError> {
+        let portfolios_set = portfolios.into_iter().collect::<BTreeSet<_>>();
+        let (did, venue_id) = Self::ensure_can_affirm_with_receipts(
+            origin,
+            instruction_id,
+            receipts_details.len(),
+            &portfolios_set,
+        )?;
+        Self::ensure_valid_ecdsa_receipts_details(venue_id, instruction_id, &receipts_details)?;
+
+        let claimed_receipts = receipts_details
+            .into_iter()
+            .map(|receipt_details| {
+                (
+                    receipt_details.leg_id(),
+                    receipt_details.uid(),
+                    ReceiptSigner::Ecdsa(*receipt_details.signer()),
+                    receipt_details.metadata().clone(),
+                )
+            })
+            .collect();
+        Self::unsafe_affirm_with_receipts(
+            did,
+            instruction_id,
+            portfolios_set,
+            claimed_receipts,
+            affirmation_count,
+        )
+    }
+
+    /// Ensures the caller can affirm the instruction with `number_of_receipts` receipts for the given `portfolios_set`.
+    /// Returns the caller's [`IdentityId`] and the [`VenueId`] of the instruction.
+    fn ensure_can_affirm_with_receipts(
+        origin: <T as frame_system::Config>::RuntimeOrigin,
+        instruction_id: InstructionId,
+        number_of_receipts: usize,
+        portfolios_set: &BTreeSet<PortfolioId>,
+    ) -> Result<(IdentityId, VenueId), DispatchError> {
         ensure!(
-            receipts_details.len() <= T::MaxNumberOfOffChainAssets::get() as usize,
+            number_of_receipts <= T::MaxNumberOfOffChainAssets::get() as usize,
             Error::<T>::MaxNumberOfReceiptsExceeded
         );
 
         let (did, secondary_key, instruction_details) =
             Self::ensure_origin_perm_and_instruction_validity(origin, instruction_id, false)?;
-        let portfolios_set = portfolios.into_iter().collect::<BTreeSet<_>>();
 
         // Verify portfolio custodianship and check if it is a counter party with a pending affirmation.
         Self::ensure_portfolios_and_affirmation_status(
             instruction_id,
-            &portfolios_set,
+            portfolios_set,
             did,
             secondary_key.as_ref(),
             &[AffirmationStatus::Pending],
         )?;
+        Ok((did, instruction_details.venue_id))
+    }
 
-        Self::ensure_valid_receipts_details(
-            instruction_details.venue_id,
-            instruction_id,
-            &receipts_details,
-        )?;
-
+    /// Affirms the instruction for `portfolios_set` and claims the already validated receipts,
+    /// given as (leg_id, receipt_uid, signer, metadata).
+    fn unsafe_affirm_with_receipts(
+        did: IdentityId,
+        instruction_id: InstructionId,
+        portfolios_set: BTreeSet<PortfolioId>,
+        claimed_receipts: Vec<(
+            LegId,
+            u64,
+            ReceiptSigner<T::AccountId>,
+            Option<ReceiptMetadata>,
+        )>,
+        affirmation_count: Option<AffirmationCount>,
+    ) -> Result<FilteredLegs, DispatchError> {
         // Lock tokens for all legs that are not of type [`Leg::OffChain`]
         let filtered_legs = Self::filtered_legs(instruction_id, &portfolios_set);
         // If the fee was estimated in advance, the input values must be at least equal to the actual values
@@ -1525,33 +3058,39 @@ impl<T: Config> Module<T> {
         // Casting is safe since `Self::ensure_portfolios_and_affirmation_status` is called
         let affirms_pending = InstructionAffirmsPending::get(instruction_id)
             .saturating_sub(portfolios_set.len() as u64)
-            .saturating_sub(receipts_details.len() as u64);
+            .saturating_sub(claimed_receipts.len() as u64);
         InstructionAffirmsPending::insert(instruction_id, affirms_pending);
 
         // Update storage
-        for receipt_detail in receipts_details {
-            <InstructionLegStatus<T>>::insert(
-                instruction_id,
-                receipt_detail.leg_id(),
-                LegStatus::ExecutionToBeSkipped(
-                    receipt_detail.signer().clone(),
-                    receipt_detail.uid(),
-                ),
-            );
-            OffChainAffirmations::insert(
-                instruction_id,
-                receipt_detail.leg_id(),
-                AffirmationStatus::Affirmed,
-            );
-            <ReceiptsUsed<T>>::insert(receipt_detail.signer(), receipt_detail.uid(), true);
-            Self::deposit_event(RawEvent::ReceiptClaimed(
-                did,
-                instruction_id,
-                receipt_detail.leg_id(),
-                receipt_detail.uid(),
-                receipt_detail.signer().clone(),
-                receipt_detail.metadata().clone(),
-            ));
+        for (leg_id, uid, signer, metadata) in claimed_receipts {
+            let leg_status = match signer {
+                ReceiptSigner::Account(signer) => {
+                    <ReceiptsUsed<T>>::insert(&signer, uid, true);
+                    Self::deposit_event(RawEvent::ReceiptClaimed(
+                        did,
+                        instruction_id,
+                        leg_id,
+                        uid,
+                        signer.clone(),
+                        metadata,
+                    ));
+                    LegStatus::ExecutionToBeSkipped(signer, uid)
+                }
+                ReceiptSigner::Ecdsa(signer) => {
+                    EcdsaReceiptsUsed::insert(signer, uid, true);
+                    Self::deposit_event(RawEvent::EcdsaReceiptClaimed(
+                        did,
+                        instruction_id,
+                        leg_id,
+                        uid,
+                        signer,
+                        metadata,
+                    ));
+                    LegStatus::ExecutionToBeSkippedWithEcdsaReceipt(signer, uid)
+                }
+            };
+            <InstructionLegStatus<T>>::insert(instruction_id, leg_id, leg_status);
+            OffChainAffirmations::insert(instruction_id, leg_id, AffirmationStatus::Affirmed);
         }
 
         for portfolio in portfolios_set {
@@ -1597,6 +3136,15 @@ impl<T: Config> Module<T> {
             portfolios,
             affirmation_count,
         )?;
+        Self::maybe_schedule_affirmed_with_receipts(id, filtered_legs)
+    }
+
+    /// Schedules the instruction of the given `id`, affirmed with receipts, if conditions are met
+    /// and returns the actual weight of the affirmation.
+    fn maybe_schedule_affirmed_with_receipts(
+        id: InstructionId,
+        filtered_legs: FilteredLegs,
+    ) -> DispatchResultWithPostInfo {
         let instruction_asset_count = filtered_legs.unfiltered_asset_count();
         let weight_limit = Self::execute_scheduled_instruction_weight_limit(
             instruction_asset_count.fungible(),
@@ -1670,6 +3218,19 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Executes and prunes an instruction that has received all of its affirmations, without scheduling it.
+    ///
+    /// NB - Use this function only in pallets that affirm every leg of the instruction themselves, e.g. the order book.
+    pub fn execute_instruction_now(
+        id: InstructionId,
+        caller_did: IdentityId,
+        weight_meter: &mut WeightMeter,
+    ) -> DispatchResult {
+        Self::execute_instruction(id, caller_did, weight_meter)?;
+        Self::prune_instruction(id, true);
+        Ok(())
+    }
+
     fn execute_settle_on_affirmation_instruction(
         id: InstructionId,
         affirms_pending: u64,
@@ -1770,6 +3331,60 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Adds or removes the ECDSA signers of a venue. They count towards the venue's [`NumberOfVenueSigners`].
+    fn base_update_venue_ecdsa_signers(
+        did: IdentityId,
+        id: VenueId,
+        signers: Vec<EthereumAddress>,
+        add_signers: bool,
+    ) -> DispatchResult {
+        // Ensure venue exists & sender is its creator.
+        Self::venue_for_management(id, did)?;
+
+        let current_number_of_signers = NumberOfVenueSigners::get(id);
+        if add_signers {
+            ensure!(
+                (current_number_of_signers as usize).saturating_add(signers.len())
+                    <= T::MaxNumberOfVenueSigners::get() as usize,
+                Error::<T>::NumberOfVenueSignersExceeded
+            );
+            let unique_signers = signers.iter().collect::<BTreeSet<_>>();
+            ensure!(
+                unique_signers.len() == signers.len()
+                    && signers
+                        .iter()
+                        .all(|signer| !Self::venue_ecdsa_signers(&id, signer)),
+                Error::<T>::SignerAlreadyExists
+            );
+            NumberOfVenueSigners::insert(id, current_number_of_signers + signers.len() as u32);
+            for signer in &signers {
+                VenueEcdsaSigners::insert(&id, signer, true);
+            }
+        } else {
+            for signer in &signers {
+                ensure!(
+                    Self::venue_ecdsa_signers(&id, signer),
+                    Error::<T>::SignerDoesNotExist
+                );
+            }
+            NumberOfVenueSigners::insert(
+                id,
+                current_number_of_signers.saturating_sub(signers.len() as u32),
+            );
+            for signer in &signers {
+                VenueEcdsaSigners::remove(&id, signer);
+            }
+        }
+
+        Self::deposit_event(RawEvent::VenueEcdsaSignersUpdated(
+            did,
+            id,
+            signers,
+            add_signers,
+        ));
+        Ok(())
+    }
+
     fn base_reject_instruction(
         origin: T::RuntimeOrigin,
         instruction_id: InstructionId,
@@ -1790,6 +3405,7 @@ impl<T: Config> Module<T> {
         }
         // Check if the caller is a mediator or a portfolio owner
         let origin_data = Identity::<T>::ensure_origin_call_permissions(origin)?;
+        let n_dependents = Self::instruction_dependents_count(instruction_id);
         let actual_weight = {
             match portfolio {
                 Some(portfolio) => {
@@ -1804,7 +3420,7 @@ impl<T: Config> Module<T> {
                         origin_data.primary_did,
                         origin_data.secondary_key.as_ref(),
                     )?;
-                    Self::reject_instruction_weight(instruction_asset_count, false)
+                    Self::reject_instruction_weight(instruction_asset_count, false, n_dependents)
                 }
                 None => {
                     // The caller must be a mediator
@@ -1815,7 +3431,7 @@ impl<T: Config> Module<T> {
                         ) != MediatorAffirmationStatus::Unknown,
                         Error::<T>::CallerIsNotAMediator
                     );
-                    Self::reject_instruction_weight(instruction_asset_count, true)
+                    Self::reject_instruction_weight(instruction_asset_count, true, n_dependents)
                 }
             }
         };
@@ -2061,6 +3677,264 @@ impl<T: Config> Module<T> {
         Ok(PostDispatchInfo::from(Some(weight_meter.consumed())))
     }
 
+    fn base_execute_partial_fill(
+        origin: T::RuntimeOrigin,
+        id: InstructionId,
+        fill: Perbill,
+        input_cost: &AssetCount,
+        weight_meter: &mut WeightMeter,
+    ) -> DispatchResultWithPostInfo {
+        ensure!(!fill.is_zero(), Error::<T>::InvalidFillRatio);
+        ensure!(
+            Self::partially_fillable(id),
+            Error::<T>::PartialFillNotAllowed
+        );
+        let (caller_did, _, instruction_details) =
+            Self::ensure_origin_perm_and_instruction_validity(origin, id, false)?;
+        // Only the venue creator can fill the instruction
+        Self::venue_for_management(instruction_details.venue_id, caller_did)?;
+
+        let mut instruction_legs: Vec<(LegId, Leg)> = InstructionLegs::iter_prefix(&id).collect();
+        instruction_legs.sort_by_key(|leg_id_leg| leg_id_leg.0);
+        let instruction_asset_count = AssetCount::from_legs(&instruction_legs);
+        Self::ensure_valid_cost(&instruction_asset_count, input_cost)?;
+
+        // Filling all the remaining amounts is the same as executing the instruction
+        if fill == Perbill::one() {
+            Self::execute_instruction_retryable(id, caller_did, weight_meter)?;
+            return Ok(PostDispatchInfo::from(Some(weight_meter.consumed())));
+        }
+
+        Self::ensure_ready_for_execution(id)?;
+        weight_meter
+            .check_accrue(<T as Config>::WeightInfo::execute_instruction_paused(
+                instruction_asset_count.fungible(),
+                instruction_asset_count.non_fungible(),
+                instruction_asset_count.off_chain(),
+            ))
+            .map_err(|_| Error::<T>::WeightLimitExceeded)?;
+        Self::ensure_allowed_venue(&instruction_legs, instruction_details.venue_id)?;
+
+        let instruction_memo = InstructionMemos::get(&id);
+        let filled_amounts = frame_storage_with_transaction(|| {
+            Self::transfer_partial_fill(
+                id,
+                &instruction_legs,
+                fill,
+                instruction_memo,
+                caller_did,
+                weight_meter,
+            )
+        })?
+        .map_err(|leg_id| {
+            Self::deposit_event(RawEvent::LegFailedExecution(caller_did, id, leg_id));
+            Error::<T>::FailedToReleaseLockOrTransferAssets
+        })?;
+
+        Self::deposit_event(RawEvent::InstructionPartiallyFilled(
+            caller_did,
+            id,
+            fill,
+            filled_amounts,
+        ));
+        Ok(PostDispatchInfo::from(Some(weight_meter.consumed())))
+    }
+
+    fn base_execute_netted_instructions(
+        origin: T::RuntimeOrigin,
+        venue_id: VenueId,
+        instruction_ids: Vec<InstructionId>,
+        input_cost: &AssetCount,
+        weight_meter: &mut WeightMeter,
+    ) -> DispatchResultWithPostInfo {
+        let caller_did = Identity::<T>::ensure_perms(origin)?;
+        // Only the venue creator can execute a netting batch
+        Self::venue_for_management(venue_id, caller_did)?;
+        ensure!(!instruction_ids.is_empty(), Error::<T>::EmptyNettingBatch);
+
+        let mut unique_instructions = BTreeSet::new();
+        for id in &instruction_ids {
+            ensure!(
+                unique_instructions.insert(*id),
+                Error::<T>::DuplicateInstructionInBatch
+            );
+        }
+        let mut batch_legs = Vec::with_capacity(instruction_ids.len());
+        let mut batch_asset_count = AssetCount::default();
+        for id in &instruction_ids {
+            let instruction_details = Self::ensure_instruction_validity(*id, true)?;
+            ensure!(
+                instruction_details.venue_id == venue_id,
+                Error::<T>::InstructionNotInVenue
+            );
+            // Prerequisites in the batch are executed together with their dependents
+            Self::ensure_ready_for_batch_execution(*id, &unique_instructions)?;
+
+            let mut instruction_legs: Vec<(LegId, Leg)> =
+                InstructionLegs::iter_prefix(id).collect();
+            instruction_legs.sort_by_key(|leg_id_leg| leg_id_leg.0);
+            Self::ensure_allowed_venue(&instruction_legs, venue_id)?;
+
+            let instruction_asset_count = AssetCount::from_legs(&instruction_legs);
+            weight_meter
+                .check_accrue(<T as Config>::WeightInfo::execute_instruction_paused(
+                    instruction_asset_count.fungible(),
+                    instruction_asset_count.non_fungible(),
+                    instruction_asset_count.off_chain(),
+                ))
+                .map_err(|_| Error::<T>::WeightLimitExceeded)?;
+            batch_asset_count = AssetCount::new(
+                batch_asset_count
+                    .fungible()
+                    .saturating_add(instruction_asset_count.fungible()),
+                batch_asset_count
+                    .non_fungible()
+                    .saturating_add(instruction_asset_count.non_fungible()),
+                batch_asset_count
+                    .off_chain()
+                    .saturating_add(instruction_asset_count.off_chain()),
+            );
+            batch_legs.push((*id, instruction_legs));
+        }
+        Self::ensure_valid_cost(&batch_asset_count, input_cost)?;
+
+        let net_legs =
+            Self::net_fungible_legs(batch_legs.iter().flat_map(|(id, instruction_legs)| {
+                instruction_legs.iter().map(move |(_, leg)| (*id, leg))
+            }));
+        with_transaction(|| {
+            for (id, instruction_legs) in &batch_legs {
+                Self::unchecked_release_locks(*id, instruction_legs);
+            }
+            // Compliance and statistics are checked once for each net transfer
+            for (leg, _) in &net_legs {
+                if let Leg::Fungible {
+                    sender,
+                    receiver,
+                    ticker,
+                    amount,
+                } = leg
+                {
+                    <Asset<T>>::base_transfer(
+                        *sender,
+                        *receiver,
+                        ticker,
+                        *amount,
+                        None,
+                        None,
+                        caller_did,
+                        weight_meter,
+                    )?;
+                }
+            }
+            for (id, instruction_legs) in &batch_legs {
+                let instruction_memo = InstructionMemos::get(id);
+                for (_, leg) in instruction_legs {
+                    if let Leg::NonFungible {
+                        sender,
+                        receiver,
+                        nfts,
+                    } = leg
+                    {
+                        <Nft<T>>::base_nft_transfer(
+                            *sender,
+                            *receiver,
+                            nfts.clone(),
+                            *id,
+                            instruction_memo.clone(),
+                            caller_did,
+                            weight_meter,
+                        )?;
+                    }
+                }
+            }
+            for (id, _) in &batch_legs {
+                Self::lock_repo_collateral(*id)
+                    .map_err(|_| Error::<T>::FailedToReleaseLockOrTransferAssets)?;
+            }
+            Ok::<_, DispatchError>(())
+        })?;
+
+        // Prerequisites always have a lower id than their dependents, so they are pruned first
+        for id in &unique_instructions {
+            let _ = T::Scheduler::cancel_named(id.execution_name());
+            Self::prune_instruction(*id, true);
+            Self::deposit_event(RawEvent::InstructionExecuted(caller_did, *id));
+        }
+        let mut batch_net_legs = Vec::with_capacity(net_legs.len());
+        for (leg, covered_instructions) in net_legs {
+            Self::deposit_event(RawEvent::NetTransferExecuted(
+                caller_did,
+                venue_id,
+                leg.clone(),
+                covered_instructions.into_iter().collect(),
+            ));
+            batch_net_legs.push(leg);
+        }
+        Self::deposit_event(RawEvent::NettedInstructionsExecuted(
+            caller_did,
+            venue_id,
+            instruction_ids,
+            batch_net_legs,
+        ));
+        Ok(PostDispatchInfo::from(Some(weight_meter.consumed())))
+    }
+
+    /// Returns the [`Leg::Fungible`] transfers that result in the same balance changes as all fungible `legs`,
+    /// where the legs between the same two portfolios for the same ticker are offset against each other.
+    /// Portfolios that did not trade with each other are never paired, so compliance is checked between the original counterparties.
+    /// Each transfer is returned with the instructions whose legs it settles.
+    fn net_fungible_legs<'a>(
+        legs: impl Iterator<Item = (InstructionId, &'a Leg)>,
+    ) -> Vec<(Leg, BTreeSet<InstructionId>)> {
+        // Tracks the amount sent in each direction between two portfolios, for each ticker
+        let mut positions: BTreeMap<
+            (Ticker, PortfolioId, PortfolioId),
+            (Balance, Balance, BTreeSet<InstructionId>),
+        > = BTreeMap::new();
+        for (id, leg) in legs {
+            if let Leg::Fungible {
+                sender,
+                receiver,
+                ticker,
+                amount,
+            } = leg
+            {
+                if sender < receiver {
+                    let position = positions.entry((*ticker, *sender, *receiver)).or_default();
+                    position.0 = position.0.saturating_add(*amount);
+                    position.2.insert(id);
+                } else {
+                    let position = positions.entry((*ticker, *receiver, *sender)).or_default();
+                    position.1 = position.1.saturating_add(*amount);
+                    position.2.insert(id);
+                }
+            }
+        }
+
+        positions
+            .into_iter()
+            .filter_map(
+                |((ticker, first, second), (sent, received, instructions))| {
+                    let (sender, receiver, amount) = match sent.cmp(&received) {
+                        Ordering::Greater => (first, second, sent - received),
+                        Ordering::Less => (second, first, received - sent),
+                        Ordering::Equal => return None,
+                    };
+                    Some((
+                        Leg::Fungible {
+                            sender,
+                            receiver,
+                            ticker,
+                            amount,
+                        },
+                        instructions,
+                    ))
+                },
+            )
+            .collect()
+    }
+
     /// Returns `Ok` if `origin` represents the root, otherwise returns an `Err` with the consumed weight for this function.
     fn ensure_root_origin(origin: T::RuntimeOrigin) -> Result<(), DispatchErrorWithPostInfo> {
         ensure_root(origin).map_err(|e| DispatchErrorWithPostInfo {
@@ -2139,42 +4013,108 @@ impl<T: Config> Module<T> {
                 Error::<T>::ReceiptAlreadyClaimed
             );
 
-            let leg = InstructionLegs::get(&instruction_id, &receipt_details.leg_id())
-                .ok_or(Error::<T>::LegNotFound)?;
-            match leg {
-                Leg::OffChain {
+            let receipt = Self::ensure_pending_offchain_leg_receipt(
+                instruction_id,
+                receipt_details.leg_id(),
+                receipt_details.uid(),
+            )?;
+            ensure!(
+                receipt_details
+                    .signature()
+                    .verify(&receipt.encode()[..], receipt_details.signer()),
+                Error::<T>::InvalidSignature
+            );
+        }
+        Ok(())
+    }
+
+    /// Ensures the all ECDSA receipts are valid. On top of the checks done for [`ReceiptDetails`], the receipt must
+    /// not be expired and its signature must be over an [`EcdsaReceipt`] bound to this chain's genesis hash.
+    fn ensure_valid_ecdsa_receipts_details(
+        venue_id: VenueId,
+        instruction_id: InstructionId,
+        receipts_details: &[EcdsaReceiptDetails<T::Moment>],
+    ) -> DispatchResult {
+        let genesis_hash = System::<T>::block_hash(T::BlockNumber::zero());
+        let now = <pallet_timestamp::Pallet<T>>::get();
+        let mut unique_signers_uid_set = BTreeSet::new();
+        let mut unique_legs = BTreeSet::new();
+        for receipt_details in receipts_details {
+            ensure!(
+                receipt_details.instruction_id() == &instruction_id,
+                Error::<T>::ReceiptInstructionIdMissmatch
+            );
+            ensure!(
+                unique_signers_uid_set.insert((*receipt_details.signer(), receipt_details.uid())),
+                Error::<T>::DuplicateReceiptUid
+            );
+            ensure!(
+                unique_legs.insert(receipt_details.leg_id()),
+                Error::<T>::MultipleReceiptsForOneLeg
+            );
+            ensure!(
+                Self::venue_ecdsa_signers(venue_id, receipt_details.signer()),
+                Error::<T>::UnauthorizedSigner
+            );
+            ensure!(
+                !Self::ecdsa_receipts_used(receipt_details.signer(), &receipt_details.uid()),
+                Error::<T>::ReceiptAlreadyClaimed
+            );
+            if let Some(expiry) = receipt_details.expiry() {
+                ensure!(*expiry > now, Error::<T>::ReceiptExpired);
+            }
+
+            let receipt = Self::ensure_pending_offchain_leg_receipt(
+                instruction_id,
+                receipt_details.leg_id(),
+                receipt_details.uid(),
+            )?;
+            let ecdsa_receipt = EcdsaReceipt::new(genesis_hash, receipt, *receipt_details.expiry());
+            ensure!(
+                eth_check(
+                    &ecdsa_receipt,
+                    ECDSA_RECEIPT_PREFIX,
+                    receipt_details.signature()
+                ) == Some(*receipt_details.signer()),
+                Error::<T>::InvalidSignature
+            );
+        }
+        Ok(())
+    }
+
+    /// Ensures the leg of the given `leg_id` is an offchain leg pending affirmation and returns
+    /// the [`Receipt`] that must be signed to affirm it.
+    fn ensure_pending_offchain_leg_receipt(
+        instruction_id: InstructionId,
+        leg_id: LegId,
+        uid: u64,
+    ) -> Result<Receipt<Balance>, DispatchError> {
+        let leg = InstructionLegs::get(&instruction_id, &leg_id).ok_or(Error::<T>::LegNotFound)?;
+        match leg {
+            Leg::OffChain {
+                sender_identity,
+                receiver_identity,
+                ticker,
+                amount,
+            } => {
+                ensure!(
+                    OffChainAffirmations::get(instruction_id, leg_id) == AffirmationStatus::Pending,
+                    Error::<T>::UnexpectedAffirmationStatus
+                );
+                Ok(Receipt::new(
+                    uid,
+                    instruction_id,
+                    leg_id,
                     sender_identity,
                     receiver_identity,
                     ticker,
                     amount,
-                } => {
-                    ensure!(
-                        OffChainAffirmations::get(instruction_id, receipt_details.leg_id())
-                            == AffirmationStatus::Pending,
-                        Error::<T>::UnexpectedAffirmationStatus
-                    );
-                    let receipt = Receipt::new(
-                        receipt_details.uid(),
-                        instruction_id,
-                        receipt_details.leg_id(),
-                        sender_identity,
-                        receiver_identity,
-                        ticker,
-                        amount,
-                    );
-                    ensure!(
-                        receipt_details
-                            .signature()
-                            .verify(&receipt.encode()[..], receipt_details.signer()),
-                        Error::<T>::InvalidSignature
-                    );
-                }
-                Leg::Fungible { .. } | Leg::NonFungible { .. } => {
-                    return Err(Error::<T>::ReceiptForInvalidLegType.into())
-                }
+                ))
+            }
+            Leg::Fungible { .. } | Leg::NonFungible { .. } => {
+                Err(Error::<T>::ReceiptForInvalidLegType.into())
             }
         }
-        Ok(())
     }
 
     /// Returns [`WeightMeter`] if the provided `weight_limit` is greater than `minimum_weight`, otherwise returns an error.
@@ -2396,12 +4336,18 @@ impl<T: Config> Module<T> {
         <T as Config>::WeightInfo::withdraw_affirmation_input(Some(affirmation_count))
     }
 
-    /// Returns the weight for calling `reject_instruction_weight` with the number of assets in `instruction_asset_count`.
-    fn reject_instruction_weight(instruction_asset_count: AssetCount, as_mediator: bool) -> Weight {
+    /// Returns the weight for calling `reject_instruction_weight` with the number of assets in `instruction_asset_count`
+    /// and `n_dependents` instructions depending on it.
+    fn reject_instruction_weight(
+        instruction_asset_count: AssetCount,
+        as_mediator: bool,
+        n_dependents: u32,
+    ) -> Weight {
         <T as Config>::WeightInfo::reject_instruction_input(
             Some(instruction_asset_count),
             as_mediator,
         )
+        .saturating_add(<T as Config>::WeightInfo::reject_dependents(n_dependents))
     }
 
     pub fn get_actual_weight(call: &Call<T>) -> Option<Weight> {
@@ -2434,7 +4380,11 @@ impl<T: Config> Module<T> {
             }
             Call::reject_instruction { id, .. } => {
                 let asset_count = Self::get_instruction_asset_count(id);
-                Some(Self::reject_instruction_weight(asset_count, false))
+                Some(Self::reject_instruction_weight(
+                    asset_count,
+                    false,
+                    Self::instruction_dependents_count(id),
+                ))
             }
             _ => None,
         }
@@ -2477,4 +4427,167 @@ impl<T: Config> Module<T> {
             filtered_legs.unfiltered_asset_count().off_chain(),
         )
     }
+
+    /// Returns an [`InstructionExecutionReport`] with the outcome of executing the instruction of the given `id`
+    /// against the current state. All changes made while simulating the execution are rolled back.
+    pub fn simulate_instruction_execution(id: InstructionId) -> InstructionExecutionReport {
+        frame_storage_with_transaction(|| {
+            TransactionOutcome::Rollback(Ok::<_, DispatchError>(
+                Self::unchecked_simulate_instruction_execution(id),
+            ))
+        })
+        .unwrap_or_else(|e| InstructionExecutionReport::new(vec![e.into()], Vec::new()))
+    }
+
+    /// Releases the locks and transfers all legs of the instruction, recording every failure instead of
+    /// stopping at the first one. Must only be called inside a transaction that gets rolled back.
+    fn unchecked_simulate_instruction_execution(id: InstructionId) -> InstructionExecutionReport {
+        let mut instruction_errors: Vec<&'static str> = Vec::new();
+        let mut record_error = |result: DispatchResult| {
+            if let Err(e) = result {
+                let e: &'static str = e.into();
+                if !instruction_errors.contains(&e) {
+                    instruction_errors.push(e);
+                }
+            }
+        };
+
+        let details = match Self::ensure_instruction_validity(id, true) {
+            Ok(details) => details,
+            Err(e) if e == Error::<T>::UnknownInstruction.into() => {
+                return InstructionExecutionReport::new(vec![e.into()], Vec::new());
+            }
+            Err(e) => {
+                record_error(Err(e));
+                Self::instruction_details(id)
+            }
+        };
+        record_error(Self::ensure_ready_for_execution(id));
+
+        let mut instruction_legs: Vec<(LegId, Leg)> = InstructionLegs::iter_prefix(&id).collect();
+        instruction_legs.sort_by_key(|leg_id_leg| leg_id_leg.0);
+        record_error(Self::ensure_allowed_venue(
+            &instruction_legs,
+            details.venue_id,
+        ));
+
+        let caller_did = SettlementDID.as_id();
+        let mut weight_meter = WeightMeter::max_limit_no_minimum();
+        Self::unchecked_release_locks(id, &instruction_legs);
+        let mut legs = Vec::with_capacity(instruction_legs.len());
+        for (leg_id, leg) in instruction_legs {
+            let leg_report = match leg {
+                Leg::Fungible {
+                    sender,
+                    receiver,
+                    ticker,
+                    amount,
+                } => {
+                    match <Asset<T>>::unsafe_can_transfer_granular(
+                        Some(T::Portfolio::current_custodian(&sender)),
+                        sender,
+                        Some(T::Portfolio::current_custodian(&receiver)),
+                        receiver,
+                        &ticker,
+                        amount,
+                        &mut weight_meter,
+                    ) {
+                        Ok(granular_result) => {
+                            // Transfers the leg so that the following legs are checked against the updated state
+                            if granular_result.result {
+                                record_error(<Asset<T>>::base_transfer(
+                                    sender,
+                                    receiver,
+                                    &ticker,
+                                    amount,
+                                    Some(id),
+                                    None,
+                                    caller_did,
+                                    &mut weight_meter,
+                                ));
+                            }
+                            LegTransferReport::Fungible(granular_result)
+                        }
+                        Err(e) => {
+                            record_error(Err(e));
+                            continue;
+                        }
+                    }
+                }
+                Leg::NonFungible {
+                    sender,
+                    receiver,
+                    nfts,
+                } => match <Nft<T>>::validate_nft_transfer(
+                    &sender,
+                    &receiver,
+                    &nfts,
+                    &mut weight_meter,
+                ) {
+                    Ok(_) => {
+                        record_error(<Nft<T>>::base_nft_transfer(
+                            sender,
+                            receiver,
+                            nfts,
+                            id,
+                            None,
+                            caller_did,
+                            &mut weight_meter,
+                        ));
+                        LegTransferReport::non_fungible(None)
+                    }
+                    Err(e) => LegTransferReport::non_fungible(Some(e.into())),
+                },
+                Leg::OffChain { .. } => LegTransferReport::OffChain(
+                    Self::offchain_affirmations(id, leg_id) == AffirmationStatus::Affirmed,
+                ),
+            };
+            legs.push((leg_id, leg_report));
+        }
+
+        InstructionExecutionReport::new(instruction_errors, legs)
+    }
+}
+
+pub mod migration {
+    use super::*;
+    use frame_support::IterableStorageMap;
+    use sp_runtime::runtime_logger::RuntimeLogger;
+
+    mod v2 {
+        use super::*;
+
+        #[derive(Decode)]
+        pub struct Instruction<Moment, BlockNumber> {
+            pub instruction_id: InstructionId,
+            pub venue_id: VenueId,
+            pub settlement_type: SettlementType<BlockNumber>,
+            pub created_at: Option<Moment>,
+            pub trade_date: Option<Moment>,
+            pub value_date: Option<Moment>,
+        }
+    }
+
+    pub fn migrate_to_v3<T: Config>() {
+        RuntimeLogger::init();
+        log::info!(" >>> Adding the expiry date to InstructionDetails");
+        migrate_instruction_details::<T>();
+        log::info!(" >>> InstructionDetails has been migrated");
+    }
+
+    fn migrate_instruction_details<T: Config>() {
+        InstructionDetails::<T>::translate::<v2::Instruction<T::Moment, T::BlockNumber>, _>(
+            |_, old| {
+                Some(Instruction {
+                    instruction_id: old.instruction_id,
+                    venue_id: old.venue_id,
+                    settlement_type: old.settlement_type,
+                    created_at: old.created_at,
+                    trade_date: old.trade_date,
+                    value_date: old.value_date,
+                    expiry: None,
+                })
+            },
+        );
+    }
 }
@@ -477,6 +477,8 @@ decl_module! {
                     None,
                     legs,
                     None,
+                    None,
+                    None,
                     None
                 )?;
 
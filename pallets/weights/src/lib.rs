@@ -28,6 +28,9 @@ pub mod pallet_compliance_manager;
 pub mod pallet_contracts;
 pub mod pallet_corporate_actions;
 pub mod pallet_corporate_ballot;
+pub mod pallet_corporate_coupon;
+pub mod pallet_corporate_exchange;
+pub mod pallet_corporate_reorganization;
 pub mod pallet_external_agents;
 pub mod pallet_grandpa;
 pub mod pallet_group;
@@ -36,6 +39,7 @@ pub mod pallet_im_online;
 pub mod pallet_indices;
 pub mod pallet_multisig;
 pub mod pallet_nft;
+pub mod pallet_order_book;
 pub mod pallet_pips;
 pub mod pallet_portfolio;
 pub mod pallet_preimage;
@@ -21,6 +21,9 @@
 //! DATE: 2023-08-24, STEPS: `100`, REPEAT: 5, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: None, DB CACHE: 512
 //! HOSTNAME: `ubuntu-8gb-hel1-5`, CPU: `AMD EPYC Processor`
+//!
+//! The weights of `create_vesting_schedule`, `release_vested_tokens`, `revoke_vesting_schedule`, `freeze_holder`, `unfreeze_holder`, `force_transfer`, `lower_max_supply`, `raise_max_supply` and `set_issuance_tranches` are estimated from the neighbouring benchmarks,
+//! until they are regenerated with `benchmark pallet -p=pallet_asset`.
 
 // Executed Command:
 // target/release/polymesh
@@ -742,4 +745,53 @@ impl pallet_asset::WeightInfo for SubstrateWeight {
             .saturating_add(DbWeight::get().writes(1))
             .saturating_add(Weight::from_proof_size(160).saturating_mul(n.into()))
     }
+    fn create_vesting_schedule() -> Weight {
+        Weight::from_ref_time(60_000_000)
+            .saturating_add(DbWeight::get().reads(8))
+            .saturating_add(DbWeight::get().writes(2))
+    }
+    /// The range of component `s` is `[1, 16]`.
+    fn release_vested_tokens(s: u32) -> Weight {
+        Weight::from_ref_time(45_000_000)
+            .saturating_add(Weight::from_ref_time(500_000).saturating_mul(s.into()))
+            .saturating_add(DbWeight::get().reads(3))
+            .saturating_add(DbWeight::get().writes(2))
+    }
+    fn revoke_vesting_schedule() -> Weight {
+        Weight::from_ref_time(140_000_000)
+            .saturating_add(DbWeight::get().reads(16))
+            .saturating_add(DbWeight::get().writes(7))
+    }
+    fn freeze_holder() -> Weight {
+        Weight::from_ref_time(66_000_000)
+            .saturating_add(DbWeight::get().reads(6))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    fn unfreeze_holder() -> Weight {
+        Weight::from_ref_time(66_000_000)
+            .saturating_add(DbWeight::get().reads(6))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    fn force_transfer() -> Weight {
+        Weight::from_ref_time(130_000_000)
+            .saturating_add(DbWeight::get().reads(16))
+            .saturating_add(DbWeight::get().writes(5))
+    }
+    fn lower_max_supply() -> Weight {
+        Weight::from_ref_time(66_000_000)
+            .saturating_add(DbWeight::get().reads(6))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    fn raise_max_supply() -> Weight {
+        Weight::from_ref_time(64_000_000)
+            .saturating_add(DbWeight::get().reads(5))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    /// The range of component `t` is `[0, 16]`.
+    fn set_issuance_tranches(t: u32) -> Weight {
+        Weight::from_ref_time(60_000_000)
+            .saturating_add(Weight::from_ref_time(150_000).saturating_mul(t.into()))
+            .saturating_add(DbWeight::get().reads(5))
+            .saturating_add(DbWeight::get().writes(1))
+    }
 }
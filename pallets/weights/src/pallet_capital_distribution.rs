@@ -21,6 +21,9 @@
 //! DATE: 2023-08-24, STEPS: `100`, REPEAT: 5, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: None, DB CACHE: 512
 //! HOSTNAME: `ubuntu-8gb-hel1-5`, CPU: `AMD EPYC Processor`
+//!
+//! The weights of `set_elective_options`, `elect` and `refund_withheld_tax` are estimated from the neighbouring benchmarks,
+//! until they are regenerated with `benchmark pallet -p=pallet_capital_distribution`.
 
 // Executed Command:
 // target/release/polymesh
@@ -221,4 +224,23 @@ impl pallet_corporate_actions::distribution::WeightInfo for SubstrateWeight {
             .saturating_add(DbWeight::get().reads(7))
             .saturating_add(DbWeight::get().writes(2))
     }
+    /// The range of component `c` is `[1, 8]`.
+    fn set_elective_options(c: u32) -> Weight {
+        Weight::from_ref_time(110_000_000)
+            .saturating_add(Weight::from_ref_time(40_000_000).saturating_mul(c.into()))
+            .saturating_add(DbWeight::get().reads(8))
+            .saturating_add(DbWeight::get().reads((2_u64).saturating_mul(c.into())))
+            .saturating_add(DbWeight::get().writes(1))
+            .saturating_add(DbWeight::get().writes((2_u64).saturating_mul(c.into())))
+    }
+    fn elect() -> Weight {
+        Weight::from_ref_time(80_000_000)
+            .saturating_add(DbWeight::get().reads(5))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    fn refund_withheld_tax() -> Weight {
+        Weight::from_ref_time(400_000_000)
+            .saturating_add(DbWeight::get().reads(21))
+            .saturating_add(DbWeight::get().writes(5))
+    }
 }
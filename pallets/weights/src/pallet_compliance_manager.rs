@@ -21,6 +21,9 @@
 //! DATE: 2023-08-24, STEPS: `100`, REPEAT: 5, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: None, DB CACHE: 512
 //! HOSTNAME: `ubuntu-8gb-hel1-5`, CPU: `AMD EPYC Processor`
+//!
+//! The weights of `set_compliance_expression`, `create_compliance_template`, `update_compliance_template`, `subscribe_to_compliance_template`, `unsubscribe_from_compliance_template` and `accept_compliance_template_version` are estimated from the neighbouring benchmarks,
+//! until they are regenerated with `benchmark pallet -p=pallet_compliance_manager`.
 
 // Executed Command:
 // target/release/polymesh
@@ -272,4 +275,42 @@ impl pallet_compliance_manager::WeightInfo for SubstrateWeight {
             // Standard Error: 1_229
             .saturating_add(Weight::from_ref_time(185_731).saturating_mul(i.into()))
     }
+    /// The range of component `n` is `[1, 50]`.
+    fn set_compliance_expression(n: u32) -> Weight {
+        Weight::from_ref_time(105_144_423)
+            .saturating_add(Weight::from_ref_time(1_000_000).saturating_mul(n.into()))
+            .saturating_add(DbWeight::get().reads(6))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    /// The range of component `c` is `[0, 2]`.
+    fn create_compliance_template(c: u32) -> Weight {
+        Weight::from_ref_time(105_144_423)
+            .saturating_add(Weight::from_ref_time(9_649_180).saturating_mul(c.into()))
+            .saturating_add(DbWeight::get().reads(5))
+            .saturating_add(DbWeight::get().writes(3))
+    }
+    /// The range of component `c` is `[0, 2]`.
+    fn update_compliance_template(c: u32, s: u32) -> Weight {
+        Weight::from_ref_time(105_144_423)
+            .saturating_add(Weight::from_ref_time(9_649_180).saturating_mul(c.into()))
+            .saturating_add(Weight::from_ref_time(12_000_000).saturating_mul(s.into()))
+            .saturating_add(DbWeight::get().reads(5))
+            .saturating_add(DbWeight::get().reads((2_u64).saturating_mul(s.into())))
+            .saturating_add(DbWeight::get().writes(2))
+    }
+    fn subscribe_to_compliance_template() -> Weight {
+        Weight::from_ref_time(80_000_000)
+            .saturating_add(DbWeight::get().reads(9))
+            .saturating_add(DbWeight::get().writes(3))
+    }
+    fn unsubscribe_from_compliance_template() -> Weight {
+        Weight::from_ref_time(71_234_000)
+            .saturating_add(DbWeight::get().reads(7))
+            .saturating_add(DbWeight::get().writes(3))
+    }
+    fn accept_compliance_template_version() -> Weight {
+        Weight::from_ref_time(80_000_000)
+            .saturating_add(DbWeight::get().reads(8))
+            .saturating_add(DbWeight::get().writes(1))
+    }
 }
@@ -21,6 +21,9 @@
 //! DATE: 2023-08-24, STEPS: `100`, REPEAT: 5, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: None, DB CACHE: 512
 //! HOSTNAME: `ubuntu-8gb-hel1-5`, CPU: `AMD EPYC Processor`
+//!
+//! The weights of `set_issuer_tax_jurisdiction` and `set_treaty_withholding_tax` are estimated from the neighbouring benchmarks,
+//! until they are regenerated with `benchmark pallet -p=pallet_corporate_actions`.
 
 // Executed Command:
 // target/release/polymesh
@@ -359,4 +362,14 @@ impl pallet_corporate_actions::WeightInfo for SubstrateWeight {
             .saturating_add(DbWeight::get().reads(13))
             .saturating_add(DbWeight::get().writes(6))
     }
+    fn set_issuer_tax_jurisdiction() -> Weight {
+        Weight::from_ref_time(57_198_000)
+            .saturating_add(DbWeight::get().reads(5))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    fn set_treaty_withholding_tax() -> Weight {
+        Weight::from_ref_time(20_000_000)
+            .saturating_add(DbWeight::get().reads(1))
+            .saturating_add(DbWeight::get().writes(1))
+    }
 }
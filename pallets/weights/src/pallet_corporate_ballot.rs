@@ -21,6 +21,9 @@
 //! DATE: 2023-08-24, STEPS: `100`, REPEAT: 5, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: None, DB CACHE: 512
 //! HOSTNAME: `ubuntu-8gb-hel1-5`, CPU: `AMD EPYC Processor`
+//!
+//! The weights of `finalize_ballot`, `delegate`, `revoke_delegation` and `proxy_vote` are estimated from the neighbouring benchmarks,
+//! until they are regenerated with `benchmark pallet -p=pallet_corporate_ballot`.
 
 // Executed Command:
 // target/release/polymesh
@@ -205,4 +208,38 @@ impl pallet_corporate_actions::ballot::WeightInfo for SubstrateWeight {
             .saturating_add(DbWeight::get().reads(6))
             .saturating_add(DbWeight::get().writes(4))
     }
+    /// The range of component `v` is `[0, 1000]`.
+    /// The range of component `c` is `[0, 1000]`.
+    fn finalize_ballot(v: u32, c: u32) -> Weight {
+        Weight::from_ref_time(60_000_000)
+            .saturating_add(Weight::from_ref_time(150_000).saturating_mul(c.into()))
+            .saturating_add(Weight::from_ref_time(10_000_000).saturating_mul(v.into()))
+            .saturating_add(
+                Weight::from_ref_time(5_000)
+                    .saturating_mul(v.into())
+                    .saturating_mul(c.into()),
+            )
+            .saturating_add(DbWeight::get().reads(6))
+            .saturating_add(DbWeight::get().reads((1_u64).saturating_mul(v.into())))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    fn delegate() -> Weight {
+        Weight::from_ref_time(50_000_000)
+            .saturating_add(DbWeight::get().reads(3))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    fn revoke_delegation() -> Weight {
+        Weight::from_ref_time(40_000_000)
+            .saturating_add(DbWeight::get().reads(2))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    /// The range of component `c` is `[0, 1000]`.
+    /// The range of component `t` is `[0, 1000]`.
+    fn proxy_vote(c: u32, t: u32) -> Weight {
+        Weight::from_ref_time(240_000_000)
+            .saturating_add(Weight::from_ref_time(146_863).saturating_mul(c.into()))
+            .saturating_add(Weight::from_ref_time(171_367).saturating_mul(t.into()))
+            .saturating_add(DbWeight::get().reads(13))
+            .saturating_add(DbWeight::get().writes(3))
+    }
 }
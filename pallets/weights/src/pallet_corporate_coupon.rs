@@ -0,0 +1,56 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for pallet_corporate_coupon
+//!
+//! Estimated from the `pallet_corporate_actions` and `pallet_capital_distribution` benchmarks,
+//! until they are regenerated with `benchmark pallet -p=pallet_corporate_coupon`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use polymesh_runtime_common::{RocksDbWeight as DbWeight, Weight};
+
+/// Weights for pallet_corporate_coupon using the Substrate node and recommended hardware.
+pub struct SubstrateWeight;
+impl pallet_corporate_actions::coupon::WeightInfo for SubstrateWeight {
+    fn create_schedule() -> Weight {
+        Weight::from_ref_time(60_000_000)
+            .saturating_add(DbWeight::get().reads(9))
+            .saturating_add(DbWeight::get().writes(2))
+    }
+    fn remove_schedule() -> Weight {
+        Weight::from_ref_time(50_000_000)
+            .saturating_add(DbWeight::get().reads(6))
+            .saturating_add(DbWeight::get().writes(2))
+    }
+    fn pay_coupon() -> Weight {
+        Weight::from_ref_time(180_000_000)
+            .saturating_add(DbWeight::get().reads(21))
+            .saturating_add(DbWeight::get().writes(10))
+    }
+    fn redeem_matured(h: u32, p: u32) -> Weight {
+        Weight::from_ref_time(40_000_000)
+            .saturating_add(Weight::from_ref_time(60_000_000).saturating_mul(h.into()))
+            .saturating_add(DbWeight::get().reads(6))
+            .saturating_add(DbWeight::get().reads((8_u64).saturating_mul(h.into())))
+            .saturating_add(DbWeight::get().writes((5_u64).saturating_mul(h.into())))
+            .saturating_add(Weight::from_ref_time(10_000_000).saturating_mul(p.into()))
+            .saturating_add(DbWeight::get().reads((2_u64).saturating_mul(p.into())))
+            .saturating_add(DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+    }
+}
@@ -0,0 +1,72 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for pallet_corporate_exchange
+//!
+//! Estimated from the `pallet_capital_distribution` and `pallet_sto::invest` benchmarks,
+//! until they are regenerated with `benchmark pallet -p=pallet_corporate_exchange`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use polymesh_runtime_common::{RocksDbWeight as DbWeight, Weight};
+
+/// Weights for pallet_corporate_exchange using the Substrate node and recommended hardware.
+pub struct SubstrateWeight;
+impl pallet_corporate_actions::exchange::WeightInfo for SubstrateWeight {
+    fn create_offer() -> Weight {
+        Weight::from_ref_time(110_000_000)
+            .saturating_add(DbWeight::get().reads(11))
+            .saturating_add(DbWeight::get().writes(2))
+    }
+    // Same settlement storage as `pallet_sto::invest`.
+    /// The range of component `p` is `[0, 10]`.
+    fn elect(p: u32) -> Weight {
+        Weight::from_ref_time(2_200_000_000)
+            // Standard Error: 150_000
+            .saturating_add(Weight::from_ref_time(250_000_000).saturating_mul(p.into()))
+            .saturating_add(DbWeight::get().reads(130))
+            .saturating_add(DbWeight::get().reads((14_u64).saturating_mul(p.into())))
+            .saturating_add(DbWeight::get().writes(58))
+            .saturating_add(DbWeight::get().writes((6_u64).saturating_mul(p.into())))
+    }
+    // Same settlement storage as `pallet_sto::invest`.
+    /// The range of component `p` is `[0, 10]`.
+    fn push_exchange(p: u32) -> Weight {
+        Weight::from_ref_time(2_200_000_000)
+            // Standard Error: 150_000
+            .saturating_add(Weight::from_ref_time(250_000_000).saturating_mul(p.into()))
+            .saturating_add(DbWeight::get().reads(132))
+            .saturating_add(DbWeight::get().reads((14_u64).saturating_mul(p.into())))
+            .saturating_add(DbWeight::get().writes(58))
+            .saturating_add(DbWeight::get().writes((6_u64).saturating_mul(p.into())))
+    }
+    /// The range of component `t` is `[0, 1000]`.
+    fn reclaim(t: u32) -> Weight {
+        Weight::from_ref_time(80_000_000)
+            // Standard Error: 5_000
+            .saturating_add(Weight::from_ref_time(8_000_000).saturating_mul(t.into()))
+            .saturating_add(DbWeight::get().reads(12))
+            .saturating_add(DbWeight::get().reads((3_u64).saturating_mul(t.into())))
+            .saturating_add(DbWeight::get().writes(2))
+    }
+    fn remove_offer() -> Weight {
+        Weight::from_ref_time(70_000_000)
+            .saturating_add(DbWeight::get().reads(6))
+            .saturating_add(DbWeight::get().writes(2))
+    }
+}
@@ -21,6 +21,9 @@
 //! DATE: 2023-08-24, STEPS: `100`, REPEAT: 5, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: None, DB CACHE: 512
 //! HOSTNAME: `ubuntu-8gb-hel1-5`, CPU: `AMD EPYC Processor`
+//!
+//! The weight of `add_claim_with_attestation` is estimated from the neighbouring benchmarks,
+//! until they are regenerated with `benchmark pallet -p=pallet_identity`.
 
 // Executed Command:
 // target/release/polymesh
@@ -289,6 +292,11 @@ impl pallet_identity::WeightInfo for SubstrateWeight {
             .saturating_add(DbWeight::get().reads(6))
             .saturating_add(DbWeight::get().writes(1))
     }
+    fn add_claim_with_attestation() -> Weight {
+        Weight::from_ref_time(129_650_000)
+            .saturating_add(DbWeight::get().reads(10))
+            .saturating_add(DbWeight::get().writes(2))
+    }
     // Storage: Identity KeyRecords (r:1 w:0)
     // Proof Skipped: Identity KeyRecords (max_values: None, max_size: None, mode: Measured)
     // Storage: Identity Claims (r:1 w:1)
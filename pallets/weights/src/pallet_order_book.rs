@@ -0,0 +1,58 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for pallet_order_book
+//!
+//! Estimated from the `pallet_sto` benchmarks of the equivalent lock and settlement paths,
+//! until they are regenerated with `benchmark pallet -p=pallet_order_book`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use polymesh_runtime_common::{RocksDbWeight as DbWeight, Weight};
+
+/// Weights for pallet_order_book using the Substrate node and recommended hardware.
+pub struct SubstrateWeight;
+impl pallet_order_book::WeightInfo for SubstrateWeight {
+    fn place_order(n: u32, p: u32) -> Weight {
+        Weight::from_ref_time(180_000_000)
+            .saturating_add(Weight::from_ref_time(400_000).saturating_mul(n.into()))
+            .saturating_add(Weight::from_ref_time(150_000).saturating_mul(p.into()))
+            .saturating_add(DbWeight::get().reads(9))
+            .saturating_add(DbWeight::get().writes(6))
+    }
+    fn cancel_order(n: u32) -> Weight {
+        Weight::from_ref_time(110_000_000)
+            .saturating_add(Weight::from_ref_time(400_000).saturating_mul(n.into()))
+            .saturating_add(DbWeight::get().reads(4))
+            .saturating_add(DbWeight::get().writes(3))
+    }
+    fn amend_order(n: u32, p: u32) -> Weight {
+        Weight::from_ref_time(190_000_000)
+            .saturating_add(Weight::from_ref_time(800_000).saturating_mul(n.into()))
+            .saturating_add(Weight::from_ref_time(150_000).saturating_mul(p.into()))
+            .saturating_add(DbWeight::get().reads(7))
+            .saturating_add(DbWeight::get().writes(5))
+    }
+    // Same settlement storage as `pallet_sto::invest`.
+    fn match_orders(n: u32) -> Weight {
+        Weight::from_ref_time(2_100_000_000)
+            .saturating_add(Weight::from_ref_time(800_000).saturating_mul(n.into()))
+            .saturating_add(DbWeight::get().reads(127))
+            .saturating_add(DbWeight::get().writes(56))
+    }
+}
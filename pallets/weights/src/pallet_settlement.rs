@@ -21,6 +21,9 @@
 //! DATE: 2023-08-24, STEPS: `100`, REPEAT: 5, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: None, DB CACHE: 512
 //! HOSTNAME: `ubuntu-8gb-hel1-5`, CPU: `AMD EPYC Processor`
+//!
+//! The weights of `expire_instruction`, `reject_dependents`, `set_venue_trading_window`, `set_venue_halt`, `set_venue_instruction_limits` and `close_repo_early` are estimated from the neighbouring benchmarks,
+//! until they are regenerated with `benchmark pallet -p=pallet_settlement`.
 
 // Executed Command:
 // target/release/polymesh
@@ -1111,4 +1114,57 @@ impl pallet_settlement::WeightInfo for SubstrateWeight {
             .saturating_add(Weight::from_proof_size(28970).saturating_mul(n.into()))
             .saturating_add(Weight::from_proof_size(10141).saturating_mul(o.into()))
     }
+    /// The range of component `f` is `[1, 10]`.
+    /// The range of component `n` is `[0, 100]`.
+    /// The range of component `o` is `[0, 10]`.
+    fn expire_instruction(f: u32, n: u32, o: u32) -> Weight {
+        Weight::from_ref_time(976_274_000)
+            .saturating_add(Weight::from_ref_time(81_046_062).saturating_mul(f.into()))
+            .saturating_add(Weight::from_ref_time(54_529_384).saturating_mul(n.into()))
+            .saturating_add(DbWeight::get().reads(8))
+            .saturating_add(DbWeight::get().reads((5_u64).saturating_mul(f.into())))
+            .saturating_add(DbWeight::get().reads((5_u64).saturating_mul(n.into())))
+            .saturating_add(DbWeight::get().reads((3_u64).saturating_mul(o.into())))
+            .saturating_add(DbWeight::get().writes(7))
+            .saturating_add(DbWeight::get().writes((7_u64).saturating_mul(f.into())))
+            .saturating_add(DbWeight::get().writes((7_u64).saturating_mul(n.into())))
+            .saturating_add(DbWeight::get().writes((3_u64).saturating_mul(o.into())))
+    }
+    /// The range of component `d` is `[0, 10]`.
+    fn reject_dependents(d: u32) -> Weight {
+        Weight::from_ref_time(21_837_000)
+            .saturating_add(Weight::from_ref_time(412_583_261).saturating_mul(d.into()))
+            .saturating_add(DbWeight::get().reads(2))
+            .saturating_add(DbWeight::get().reads((64_u64).saturating_mul(d.into())))
+            .saturating_add(DbWeight::get().writes(2))
+            .saturating_add(DbWeight::get().writes((78_u64).saturating_mul(d.into())))
+    }
+    fn set_venue_trading_window() -> Weight {
+        Weight::from_ref_time(47_012_000)
+            .saturating_add(DbWeight::get().reads(2))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    fn set_venue_halt() -> Weight {
+        Weight::from_ref_time(44_218_000)
+            .saturating_add(DbWeight::get().reads(2))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    fn set_venue_instruction_limits() -> Weight {
+        Weight::from_ref_time(45_106_000)
+            .saturating_add(DbWeight::get().reads(2))
+            .saturating_add(DbWeight::get().writes(1))
+    }
+    /// The range of component `f` is `[1, 10]`.
+    /// The range of component `n` is `[0, 100]`.
+    fn close_repo_early(f: u32, n: u32) -> Weight {
+        Weight::from_ref_time(1_720_778_310)
+            .saturating_add(Weight::from_ref_time(661_675_297).saturating_mul(f.into()))
+            .saturating_add(Weight::from_ref_time(207_945_173).saturating_mul(n.into()))
+            .saturating_add(DbWeight::get().reads(6))
+            .saturating_add(DbWeight::get().reads((54_u64).saturating_mul(f.into())))
+            .saturating_add(DbWeight::get().reads((12_u64).saturating_mul(n.into())))
+            .saturating_add(DbWeight::get().writes(2))
+            .saturating_add(DbWeight::get().writes((22_u64).saturating_mul(f.into())))
+            .saturating_add(DbWeight::get().writes((11_u64).saturating_mul(n.into())))
+    }
 }
@@ -26,6 +26,7 @@ use crate::compliance_manager::AssetComplianceResult;
 use crate::identity_id::PortfolioValidityResult;
 use crate::impl_checked_inc;
 use crate::transfer_compliance::TransferConditionResult;
+use crate::{Balance, IdentityId, Moment, PortfolioId};
 
 /// A per-ticker checkpoint ID.
 #[derive(Encode, Decode, TypeInfo)]
@@ -141,6 +142,105 @@ impl AssetType {
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct FundingRoundName(pub Vec<u8>);
 
+/// How the tokens of a [`VestingSchedule`] are released over time.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VestingRelease {
+    /// All tokens are released at the cliff.
+    Cliff,
+    /// Tokens are released continuously between `start` and `end`.
+    Linear,
+    /// Tokens are released in equal steps every given number of milliseconds between `start` and `end`.
+    Step(Moment),
+}
+
+/// A vesting schedule over tokens held in a portfolio.
+///
+/// Tokens which are not yet released are locked in the portfolio.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    /// The total amount of tokens that vest.
+    pub amount: Balance,
+    /// The date at which tokens start vesting.
+    pub start: Moment,
+    /// The date before which no tokens are vested.
+    pub cliff: Moment,
+    /// The date at which all tokens are vested.
+    pub end: Moment,
+    /// How tokens are released between `start` and `end`.
+    pub release: VestingRelease,
+    /// The amount of vested tokens already released from the lock.
+    pub released: Balance,
+}
+
+impl VestingSchedule {
+    /// Returns `true` if the amount, dates and release of the schedule are consistent.
+    pub fn is_valid(&self) -> bool {
+        let valid_release = match self.release {
+            VestingRelease::Step(period) => period > 0,
+            VestingRelease::Cliff | VestingRelease::Linear => true,
+        };
+        self.amount > 0
+            && self.released == 0
+            && self.start <= self.cliff
+            && self.cliff <= self.end
+            && valid_release
+    }
+
+    /// Returns the amount of tokens vested at `now`.
+    pub fn vested_at(&self, now: Moment) -> Balance {
+        if now < self.cliff {
+            return 0;
+        }
+        if now >= self.end {
+            return self.amount;
+        }
+        let elapsed = match self.release {
+            VestingRelease::Cliff => return self.amount,
+            VestingRelease::Linear => now - self.start,
+            VestingRelease::Step(period) => (now - self.start) / period * period,
+        };
+        // `start <= cliff <= now < end`, so the duration is not zero.
+        let (elapsed, duration) = (elapsed as Balance, (self.end - self.start) as Balance);
+        self.amount
+            .checked_mul(elapsed)
+            .map(|v| v / duration)
+            .unwrap_or_else(|| self.amount / duration * elapsed)
+    }
+
+    /// Returns the amount of tokens vested at `now` which are still locked.
+    pub fn releasable_at(&self, now: Moment) -> Balance {
+        self.vested_at(now).saturating_sub(self.released)
+    }
+}
+
+/// A tranche of an issuance schedule.
+///
+/// From `start` on, and until the next tranche starts, issuance can raise the supply of the asset up to `supply`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IssuanceTranche {
+    /// The date at which the tranche starts.
+    pub start: Moment,
+    /// The maximum supply of the asset during the tranche.
+    pub supply: Balance,
+}
+
+/// A holder of an asset that can be frozen individually.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AssetHolder {
+    /// All portfolios of an identity.
+    Identity(IdentityId),
+    /// A single portfolio.
+    Portfolio(PortfolioId),
+}
+
 /// Result of a granular can transfer.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
 #[derive(Decode, Encode, Clone, PartialEq, Eq)]
@@ -161,7 +261,7 @@ pub struct GranularCanTransferResult {
     pub sender_insufficient_balance: bool,
     /// Portfolio validity result.
     pub portfolio_validity_result: PortfolioValidityResult,
-    /// Asset is frozen.
+    /// Asset is frozen, either entirely or for the sender or receiver.
     pub asset_frozen: bool,
     /// Result of transfer condition check.
     pub transfer_condition_result: Vec<TransferConditionResult>,
@@ -179,6 +179,147 @@ impl CheckpointSchedule {
     }
 }
 
+/// A recurring window of time that opens at every occurrence of `schedule` and stays open for
+/// `duration` milli-seconds.
+///
+/// For example, a schedule starting on a Monday at 09:00 with a period of one day and a duration of
+/// eight hours describes a window open from 09:00 to 17:00 every day.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CalendarWindow {
+    /// The start of the first window and the period at which the window recurs.
+    pub schedule: CheckpointSchedule,
+    /// The length of each window in milli-seconds.
+    pub duration: Moment,
+}
+
+impl CalendarWindow {
+    /// Returns `true` if `now_as_ms_utc` falls within one of the windows.
+    ///
+    /// The window is open if the latest occurrence of the schedule that is not after `now` started
+    /// less than `duration` milli-seconds ago.
+    pub fn is_open(&self, now_as_ms_utc: Moment) -> bool {
+        last_occurrence_ms(&self.schedule, now_as_ms_utc).map_or(false, |occurrence| {
+            now_as_ms_utc < occurrence.saturating_add(self.duration)
+        })
+    }
+}
+
+/// Returns the latest occurrence of `schedule` that is not after `now_as_ms_utc`, in milli-seconds
+/// Unix time, or `None` if the schedule has not started yet.
+///
+/// Unlike [`CheckpointSchedule::next_checkpoint`], the occurrence of the current month or year is
+/// taken into account even if `now` is before its day of the month.
+fn last_occurrence_ms(schedule: &CheckpointSchedule, now_as_ms_utc: Moment) -> Option<Moment> {
+    if now_as_ms_utc < schedule.start {
+        return None;
+    }
+    let period = match schedule.period.to_recurring() {
+        Some(period) => period,
+        // A non-recurring schedule only occurs at its start.
+        None => return Some(schedule.start),
+    };
+    let months_per_period = match period.as_fixed_or_variable() {
+        FixedOrVariableCalendarUnit::Fixed(period_as_secs) => {
+            let period_as_ms = period_as_secs.checked_mul(1000)?;
+            let elapsed_periods = (now_as_ms_utc - schedule.start) / period_as_ms;
+            return Some(schedule.start + elapsed_periods * period_as_ms);
+        }
+        FixedOrVariableCalendarUnit::Variable(VariableCalendarUnit::Month) => {
+            u32::try_from(period.amount.get()).ok()?
+        }
+        FixedOrVariableCalendarUnit::Variable(VariableCalendarUnit::Year) => {
+            u32::try_from(period.amount.get()).ok()?.checked_mul(12)?
+        }
+    };
+    let date_time_start =
+        NaiveDateTime::from_timestamp_opt(i64::try_from(schedule.start / 1000).ok()?, 0)?;
+    let date_now =
+        NaiveDateTime::from_timestamp_opt(i64::try_from(now_as_ms_utc / 1000).ok()?, 0)?.date();
+    let elapsed_months = u32::try_from(
+        (date_now.year() - date_time_start.year()) * 12 + date_now.month0() as i32
+            - date_time_start.month0() as i32,
+    )
+    .ok()?;
+    // The occurrence of the current period may still be ahead of `now`, in which case the previous one is returned.
+    let elapsed_periods = elapsed_months / months_per_period;
+    for periods in [elapsed_periods, elapsed_periods.saturating_sub(1)] {
+        let occurrence = add_months(date_time_start, periods.checked_mul(months_per_period)?)?;
+        let occurrence_ms = Moment::try_from(occurrence.timestamp())
+            .ok()?
+            .checked_mul(1000)?
+            .checked_add(schedule.start % 1000)?;
+        if occurrence_ms <= now_as_ms_utc {
+            return Some(occurrence_ms);
+        }
+    }
+    None
+}
+
+/// Adds `months` to `date_time`, keeping its day of the month unless the resulting month is shorter.
+fn add_months(date_time: NaiveDateTime, months: u32) -> Option<NaiveDateTime> {
+    let month0 = date_time.month0().checked_add(months)?;
+    let year = date_time
+        .year()
+        .checked_add(i32::try_from(month0 / 12).ok()?)?;
+    let month = month0 % 12 + 1;
+    let max_month_days = match month {
+        // Handle 30-day months.
+        4 | 6 | 9 | 11 => 30,
+        // Handle February.
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        // No month has more than 31 days.
+        _ => 31,
+    };
+    let date = NaiveDate::from_ymd_opt(year, month, date_time.day().min(max_month_days))?;
+    Some(date.and_time(date_time.time()))
+}
+
+/// Day count conventions, which determine the fraction of a year accrued between two dates,
+/// e.g., when computing the interest of a bond coupon.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DayCount {
+    /// Actual days elapsed, over a year of 360 days.
+    Actual360,
+    /// Actual days elapsed, over a year of 365 days.
+    Actual365,
+    /// Months of 30 days each, over a year of 360 days, per the 30/360 bond basis.
+    Thirty360,
+}
+
+impl DayCount {
+    /// Returns the days accrued between `start` and `end`, in milli-seconds Unix time,
+    /// together with the number of days in a year under this convention.
+    ///
+    /// Returns `None` if `end < start` or the dates cannot be represented.
+    pub fn accrued_days(&self, start: Moment, end: Moment) -> Option<(u64, u64)> {
+        const MS_DAY: Moment = 1000 * 60 * 60 * 24;
+        let actual_days = end.checked_sub(start)? / MS_DAY;
+        match self {
+            Self::Actual360 => Some((actual_days, 360)),
+            Self::Actual365 => Some((actual_days, 365)),
+            Self::Thirty360 => {
+                let date = |ms: Moment| {
+                    NaiveDateTime::from_timestamp_opt(i64::try_from(ms / 1000).ok()?, 0)
+                        .map(|dt| dt.date())
+                };
+                let (start, end) = (date(start)?, date(end)?);
+                // Day 31 counts as day 30, and so does the end day, if the start day does.
+                let start_day = start.day().min(30);
+                let end_day = if start_day == 30 {
+                    end.day().min(30)
+                } else {
+                    end.day()
+                };
+                let days = 360 * i64::from(end.year() - start.year())
+                    + 30 * (i64::from(end.month()) - i64::from(start.month()))
+                    + (i64::from(end_day) - i64::from(start_day));
+                Some((u64::try_from(days).ok()?, 360))
+            }
+        }
+    }
+}
+
 fn next_checkpoint_secs(
     start: Moment,
     period: CalendarPeriod,
@@ -283,3 +424,89 @@ fn next_checkpoint_secs(
 pub fn is_leap_year(year: i32) -> bool {
     (year % 4 == 0) & ((year % 100 != 0) | (year % 400 == 0))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS_HOUR: Moment = 60 * 60 * 1000;
+    const MS_DAY: Moment = 24 * MS_HOUR;
+
+    fn window(start: Moment, unit: CalendarUnit, amount: u64, duration: Moment) -> CalendarWindow {
+        CalendarWindow {
+            schedule: CheckpointSchedule {
+                start,
+                period: CalendarPeriod { unit, amount },
+            },
+            duration,
+        }
+    }
+
+    #[test]
+    fn daily_window_boundaries() {
+        // Every day from 2021-01-04T09:00:00 to 17:00:00.
+        let start = 1_609_750_800_000;
+        let window = window(start, CalendarUnit::Day, 1, 8 * MS_HOUR);
+
+        assert!(!window.is_open(0));
+        assert!(!window.is_open(start - 1));
+        assert!(window.is_open(start));
+        assert!(window.is_open(start + 8 * MS_HOUR - 1));
+        assert!(!window.is_open(start + 8 * MS_HOUR));
+        assert!(!window.is_open(start + MS_DAY - 1));
+        assert!(window.is_open(start + MS_DAY));
+        assert!(window.is_open(start + MS_DAY + 8 * MS_HOUR - 1));
+        assert!(!window.is_open(start + MS_DAY + 8 * MS_HOUR));
+        assert!(window.is_open(start + 365 * MS_DAY + MS_HOUR));
+    }
+
+    #[test]
+    fn non_recurring_window_boundaries() {
+        let start = 1_609_750_800_000;
+        let window = window(start, CalendarUnit::Day, 0, MS_HOUR);
+
+        assert!(!window.is_open(start - 1));
+        assert!(window.is_open(start));
+        assert!(window.is_open(start + MS_HOUR - 1));
+        assert!(!window.is_open(start + MS_HOUR));
+        assert!(!window.is_open(start + MS_DAY));
+    }
+
+    #[test]
+    fn monthly_window_boundaries() {
+        // Every month from the 31st at 09:00:00 for one hour, which is the 28th in February 2021.
+        let start = 1_612_083_600_000;
+        let window = window(start, CalendarUnit::Month, 1, MS_HOUR);
+
+        // 2021-02-28T08:59:00 and 2021-02-28T09:30:00.
+        assert!(!window.is_open(1_614_502_740_000));
+        assert!(window.is_open(1_614_504_600_000));
+        // 2021-03-01T09:00:00 and 2021-03-31T09:00:00.
+        assert!(!window.is_open(1_614_589_200_000));
+        assert!(window.is_open(1_617_181_200_000));
+    }
+
+    #[test]
+    fn yearly_window_boundaries() {
+        // Every year on the 31st of January at 09:00:00 for one day.
+        let start = 1_612_083_600_000;
+        let window = window(start, CalendarUnit::Year, 1, MS_DAY);
+
+        assert!(window.is_open(start + MS_DAY - 1));
+        assert!(!window.is_open(start + MS_DAY));
+        // 2022-01-31T08:59:59.999 and 2022-01-31T09:00:00.
+        assert!(!window.is_open(start + 365 * MS_DAY - 1));
+        assert!(window.is_open(start + 365 * MS_DAY));
+    }
+
+    #[test]
+    fn window_longer_than_period_is_always_open() {
+        let start = 1_609_750_800_000;
+        let window = window(start, CalendarUnit::Hour, 1, 2 * MS_HOUR);
+
+        assert!(!window.is_open(start - 1));
+        assert!(window.is_open(start));
+        assert!(window.is_open(start + MS_HOUR - 1));
+        assert!(window.is_open(start + 10 * MS_DAY + 1));
+    }
+}
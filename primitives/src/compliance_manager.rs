@@ -13,8 +13,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use crate::condition::{conditions_total_counts, Condition};
+use crate::condition::{conditions_total_counts, Condition, TrustedIssuer};
+use crate::{impl_checked_inc, Claim, IdentityId, Scope};
 use codec::{Decode, Encode};
+use polymesh_primitives_derive::VecU8StrongTyped;
 use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use sp_runtime::{Deserialize, Serialize};
@@ -141,3 +143,287 @@ impl From<AssetCompliance> for AssetComplianceResult {
         }
     }
 }
+
+/// A party to a transfer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo, Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum TransferParty {
+    /// The identity sending the tokens.
+    Sender,
+    /// The identity receiving the tokens.
+    Receiver,
+}
+
+impl TransferParty {
+    /// Returns `sender` or `receiver`, depending on the party.
+    pub fn select<I>(self, sender: I, receiver: I) -> I {
+        match self {
+            Self::Sender => sender,
+            Self::Receiver => receiver,
+        }
+    }
+}
+
+/// A compliance rule as a tree of boolean operators over conditions.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub enum ComplianceExpression {
+    /// Holds if the condition holds for the given party of the transfer.
+    Condition(TransferParty, Condition),
+    /// Holds if the sender and receiver both have a jurisdiction claim with the given scope
+    /// for the same country, from the given trusted issuers,
+    /// or from the default trusted issuers of the asset if empty.
+    SameJurisdiction(Scope, Vec<TrustedIssuer>),
+    /// Holds if all sub-expressions hold.
+    And(Vec<ComplianceExpression>),
+    /// Holds if any sub-expression holds.
+    Or(Vec<ComplianceExpression>),
+    /// Holds if the sub-expression does not hold.
+    Not(Box<ComplianceExpression>),
+}
+
+impl ComplianceExpression {
+    /// Returns the sub-expressions of this node.
+    fn children(&self) -> &[ComplianceExpression] {
+        match self {
+            Self::And(exprs) | Self::Or(exprs) => exprs,
+            Self::Not(expr) => sp_std::slice::from_ref(expr),
+            Self::Condition(..) | Self::SameJurisdiction(..) => &[],
+        }
+    }
+
+    /// Returns the depth of the expression, where a leaf has depth 1.
+    pub fn depth(&self) -> u32 {
+        self.children()
+            .iter()
+            .map(|expr| expr.depth())
+            .max()
+            .unwrap_or(0)
+            .saturating_add(1)
+    }
+
+    /// Returns the worst case number of claim reads of this expression,
+    /// counting every node as at least one read.
+    pub fn complexity(&self, default_issuer_count: usize) -> u32 {
+        let own = match self {
+            Self::Condition(_, condition) => condition.complexity(default_issuer_count),
+            Self::SameJurisdiction(_, issuers) => {
+                let issuers = match issuers.len() {
+                    0 => default_issuer_count,
+                    count => count,
+                };
+                // Jurisdiction claims are read for both parties.
+                issuers
+                    .max(1)
+                    .saturating_mul(2)
+                    .try_into()
+                    .unwrap_or(u32::MAX)
+            }
+            Self::And(..) | Self::Or(..) | Self::Not(..) => 0,
+        };
+        self.children().iter().fold(own.max(1), |total, expr| {
+            total.saturating_add(expr.complexity(default_issuer_count))
+        })
+    }
+
+    /// Returns all conditions in this expression.
+    pub fn conditions(&self) -> Vec<&Condition> {
+        let mut conditions = Vec::new();
+        self.for_each_leaf(&mut |expr| {
+            if let Self::Condition(_, condition) = expr {
+                conditions.push(condition);
+            }
+        });
+        conditions
+    }
+
+    /// Returns all trusted issuers specified in this expression.
+    pub fn issuers(&self) -> Vec<&TrustedIssuer> {
+        let mut issuers = Vec::new();
+        self.for_each_leaf(&mut |expr| match expr {
+            Self::Condition(_, condition) => issuers.extend(&condition.issuers),
+            Self::SameJurisdiction(_, ti) => issuers.extend(ti),
+            _ => {}
+        });
+        issuers
+    }
+
+    /// Calls `f` on each leaf of the expression.
+    fn for_each_leaf<'a>(&'a self, f: &mut impl FnMut(&'a Self)) {
+        match self {
+            Self::Condition(..) | Self::SameJurisdiction(..) => f(self),
+            _ => self
+                .children()
+                .iter()
+                .for_each(|expr| expr.for_each_leaf(f)),
+        }
+    }
+
+    /// Dedup `ClaimType`s in `TrustedFor::Specific`.
+    pub fn dedup(&mut self) {
+        match self {
+            Self::Condition(_, condition) => condition
+                .issuers
+                .iter_mut()
+                .for_each(|issuer| issuer.dedup()),
+            Self::SameJurisdiction(_, issuers) => {
+                issuers.iter_mut().for_each(|issuer| issuer.dedup())
+            }
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter_mut().for_each(Self::dedup),
+            Self::Not(expr) => expr.dedup(),
+        }
+    }
+
+    /// Returns the number of nodes, conditions, claims, issuers, and claim_types.
+    ///
+    /// This is used for weight calculation.
+    pub fn counts(&self) -> (u32, u32, u32, u32, u32) {
+        let mut nodes = 0u32;
+        self.for_each_node(&mut |_| nodes = nodes.saturating_add(1));
+        let (conditions, claims, issuers, claim_types) = conditions_total_counts(self.conditions());
+        (nodes, conditions, claims, issuers, claim_types)
+    }
+
+    /// Calls `f` on each node of the expression.
+    fn for_each_node(&self, f: &mut impl FnMut(&Self)) {
+        f(self);
+        self.children()
+            .iter()
+            .for_each(|expr| expr.for_each_node(f));
+    }
+}
+
+/// A compliance expression along with the evaluation result of each of its nodes.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub enum ComplianceExpressionResult {
+    /// Result of a `ComplianceExpression::Condition`.
+    Condition(TransferParty, ConditionResult),
+    /// Result of a `ComplianceExpression::SameJurisdiction`.
+    SameJurisdiction(Scope, Vec<TrustedIssuer>, bool),
+    /// Result of a `ComplianceExpression::And` and of each of its sub-expressions.
+    And(Vec<ComplianceExpressionResult>, bool),
+    /// Result of a `ComplianceExpression::Or` and of each of its sub-expressions.
+    Or(Vec<ComplianceExpressionResult>, bool),
+    /// Result of a `ComplianceExpression::Not` and of its sub-expression.
+    Not(Box<ComplianceExpressionResult>, bool),
+}
+
+impl ComplianceExpressionResult {
+    /// Returns the result of the root node.
+    pub fn result(&self) -> bool {
+        match self {
+            Self::Condition(_, condition) => condition.result,
+            Self::SameJurisdiction(.., result)
+            | Self::And(_, result)
+            | Self::Or(_, result)
+            | Self::Not(_, result) => *result,
+        }
+    }
+}
+
+/// A global and unique compliance template ID.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub struct ComplianceTemplateId(pub u64);
+impl_checked_inc!(ComplianceTemplateId);
+
+/// The name of a compliance template.
+#[derive(Decode, Encode, TypeInfo, VecU8StrongTyped)]
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ComplianceTemplateName(pub Vec<u8>);
+
+/// A compliance template, shared by the assets subscribed to it.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub struct ComplianceTemplate {
+    /// The identity owning the template.
+    pub owner: IdentityId,
+    /// The name of the template.
+    pub name: ComplianceTemplateName,
+    /// The latest version of the template.
+    pub version: u32,
+    /// The latest version which applies to subscribers without being accepted.
+    pub auto_version: u32,
+}
+
+/// The rules of a version of a compliance template.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo, Default, Clone, PartialEq, Eq, Debug)]
+pub struct ComplianceTemplateRules {
+    /// Compliance requirements of the subscribed assets.
+    pub requirements: Vec<ComplianceRequirement>,
+    /// Default trusted claim issuers of the subscribed assets.
+    pub trusted_issuers: Vec<TrustedIssuer>,
+}
+
+/// The subscription of an asset to a compliance template.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo, Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TemplateSubscription {
+    /// The template subscribed to.
+    pub template_id: ComplianceTemplateId,
+    /// The latest version of the template accepted by an agent of the asset.
+    pub accepted_version: u32,
+}
+
+impl TemplateSubscription {
+    /// Returns the version of `template` which applies to the asset.
+    pub fn version(&self, template: &ComplianceTemplate) -> u32 {
+        self.accepted_version.max(template.auto_version)
+    }
+}
+
+/// A claim missing to satisfy a condition.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Hash)]
+pub struct MissingClaim {
+    /// The missing claim.
+    pub claim: Claim,
+    /// The trusted issuers whose claim would satisfy the condition.
+    pub issuers: Vec<IdentityId>,
+}
+
+/// An individual condition along with its evaluation result and, if it doesn't hold,
+/// the claims which would satisfy it.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Hash)]
+pub struct ConditionSimulation {
+    /// Condition being evaluated.
+    pub condition: Condition,
+    /// Result of evaluation.
+    pub result: bool,
+    /// Claims any of which would satisfy the condition, if it doesn't hold.
+    /// Empty if the condition cannot be satisfied by adding a claim.
+    pub missing_claims: Vec<MissingClaim>,
+}
+
+/// A compliance requirement along with the simulation of its conditions.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Hash)]
+pub struct ComplianceRequirementSimulation {
+    /// List of sender conditions.
+    pub sender_conditions: Vec<ConditionSimulation>,
+    /// List of receiver conditions.
+    pub receiver_conditions: Vec<ConditionSimulation>,
+    /// Unique identifier of the compliance requirement.
+    pub id: u32,
+    /// Result of this requirement's evaluation.
+    pub result: bool,
+}
+
+/// Simulation of an asset compliance for a hypothetical transfer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct AssetComplianceSimulation {
+    /// This flag indicates if asset compliance should be enforced.
+    pub paused: bool,
+    /// List of compliance requirements.
+    pub requirements: Vec<ComplianceRequirementSimulation>,
+    /// Result of the compliance expression, if any.
+    pub expression: Option<ComplianceExpressionResult>,
+    /// Final evaluation result of the asset compliance.
+    pub result: bool,
+}
@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{Claim, ClaimType, IdentityId};
+use crate::{Balance, Claim, ClaimType, IdentityClaim, IdentityId, Moment};
 use codec::{Decode, Encode};
 use core::iter;
 use either::Either;
@@ -33,6 +33,54 @@ pub enum TargetIdentity {
     Specific(IdentityId),
 }
 
+/// The date of a claim checked by a [`ClaimAge`].
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo, Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum ClaimDate {
+    /// The date at which the claim was first issued.
+    Issuance,
+    /// The date at which the claim was last updated.
+    LastUpdate,
+}
+
+/// The maximum age of a claim.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo, Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct ClaimAge {
+    /// The date of the claim from which its age is computed.
+    pub date: ClaimDate,
+    /// The maximum age, in milliseconds.
+    pub max_age: Moment,
+}
+
+impl ClaimAge {
+    /// Returns `true` if `claim` is no older than `max_age` at `now`.
+    pub fn allows(&self, claim: &IdentityClaim, now: Moment) -> bool {
+        let date = match self.date {
+            ClaimDate::Issuance => claim.issuance_date,
+            ClaimDate::LastUpdate => claim.last_update_date,
+        };
+        now.saturating_sub(date) <= self.max_age
+    }
+}
+
+/// Inclusive bounds on the amount of a transfer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo, Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct AmountBounds {
+    /// The minimum amount, if any.
+    pub min: Option<Balance>,
+    /// The maximum amount, if any.
+    pub max: Option<Balance>,
+}
+
+impl AmountBounds {
+    /// Returns `true` if `amount` is within the bounds.
+    pub fn contains(&self, amount: Balance) -> bool {
+        self.min.map_or(true, |min| min <= amount) && self.max.map_or(true, |max| amount <= max)
+    }
+}
+
 /// It defines the type of condition supported, and the filter information we will use to evaluate as a
 /// predicate.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -48,6 +96,10 @@ pub enum ConditionType {
     IsNoneOf(Vec<Claim>),
     /// Condition to ensure that the sender/receiver is a particular identity or an external agent.
     IsIdentity(TargetIdentity),
+    /// Condition to ensure that claim filter produces one claim no older than the given age.
+    IsRecent(Claim, ClaimAge),
+    /// Condition to ensure that the amount transferred is within the given bounds.
+    IsAmountWithin(AmountBounds),
 }
 
 impl ConditionType {
@@ -56,7 +108,9 @@ impl ConditionType {
         match self {
             ConditionType::IsIdentity(..)
             | ConditionType::IsPresent(..)
-            | ConditionType::IsAbsent(..) => 1,
+            | ConditionType::IsAbsent(..)
+            | ConditionType::IsRecent(..)
+            | ConditionType::IsAmountWithin(..) => 1,
             ConditionType::IsNoneOf(claims) | ConditionType::IsAnyOf(claims) => claims.len(),
         }
     }
@@ -189,9 +243,13 @@ impl Condition {
     /// Returns all the claims in the condition.
     pub fn claims(&self) -> impl Iterator<Item = &Claim> {
         match &self.condition_type {
-            ConditionType::IsPresent(c) | ConditionType::IsAbsent(c) => Either::Left(iter::once(c)),
+            ConditionType::IsPresent(c)
+            | ConditionType::IsAbsent(c)
+            | ConditionType::IsRecent(c, _) => Either::Left(iter::once(c)),
             ConditionType::IsAnyOf(cs) | ConditionType::IsNoneOf(cs) => Either::Right(cs.iter()),
-            ConditionType::IsIdentity(_) => Either::Right([].iter()),
+            ConditionType::IsIdentity(_) | ConditionType::IsAmountWithin(_) => {
+                Either::Right([].iter())
+            }
         }
     }
 }
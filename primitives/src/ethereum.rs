@@ -13,7 +13,9 @@ use sp_std::vec::Vec;
 /// An Ethereum address (i.e. 20 bytes, used to represent an Ethereum account).
 ///
 /// This gets serialized to the 0x-prefixed hex representation.
-#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, Default, Debug)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo, Default, Debug
+)]
 pub struct EthereumAddress(pub [u8; 20]);
 
 #[cfg(feature = "std")]
@@ -59,6 +61,8 @@ impl PartialEq for EcdsaSignature {
     }
 }
 
+impl Eq for EcdsaSignature {}
+
 impl sp_std::fmt::Debug for EcdsaSignature {
     fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
         write!(f, "EcdsaSignature({:?})", &self.0[..])
@@ -172,7 +172,10 @@ pub use document::{Document, DocumentId, DocumentName, DocumentUri};
 
 /// Rules for claims.
 pub mod condition;
-pub use condition::{Condition, ConditionType, TargetIdentity, TrustedFor, TrustedIssuer};
+pub use condition::{
+    AmountBounds, ClaimAge, ClaimDate, Condition, ConditionType, TargetIdentity, TrustedFor,
+    TrustedIssuer,
+};
 
 /// Predicate calculation for Claims.
 pub mod proposition;
@@ -219,6 +222,9 @@ pub use weight_meter::WeightMeter;
 /// Settlement type definitions.
 pub mod settlement;
 
+/// Order book type definitions.
+pub mod order_book;
+
 /// Constants definitions.
 pub mod constants;
 
@@ -0,0 +1,140 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Shareable types for the order book.
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+
+use crate::settlement::VenueId;
+use crate::{impl_checked_inc, Balance, IdentityId, PortfolioId, Ticker};
+
+/// Prices are entered as a multiple of `PRICE_DIVISOR`,
+/// i.e. a price of 1 unit is 1_000_000 and a price of 1.5 units is 1_500_000.
+pub const PRICE_DIVISOR: Balance = 1_000_000;
+
+/// Returns the amount of quote asset to pay for `amount` of base asset at `price`,
+/// or `None` if the computation overflows.
+pub fn quote_amount(amount: Balance, price: Balance) -> Option<Balance> {
+    amount.checked_mul(price).map(|quote| quote / PRICE_DIVISOR)
+}
+
+/// A global and unique order ID.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub struct OrderId(pub u64);
+impl_checked_inc!(OrderId);
+
+/// The side of an order.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub enum Side {
+    /// Buys the base asset, paying with the quote asset.
+    #[default]
+    Buy,
+    /// Sells the base asset, receiving the quote asset.
+    Sell,
+}
+
+/// The pair of assets traded in an order book.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub struct TradingPair {
+    /// The asset being bought or sold.
+    pub base: Ticker,
+    /// The asset the price is expressed in.
+    pub quote: Ticker,
+}
+
+impl TradingPair {
+    /// Returns the ticker of the asset locked by an order of the given `side`.
+    pub fn locked_ticker(&self, side: Side) -> Ticker {
+        match side {
+            Side::Buy => self.quote,
+            Side::Sell => self.base,
+        }
+    }
+}
+
+/// A limit order resting in an order book.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Order {
+    /// The identity that placed the order.
+    pub owner: IdentityId,
+    /// The portfolio funding the order and receiving the proceeds.
+    pub portfolio: PortfolioId,
+    /// The venue the order book is attached to.
+    pub venue_id: VenueId,
+    /// The traded pair.
+    pub pair: TradingPair,
+    /// Whether the order buys or sells the base asset.
+    pub side: Side,
+    /// The limit price, as a multiple of [`PRICE_DIVISOR`].
+    pub price: Balance,
+    /// The amount of base asset still to be filled.
+    pub remaining: Balance,
+    /// The amount locked in `portfolio`, in base asset for sell orders and in quote asset for buy orders.
+    pub locked: Balance,
+    /// Time priority of the order, lower values were queued first.
+    pub priority: u64,
+}
+
+impl Order {
+    /// Returns the amount that must be locked to fill `amount` of base asset at `price`.
+    pub fn lock_amount(side: Side, price: Balance, amount: Balance) -> Option<Balance> {
+        match side {
+            Side::Buy => quote_amount(amount, price),
+            Side::Sell => Some(amount),
+        }
+    }
+
+    /// Returns the ticker of the asset locked by the order.
+    pub fn locked_ticker(&self) -> Ticker {
+        self.pair.locked_ticker(self.side)
+    }
+}
+
+/// The aggregated amount of all orders at a given price.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct PriceLevel {
+    /// The price of the level, as a multiple of [`PRICE_DIVISOR`].
+    pub price: Balance,
+    /// The sum of the remaining amount of all orders at this price.
+    pub amount: Balance,
+    /// The number of orders at this price.
+    pub orders: u32,
+}
+
+/// The aggregated state of an order book, best prices first.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct OrderBookDepth {
+    /// Buy orders, from the highest to the lowest price.
+    pub bids: Vec<PriceLevel>,
+    /// Sell orders, from the lowest to the highest price.
+    pub asks: Vec<PriceLevel>,
+}
@@ -185,6 +185,7 @@ mod tests {
         Context {
             claims: claims.into_iter(),
             id: <_>::default(),
+            amount: 0,
         }
     }
 
@@ -296,6 +297,7 @@ mod tests {
             Context {
                 id: identity1,
                 claims: vec![].into_iter(),
+                amount: 0,
             },
             |context: Context<_>| context.id == identity1,
         ));
@@ -304,6 +306,7 @@ mod tests {
             Context {
                 id: identity1,
                 claims: vec![].into_iter(),
+                amount: 0,
             },
             |_| false,
         ));
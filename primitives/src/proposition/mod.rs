@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{Claim, Condition, ConditionType, IdentityId, TargetIdentity};
+use crate::{Balance, Claim, Condition, ConditionType, IdentityId, TargetIdentity};
 use codec::{Decode, Encode};
 
 use sp_std::prelude::*;
@@ -28,6 +28,8 @@ pub struct Context<C> {
     /// It could be the sender DID during the evaluation of sender's conditions or
     /// the receiver DID on a receiver's condition evaluation.
     pub id: IdentityId,
+    /// The amount of the transfer under evaluation.
+    pub amount: Balance,
 }
 
 // Proposition Trait
@@ -118,5 +120,8 @@ pub fn run<C: Iterator<Item = Claim>, E: Proposition<C>>(
             IsIdentityProposition { identity: *id }.evaluate(context)
         }
         ConditionType::IsIdentity(TargetIdentity::ExternalAgent) => ea_prop.evaluate(context),
+        // The context only holds the claims which are recent enough.
+        ConditionType::IsRecent(claim, _) => exists(claim).evaluate(context),
+        ConditionType::IsAmountWithin(bounds) => bounds.contains(context.amount),
     }
 }
@@ -28,10 +28,16 @@ use sp_std::vec::Vec;
 
 use polymesh_primitives_derive::{SliceU8StrongTyped, VecU8StrongTyped};
 
-use crate::constants::SETTLEMENT_INSTRUCTION_EXECUTION;
+use crate::asset::GranularCanTransferResult;
+use crate::constants::{
+    SETTLEMENT_DEPENDENTS_REJECTION, SETTLEMENT_INSTRUCTION_EXECUTION,
+    SETTLEMENT_INSTRUCTION_EXPIRY, SETTLEMENT_REPO_UNWIND,
+};
+use crate::ethereum::{EcdsaSignature, EthereumAddress};
 use crate::{impl_checked_inc, Balance, IdentityId, NFTs, PortfolioId, Ticker};
 
 /// A global and unique venue ID.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, TypeInfo)]
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
 pub struct VenueId(pub u64);
@@ -88,6 +94,8 @@ pub enum LegStatus<AccountId> {
     ExecutionPending,
     /// receipt used, (receipt signer, receipt uid)
     ExecutionToBeSkipped(AccountId, u64),
+    /// ECDSA receipt used, (receipt signer, receipt uid)
+    ExecutionToBeSkippedWithEcdsaReceipt(EthereumAddress, u64),
 }
 
 /// Status of an affirmation
@@ -119,6 +127,7 @@ pub enum SettlementType<BlockNumber> {
 }
 
 /// A per-Instruction leg ID.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, TypeInfo)]
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
 pub struct LegId(pub u64);
@@ -136,6 +145,21 @@ impl InstructionId {
     pub fn execution_name(&self) -> Vec<u8> {
         (SETTLEMENT_INSTRUCTION_EXECUTION, self.0).encode()
     }
+
+    /// Converts an instruction id into a scheduler name for its expiry.
+    pub fn expiry_name(&self) -> Vec<u8> {
+        (SETTLEMENT_INSTRUCTION_EXPIRY, self.0).encode()
+    }
+
+    /// Converts an instruction id into a scheduler name for the rejection of its dependents.
+    pub fn dependents_rejection_name(&self) -> Vec<u8> {
+        (SETTLEMENT_DEPENDENTS_REJECTION, self.0).encode()
+    }
+
+    /// Converts an instruction id into a scheduler name for the unwind of its repo agreement.
+    pub fn repo_unwind_name(&self) -> Vec<u8> {
+        (SETTLEMENT_REPO_UNWIND, self.0).encode()
+    }
 }
 
 /// Details about an instruction.
@@ -154,6 +178,8 @@ pub struct Instruction<Moment, BlockNumber> {
     pub trade_date: Option<Moment>,
     /// Date after which the instruction should be settled (not enforced)
     pub value_date: Option<Moment>,
+    /// Date after which the instruction is automatically rejected
+    pub expiry: Option<Moment>,
 }
 
 /// Defines a [`Leg`] (i.e the action of a settlement).
@@ -209,6 +235,84 @@ impl Leg {
             Leg::OffChain { ticker, .. } => *ticker,
         }
     }
+
+    /// Returns the leg that transfers the same assets back from the receiver to the sender.
+    pub fn reversed(&self) -> Self {
+        match self {
+            Leg::Fungible {
+                sender,
+                receiver,
+                ticker,
+                amount,
+            } => Leg::Fungible {
+                sender: *receiver,
+                receiver: *sender,
+                ticker: *ticker,
+                amount: *amount,
+            },
+            Leg::NonFungible {
+                sender,
+                receiver,
+                nfts,
+            } => Leg::NonFungible {
+                sender: *receiver,
+                receiver: *sender,
+                nfts: nfts.clone(),
+            },
+            Leg::OffChain {
+                sender_identity,
+                receiver_identity,
+                ticker,
+                amount,
+            } => Leg::OffChain {
+                sender_identity: *receiver_identity,
+                receiver_identity: *sender_identity,
+                ticker: *ticker,
+                amount: *amount,
+            },
+        }
+    }
+}
+
+/// The status of a repo agreement.
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RepoStatus {
+    /// The opening legs have not been executed yet.
+    #[default]
+    Pending,
+    /// The opening legs have been executed and the closing legs are waiting for the maturity date.
+    Open,
+}
+
+/// A repo or securities lending agreement.
+/// The opening legs are settled by the instruction the agreement belongs to, and the closing legs,
+/// which return the assets to their original owners, are automatically settled at `maturity`.
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RepoAgreement<Moment> {
+    /// The [`VenueId`] of the venue the agreement belongs to.
+    pub venue_id: VenueId,
+    /// The current [`RepoStatus`] of the agreement.
+    pub status: RepoStatus,
+    /// The legs that are settled when the agreement is unwound.
+    pub closing_legs: Vec<Leg>,
+    /// The date at which the agreement is unwound.
+    pub maturity: Moment,
+}
+
+/// A superseded version of an instruction, kept when the instruction is amended.
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstructionVersion<Moment> {
+    /// The legs of the instruction at this version.
+    pub legs: Vec<Leg>,
+    /// The trade date of the instruction at this version.
+    pub trade_date: Option<Moment>,
+    /// The value date of the instruction at this version.
+    pub value_date: Option<Moment>,
+    /// The date at which this version was superseded.
+    pub amended_at: Moment,
 }
 
 /// Details about a venue.
@@ -221,6 +325,34 @@ pub struct Venue {
     pub venue_type: VenueType,
 }
 
+/// Limits on the size of the instructions that can be added to a venue.
+#[derive(Encode, Decode, TypeInfo)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct VenueInstructionLimits {
+    /// The maximum number of legs in a single instruction.
+    pub max_legs: Option<u32>,
+    /// The maximum amount transferred in a single fungible leg.
+    pub max_leg_amount: Option<Balance>,
+}
+
+impl VenueInstructionLimits {
+    /// Returns `true` if `legs` are within the limits.
+    pub fn allows(&self, legs: &[Leg]) -> bool {
+        if let Some(max_legs) = self.max_legs {
+            if legs.len() > max_legs as usize {
+                return false;
+            }
+        }
+        if let Some(max_leg_amount) = self.max_leg_amount {
+            return legs.iter().all(|leg| match leg {
+                Leg::Fungible { amount, .. } => *amount <= max_leg_amount,
+                Leg::NonFungible { .. } | Leg::OffChain { .. } => true,
+            });
+        }
+        true
+    }
+}
+
 /// An offchain transaction receipt.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, PartialOrd, Ord)]
 pub struct Receipt<Balance> {
@@ -336,6 +468,120 @@ impl<AccountId, OffChainSignature> ReceiptDetails<AccountId, OffChainSignature>
     }
 }
 
+/// The prefix of the message signed for an [`EcdsaReceipt`].
+pub const ECDSA_RECEIPT_PREFIX: &[u8] = b"polymesh_settlement_receipt";
+
+/// An offchain transaction receipt signed with an ECDSA (secp256k1) key.
+///
+/// The receipt is bound to a chain by its genesis hash, so it can't be replayed on another chain,
+/// and can't be claimed after its `expiry`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct EcdsaReceipt<Balance, Moment, Hash> {
+    /// The genesis hash of the chain the receipt is valid on.
+    genesis_hash: Hash,
+    /// The receipt of the offchain transfer.
+    receipt: Receipt<Balance>,
+    /// The time after which the receipt can no longer be claimed.
+    expiry: Option<Moment>,
+}
+
+impl<Balance, Moment, Hash> EcdsaReceipt<Balance, Moment, Hash> {
+    /// Creates a new [`EcdsaReceipt`].
+    pub fn new(genesis_hash: Hash, receipt: Receipt<Balance>, expiry: Option<Moment>) -> Self {
+        Self {
+            genesis_hash,
+            receipt,
+            expiry,
+        }
+    }
+}
+
+/// Details about an offchain transaction receipt signed with an ECDSA (secp256k1) key.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub struct EcdsaReceiptDetails<Moment> {
+    /// Unique receipt number set by the signer for their receipts
+    uid: u64,
+    /// The [`InstructionId`] of the instruction which contains the offchain transfer.
+    instruction_id: InstructionId,
+    /// The [`LegId`] which which contains the offchain transfer.
+    leg_id: LegId,
+    /// The [`EthereumAddress`] of the signer for this receipt.
+    signer: EthereumAddress,
+    /// Signature of the [`EcdsaReceipt`], following Ethereum's `personal_sign`.
+    signature: EcdsaSignature,
+    /// The time after which the receipt can no longer be claimed.
+    expiry: Option<Moment>,
+    /// The [`ReceiptMetadata`] that can be used to attach messages to receipts.
+    metadata: Option<ReceiptMetadata>,
+}
+
+impl<Moment> EcdsaReceiptDetails<Moment> {
+    /// Creates a new [`EcdsaReceiptDetails`].
+    pub fn new(
+        uid: u64,
+        instruction_id: InstructionId,
+        leg_id: LegId,
+        signer: EthereumAddress,
+        signature: EcdsaSignature,
+        expiry: Option<Moment>,
+        metadata: Option<ReceiptMetadata>,
+    ) -> Self {
+        Self {
+            uid,
+            instruction_id,
+            leg_id,
+            signer,
+            signature,
+            expiry,
+            metadata,
+        }
+    }
+
+    /// Returns the uid of the receipt details.
+    pub fn uid(&self) -> u64 {
+        self.uid
+    }
+
+    /// Returns the [`InstructionId`] of the receipt details.
+    pub fn instruction_id(&self) -> &InstructionId {
+        &self.instruction_id
+    }
+
+    /// Returns the [`LegId`] of the receipt details.
+    pub fn leg_id(&self) -> LegId {
+        self.leg_id
+    }
+
+    /// Returns the [`EthereumAddress`] of the signer of the receipt details.
+    pub fn signer(&self) -> &EthereumAddress {
+        &self.signer
+    }
+
+    /// Returns the signature of the receipt details.
+    pub fn signature(&self) -> &EcdsaSignature {
+        &self.signature
+    }
+
+    /// Returns the expiry of the receipt details.
+    pub fn expiry(&self) -> &Option<Moment> {
+        &self.expiry
+    }
+
+    /// Returns the [`ReceiptMetadata`] of the receipt details.
+    pub fn metadata(&self) -> &Option<ReceiptMetadata> {
+        &self.metadata
+    }
+}
+
+/// The signer of a claimed offchain receipt.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq, TypeInfo)]
+pub enum ReceiptSigner<AccountId> {
+    /// A venue signer, signing with its account key.
+    Account(AccountId),
+    /// A venue ECDSA signer, identified by its Ethereum address.
+    Ecdsa(EthereumAddress),
+}
+
 /// Stores the number of fungible, non fungible and offchain transfers in a set of legs.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Debug, Decode, Default, Encode, Eq, PartialEq, TypeInfo)]
@@ -700,6 +946,60 @@ impl ExecuteInstructionInfo {
     }
 }
 
+/// The simulated outcome of transferring a single leg of an instruction.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Decode, Encode, Clone, PartialEq, Eq)]
+pub enum LegTransferReport {
+    /// The granular result of all compliance, transfer condition and portfolio checks for a fungible leg.
+    Fungible(GranularCanTransferResult),
+    /// If the NFTs in the leg can't be transferred, contains the error.
+    NonFungible(Option<String>),
+    /// Off-chain legs are not transferred. Contains `true` if the leg's receipt has been affirmed.
+    OffChain(bool),
+}
+
+impl LegTransferReport {
+    /// Creates a [`LegTransferReport::NonFungible`] from the error returned when validating the NFT transfer.
+    pub fn non_fungible(error: Option<&str>) -> Self {
+        LegTransferReport::NonFungible(error.map(|e| e.to_string()))
+    }
+
+    /// Returns `true` if the leg would be settled successfully.
+    pub fn result(&self) -> bool {
+        match self {
+            LegTransferReport::Fungible(granular_result) => granular_result.result,
+            LegTransferReport::NonFungible(error) => error.is_none(),
+            LegTransferReport::OffChain(affirmed) => *affirmed,
+        }
+    }
+}
+
+/// The outcome of simulating the execution of an instruction against the current state, without committing any change.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Decode, Encode, Clone, PartialEq, Eq)]
+pub struct InstructionExecutionReport {
+    /// All errors that would prevent the instruction from being executed, regardless of its legs.
+    pub instruction_errors: Vec<String>,
+    /// The simulated outcome of each leg, in execution order.
+    pub legs: Vec<(LegId, LegTransferReport)>,
+    /// `true` if executing the instruction would succeed.
+    pub result: bool,
+}
+
+impl InstructionExecutionReport {
+    /// Creates an instance of [`InstructionExecutionReport`].
+    pub fn new(instruction_errors: Vec<&str>, legs: Vec<(LegId, LegTransferReport)>) -> Self {
+        Self {
+            result: instruction_errors.is_empty() && legs.iter().all(|(_, leg)| leg.result()),
+            instruction_errors: instruction_errors
+                .into_iter()
+                .map(|e| e.to_string())
+                .collect(),
+            legs,
+        }
+    }
+}
+
 /// The status of the mediator's affirmation.
 #[derive(Clone, Debug, Decode, Default, Encode, Eq, PartialEq, TypeInfo)]
 pub enum MediatorAffirmationStatus<T> {
@@ -32,7 +32,7 @@ pub type CanTransferResult = Result<u8, Error>;
 sp_api::decl_runtime_apis! {
 
     /// The API to interact with Asset.
-    #[api_version(3)]
+    #[api_version(4)]
     pub trait AssetApi<AccountId>
     where
         AccountId: Codec,
@@ -64,6 +64,19 @@ sp_api::decl_runtime_apis! {
             value: Balance
         ) -> Result<GranularCanTransferResult, DispatchError>;
 
+        /// Returns the amount of `ticker` that can currently be issued,
+        /// given its maximum supply and issuance schedule.
+        ///
+        /// ```ignore
+        /// curl http://localhost:9933 -H "Content-Type: application/json" -d '{
+        ///     "id":1,
+        ///     "jsonrpc":"2.0",
+        ///     "method": "asset_issuanceHeadroom",
+        ///     "params":["0x5449434B4552303030303031"]
+        ///   }'
+        /// ```
+        fn issuance_headroom(ticker: &Ticker) -> Balance;
+
         #[changed_in(3)]
         fn can_transfer_granular(
             from_custodian: Option<IdentityId>,
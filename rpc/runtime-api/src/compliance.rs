@@ -0,0 +1,64 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for ComplianceManager module.
+
+use frame_support::dispatch::result::Result;
+use frame_support::pallet_prelude::DispatchError;
+
+use polymesh_primitives::compliance_manager::{
+    AssetComplianceSimulation, ComplianceExpressionResult,
+};
+use polymesh_primitives::{Balance, IdentityId, Ticker};
+
+sp_api::decl_runtime_apis! {
+    #[api_version(2)]
+    pub trait ComplianceApi {
+        /// Evaluates the compliance expression of `ticker` for a transfer of `amount`
+        /// from `sender` to `receiver`, returning the result of each of its nodes,
+        /// or `None` if `ticker` has no compliance expression.
+        fn compliance_expression_result(
+            ticker: &Ticker,
+            sender: IdentityId,
+            receiver: IdentityId,
+            amount: Balance
+        ) -> Result<Option<ComplianceExpressionResult>, DispatchError>;
+
+        /// Evaluates the compliance of `ticker` for a hypothetical transfer of `amount`
+        /// from `sender` to `receiver`, regardless of their portfolios and balances.
+        /// For each condition which doesn't hold, returns the claims which would satisfy it
+        /// and the trusted issuers of each.
+        ///
+        /// ```ignore
+        /// curl http://localhost:9933 -H "Content-Type: application/json" -d '{
+        ///     "id":1,
+        ///     "jsonrpc":"2.0",
+        ///     "method": "compliance_simulate",
+        ///     "params":[
+        ///       "0x5449434B4552303030303031",
+        ///       "0x0100000000000000000000000000000000000000000000000000000000000000",
+        ///       "0x0200000000000000000000000000000000000000000000000000000000000000",
+        ///        0
+        ///     ]
+        ///   }'
+        /// ```
+        fn simulate_compliance(
+            ticker: &Ticker,
+            sender: IdentityId,
+            receiver: IdentityId,
+            amount: Balance
+        ) -> Result<AssetComplianceSimulation, DispatchError>;
+    }
+}
@@ -0,0 +1,30 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for CorporateBallot module.
+
+use sp_std::vec::Vec;
+
+use pallet_corporate_actions::ballot::MotionOutcome;
+use pallet_corporate_actions::CAId;
+
+sp_api::decl_runtime_apis! {
+    pub trait CorporateBallotApi {
+        /// Returns the [`MotionOutcome`] of each motion in the ballot of `ca_id`,
+        /// including the rounds of any instant-runoff elimination,
+        /// if the ballot has been finalized.
+        fn get_final_results(ca_id: CAId) -> Option<Vec<MotionOutcome>>;
+    }
+}
@@ -17,8 +17,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub mod asset;
+pub mod compliance;
+pub mod corporate_ballot;
 pub mod identity;
 pub mod nft;
+pub mod order_book;
 pub mod pips;
 pub mod settlement;
 pub mod transaction_payment;
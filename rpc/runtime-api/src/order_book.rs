@@ -0,0 +1,63 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for OrderBook module.
+
+use sp_std::vec::Vec;
+
+use polymesh_primitives::order_book::{Order, OrderBookDepth, OrderId, Side, TradingPair};
+use polymesh_primitives::settlement::VenueId;
+
+sp_api::decl_runtime_apis! {
+    pub trait OrderBookApi {
+        /// Returns an [`OrderBookDepth`] with the aggregated amount and number of orders of the best `levels` prices
+        /// on each side of the order book of `pair` in `venue_id`.
+        ///
+        /// ```ignore
+        /// curl http://localhost:9933 -H "Content-Type: application/json" -d '{
+        ///     "id":1,
+        ///     "jsonrpc":"2.0",
+        ///     "method": "orderBook_getDepth",
+        ///     "params": [1, { "base": "0x41434d450000000000000000", "quote": "0x555344430000000000000000" }, 10]
+        ///   }'
+        /// ```
+        fn get_depth(venue_id: VenueId, pair: TradingPair, levels: u32) -> OrderBookDepth;
+
+        /// Returns the open [`Order`] of the given `order_id`, if any.
+        ///
+        /// ```ignore
+        /// curl http://localhost:9933 -H "Content-Type: application/json" -d '{
+        ///     "id":1,
+        ///     "jsonrpc":"2.0",
+        ///     "method": "orderBook_getOrder",
+        ///     "params": [1]
+        ///   }'
+        /// ```
+        fn get_order(order_id: OrderId) -> Option<Order>;
+
+        /// Returns the open orders of one side of the order book of `pair` in `venue_id`,
+        /// best price first and then by time priority.
+        ///
+        /// ```ignore
+        /// curl http://localhost:9933 -H "Content-Type: application/json" -d '{
+        ///     "id":1,
+        ///     "jsonrpc":"2.0",
+        ///     "method": "orderBook_getOpenOrders",
+        ///     "params": [1, { "base": "0x41434d450000000000000000", "quote": "0x555344430000000000000000" }, "Sell"]
+        ///   }'
+        /// ```
+        fn get_open_orders(venue_id: VenueId, pair: TradingPair, side: Side) -> Vec<(OrderId, Order)>;
+    }
+}
@@ -17,10 +17,13 @@
 
 use sp_std::vec::Vec;
 
-use polymesh_primitives::settlement::{AffirmationCount, ExecuteInstructionInfo, InstructionId};
+use polymesh_primitives::settlement::{
+    AffirmationCount, ExecuteInstructionInfo, InstructionExecutionReport, InstructionId,
+};
 use polymesh_primitives::PortfolioId;
 
 sp_api::decl_runtime_apis! {
+    #[api_version(2)]
     pub trait SettlementApi {
         /// Returns an [`ExecuteInstructionInfo`] instance containing the consumed weight and the number of fungible and non fungible
         /// tokens in the instruction. Executing an instruction includes verifying the compliance and transfer restrictions of all assets
@@ -48,5 +51,20 @@ sp_api::decl_runtime_apis! {
         ///   }'
         /// ```
         fn get_affirmation_count(instruction_id: InstructionId, portfolios: Vec<PortfolioId>) -> AffirmationCount;
+
+        /// Returns an [`InstructionExecutionReport`] with the outcome of executing the instruction against the current state,
+        /// without committing any change. For each leg, the report contains the granular compliance, transfer condition and
+        /// portfolio results for fungible assets, the validation error for NFTs, and the affirmation status of off-chain legs.
+        ///
+        /// ```ignore
+        /// curl http://localhost:9933 -H "Content-Type: application/json" -d '{
+        ///     "id":1,
+        ///     "jsonrpc":"2.0",
+        ///     "method": "settlement_simulateInstructionExecution",
+        ///     "params": [1]
+        ///   }'
+        /// ```
+        #[api_version(2)]
+        fn simulate_instruction_execution(instruction_id: InstructionId) -> InstructionExecutionReport;
     }
 }
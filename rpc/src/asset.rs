@@ -28,7 +28,7 @@ use sp_runtime::traits::Block as BlockT;
 
 pub use node_rpc_runtime_api::asset::{AssetApi as AssetRuntimeApi, CanTransferResult};
 use polymesh_primitives::asset::GranularCanTransferResult;
-use polymesh_primitives::{IdentityId, PortfolioId, Ticker};
+use polymesh_primitives::{Balance, IdentityId, PortfolioId, Ticker};
 
 use crate::Error;
 
@@ -45,6 +45,9 @@ pub trait AssetApi<BlockHash, AccountId> {
         value: number::NumberOrHex,
         at: Option<BlockHash>,
     ) -> RpcResult<Result<GranularCanTransferResult, DispatchError>>;
+
+    #[method(name = "asset_issuanceHeadroom")]
+    fn issuance_headroom(&self, ticker: Ticker, at: Option<BlockHash>) -> RpcResult<Balance>;
 }
 
 /// An implementation of asset specific RPC methods.
@@ -155,4 +158,23 @@ where
             .into()
         })
     }
+
+    fn issuance_headroom(
+        &self,
+        ticker: Ticker,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Balance> {
+        let api = self.client.runtime_api();
+        // If the block hash is not supplied assume the best block.
+        let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.issuance_headroom(at_hash, &ticker).map_err(|e| {
+            CallError::Custom(ErrorObject::owned(
+                Error::RuntimeError.into(),
+                "Unable to call issuance_headroom runtime",
+                Some(e.to_string()),
+            ))
+            .into()
+        })
+    }
 }
@@ -0,0 +1,132 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::{convert::TryInto, sync::Arc};
+
+use frame_support::dispatch::result::Result;
+use frame_support::pallet_prelude::DispatchError;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::{CallError, ErrorCode, ErrorObject};
+use sp_api::{ApiRef, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number;
+use sp_runtime::traits::Block as BlockT;
+
+pub use node_rpc_runtime_api::compliance::ComplianceApi as ComplianceRuntimeApi;
+use polymesh_primitives::compliance_manager::{
+    AssetComplianceSimulation, ComplianceExpressionResult,
+};
+use polymesh_primitives::{Balance, IdentityId, Ticker};
+
+/// Compliance RPC methods.
+#[rpc(client, server)]
+pub trait ComplianceApi<BlockHash> {
+    /// Evaluates the compliance expression of `ticker` for a transfer of `value`.
+    #[method(name = "compliance_expressionResult")]
+    fn expression_result(
+        &self,
+        ticker: Ticker,
+        sender: IdentityId,
+        receiver: IdentityId,
+        value: number::NumberOrHex,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Result<Option<ComplianceExpressionResult>, DispatchError>>;
+
+    /// Simulates the compliance of `ticker` for a hypothetical transfer of `value`,
+    /// listing the claims missing for each condition which doesn't hold.
+    #[method(name = "compliance_simulate")]
+    fn simulate(
+        &self,
+        ticker: Ticker,
+        sender: IdentityId,
+        receiver: IdentityId,
+        value: number::NumberOrHex,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Result<AssetComplianceSimulation, DispatchError>>;
+}
+
+/// An implementation of compliance specific RPC methods.
+pub struct Compliance<T, U> {
+    client: Arc<T>,
+    _marker: std::marker::PhantomData<U>,
+}
+
+impl<T, U> Compliance<T, U> {
+    /// Creates a new `Compliance` with the given reference to the client.
+    pub fn new(client: Arc<T>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Makes sure that `value` fits into 64 bits.
+fn to_balance(value: number::NumberOrHex) -> RpcResult<Balance> {
+    let value: u64 = value.try_into().map_err(|_| {
+        CallError::Custom(ErrorObject::owned(
+            ErrorCode::InvalidParams.code(),
+            format!("{:?} doesn't fit in 64 bit unsigned value", value),
+            None::<()>,
+        ))
+    })?;
+    Ok(value.into())
+}
+
+impl<C, Block> ComplianceApiServer<<Block as BlockT>::Hash> for Compliance<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: ComplianceRuntimeApi<Block>,
+{
+    fn expression_result(
+        &self,
+        ticker: Ticker,
+        sender: IdentityId,
+        receiver: IdentityId,
+        value: number::NumberOrHex,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Result<Option<ComplianceExpressionResult>, DispatchError>> {
+        let amount = to_balance(value)?;
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| {
+                api.compliance_expression_result(at, &ticker, sender, receiver, amount)
+            },
+            "Unable to query `compliance_expression_result`."
+        )
+    }
+
+    fn simulate(
+        &self,
+        ticker: Ticker,
+        sender: IdentityId,
+        receiver: IdentityId,
+        value: number::NumberOrHex,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Result<AssetComplianceSimulation, DispatchError>> {
+        let amount = to_balance(value)?;
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| {
+                api.simulate_compliance(at, &ticker, sender, receiver, amount)
+            },
+            "Unable to query `simulate_compliance`."
+        )
+    }
+}
@@ -0,0 +1,126 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymeshAssociation/Polymesh).
+// Copyright (c) 2020 Polymesh Association
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::{CallError, ErrorObject};
+use sp_api::{ApiRef, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use sp_std::vec::Vec;
+
+pub use node_rpc_runtime_api::order_book::OrderBookApi as OrderBookRuntimeApi;
+use polymesh_primitives::order_book::{Order, OrderBookDepth, OrderId, Side, TradingPair};
+use polymesh_primitives::settlement::VenueId;
+
+/// OrderBook RPC methods.
+#[rpc(client, server)]
+pub trait OrderBookApi<BlockHash> {
+    /// Aggregated depth of the best `levels` prices on each side of an order book.
+    #[method(name = "orderBook_getDepth")]
+    fn get_depth(
+        &self,
+        venue_id: VenueId,
+        pair: TradingPair,
+        levels: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<OrderBookDepth>;
+
+    /// Details of the open order given by `order_id`.
+    #[method(name = "orderBook_getOrder")]
+    fn get_order(&self, order_id: OrderId, at: Option<BlockHash>) -> RpcResult<Option<Order>>;
+
+    /// Open orders of one side of an order book, in matching order.
+    #[method(name = "orderBook_getOpenOrders")]
+    fn get_open_orders(
+        &self,
+        venue_id: VenueId,
+        pair: TradingPair,
+        side: Side,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<(OrderId, Order)>>;
+}
+
+/// An implementation of order book specific RPC methods.
+pub struct OrderBook<T, U> {
+    client: Arc<T>,
+    _marker: std::marker::PhantomData<U>,
+}
+
+impl<T, U> OrderBook<T, U> {
+    /// Creates a new `OrderBook` with the given reference to the client.
+    pub fn new(client: Arc<T>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block> OrderBookApiServer<<Block as BlockT>::Hash> for OrderBook<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: OrderBookRuntimeApi<Block>,
+{
+    fn get_depth(
+        &self,
+        venue_id: VenueId,
+        pair: TradingPair,
+        levels: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<OrderBookDepth> {
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| {
+                api.get_depth(at, venue_id, pair, levels)
+            },
+            "Unable to query `get_depth`."
+        )
+    }
+
+    fn get_order(
+        &self,
+        order_id: OrderId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Order>> {
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| api.get_order(at, order_id),
+            "Unable to query `get_order`."
+        )
+    }
+
+    fn get_open_orders(
+        &self,
+        venue_id: VenueId,
+        pair: TradingPair,
+        side: Side,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<(OrderId, Order)>> {
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| {
+                api.get_open_orders(at, venue_id, pair, side)
+            },
+            "Unable to query `get_open_orders`."
+        )
+    }
+}
@@ -24,7 +24,9 @@ use sp_blockchain::HeaderBackend;
 use sp_runtime::traits::Block as BlockT;
 
 pub use node_rpc_runtime_api::settlement::SettlementApi as SettlementRuntimeApi;
-use polymesh_primitives::settlement::{AffirmationCount, ExecuteInstructionInfo, InstructionId};
+use polymesh_primitives::settlement::{
+    AffirmationCount, ExecuteInstructionInfo, InstructionExecutionReport, InstructionId,
+};
 use polymesh_primitives::PortfolioId;
 
 use crate::Error;
@@ -45,6 +47,13 @@ pub trait SettlementApi<BlockHash> {
         portfolios: Vec<PortfolioId>,
         at: Option<BlockHash>,
     ) -> RpcResult<AffirmationCount>;
+
+    #[method(name = "settlement_simulateInstructionExecution")]
+    fn simulate_instruction_execution(
+        &self,
+        instruction_id: InstructionId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<InstructionExecutionReport>;
 }
 
 /// An implementation of Settlement specific RPC methods.
@@ -109,4 +118,24 @@ where
                 .into()
             })
     }
+
+    fn simulate_instruction_execution(
+        &self,
+        instruction_id: InstructionId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<InstructionExecutionReport> {
+        let api = self.client.runtime_api();
+        // If the block hash is not supplied assume the best block.
+        let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.simulate_instruction_execution(at_hash, instruction_id)
+            .map_err(|e| {
+                CallError::Custom(ErrorObject::owned(
+                    Error::RuntimeError.into(),
+                    "Unable to call simulate_instruction_execution runtime",
+                    Some(e.to_string()),
+                ))
+                .into()
+            })
+    }
 }
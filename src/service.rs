@@ -100,6 +100,8 @@ pub trait RuntimeApiCollection:
     + pallet_group_rpc_runtime_api::GroupApi<Block>
     + node_rpc_runtime_api::nft::NFTApi<Block>
     + node_rpc_runtime_api::settlement::SettlementApi<Block>
+    + node_rpc_runtime_api::order_book::OrderBookApi<Block>
+    + node_rpc_runtime_api::compliance::ComplianceApi<Block>
 where
     <Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
@@ -125,7 +127,9 @@ where
         + node_rpc_runtime_api::asset::AssetApi<Block, AccountId>
         + pallet_group_rpc_runtime_api::GroupApi<Block>
         + node_rpc_runtime_api::nft::NFTApi<Block>
-        + node_rpc_runtime_api::settlement::SettlementApi<Block>,
+        + node_rpc_runtime_api::settlement::SettlementApi<Block>
+        + node_rpc_runtime_api::order_book::OrderBookApi<Block>
+        + node_rpc_runtime_api::compliance::ComplianceApi<Block>,
     <Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
 }